@@ -0,0 +1,55 @@
+#![feature(test)]
+extern crate test;
+use test::{black_box, Bencher};
+use umath::{slice, FFloat, FF32};
+
+fn data(n: usize) -> Vec<FF32> {
+    (0..n)
+        .map(|x| unsafe { FFloat::new((x % 7) as f32) })
+        .collect()
+}
+
+#[bench]
+fn axpy(b: &mut Bencher) {
+    let x = data(4096);
+    let mut y = data(4096);
+    b.iter(|| slice::axpy(black_box(unsafe { FFloat::new(1e-9) }), &x, &mut y));
+}
+
+#[bench]
+fn gemm(b: &mut Bencher) {
+    let x = data(64 * 64);
+    let mut y = data(64 * 64);
+    b.iter(|| {
+        let one = unsafe { FFloat::new(1.0) };
+        slice::gemm(black_box(one), &x, &x, one, &mut y, 64, 64, 64);
+    });
+}
+
+#[bench]
+fn fma(b: &mut Bencher) {
+    let x = data(4096);
+    let mut y = data(4096);
+    b.iter(|| slice::fma_assign(&mut y, black_box(&x), &x));
+}
+
+#[bench]
+fn sin(b: &mut Bencher) {
+    let x = data(4096);
+    let mut y = data(4096);
+    b.iter(|| slice::sin(black_box(&x), &mut y));
+}
+
+#[bench]
+fn exp(b: &mut Bencher) {
+    let x = data(4096);
+    let mut y = data(4096);
+    b.iter(|| slice::exp(black_box(&x), &mut y));
+}
+
+#[bench]
+fn ln(b: &mut Bencher) {
+    let x: Vec<_> = data(4096).into_iter().map(|x| x + 1.0).collect();
+    let mut y = data(4096);
+    b.iter(|| slice::ln(black_box(&x), &mut y));
+}
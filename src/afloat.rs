@@ -0,0 +1,94 @@
+use crate::policy::Algebraic;
+#[cfg(doc)]
+use crate::policy::Policy;
+use crate::r#trait::{FastFloat, Wrapper};
+use crate::Float;
+use core::ops::{Deref, DerefMut};
+#[cfg(doc)]
+use {
+    crate::FFloat,
+    core::intrinsics::{fadd_algebraic, fmul_algebraic},
+};
+
+/// Float wrapper that uses the algebraic intrinsics (like [`fadd_algebraic`] and [`fmul_algebraic`]).
+///
+/// These allow reassociation, contraction (fma), reciprocals, and ignoring the sign of zero, but,
+/// unlike [`FFloat`], do not assume anything about NAN or INF; this type is entirely safe.
+/// The [`Policy`] used is [`Algebraic`].
+///
+/// Do note that the results may vary (within rounding error) between optimization levels, or even compiler versions.
+///
+/// `AFloat<F>` is guaranteed to have the same memory layout and ABI as F.
+/// ```
+/// # use umath::AFloat;
+/// let result = AFloat::new(27.0) * 42109.0;
+/// assert_eq!(*result, 1136943.0);
+/// let nan = AFloat::new(f32::INFINITY) * 0.0;
+/// assert!(nan.is_nan());
+/// ```
+#[repr(transparent)]
+#[derive(Copy, Clone, PartialEq, Default)]
+pub struct AFloat<T>(T);
+
+impl<T: FastFloat> AFloat<T> {
+    /// Create a new [`AFloat`] from your {[`f32`], [`f64`]}.
+    pub const fn new(from: T) -> Self {
+        Self(from)
+    }
+}
+
+impl<T: FastFloat> From<T> for AFloat<T> {
+    fn from(value: T) -> Self {
+        Self(value)
+    }
+}
+
+impl<T: FastFloat> Wrapper for AFloat<T> {
+    type Inner = T;
+    type Policy = Algebraic;
+
+    #[inline(always)]
+    fn raw(self) -> T {
+        self.0
+    }
+
+    #[inline(always)]
+    unsafe fn wrap(x: T) -> Self {
+        // the algebraic operations never cause UB, and there is no invariant to uphold.
+        Self(x)
+    }
+}
+
+impl<T> Deref for AFloat<T> {
+    type Target = T;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl<T> DerefMut for AFloat<T> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.0
+    }
+}
+
+impl<F: FastFloat + Float<F>> Float<F> for AFloat<F> {
+    /// Create a new [`AFloat`] from your {[`f32`], [`f64`]}. This function is safe to call.
+    unsafe fn new(from: F) -> Self {
+        Self(from)
+    }
+
+    fn take(self) -> F {
+        self.0
+    }
+}
+
+#[test]
+fn usable() {
+    fn poly<F: Float<f64>>(x: F) -> F {
+        (x * 3.0 + 2.0) * x + 1.0
+    }
+    assert!((6.0..6.01).contains(&*poly(AFloat::new(1.0))));
+    assert!(poly(AFloat::new(f64::NAN)).is_nan());
+}
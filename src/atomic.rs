@@ -0,0 +1,153 @@
+use crate::generic_float::FloatMethods;
+use crate::{FFloat, FF32, FF64};
+use core::marker::PhantomData;
+use core::sync::atomic::{AtomicU32, AtomicU64, Ordering};
+
+macro_rules! atomic {
+    ($name:ident, $ff:ident, $float:ident, $atomic:ident, $bits:ident) => {
+        #[doc = concat!("A [`", stringify!($ff), "`] which can be safely shared between threads, stored as its bits in a [`", stringify!($atomic), "`].")]
+        ///
+        /// The read-modify-write operations are compare-exchange loops, using the fast operations, and checking every new value.
+        /// ```
+        #[doc = concat!("# use umath::{FFloat, ", stringify!($name), "};")]
+        /// use std::sync::atomic::Ordering;
+        #[doc = concat!("let x = ", stringify!($name), "::new(unsafe { FFloat::new(1.0) });")]
+        /// std::thread::scope(|s| {
+        ///     for _ in 0..4 {
+        ///         s.spawn(|| x.fetch_add(unsafe { FFloat::new(2.0) }, Ordering::Relaxed));
+        ///     }
+        /// });
+        /// assert_eq!(*x.load(Ordering::Relaxed), 9.0);
+        /// ```
+        #[repr(transparent)]
+        pub struct $name($atomic);
+
+        impl $name {
+            #[doc = concat!("Creates a new [`", stringify!($name), "`].")]
+            pub const fn new(v: $ff) -> Self {
+                Self($atomic::new(v.0.to_bits()))
+            }
+
+            /// Reads the bits back into a float.
+            #[inline(always)]
+            fn get(bits: $bits) -> $ff {
+                // SAFETY: every stored value came from a (checked) FFloat.
+                unsafe { FFloat::new($float::from_bits(bits)) }
+            }
+
+            #[doc = concat!("Loads the value. Refer to [`", stringify!($atomic), "::load`].")]
+            pub fn load(&self, order: Ordering) -> $ff {
+                Self::get(self.0.load(order))
+            }
+
+            #[doc = concat!("Stores a value. Refer to [`", stringify!($atomic), "::store`].")]
+            pub fn store(&self, v: $ff, order: Ordering) {
+                self.0.store(v.0.to_bits(), order);
+            }
+
+            #[doc = concat!("Stores a value, returning the previous value. Refer to [`", stringify!($atomic), "::swap`].")]
+            pub fn swap(&self, v: $ff, order: Ordering) -> $ff {
+                Self::get(self.0.swap(v.0.to_bits(), order))
+            }
+
+            /// Consumes the atomic, returning the value.
+            pub fn into_inner(self) -> $ff {
+                Self::get(self.0.into_inner())
+            }
+
+            /// Fetches the value, and applies `f` to it, which returns the new value, or [`None`] to stop; returning the previous value.
+            ///
+            /// `f` may be called more than once, if the value was changed by another thread in the meantime.
+            ///
+            /// # Errors
+            ///
+            /// If `f` returned [`None`], with the previous value.
+            #[doc = concat!("Refer to [`", stringify!($atomic), "::fetch_update`], which this mirrors (including the orderings).")]
+            pub fn fetch_update(
+                &self,
+                set_order: Ordering,
+                fetch_order: Ordering,
+                mut f: impl FnMut($ff) -> Option<$ff>,
+            ) -> Result<$ff, $ff> {
+                self.0
+                    .fetch_update(set_order, fetch_order, |x| f(Self::get(x)).map(|x| x.0.to_bits()))
+                    .map(Self::get)
+                    .map_err(Self::get)
+            }
+
+            /// [`fetch_update`](Self::fetch_update), for a `f` which always returns a new value, returning the previous value.
+            #[inline(always)]
+            fn fetch_with(&self, order: Ordering, mut f: impl FnMut($ff) -> $ff) -> $ff {
+                match self.fetch_update(order, Ordering::Relaxed, |x| Some(f(x))) {
+                    Ok(x) | Err(x) => x,
+                }
+            }
+
+            /// Adds to the value, returning the previous value.
+            pub fn fetch_add(&self, v: $ff, order: Ordering) -> $ff {
+                self.fetch_with(order, |x| x + v)
+            }
+
+            /// Subtracts from the value, returning the previous value.
+            pub fn fetch_sub(&self, v: $ff, order: Ordering) -> $ff {
+                self.fetch_with(order, |x| x - v)
+            }
+
+            /// Sets the value to the maximum of it and `v`, returning the previous value.
+            pub fn fetch_max(&self, v: $ff, order: Ordering) -> $ff {
+                self.fetch_with(order, |x| FloatMethods::max(x, v))
+            }
+
+            /// Sets the value to the minimum of it and `v`, returning the previous value.
+            pub fn fetch_min(&self, v: $ff, order: Ordering) -> $ff {
+                self.fetch_with(order, |x| FloatMethods::min(x, v))
+            }
+        }
+
+        impl Default for $name {
+            fn default() -> Self {
+                Self::new(FFloat(0.0, PhantomData))
+            }
+        }
+
+        impl From<$ff> for $name {
+            fn from(v: $ff) -> Self {
+                Self::new(v)
+            }
+        }
+
+        impl core::fmt::Debug for $name {
+            fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+                core::fmt::Debug::fmt(&self.load(Ordering::Relaxed), f)
+            }
+        }
+    };
+}
+
+atomic!(AtomicFF32, FF32, f32, AtomicU32, u32);
+atomic!(AtomicFF64, FF64, f64, AtomicU64, u64);
+
+#[test]
+#[allow(clippy::float_cmp)]
+fn max() {
+    let x = AtomicFF64::default();
+    x.fetch_max(unsafe { FFloat::new(3.0) }, Ordering::Relaxed);
+    assert_eq!(
+        *x.fetch_max(unsafe { FFloat::new(2.0) }, Ordering::Relaxed),
+        3.0
+    );
+    assert_eq!(
+        *x.fetch_sub(unsafe { FFloat::new(1.0) }, Ordering::Relaxed),
+        3.0
+    );
+    assert_eq!(
+        x.fetch_update(Ordering::Relaxed, Ordering::Relaxed, |x| (*x > 5.0)
+            .then_some(x)),
+        Err(unsafe { FFloat::new(2.0) })
+    );
+    assert_eq!(
+        x.fetch_update(Ordering::Relaxed, Ordering::Relaxed, |x| Some(x * 4.0)),
+        Ok(unsafe { FFloat::new(2.0) })
+    );
+    assert_eq!(*x.into_inner(), 8.0);
+}
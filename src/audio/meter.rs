@@ -0,0 +1,104 @@
+use super::to_db;
+use crate::generic_float::{Constructors, FloatAlone, FloatExt, FloatMethods};
+use crate::policy::Policy;
+use crate::r#trait::{FastFloat, Wrapper};
+use crate::resample::{Kernel, Resampler};
+use crate::{slice, FFloat};
+
+/// The sample peak: the largest magnitude of `x` (0 if it is empty).
+///
+/// This is the [`summary`](slice::summary) of `x`, which finds the minimum and maximum in one pass.
+/// ```
+/// # use umath::{FFloat, audio};
+/// let x = [0.25, -0.5, 0.125].map(|x| unsafe { FFloat::new(x) });
+/// assert_eq!(*audio::peak(&x), 0.5f32);
+/// ```
+#[must_use]
+pub fn peak<T: FastFloat + FloatExt, P: Policy>(x: &[FFloat<T, P>]) -> FFloat<T, P> {
+    let summary = slice::summary(x);
+    if summary.count == 0 {
+        // SAFETY: 0 is a valid float
+        return unsafe { FFloat::zero() };
+    }
+    let low = -summary.min;
+    if low > summary.max {
+        low
+    } else {
+        summary.max
+    }
+}
+
+/// The root mean square of `x`, `√(Σ x² / n)` (0 if it is empty): its average power, as a level.
+///
+/// A full scale sine has a rms of `1 / √2` (-3 dBFS).
+/// ```
+/// # use umath::{FFloat, audio};
+/// let x = [0.5, -0.5, 0.5, -0.5].map(|x| unsafe { FFloat::new(x) });
+/// assert_eq!(*audio::rms(&x), 0.5f32);
+/// ```
+#[must_use]
+#[allow(clippy::cast_precision_loss)]
+pub fn rms<T: FastFloat + FloatExt + FloatMethods, P: Policy>(x: &[FFloat<T, P>]) -> FFloat<T, P> {
+    if x.is_empty() {
+        // SAFETY: 0 is a valid float
+        return unsafe { FFloat::zero() };
+    }
+    // SAFETY: the length is finite.
+    let n = unsafe { FFloat::wrap(T::from_f64(x.len() as f64)) };
+    (slice::dot(x, x) / n).sqrt()
+}
+
+/// The true peak: an estimate of the largest magnitude of the continuous signal that `x` samples (0 if it is empty).
+///
+/// The signal can peak between samples, above the [sample peak](peak) (by up to 3 dB, for a sine at a quarter of the sample rate),
+/// and clip after a conversion to analog, or a resampling. This finds the peak of `x` upsampled by 4 (with a windowed sinc [`Resampler`]), as in itu-r bs.1770.
+///
+/// The signal is taken to be silent around `x`, so a block that starts or ends abruptly rings (and overshoots) at its edges, as it would when played.
+/// ```
+/// # use umath::{FFloat, audio};
+/// // a full scale sine, at a quarter of the sample rate, sampled 45° off its peaks
+/// let x: Vec<_> = (0..64)
+///     .map(|j| unsafe { FFloat::new((core::f64::consts::FRAC_PI_2 * f64::from(j) + core::f64::consts::FRAC_PI_4).sin()) })
+///     .collect();
+/// assert!((*audio::peak(&x) - 0.5f64.sqrt()).abs() < 1e-12);
+/// assert!(*audio::true_peak(&x) > 0.99);
+/// ```
+#[must_use]
+pub fn true_peak<T: FastFloat + FloatExt, P: Policy>(x: &[FFloat<T, P>]) -> FFloat<T, P> {
+    peak(&Resampler::new(4.0, Kernel::default()).resample(x))
+}
+
+/// A level (such as a [`peak`], or a [`rms`]) in decibels relative to full scale (dBFS): 1 is 0 dBFS, and every quieter level is negative.
+///
+/// Silence is floored to a very quiet, but finite, level (refer to [`to_db`]).
+/// ```
+/// # use umath::audio;
+/// assert_eq!(audio::dbfs(1.0f32), 0.0);
+/// assert!((audio::dbfs(0.5f32) + 6.02).abs() < 0.01);
+/// ```
+#[must_use]
+pub fn dbfs<F: FloatAlone>(level: F) -> F {
+    to_db(level)
+}
+
+#[test]
+#[allow(clippy::float_cmp)]
+fn metered() {
+    let f = |x| unsafe { FFloat::<f64>::new(x) };
+    let empty: [FFloat<f64>; 0] = [];
+    assert_eq!(*peak(&empty), 0.0);
+    assert_eq!(*rms(&empty), 0.0);
+    assert_eq!(*true_peak(&empty), 0.0);
+    assert_eq!(*peak(&[f(-2.0), f(1.0)]), 2.0);
+    assert_eq!(*peak(&[f(0.5), f(-0.25)]), 0.5);
+    // a full scale sine, of a awkward frequency
+    let mut sine: Vec<_> = (0..1000u16)
+        .map(|j| f((f64::from(j) * 0.7 + 0.3).sin()))
+        .collect();
+    assert!((*rms(&sine) - 0.5f64.sqrt()).abs() < 1e-2);
+    assert!((dbfs(*rms(&sine)) + 3.01).abs() < 0.1);
+    // faded in and out, so that it does not ring at the edges
+    crate::window::Window::Hann.apply(crate::window::Symmetry::Symmetric, &mut sine);
+    let (sampled, true_peak) = (*peak(&sine), *true_peak(&sine));
+    assert!(sampled <= true_peak && (0.99..1.0).contains(&true_peak));
+}
@@ -0,0 +1,124 @@
+//! audio math helpers: decibels, musical pitch, sample formats, and metering.
+//!
+//! The scalar helpers are generic over [`FloatAlone`], and clamp their inputs so that the result is always finite:
+//! these functions will never turn a [`FFloat`] into [`NAN`] | [`INF`].
+//! Integer (pcm) samples are converted to and from [`FFloat`]s in bulk with [`pcm_to_float`] and [`float_to_pcm`],
+//! and blocks of them are metered with [`peak`], [`rms`], and [`true_peak`] (in [`dbfs`]).
+//! ```
+//! # use umath::{FFloat, audio};
+//! let gain = unsafe { FFloat::new(0.5f32) };
+//! let db = audio::to_db(gain);
+//! assert!((-6.03..-6.02).contains(&*db));
+//! assert!((0.499..0.501).contains(&*audio::from_db(db)));
+//! ```
+use crate::generic_float::{lit, Constructors, FloatAlone};
+#[cfg(doc)]
+use crate::FFloat;
+#[cfg(doc)]
+use std::f32::{INFINITY as INF, NAN};
+
+mod meter;
+mod pcm;
+pub use meter::*;
+pub use pcm::*;
+
+/// log2(10)
+const LOG2_10: f64 = std::f64::consts::LOG2_10;
+
+/// Floors the magnitude of `x` at [`f32::MIN_POSITIVE`], so that it may be passed to a logarithm.
+fn floor_positive<F: FloatAlone>(x: F) -> F {
+    x.abs().max(lit(f64::from(f32::MIN_POSITIVE)))
+}
+
+/// `2^x`, with `x` clamped so that the result can not overflow.
+fn exp2_clamped<F: FloatAlone>(x: F) -> F {
+    // log2(MAX) rounds up to the exponent limit, so back off by one.
+    let limit = unsafe { <F as Constructors>::max() }.log2() - lit(1.0);
+    x.min(limit).exp2()
+}
+
+/// Converts a amplitude (gain) to decibels (`20 * log10(|amp|)`).
+///
+/// Amplitudes smaller than [`f32::MIN_POSITIVE`] (including silence) are treated as [`f32::MIN_POSITIVE`],
+/// producing a very quiet, but finite, value.
+pub fn to_db<F: FloatAlone>(amp: F) -> F {
+    floor_positive(amp).log10() * lit(20.0)
+}
+
+/// Converts decibels to a amplitude (gain) (`10^(db / 20)`).
+///
+/// Very loud values are clamped, so that the result never overflows to [`INF`].
+pub fn from_db<F: FloatAlone>(db: F) -> F {
+    exp2_clamped(db * lit(LOG2_10 / 20.0))
+}
+
+/// Converts a power (energy) to decibels (`10 * log10(|power|)`).
+///
+/// Powers smaller than [`f32::MIN_POSITIVE`] are treated as [`f32::MIN_POSITIVE`].
+pub fn to_db_power<F: FloatAlone>(power: F) -> F {
+    floor_positive(power).log10() * lit(10.0)
+}
+
+/// Converts decibels to a power (`10^(db / 10)`).
+///
+/// Very loud values are clamped, so that the result never overflows to [`INF`].
+pub fn from_db_power<F: FloatAlone>(db: F) -> F {
+    exp2_clamped(db * lit(LOG2_10 / 10.0))
+}
+
+/// log2(440), the reference pitch (A4).
+const LOG2_A4: f64 = 8.781_359_713_524_66;
+/// The MIDI note number of A4.
+const A4: f64 = 69.0;
+
+/// Converts a (possibly fractional) number of semitones to a frequency ratio (`2^(semitones / 12)`).
+/// An octave is 12 semitones, doubling the frequency.
+///
+/// Huge intervals are clamped, so that the result never overflows to [`INF`].
+pub fn semitones_to_ratio<F: FloatAlone>(semitones: F) -> F {
+    exp2_clamped(semitones / lit(12.0))
+}
+
+/// Converts a frequency ratio to semitones (`12 * log2(|ratio|)`).
+///
+/// Ratios smaller than [`f32::MIN_POSITIVE`] are treated as [`f32::MIN_POSITIVE`].
+pub fn ratio_to_semitones<F: FloatAlone>(ratio: F) -> F {
+    floor_positive(ratio).log2() * lit(12.0)
+}
+
+/// Converts a frequency (in Hz) to a (fractional) MIDI note number, where A4 (440Hz) is note 69.
+///
+/// Frequencies smaller than [`f32::MIN_POSITIVE`] are treated as [`f32::MIN_POSITIVE`].
+/// ```
+/// # use umath::audio::freq_to_note;
+/// assert_eq!(freq_to_note(440.0f32).round(), 69.0);
+/// assert_eq!(freq_to_note(261.63f32).round(), 60.0);
+/// ```
+pub fn freq_to_note<F: FloatAlone>(freq: F) -> F {
+    // dividing by 440 first could produce a subnormal, so subtract in the log domain instead.
+    (floor_positive(freq).log2() - lit(LOG2_A4)) * lit(12.0) + lit(A4)
+}
+
+/// Converts a (fractional) MIDI note number to a frequency (in Hz), where note 69 is A4 (440Hz).
+///
+/// Absurdly high notes are clamped, so that the result never overflows to [`INF`].
+pub fn note_to_freq<F: FloatAlone>(note: F) -> F {
+    exp2_clamped((note - lit(A4)) / lit(12.0) + lit(LOG2_A4))
+}
+
+#[test]
+fn edges() {
+    use crate::FFloat;
+    let silence = to_db(unsafe { FFloat::new(0.0f32) });
+    assert!(*silence < -700.0);
+    assert!(from_db(unsafe { FFloat::new(1e6f32) }).is_finite());
+    assert!(from_db_power(1e6f64).is_finite());
+    assert!((19.99..20.01).contains(&to_db_power(100.0f64)));
+    assert!((99.9..100.1).contains(&from_db_power(20.0f32)));
+
+    assert!(note_to_freq(unsafe { FFloat::new(1e9f32) }).is_finite());
+    assert!(freq_to_note(unsafe { FFloat::new(0.0f64) }).is_finite());
+    assert!((439.99..440.01).contains(&note_to_freq(69.0f64)));
+    assert!((1.99..2.01).contains(&semitones_to_ratio(12.0f32)));
+    assert!((-12.01..-11.99).contains(&ratio_to_semitones(0.5f32)));
+}
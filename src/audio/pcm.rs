@@ -0,0 +1,251 @@
+use crate::generic_float::FloatExt;
+use crate::policy::Policy;
+use crate::r#trait::{FastFloat, Wrapper};
+use crate::FFloat;
+
+/// A signed integer (pcm) sample format, with a full scale of `[-1, 1)`.
+pub trait Pcm: Copy {
+    /// `2^(bits - 1)`: the magnitude of the most negative sample, which is -1.
+    const SCALE: f64;
+    /// The sample, from `-SCALE` to `SCALE - 1`.
+    fn to_f64(self) -> f64;
+    /// The sample nearest to `x` (which is a integer), saturating at the ends of the range.
+    fn from_f64(x: f64) -> Self;
+}
+
+impl Pcm for i16 {
+    const SCALE: f64 = 32768.0;
+    fn to_f64(self) -> f64 {
+        f64::from(self)
+    }
+    #[allow(clippy::cast_possible_truncation)]
+    fn from_f64(x: f64) -> Self {
+        // `as` saturates.
+        x as i16
+    }
+}
+
+impl Pcm for i32 {
+    const SCALE: f64 = 2_147_483_648.0;
+    fn to_f64(self) -> f64 {
+        f64::from(self)
+    }
+    #[allow(clippy::cast_possible_truncation)]
+    fn from_f64(x: f64) -> Self {
+        // `as` saturates.
+        x as i32
+    }
+}
+
+/// A 24 bit sample, packed into 3 little endian bytes (as in wav files).
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Hash)]
+#[repr(transparent)]
+pub struct I24([u8; 3]);
+
+impl I24 {
+    /// The smallest sample, `-2^23`.
+    pub const MIN: Self = Self([0x00, 0x00, 0x80]);
+    /// The largest sample, `2^23 - 1`.
+    pub const MAX: Self = Self([0xff, 0xff, 0x7f]);
+
+    /// Creates a sample, if `x` fits in 24 bits.
+    #[must_use]
+    pub const fn new(x: i32) -> Option<Self> {
+        if x < -(1 << 23) || x >= 1 << 23 {
+            return None;
+        }
+        let [a, b, c, _] = x.to_le_bytes();
+        Some(Self([a, b, c]))
+    }
+
+    /// The sample, as a (sign extended) `i32`.
+    #[must_use]
+    pub const fn get(self) -> i32 {
+        let [a, b, c] = self.0;
+        i32::from_le_bytes([0, a, b, c]) >> 8
+    }
+
+    /// Reads a sample from its little endian bytes.
+    #[must_use]
+    pub const fn from_le_bytes(bytes: [u8; 3]) -> Self {
+        Self(bytes)
+    }
+
+    /// The little endian bytes of the sample.
+    #[must_use]
+    pub const fn to_le_bytes(self) -> [u8; 3] {
+        self.0
+    }
+}
+
+impl From<I24> for i32 {
+    fn from(x: I24) -> Self {
+        x.get()
+    }
+}
+
+impl Pcm for I24 {
+    const SCALE: f64 = 8_388_608.0;
+    fn to_f64(self) -> f64 {
+        f64::from(self.get())
+    }
+    #[allow(clippy::cast_possible_truncation)]
+    fn from_f64(x: f64) -> Self {
+        let x = x.clamp(-Self::SCALE, Self::SCALE - 1.0) as i32;
+        let [a, b, c, _] = x.to_le_bytes();
+        Self([a, b, c])
+    }
+}
+
+/// Converts pcm samples to [`FFloat`]s, dividing them by the full scale: the most negative sample is -1, and the largest is just below 1.
+///
+/// # Panics
+///
+/// If `input` and `output` have different lengths.
+/// ```
+/// # use umath::{FFloat, audio::{self, I24}};
+/// let mut output = [unsafe { FFloat::new(0.0f32) }; 3];
+/// audio::pcm_to_float(&[i16::MIN, 0, 16384], &mut output);
+/// assert_eq!(output, [-1.0, 0.0, 0.5]);
+/// audio::pcm_to_float(&[I24::MIN, I24::new(0).unwrap(), I24::new(1 << 22).unwrap()], &mut output);
+/// assert_eq!(output, [-1.0, 0.0, 0.5]);
+/// ```
+#[track_caller]
+pub fn pcm_to_float<S: Pcm, T: FastFloat + FloatExt, P: Policy>(
+    input: &[S],
+    output: &mut [FFloat<T, P>],
+) {
+    assert_eq!(input.len(), output.len(), "slice lengths differ");
+    let scale = 1.0 / S::SCALE;
+    for (y, x) in output.iter_mut().zip(input) {
+        // SAFETY: the samples are in [-1, 1).
+        *y = unsafe { FFloat::wrap(T::from_f64(x.to_f64() * scale)) };
+    }
+}
+
+/// Converts [`FFloat`]s to pcm samples, multiplying them by the full scale, and rounding them to the nearest sample.
+/// Values outside of the range (including 1) are clipped to the ends of it.
+///
+/// # Panics
+///
+/// If `input` and `output` have different lengths.
+/// ```
+/// # use umath::{FFloat, audio};
+/// let f = |x| unsafe { FFloat::new(x) };
+/// let mut output = [0i16; 4];
+/// audio::float_to_pcm(&[-1.0, 0.5, 1.0, 7.0].map(f), &mut output);
+/// assert_eq!(output, [i16::MIN, 16384, i16::MAX, i16::MAX]);
+/// ```
+#[track_caller]
+pub fn float_to_pcm<S: Pcm, T: FastFloat + Into<f64>, P: Policy>(
+    input: &[FFloat<T, P>],
+    output: &mut [S],
+) {
+    assert_eq!(input.len(), output.len(), "slice lengths differ");
+    for (y, x) in output.iter_mut().zip(input) {
+        let x: f64 = x.raw().into();
+        *y = S::from_f64(quantize::<S>(x * S::SCALE));
+    }
+}
+
+/// `x` rounded, and clamped to the range of `S`.
+fn quantize<S: Pcm>(x: f64) -> f64 {
+    x.round().clamp(-S::SCALE, S::SCALE - 1.0)
+}
+
+/// The state of a triangular (tpdf) dither: the sum of two uniform random values, of up to half a step each, added before rounding.
+///
+/// Dither turns the rounding error of [`float_to_pcm_dithered`] into a constant, uncorrelated, noise floor,
+/// instead of a distortion that follows the signal (which is audible on quiet, or fading, material).
+/// The random values are from a xorshift generator: this is not meant for anything but dither.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Dither {
+    state: u64,
+}
+
+impl Dither {
+    /// Seeds the generator.
+    #[must_use]
+    pub const fn new(seed: u64) -> Self {
+        // the state must not be 0.
+        Self { state: seed | 1 }
+    }
+
+    /// A uniform random value in `[-0.5, 0.5)`.
+    #[allow(clippy::cast_precision_loss)]
+    fn uniform(&mut self) -> f64 {
+        // xorshift64*
+        self.state ^= self.state >> 12;
+        self.state ^= self.state << 25;
+        self.state ^= self.state >> 27;
+        let bits = self.state.wrapping_mul(0x2545_f491_4f6c_dd1d) >> 11;
+        bits as f64 / (1u64 << 53) as f64 - 0.5
+    }
+
+    /// A triangular random value in `(-1, 1)`.
+    fn triangular(&mut self) -> f64 {
+        self.uniform() + self.uniform()
+    }
+}
+
+impl Default for Dither {
+    fn default() -> Self {
+        Self::new(0x853c_49e6_748f_ea9b)
+    }
+}
+
+/// [`float_to_pcm`], with triangular `dither` added before rounding.
+///
+/// # Panics
+///
+/// If `input` and `output` have different lengths.
+#[track_caller]
+pub fn float_to_pcm_dithered<S: Pcm, T: FastFloat + Into<f64>, P: Policy>(
+    input: &[FFloat<T, P>],
+    output: &mut [S],
+    dither: &mut Dither,
+) {
+    assert_eq!(input.len(), output.len(), "slice lengths differ");
+    for (y, x) in output.iter_mut().zip(input) {
+        let x: f64 = x.raw().into();
+        *y = S::from_f64(quantize::<S>(x * S::SCALE + dither.triangular()));
+    }
+}
+
+#[test]
+fn converted() {
+    let f = |x| unsafe { FFloat::<f64>::new(x) };
+    assert_eq!(I24::new(-(1 << 23)), Some(I24::MIN));
+    assert_eq!(I24::new((1 << 23) - 1), Some(I24::MAX));
+    assert_eq!(I24::new(1 << 23), None);
+    for x in [-(1 << 23), -5, -1, 0, 1, 1234, (1 << 23) - 1] {
+        assert_eq!(I24::new(x).unwrap().get(), x);
+    }
+    // round trips are exact
+    let samples: Vec<i32> = (-50..50)
+        .map(|i| i * 40_000_000)
+        .chain([i32::MIN, i32::MAX])
+        .collect();
+    let mut floats = vec![f(0.0); samples.len()];
+    pcm_to_float(&samples, &mut floats);
+    assert!(floats.iter().all(|x| (-1.0..1.0).contains(&**x)));
+    let mut back = vec![0i32; samples.len()];
+    float_to_pcm(&floats, &mut back);
+    assert_eq!(back, samples);
+    // clipped, in every format
+    let loud = [f(-3.0), f(1.0), f(2.0)];
+    let mut narrow = [I24::default(); 3];
+    float_to_pcm(&loud, &mut narrow);
+    assert_eq!(narrow, [I24::MIN, I24::MAX, I24::MAX]);
+    let mut wide = [0i32; 3];
+    float_to_pcm_dithered(&loud, &mut wide, &mut Dither::default());
+    assert_eq!(wide, [i32::MIN, i32::MAX, i32::MAX]);
+    // dither is at most a step, and averages out
+    let mut dither = Dither::new(7);
+    let quiet = vec![f(100.25 / 32768.0); 10_000];
+    let mut dithered = vec![0i16; quiet.len()];
+    float_to_pcm_dithered(&quiet, &mut dithered, &mut dither);
+    assert!(dithered.iter().all(|&x| (99..=102).contains(&x)));
+    let mean = dithered.iter().map(|&x| f64::from(x)).sum::<f64>() / 10_000.0;
+    assert!((mean - 100.25).abs() < 0.02, "{mean}");
+}
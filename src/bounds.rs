@@ -0,0 +1,252 @@
+//! bounding volumes: axis aligned boxes ([`Aabb`]), and [spheres](Sphere), with the queries of a collision broad phase.
+//!
+//! Their extents are [vectors](crate::vector), so with [`FFloat`](crate::FFloat)s they are [`Ord`]: boxes can be sorted by a side directly, such as for sweep and prune.
+//! ```
+//! # use umath::{FFloat, bounds::{Aabb, Sphere}, vector::Vec3};
+//! let f = |x| unsafe { FFloat::new(x) };
+//! let v = |x, y, z| Vec3::new(f(x), f(y), f(z));
+//! let crate_ = Aabb::new(v(0.0f32, 0.0, 0.0), v(1.0, 1.0, 1.0));
+//! let ball = Sphere::new(v(2.0, 0.5, 0.5), f(1.5));
+//! assert!(ball.overlaps_aabb(crate_));
+//! assert_eq!(crate_.closest_point(v(2.0, 0.5, 0.5)), v(1.0, 0.5, 0.5));
+//! let mut boxes = vec![Aabb::new(v(3.0, 0.0, 0.0), v(4.0, 1.0, 1.0)), crate_];
+//! boxes.sort_by_key(|b| b.min.x);
+//! assert_eq!(boxes[0], crate_);
+//! ```
+use crate::generic_float::{lit, FloatAlone};
+use crate::vector::Vec3;
+
+/// Whether every component of `a` is at most the same component of `b`.
+fn all_le<F: FloatAlone>(a: Vec3<F>, b: Vec3<F>) -> bool {
+    a.x <= b.x && a.y <= b.y && a.z <= b.z
+}
+
+/// A axis aligned bounding box: the points from `min` to `max`, in every dimension. Refer to the [module level documentation](self).
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Aabb<F> {
+    /// The corner with the smallest components.
+    pub min: Vec3<F>,
+    /// The corner with the largest components.
+    pub max: Vec3<F>,
+}
+
+impl<F: FloatAlone> Aabb<F> {
+    /// Creates a box, from its corners.
+    ///
+    /// # Panics
+    ///
+    /// If any component of `min` is more than that of `max`.
+    #[track_caller]
+    pub fn new(min: Vec3<F>, max: Vec3<F>) -> Self {
+        assert!(all_le(min, max), "min must not be more than max");
+        Self { min, max }
+    }
+
+    /// The smallest box around `points`, or [`None`], if there are none.
+    pub fn from_points(points: impl IntoIterator<Item = Vec3<F>>) -> Option<Self> {
+        let mut points = points.into_iter();
+        let first = points.next()?;
+        Some(points.fold(Self::new(first, first), Self::including))
+    }
+
+    /// The center.
+    pub fn center(self) -> Vec3<F> {
+        (self.min + self.max) * lit::<F>(0.5)
+    }
+
+    /// The size along each axis, `max - min`.
+    pub fn size(self) -> Vec3<F> {
+        self.max - self.min
+    }
+
+    /// The surface area (the cost of a box, to a surface area heuristic).
+    pub fn surface_area(self) -> F {
+        let Vec3 { x, y, z } = self.size();
+        (x * y + y * z + z * x) * lit::<F>(2.0)
+    }
+
+    /// The smallest box around both boxes.
+    pub fn union(self, other: Self) -> Self {
+        Self {
+            min: self.min.min(other.min),
+            max: self.max.max(other.max),
+        }
+    }
+
+    /// The smallest box around the box, and `point`.
+    pub fn including(self, point: Vec3<F>) -> Self {
+        Self {
+            min: self.min.min(point),
+            max: self.max.max(point),
+        }
+    }
+
+    /// The box where both boxes overlap, or [`None`], if they do not.
+    pub fn intersection(self, other: Self) -> Option<Self> {
+        let (min, max) = (self.min.max(other.min), self.max.min(other.max));
+        all_le(min, max).then_some(Self { min, max })
+    }
+
+    /// Whether `point` is in the box (or on its surface).
+    pub fn contains_point(self, point: Vec3<F>) -> bool {
+        all_le(self.min, point) && all_le(point, self.max)
+    }
+
+    /// Whether `other` is entirely in the box.
+    pub fn contains(self, other: Self) -> bool {
+        all_le(self.min, other.min) && all_le(other.max, self.max)
+    }
+
+    /// Whether the boxes overlap (or touch).
+    pub fn overlaps(self, other: Self) -> bool {
+        all_le(self.min, other.max) && all_le(other.min, self.max)
+    }
+
+    /// The point of the box closest to `point` (which is `point` itself, if it is in the box).
+    pub fn closest_point(self, point: Vec3<F>) -> Vec3<F> {
+        point.max(self.min).min(self.max)
+    }
+
+    /// The squared distance from the box to `point` (0, if it is in the box).
+    pub fn distance_squared(self, point: Vec3<F>) -> F {
+        (self.closest_point(point) - point).length_squared()
+    }
+}
+
+/// A sphere (or ball): the points at most `radius` from `center`. Refer to the [module level documentation](self).
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Sphere<F> {
+    /// The center.
+    pub center: Vec3<F>,
+    /// The radius.
+    pub radius: F,
+}
+
+impl<F: FloatAlone> Sphere<F> {
+    /// Creates a sphere.
+    ///
+    /// # Panics
+    ///
+    /// If `radius` is negative.
+    #[track_caller]
+    pub fn new(center: Vec3<F>, radius: F) -> Self {
+        // SAFETY: 0 is a valid float
+        assert!(
+            radius >= unsafe { F::zero() },
+            "the radius must not be negative"
+        );
+        Self { center, radius }
+    }
+
+    /// The smallest sphere around `aabb`: centered on it, through its corners.
+    pub fn around(aabb: Aabb<F>) -> Self {
+        Self {
+            center: aabb.center(),
+            radius: aabb.size().length() * lit::<F>(0.5),
+        }
+    }
+
+    /// The smallest box around the sphere.
+    pub fn bounding_box(self) -> Aabb<F> {
+        let extent = Vec3::splat(self.radius);
+        Aabb {
+            min: self.center - extent,
+            max: self.center + extent,
+        }
+    }
+
+    /// The smallest sphere around both spheres.
+    pub fn union(self, other: Self) -> Self {
+        let offset = other.center - self.center;
+        let distance = offset.length();
+        if distance + other.radius <= self.radius {
+            return self;
+        }
+        if distance + self.radius <= other.radius {
+            return other;
+        }
+        // the diameter reaches from the far side of one, to the far side of the other (the distance is not 0, as neither contains the other).
+        let radius = (distance + self.radius + other.radius) * lit::<F>(0.5);
+        Self {
+            center: self.center + offset * ((radius - self.radius) / distance),
+            radius,
+        }
+    }
+
+    /// Whether `point` is in the sphere (or on its surface).
+    pub fn contains_point(self, point: Vec3<F>) -> bool {
+        (point - self.center).length_squared() <= self.radius * self.radius
+    }
+
+    /// Whether `other` is entirely in the sphere.
+    pub fn contains(self, other: Self) -> bool {
+        other.radius <= self.radius
+            && (other.center - self.center).length() + other.radius <= self.radius
+    }
+
+    /// Whether the spheres overlap (or touch).
+    pub fn overlaps(self, other: Self) -> bool {
+        let reach = self.radius + other.radius;
+        (other.center - self.center).length_squared() <= reach * reach
+    }
+
+    /// Whether the sphere overlaps (or touches) `aabb`: whether the point of the box closest to the center is in the sphere.
+    pub fn overlaps_aabb(self, aabb: Aabb<F>) -> bool {
+        aabb.distance_squared(self.center) <= self.radius * self.radius
+    }
+
+    /// The point of the sphere closest to `point` (which is `point` itself, if it is in the sphere).
+    pub fn closest_point(self, point: Vec3<F>) -> Vec3<F> {
+        let offset = point - self.center;
+        let distance = offset.length();
+        if distance <= self.radius {
+            return point;
+        }
+        self.center + offset * (self.radius / distance)
+    }
+}
+
+#[test]
+#[allow(clippy::float_cmp)]
+fn bounded() {
+    let v = |x, y, z| Vec3::new(x, y, z);
+    let unit = Aabb::new(v(0.0, 0.0, 0.0), v(1.0, 1.0, 1.0));
+    let shifted = Aabb::new(v(0.5, 0.5, -1.0), v(2.0, 1.5, 0.5));
+    assert!(unit.overlaps(shifted) && shifted.overlaps(unit));
+    assert_eq!(
+        unit.intersection(shifted),
+        Some(Aabb::new(v(0.5, 0.5, 0.0), v(1.0, 1.0, 0.5)))
+    );
+    let union = unit.union(shifted);
+    assert_eq!(union, Aabb::new(v(0.0, 0.0, -1.0), v(2.0, 1.5, 1.0)));
+    assert!(union.contains(unit) && union.contains(shifted) && !unit.contains(union));
+    let far = Aabb::new(v(3.0, 0.0, 0.0), v(4.0, 1.0, 1.0));
+    assert!(!unit.overlaps(far) && unit.intersection(far).is_none());
+    assert_eq!(
+        Aabb::from_points([v(1.0, -2.0, 0.0), v(-1.0, 3.0, 0.5), v(0.0, 0.0, -4.0)]),
+        Some(Aabb::new(v(-1.0, -2.0, -4.0), v(1.0, 3.0, 0.5)))
+    );
+    assert_eq!(Aabb::<f64>::from_points([]), None);
+    assert_eq!(unit.surface_area(), 6.0);
+    assert_eq!(unit.center(), v(0.5, 0.5, 0.5));
+    assert_eq!(unit.closest_point(v(0.25, 0.75, 0.5)), v(0.25, 0.75, 0.5));
+    assert_eq!(unit.distance_squared(v(3.0, 2.0, 0.5)), 5.0);
+    // spheres
+    let ball = Sphere::new(v(0.0, 0.0, 0.0), 1.0);
+    let other = Sphere::new(v(3.0, 0.0, 0.0), 1.0);
+    assert!(!ball.overlaps(other) && ball.overlaps(Sphere::new(v(2.0, 0.0, 0.0), 1.0)));
+    let both = ball.union(other);
+    assert_eq!(both, Sphere::new(v(1.5, 0.0, 0.0), 2.5));
+    assert!(both.contains(ball) && both.contains(other) && !ball.contains(both));
+    assert_eq!(both.union(ball), both);
+    assert_eq!(ball.closest_point(v(0.0, 4.0, 0.0)), v(0.0, 1.0, 0.0));
+    assert!(ball.contains_point(v(0.6, 0.8, 0.0)) && !ball.contains_point(v(0.8, 0.8, 0.0)));
+    assert!(ball.overlaps_aabb(Aabb::new(v(0.6, 0.6, 0.0), v(2.0, 2.0, 2.0))));
+    assert!(!ball.overlaps_aabb(Aabb::new(v(0.75, 0.75, 0.0), v(2.0, 2.0, 2.0))));
+    assert_eq!(
+        ball.bounding_box(),
+        Aabb::new(v(-1.0, -1.0, -1.0), v(1.0, 1.0, 1.0))
+    );
+    let around = Sphere::around(unit);
+    assert!((around.radius - 0.75f64.sqrt()).abs() < 1e-12 && around.center == unit.center());
+}
@@ -0,0 +1,97 @@
+use crate::policy::{Fast, Policy};
+use crate::r#trait::{FastFloat, Wrapper};
+use crate::FFloat;
+use core::cell::Cell;
+use core::marker::PhantomData;
+
+/// A [`Cell`] holding a [`FFloat`], for shared (single threaded) mutable float state.
+///
+/// Unlike a <code>[Cell]<[f32]></code>, there is no way to write a raw float into this, and every write is checked.
+/// ```
+/// # use umath::{FCell, FFloat};
+/// let scrub = FCell::new(unsafe { FFloat::new(0.5f32) });
+/// let r = &scrub;
+/// r.update(|x| x * 2.0);
+/// assert_eq!(*scrub.get(), 1.0);
+/// ```
+#[repr(transparent)]
+pub struct FCell<T, P = Fast>(Cell<T>, PhantomData<P>);
+
+impl<T: FastFloat, P: Policy> FCell<T, P> {
+    /// Creates a new [`FCell`] containing the given value.
+    pub const fn new(value: FFloat<T, P>) -> Self {
+        Self(Cell::new(value.0), PhantomData)
+    }
+
+    /// Returns a copy of the value.
+    pub fn get(&self) -> FFloat<T, P> {
+        // SAFETY: every write is checked.
+        unsafe { FFloat::wrap(self.0.get()) }
+    }
+
+    /// Sets the value, checking it.
+    pub fn set(&self, value: FFloat<T, P>) {
+        value.check();
+        self.0.set(value.0);
+    }
+
+    /// Replaces the value (checking it), returning the old value.
+    pub fn replace(&self, value: FFloat<T, P>) -> FFloat<T, P> {
+        value.check();
+        // SAFETY: every write is checked.
+        unsafe { FFloat::wrap(self.0.replace(value.0)) }
+    }
+
+    /// Updates the value with `f`, checking the result, and returns the new value.
+    pub fn update(&self, f: impl FnOnce(FFloat<T, P>) -> FFloat<T, P>) -> FFloat<T, P> {
+        let new = f(self.get());
+        self.set(new);
+        new
+    }
+
+    /// Swaps the values of two cells.
+    pub fn swap(&self, other: &Self) {
+        self.0.swap(&other.0);
+    }
+
+    /// Unwraps the value.
+    pub fn into_inner(self) -> FFloat<T, P> {
+        // SAFETY: every write is checked.
+        unsafe { FFloat::wrap(self.0.into_inner()) }
+    }
+
+    /// Returns a mutable reference to the value. This is safe, as the borrow checker prevents any other access to the cell.
+    pub fn get_mut(&mut self) -> &mut FFloat<T, P> {
+        // SAFETY: FFloat is repr(transparent).
+        unsafe { &mut *(self.0.as_ptr().cast::<FFloat<T, P>>()) }
+    }
+}
+
+impl<T: FastFloat, P: Policy> Clone for FCell<T, P> {
+    fn clone(&self) -> Self {
+        Self::new(self.get())
+    }
+}
+
+impl<T: FastFloat, P: Policy> From<FFloat<T, P>> for FCell<T, P> {
+    fn from(value: FFloat<T, P>) -> Self {
+        Self::new(value)
+    }
+}
+
+impl<T: FastFloat, P: Policy> core::fmt::Debug for FCell<T, P> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_tuple("FCell").field(&self.get()).finish()
+    }
+}
+
+#[test]
+#[allow(clippy::float_cmp)]
+fn swap() {
+    let a = FCell::new(unsafe { FFloat::new(1.0f64) });
+    let mut b = FCell::new(unsafe { FFloat::new(2.0f64) });
+    a.swap(&b);
+    *b.get_mut() += 1.0;
+    assert_eq!(*a.replace(b.get()), 2.0);
+    assert_eq!(*a.into_inner(), 2.0);
+}
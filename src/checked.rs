@@ -0,0 +1,49 @@
+use crate::policy::{Ieee, Policy};
+use crate::r#trait::{FastFloat, Wrapper};
+use crate::FFloat;
+#[cfg(doc)]
+use std::f32::{INFINITY as INF, NAN};
+
+macro_rules! checked {
+    ($($name:ident => $op:ident, $verb:literal);+ $(;)?) => {
+        /// Checked arithmetic.
+        ///
+        /// These do not use fast math: they compute with ordinary IEEE operations, and return [`None`] if the result would be [`NAN`] | [`INF`].
+        /// They are the safe escape hatch, for when the inputs may push the result out of range.
+        /// ```
+        /// # use umath::FFloat;
+        /// let one = unsafe { FFloat::new(1.0f32) };
+        /// let tiny = unsafe { FFloat::new(1e-40f32) };
+        /// assert_eq!(one.try_div(tiny), None);
+        /// assert_eq!(one.try_div(one), Some(one));
+        /// ```
+        impl<T: FastFloat, P: Policy> FFloat<T, P> {
+            $(
+                #[doc = concat!("Checked ", $verb, ". Returns [`None`] if the result would be [`NAN`] | [`INF`].")]
+                pub fn $name(self, rhs: Self) -> Option<Self> {
+                    // SAFETY: IEEE operations are always safe.
+                    let x = unsafe { T::$op::<Ieee>(self.raw(), rhs.raw()) };
+                    // SAFETY: a finite value satisfies every policy.
+                    (!x.bad::<Ieee>()).then(|| unsafe { Self::wrap(x) })
+                }
+            )+
+        }
+    };
+}
+
+checked! {
+    try_add => add, "addition";
+    try_sub => sub, "subtraction";
+    try_mul => mul, "multiplication";
+    try_div => div, "division";
+    try_rem => rem, "remainder";
+}
+
+#[test]
+fn overflow() {
+    let max = unsafe { FFloat::new(f64::MAX) };
+    assert_eq!(max.try_add(max), None);
+    assert_eq!(max.try_sub(max).map(|x| *x), Some(0.0));
+    assert_eq!(max.try_mul(unsafe { FFloat::new(-2.0) }), None);
+    assert_eq!(max.try_rem(unsafe { FFloat::new(0.0) }), None);
+}
@@ -0,0 +1,166 @@
+//! complex numbers.
+//!
+//! [`Complex`] is generic over [`FloatAlone`], so a <code>[Complex]<[FFloat]></code> computes everything with the fast intrinsics.
+//! ```
+//! # use umath::{FFloat, complex::Complex};
+//! let f = |x| unsafe { FFloat::new(x) };
+//! let i = Complex::new(f(0.0f64), f(1.0));
+//! assert_eq!(i * i, Complex::new(f(-1.0), f(0.0)));
+//! let z = Complex::from_polar(f(2.0), f(0.5));
+//! assert!((*z.norm() - 2.0).abs() < 1e-12);
+//! assert!((*z.arg() - 0.5).abs() < 1e-12);
+//! ```
+use crate::generic_float::FloatAlone;
+use core::ops::{Add, AddAssign, Div, DivAssign, Mul, MulAssign, Neg, Sub, SubAssign};
+
+/// A complex number, `re + im·i`. Refer to the [module level documentation](self).
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct Complex<F> {
+    /// The real part.
+    pub re: F,
+    /// The imaginary part.
+    pub im: F,
+}
+
+impl<F: FloatAlone> Complex<F> {
+    /// Creates a new [`Complex`] number.
+    pub const fn new(re: F, im: F) -> Self {
+        Self { re, im }
+    }
+
+    /// Creates a [`Complex`] number from its polar form, `r·e^(θi)`.
+    pub fn from_polar(r: F, θ: F) -> Self {
+        Self::new(r * θ.cos(), r * θ.sin())
+    }
+
+    /// Converts to polar form, `(norm, arg)`.
+    pub fn to_polar(self) -> (F, F) {
+        (self.norm(), self.arg())
+    }
+
+    /// The complex conjugate, `re - im·i`.
+    pub fn conj(self) -> Self {
+        Self::new(self.re, -self.im)
+    }
+
+    /// The squared magnitude, `re² + im²`. This is cheaper than [`Complex::norm`].
+    pub fn norm_sqr(self) -> F {
+        self.re * self.re + self.im * self.im
+    }
+
+    /// The magnitude, `|self|`.
+    pub fn norm(self) -> F {
+        self.re.hypot(self.im)
+    }
+
+    /// The angle, in radians, in `-π..=π`.
+    pub fn arg(self) -> F {
+        self.im.atan2(self.re)
+    }
+
+    /// Multiplies by `i`, (rotating by 90°). This is cheaper than a full multiplication.
+    pub fn mul_i(self) -> Self {
+        Self::new(-self.im, self.re)
+    }
+
+    /// `e^self`.
+    pub fn exp(self) -> Self {
+        Self::from_polar(self.re.exp(), self.im)
+    }
+
+    /// The (principal) natural logarithm. `self` must not be zero.
+    pub fn ln(self) -> Self {
+        Self::new(self.norm().ln(), self.arg())
+    }
+}
+
+impl<F: FloatAlone> From<F> for Complex<F> {
+    fn from(re: F) -> Self {
+        Self::new(re, unsafe { F::zero() })
+    }
+}
+
+impl<F: FloatAlone> Add for Complex<F> {
+    type Output = Self;
+    fn add(self, rhs: Self) -> Self {
+        Self::new(self.re + rhs.re, self.im + rhs.im)
+    }
+}
+
+impl<F: FloatAlone> Sub for Complex<F> {
+    type Output = Self;
+    fn sub(self, rhs: Self) -> Self {
+        Self::new(self.re - rhs.re, self.im - rhs.im)
+    }
+}
+
+impl<F: FloatAlone> Mul for Complex<F> {
+    type Output = Self;
+    fn mul(self, rhs: Self) -> Self {
+        Self::new(
+            self.re * rhs.re - self.im * rhs.im,
+            self.re * rhs.im + self.im * rhs.re,
+        )
+    }
+}
+
+impl<F: FloatAlone> Div for Complex<F> {
+    type Output = Self;
+    fn div(self, rhs: Self) -> Self {
+        let d = rhs.norm_sqr();
+        let n = self * rhs.conj();
+        Self::new(n.re / d, n.im / d)
+    }
+}
+
+impl<F: FloatAlone> Mul<F> for Complex<F> {
+    type Output = Self;
+    fn mul(self, rhs: F) -> Self {
+        Self::new(self.re * rhs, self.im * rhs)
+    }
+}
+
+impl<F: FloatAlone> Div<F> for Complex<F> {
+    type Output = Self;
+    fn div(self, rhs: F) -> Self {
+        Self::new(self.re / rhs, self.im / rhs)
+    }
+}
+
+impl<F: FloatAlone> Neg for Complex<F> {
+    type Output = Self;
+    fn neg(self) -> Self {
+        Self::new(-self.re, -self.im)
+    }
+}
+
+macro_rules! assign {
+    ($($trait:ident, $fn:ident => $op:ident, $rhs:ty);+ $(;)?) => {
+        $(
+            impl<F: FloatAlone> $trait<$rhs> for Complex<F> {
+                fn $fn(&mut self, rhs: $rhs) {
+                    *self = (*self).$op(rhs);
+                }
+            }
+        )+
+    };
+}
+
+assign! {
+    AddAssign, add_assign => add, Self;
+    SubAssign, sub_assign => sub, Self;
+    MulAssign, mul_assign => mul, Self;
+    DivAssign, div_assign => div, Self;
+    MulAssign, mul_assign => mul, F;
+    DivAssign, div_assign => div, F;
+}
+
+#[test]
+fn euler() {
+    let π = core::f64::consts::PI;
+    let z = Complex::new(0.0, π).exp() + Complex::from(1.0);
+    assert!(z.norm() < 1e-15);
+    let w = Complex::new(3.0, 4.0);
+    assert_eq!(w / w, Complex::from(1.0));
+    assert!((w.ln().exp() - w).norm() < 1e-12);
+}
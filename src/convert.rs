@@ -0,0 +1,252 @@
+//! conversions between the float wrappers.
+//!
+//! Demoting (to a type with a weaker invariant, or no contract) is safe, and done with [`From`] | [`TryFrom`].
+//! Promoting to [`FFloat`] or [`NotNan`] is `unsafe`, as their operations are (and hence require the promise that you will not break the invariant),
+//! but does not need to check the value again.
+//!
+//! With the `ordered-float` feature, the `ordered_float` wrappers convert too: [`Finite`] | [`NotNan`] to `ordered_float::NotNan`,
+//! any float to (and from) `OrderedFloat` | [`AFloat`], and either `ordered_float` wrapper can be checked into a [`Finite`].
+use crate::generic_float::Constructors;
+use crate::policy::{Flags, Policy};
+use crate::r#trait::{FastFloat, Wrapper};
+use crate::{AFloat, FFloat, Finite, NonNegative, NotNan, UnitFloat};
+#[cfg(doc)]
+use std::f32::{INFINITY as INF, NAN};
+
+/// Error for conversions to [`Finite`], for when the float was [`NAN`] | [`INF`].
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct NotFinite;
+
+impl core::fmt::Display for NotFinite {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.write_str("float is NAN | INF")
+    }
+}
+
+impl std::error::Error for NotFinite {}
+
+macro_rules! try_from {
+    ($for:ty) => {
+        impl TryFrom<$for> for Finite<$for> {
+            type Error = NotFinite;
+            fn try_from(value: $for) -> Result<Self, Self::Error> {
+                Self::new(value).ok_or(NotFinite)
+            }
+        }
+    };
+}
+try_from!(f32);
+try_from!(f64);
+
+impl<T: FastFloat> TryFrom<NotNan<T>> for Finite<T> {
+    type Error = NotFinite;
+    fn try_from(value: NotNan<T>) -> Result<Self, Self::Error> {
+        Self::new(value.raw()).ok_or(NotFinite)
+    }
+}
+
+impl<T: FastFloat> TryFrom<AFloat<T>> for Finite<T> {
+    type Error = NotFinite;
+    fn try_from(value: AFloat<T>) -> Result<Self, Self::Error> {
+        Self::new(value.raw()).ok_or(NotFinite)
+    }
+}
+
+impl<
+        T: FastFloat,
+        const NSZ: bool,
+        const ARCP: bool,
+        const CONTRACT: bool,
+        const REASSOC: bool,
+    > From<FFloat<T, Flags<true, true, NSZ, ARCP, CONTRACT, REASSOC>>> for Finite<T>
+{
+    /// Demotes a [`FFloat`] that forbids [`NAN`] | [`INF`]. This does not need to check the value.
+    fn from(value: FFloat<T, Flags<true, true, NSZ, ARCP, CONTRACT, REASSOC>>) -> Self {
+        // the policy forbids NAN | INF.
+        Self::unchecked(value.raw())
+    }
+}
+
+impl<T: FastFloat, P: Policy> From<FFloat<T, P>> for AFloat<T> {
+    fn from(value: FFloat<T, P>) -> Self {
+        Self::new(value.raw())
+    }
+}
+
+impl<T: FastFloat> From<NotNan<T>> for AFloat<T> {
+    fn from(value: NotNan<T>) -> Self {
+        Self::new(value.raw())
+    }
+}
+
+impl<T: FastFloat> From<Finite<T>> for AFloat<T> {
+    fn from(value: Finite<T>) -> Self {
+        Self::new(value.raw())
+    }
+}
+
+impl<T: FastFloat + Constructors> From<UnitFloat<T>> for Finite<T> {
+    fn from(value: UnitFloat<T>) -> Self {
+        // 0..=1 is finite.
+        Self::unchecked(value.raw())
+    }
+}
+
+impl<T: FastFloat + Constructors> From<UnitFloat<T>> for AFloat<T> {
+    fn from(value: UnitFloat<T>) -> Self {
+        Self::new(value.raw())
+    }
+}
+
+impl<T: FastFloat + Constructors> From<NonNegative<T>> for Finite<T> {
+    fn from(value: NonNegative<T>) -> Self {
+        // a non negative number is finite.
+        Self::unchecked(value.raw())
+    }
+}
+
+impl<T: FastFloat + Constructors> From<NonNegative<T>> for AFloat<T> {
+    fn from(value: NonNegative<T>) -> Self {
+        Self::new(value.raw())
+    }
+}
+
+impl<T: FastFloat + Constructors> From<UnitFloat<T>> for NonNegative<T> {
+    fn from(value: UnitFloat<T>) -> Self {
+        // SAFETY: 0..=1 is not negative.
+        unsafe { Self::wrap(value.raw()) }
+    }
+}
+
+impl<T: FastFloat + Constructors> NonNegative<T> {
+    /// Promotes this [`NonNegative`] to a [`FFloat`], without checking it again.
+    #[doc = include_str!("ffloat_safety_noconstr.md")]
+    pub unsafe fn into_ffloat<P: Policy>(self) -> FFloat<T, P> {
+        FFloat::with_policy(self.raw())
+    }
+}
+
+impl<T: FastFloat + Constructors> UnitFloat<T> {
+    /// Promotes this [`UnitFloat`] to a [`FFloat`], without checking it again.
+    #[doc = include_str!("ffloat_safety_noconstr.md")]
+    pub unsafe fn into_ffloat<P: Policy>(self) -> FFloat<T, P> {
+        FFloat::with_policy(self.raw())
+    }
+}
+
+impl<T: FastFloat> Finite<T> {
+    /// Promotes this [`Finite`] to a [`FFloat`], without checking it again.
+    #[doc = include_str!("ffloat_safety_noconstr.md")]
+    pub unsafe fn into_ffloat<P: Policy>(self) -> FFloat<T, P> {
+        FFloat::with_policy(self.raw())
+    }
+
+    /// Promotes this [`Finite`] to a [`NotNan`], without checking it again.
+    ///
+    /// # Safety
+    ///
+    /// Refer to [`NotNan::new`].
+    pub unsafe fn into_not_nan(self) -> NotNan<T> {
+        NotNan::new(self.raw())
+    }
+}
+
+impl<T: FastFloat> NotNan<T> {
+    /// Demotes this [`NotNan`] to a [`Finite`], if it is not [`INF`].
+    pub fn to_finite(self) -> Option<Finite<T>> {
+        Finite::try_from(self).ok()
+    }
+}
+
+// a Finite | NotNan is a ordered_float::NotNan, any float is a OrderedFloat | AFloat, and either can be checked into a Finite.
+#[cfg(feature = "ordered-float")]
+macro_rules! ordered {
+    ($for:ty) => {
+        impl From<Finite<$for>> for ordered_float::NotNan<$for> {
+            fn from(value: Finite<$for>) -> Self {
+                // SAFETY: a finite number is not NAN.
+                unsafe { Self::new_unchecked(value.raw()) }
+            }
+        }
+
+        impl From<NotNan<$for>> for ordered_float::NotNan<$for> {
+            fn from(value: NotNan<$for>) -> Self {
+                // SAFETY: a NotNan is not NAN.
+                unsafe { Self::new_unchecked(value.raw()) }
+            }
+        }
+
+        impl<P: Policy> From<FFloat<$for, P>> for ordered_float::OrderedFloat<$for> {
+            fn from(value: FFloat<$for, P>) -> Self {
+                Self(value.raw())
+            }
+        }
+
+        impl From<AFloat<$for>> for ordered_float::OrderedFloat<$for> {
+            fn from(value: AFloat<$for>) -> Self {
+                Self(value.raw())
+            }
+        }
+
+        impl From<ordered_float::NotNan<$for>> for AFloat<$for> {
+            fn from(value: ordered_float::NotNan<$for>) -> Self {
+                Self::new(value.into_inner())
+            }
+        }
+
+        impl From<ordered_float::OrderedFloat<$for>> for AFloat<$for> {
+            fn from(value: ordered_float::OrderedFloat<$for>) -> Self {
+                Self::new(value.0)
+            }
+        }
+
+        impl TryFrom<ordered_float::NotNan<$for>> for Finite<$for> {
+            type Error = NotFinite;
+            fn try_from(value: ordered_float::NotNan<$for>) -> Result<Self, Self::Error> {
+                Self::new(value.into_inner()).ok_or(NotFinite)
+            }
+        }
+
+        impl TryFrom<ordered_float::OrderedFloat<$for>> for Finite<$for> {
+            type Error = NotFinite;
+            fn try_from(value: ordered_float::OrderedFloat<$for>) -> Result<Self, Self::Error> {
+                Self::new(value.0).ok_or(NotFinite)
+            }
+        }
+    };
+}
+#[cfg(feature = "ordered-float")]
+ordered!(f32);
+#[cfg(feature = "ordered-float")]
+ordered!(f64);
+
+#[test]
+fn roundtrip() {
+    let f = Finite::must(2.0f32);
+    let fast: crate::FF32 = unsafe { f.into_ffloat() } * 4.0;
+    assert_eq!(Finite::from(fast), Finite::must(8.0));
+    assert_eq!(Finite::try_from(f32::NAN), Err(NotFinite));
+    let inf = unsafe { NotNan::new(f32::INFINITY) };
+    assert_eq!(inf.to_finite(), None);
+    assert!(AFloat::from(inf).is_infinite());
+    let half = UnitFloat::new(0.5f32).unwrap();
+    assert_eq!(Finite::from(half), Finite::must(0.5));
+}
+
+#[test]
+#[cfg(feature = "ordered-float")]
+#[allow(clippy::float_cmp)]
+fn ordered_float() {
+    use ordered_float::{NotNan as Ordered, OrderedFloat};
+    let f = Finite::must(2.5f64);
+    let ordered = Ordered::from(f);
+    assert_eq!(ordered.into_inner(), 2.5);
+    assert_eq!(Finite::try_from(ordered), Ok(f));
+    assert_eq!(
+        Finite::try_from(OrderedFloat(f64::INFINITY)),
+        Err(NotFinite)
+    );
+    let fast = unsafe { crate::FF32::new(3.0) };
+    assert_eq!(OrderedFloat::from(fast), OrderedFloat(3.0));
+    assert!(AFloat::from(OrderedFloat(f32::NAN)).is_nan());
+}
@@ -0,0 +1,201 @@
+//! convolution and correlation of [`FFloat`] slices.
+//!
+//! Short kernels are convolved directly, with a [`dot`](slice::dot) product per output (so the inner loop is the vectorized kernel);
+//! kernels longer than [`DIRECT_LIMIT`] are convolved by multiplying spectra, with a [`RealFft`] of the zero padded signal and kernel, which takes `O(n log n)` instead of `O(n k)`.
+//! Both give the same result (up to rounding), so the choice is only a matter of speed.
+//!
+//! The [`Mode`] picks which part of the full convolution is returned, as in numpy | scipy.
+//! ```
+//! # use umath::{FFloat, convolution::{self, Mode}};
+//! let f = |x| unsafe { FFloat::new(x) };
+//! let signal = [1.0, 2.0, 3.0].map(f);
+//! let kernel = [0.0, 1.0, 0.5].map(f);
+//! let full = convolution::convolve(&signal, &kernel, Mode::Full);
+//! assert_eq!(full, [0.0, 1.0, 2.5, 4.0, 1.5]);
+//! assert_eq!(convolution::convolve(&signal, &kernel, Mode::Same), [1.0, 2.5, 4.0]);
+//! assert_eq!(convolution::convolve(&signal, &kernel, Mode::Valid), [2.5]);
+//! assert_eq!(convolution::correlate(&signal, &kernel, Mode::Full), [0.5, 2.0, 3.5, 3.0, 0.0]);
+//! ```
+use crate::complex::Complex;
+use crate::fft::RealFft;
+use crate::generic_float::{Constructors, FloatAlone};
+use crate::policy::Policy;
+use crate::r#trait::FastFloat;
+use crate::{slice, FFloat};
+
+/// The longest kernel that is convolved directly; longer kernels go through the fft.
+pub const DIRECT_LIMIT: usize = 64;
+
+/// Which part of the convolution of a signal of length `n` with a kernel of length `k` is returned.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub enum Mode {
+    /// Every output where the signal and kernel overlap at all: `n + k - 1` of them.
+    #[default]
+    Full,
+    /// The middle `n` outputs of [`Full`](Self::Full), lined up with the signal (so a kernel centered on its middle tap does not shift it).
+    Same,
+    /// Only the outputs where the kernel lies entirely within the signal: `n - k + 1` of them (none, if the kernel is longer).
+    Valid,
+}
+
+impl Mode {
+    /// The range of the full convolution that is returned.
+    fn range(self, n: usize, k: usize) -> core::ops::Range<usize> {
+        match self {
+            Self::Full => 0..n + k - 1,
+            Self::Same => (k - 1) / 2..(k - 1) / 2 + n,
+            Self::Valid => k - 1..n.max(k - 1),
+        }
+    }
+}
+
+/// The convolution of `signal` with `kernel`, `y[i] = Σ signal[j] * kernel[i - j]`, trimmed by `mode`.
+///
+/// # Panics
+///
+/// If `kernel` is empty.
+#[must_use]
+#[track_caller]
+pub fn convolve<T: FastFloat + Constructors, P: Policy>(
+    signal: &[FFloat<T, P>],
+    kernel: &[FFloat<T, P>],
+    mode: Mode,
+) -> Vec<FFloat<T, P>>
+where
+    FFloat<T, P>: FloatAlone,
+{
+    assert!(!kernel.is_empty(), "the kernel must not be empty");
+    let range = mode.range(signal.len(), kernel.len());
+    if kernel.len() <= DIRECT_LIMIT {
+        let taps: Vec<_> = kernel.iter().rev().copied().collect();
+        direct(signal, &taps, range)
+    } else {
+        spectral(signal, kernel, range)
+    }
+}
+
+/// The cross correlation of `signal` with `kernel`, `y[i] = Σ signal[i + j] * kernel[j]` (for the lags from `1 - k` to `n - 1`), trimmed by `mode`.
+///
+/// This is the convolution with the reversed kernel: it slides the kernel along the signal without flipping it, as in template matching.
+///
+/// # Panics
+///
+/// If `kernel` is empty.
+#[must_use]
+#[track_caller]
+pub fn correlate<T: FastFloat + Constructors, P: Policy>(
+    signal: &[FFloat<T, P>],
+    kernel: &[FFloat<T, P>],
+    mode: Mode,
+) -> Vec<FFloat<T, P>>
+where
+    FFloat<T, P>: FloatAlone,
+{
+    assert!(!kernel.is_empty(), "the kernel must not be empty");
+    let range = mode.range(signal.len(), kernel.len());
+    if kernel.len() <= DIRECT_LIMIT {
+        direct(signal, kernel, range)
+    } else {
+        let reversed: Vec<_> = kernel.iter().rev().copied().collect();
+        spectral(signal, &reversed, range)
+    }
+}
+
+/// `range` of the full correlation of `signal` with `taps`: each output is a dot product of `taps` with a window of the zero padded signal.
+fn direct<T: FastFloat + Constructors, P: Policy>(
+    signal: &[FFloat<T, P>],
+    taps: &[FFloat<T, P>],
+    range: core::ops::Range<usize>,
+) -> Vec<FFloat<T, P>> {
+    // SAFETY: 0 is a valid float
+    let zero = unsafe { FFloat::zero() };
+    let padding = core::iter::repeat_n(zero, taps.len() - 1);
+    let padded: Vec<_> = padding
+        .clone()
+        .chain(signal.iter().copied())
+        .chain(padding)
+        .collect();
+    range
+        .map(|i| slice::dot(&padded[i..i + taps.len()], taps))
+        .collect()
+}
+
+/// `range` of the full convolution of `signal` with `kernel`, by multiplying their spectra.
+fn spectral<T: FastFloat + Constructors, P: Policy>(
+    signal: &[FFloat<T, P>],
+    kernel: &[FFloat<T, P>],
+    range: core::ops::Range<usize>,
+) -> Vec<FFloat<T, P>>
+where
+    FFloat<T, P>: FloatAlone,
+{
+    if range.is_empty() {
+        return Vec::new();
+    }
+    // SAFETY: 0 is a valid float
+    let zero = unsafe { FFloat::zero() };
+    // long enough that the circular convolution does not wrap around.
+    let size = (signal.len() + kernel.len() - 1).next_power_of_two().max(2);
+    let fft = RealFft::new(size);
+    let spectrum = |x: &[FFloat<T, P>]| {
+        let mut padded = x.to_vec();
+        padded.resize(size, zero);
+        let mut spectrum = vec![Complex::from(zero); size / 2 + 1];
+        fft.forward(&padded, &mut spectrum);
+        spectrum
+    };
+    let mut product = spectrum(signal);
+    for (x, y) in product.iter_mut().zip(spectrum(kernel)) {
+        *x *= y;
+    }
+    let mut output = vec![zero; size];
+    fft.inverse(&mut product, &mut output);
+    output.truncate(range.end);
+    output.drain(..range.start);
+    output
+}
+
+#[test]
+#[allow(clippy::cast_precision_loss)]
+fn convolved() {
+    let f = |x| unsafe { FFloat::<f64>::new(x) };
+    let sequence = |n: usize, step: f64| -> Vec<_> {
+        (0..n).map(|i| f((i as f64 * step).sin() + 0.5)).collect()
+    };
+    for (n, k) in [
+        (1, 1),
+        (7, 3),
+        (3, 7),
+        (10, 10),
+        (50, 64),
+        (200, 65),
+        (40, 100),
+    ] {
+        let (signal, kernel) = (sequence(n, 0.7), sequence(k, 1.9));
+        // the textbook definition
+        let slow: Vec<_> = (0..n + k - 1)
+            .map(|i| {
+                (0..n)
+                    .filter(|j| (0..k).contains(&(i.wrapping_sub(*j))))
+                    .fold(f(0.0), |acc, j| acc + signal[j] * kernel[i - j])
+            })
+            .collect();
+        let close = |x: &[FFloat<f64>], y: &[FFloat<f64>]| {
+            x.len() == y.len() && x.iter().zip(y).all(|(x, y)| (**x - **y).abs() < 1e-9)
+        };
+        for mode in [Mode::Full, Mode::Same, Mode::Valid] {
+            let range = mode.range(n, k);
+            let expected = slow.get(range.clone()).unwrap_or_default();
+            assert!(
+                close(&convolve(&signal, &kernel, mode), expected),
+                "{n} {k} {mode:?}"
+            );
+            let taps: Vec<_> = kernel.iter().rev().copied().collect();
+            assert!(close(&direct(&signal, &taps, range.clone()), expected));
+            assert!(close(&spectral(&signal, &kernel, range), expected));
+            // correlating with the reversed kernel is convolving
+            assert!(close(&correlate(&signal, &taps, mode), expected));
+        }
+        assert_eq!(convolve(&signal, &kernel, Mode::Same).len(), n);
+    }
+}
@@ -0,0 +1,110 @@
+//! evaluation of bezier and catmull-rom curves: points, and tangents.
+//!
+//! The curves are generic over their [`Point`]s: anything that can be added, subtracted, and scaled by a float, such as the float itself (for easing and animation curves),
+//! a [`Complex`](crate::complex::Complex) (as a point in the plane), or a [vector](crate::vector).
+//!
+//! Each curve is evaluated in the power basis, by horner's method: a chain of `a * t + b`, which a [`Policy`](crate::policy::Policy) that allows contraction (such as [`Fast`](crate::policy::Fast)) turns into fused multiply adds.
+//! ```
+//! # use umath::{FFloat, complex::Complex, curve};
+//! let f = |x| unsafe { FFloat::new(x) };
+//! let p = |x, y| Complex::new(f(x), f(y));
+//! // a quarter circle, roughly
+//! let control = [p(1.0, 0.0), p(1.0, 0.5523), p(0.5523, 1.0), p(0.0, 1.0)];
+//! let (point, tangent) = curve::cubic_bezier(control, f(0.5f64));
+//! assert!((*point.norm() - 1.0).abs() < 1e-3);
+//! // perpendicular to the radius
+//! assert!((*point.re * *tangent.re + *point.im * *tangent.im).abs() < 1e-12);
+//! ```
+use crate::generic_float::{lit, FloatAlone};
+use core::ops::{Add, Mul, Sub};
+
+/// A point (or vector) a curve can go through: something that can be added, subtracted, and scaled by a `F`.
+pub trait Point<F>: Copy + Add<Output = Self> + Sub<Output = Self> + Mul<F, Output = Self> {}
+
+impl<F, V: Copy + Add<Output = V> + Sub<Output = V> + Mul<F, Output = V>> Point<F> for V {}
+
+/// The point at `t` on the quadratic bezier curve with the control points `[p0, p1, p2]`, and the tangent there (the derivative with respect to `t`).
+///
+/// The curve starts at `p0` (`t = 0`), heading toward `p1`, and ends at `p2` (`t = 1`).
+/// ```
+/// # use umath::curve::quadratic_bezier;
+/// let (point, tangent) = quadratic_bezier([0.0, 1.0, 0.0], 0.5f64);
+/// assert_eq!((point, tangent), (0.5, 0.0));
+/// ```
+#[must_use]
+pub fn quadratic_bezier<F: FloatAlone, V: Point<F>>([p0, p1, p2]: [V; 3], t: F) -> (V, V) {
+    let two = lit::<F>(2.0);
+    let c1 = (p1 - p0) * two;
+    let c2 = p0 - p1 * two + p2;
+    (p0 + (c1 + c2 * t) * t, c1 + c2 * (t * two))
+}
+
+/// The point at `t` on the cubic bezier curve with the control points `[p0, p1, p2, p3]`, and the tangent there (the derivative with respect to `t`).
+///
+/// The curve starts at `p0` (`t = 0`), heading toward `p1`, and ends at `p3` (`t = 1`), coming from `p2`.
+/// ```
+/// # use umath::curve::cubic_bezier;
+/// // smoothstep: from 0 to 1, flat at both ends
+/// let (progress, rate) = cubic_bezier([0.0, 0.0, 1.0, 1.0], 0.5f64);
+/// assert_eq!((progress, rate), (0.5, 1.5));
+/// ```
+#[must_use]
+pub fn cubic_bezier<F: FloatAlone, V: Point<F>>([p0, p1, p2, p3]: [V; 4], t: F) -> (V, V) {
+    let (two, three) = (lit::<F>(2.0), lit::<F>(3.0));
+    let c1 = (p1 - p0) * three;
+    let c2 = (p0 - p1 * two + p2) * three;
+    let c3 = p3 - p0 + (p1 - p2) * three;
+    let point = p0 + (c1 + (c2 + c3 * t) * t) * t;
+    let tangent = c1 + (c2 * two + c3 * (t * three)) * t;
+    (point, tangent)
+}
+
+/// The point at `t` on the (uniform) catmull-rom spline segment between `p1` (`t = 0`) and `p2` (`t = 1`), and the tangent there (the derivative with respect to `t`).
+///
+/// The tangent at each end is half the difference of its neighbours (`(p2 - p0) / 2` at `p1`), so consecutive segments, over a sliding window of the points, join smoothly:
+/// a curve through every point of a path.
+/// ```
+/// # use umath::curve::catmull_rom;
+/// let path = [0.0, 1.0, 4.0, 9.0, 16.0];
+/// for window in path.windows(4) {
+///     let window = [window[0], window[1], window[2], window[3]];
+///     assert_eq!(catmull_rom(window, 0.0f64).0, window[1]);
+///     assert_eq!(catmull_rom(window, 1.0f64).0, window[2]);
+/// }
+/// ```
+#[must_use]
+pub fn catmull_rom<F: FloatAlone, V: Point<F>>([p0, p1, p2, p3]: [V; 4], t: F) -> (V, V) {
+    let [half, two, three, four, five] = [0.5, 2.0, 3.0, 4.0, 5.0].map(lit::<F>);
+    let c1 = p2 - p0;
+    let c2 = p0 * two - p1 * five + p2 * four - p3;
+    let c3 = (p1 - p2) * three + p3 - p0;
+    let point = p1 + (c1 + (c2 + c3 * t) * t) * (t * half);
+    let tangent = (c1 + (c2 * two + c3 * (t * three)) * t) * half;
+    (point, tangent)
+}
+
+#[test]
+fn curves() {
+    fn lerp(from: f64, to: f64, t: f64) -> f64 {
+        from + (to - from) * t
+    }
+    let control = [0.3, -1.0, 2.5, 1.75];
+    for i in 0..=10u8 {
+        let t = f64::from(i) / 10.0;
+        // de casteljau
+        let [ab, bc, cd] = [0, 1, 2].map(|k| lerp(control[k], control[k + 1], t));
+        let [abc, bcd] = [lerp(ab, bc, t), lerp(bc, cd, t)];
+        let (point, tangent) = cubic_bezier(control, t);
+        assert!((point - lerp(abc, bcd, t)).abs() < 1e-12);
+        assert!((tangent - (bcd - abc) * 3.0).abs() < 1e-12);
+        let (point, tangent) = quadratic_bezier([control[0], control[1], control[2]], t);
+        assert!((point - lerp(ab, bc, t)).abs() < 1e-12);
+        assert!((tangent - (bc - ab) * 2.0).abs() < 1e-12);
+    }
+    let (start, slope) = catmull_rom(control, 0.0);
+    assert!((start - control[1]).abs() < 1e-12);
+    assert!((slope - (control[2] - control[0]) / 2.0).abs() < 1e-12);
+    let (end, slope) = catmull_rom(control, 1.0);
+    assert!((end - control[2]).abs() < 1e-12);
+    assert!((slope - (control[3] - control[1]) / 2.0).abs() < 1e-12);
+}
@@ -0,0 +1,157 @@
+//! numerical differentiation of closures, by finite differences, for any <code>[Float]<[f64]></code>.
+//!
+//! When `f` is written against [`Float`], [`Dual`](crate::dual::Dual) numbers give the exact derivative, and should be preferred;
+//! these are for when `f` is a black box (a simulation, a lookup, a call into another library).
+//!
+//! A difference quotient has two sources of error: truncation, which shrinks with the step `h`, and rounding (about `ε |f| / h`), which grows as it shrinks.
+//! [`step`] balances them, for each stencil; [`richardson`] instead starts from a large step and extrapolates it toward 0, which is usually more accurate still, and estimates its own error.
+//! ```
+//! # use umath::{FFloat, diff};
+//! let f = |x: FFloat<f64>| x * x * x;
+//! let x = unsafe { FFloat::new(2.0) };
+//! let d = diff::derivative(f, x, diff::step(x, 3));
+//! assert!((*d - 12.0).abs() < 1e-9);
+//! ```
+//!
+//! ## Safety Notice
+//!
+//! For a [`FFloat`](crate::FFloat), `f` must be finite within `h` of `x` (and, for [`richardson`], within the initial `h`).
+use crate::generic_float::{lit, Constructors, Float};
+
+/// A step for differentiating at `x`: `ε^(1 / n)`, scaled by `|x|` (when that is more than 1).
+///
+/// With `n = 3` this balances the truncation and rounding errors of [`derivative`], with `n = 5` those of [`derivative5`], and with `n = 4` those of [`second_derivative`],
+/// giving about `2 / 3`, `4 / 5`, and `1 / 2` of the float's digits, for a reasonably scaled `f`.
+///
+/// # Panics
+///
+/// If `n` is 0.
+/// ```
+/// # use umath::diff::step;
+/// assert!((step(1.0f64, 3) - f64::EPSILON.cbrt()).abs() < 1e-20);
+/// assert!((step(1000.0f64, 3) - 1000.0 * f64::EPSILON.cbrt()).abs() < 1e-17);
+/// ```
+#[track_caller]
+#[must_use]
+pub fn step<F: Float<f64>>(x: F, n: u32) -> F {
+    assert!(n != 0, "n must not be 0");
+    // SAFETY: ε is finite
+    let ε = unsafe { F::ε() };
+    ε.powf(lit(1.0 / f64::from(n))) * x.abs().max(lit(1.0))
+}
+
+/// Rounds `h` so that `x + h` and `x - h` are exact: otherwise the rounding of the points is a error in the step, which is then divided by it.
+fn exact<F: Float<f64>>(x: F, h: F) -> F {
+    (x + h) - x
+}
+
+/// The derivative of `f` at `x`, by the central difference `(f(x + h) - f(x - h)) / 2h`, with a error of `O(h²)`.
+///
+/// Refer to [`step`] for choosing `h`.
+/// ```
+/// # use umath::diff::{derivative, step};
+/// let d = derivative(|x: f64| x.sin(), 1.0, step(1.0, 3));
+/// assert!((d - 1f64.cos()).abs() < 1e-10);
+/// ```
+pub fn derivative<F: Float<f64>>(mut f: impl FnMut(F) -> F, x: F, h: F) -> F {
+    let h = exact(x, h);
+    (f(x + h) - f(x - h)) / (h * 2.0)
+}
+
+/// The derivative of `f` at `x`, by the five point central stencil `(-f(x + 2h) + 8f(x + h) - 8f(x - h) + f(x - 2h)) / 12h`, with a error of `O(h⁴)`.
+///
+/// Refer to [`step`] for choosing `h`.
+/// ```
+/// # use umath::diff::{derivative5, step};
+/// let d = derivative5(|x: f64| x.exp(), 1.0, step(1.0, 5));
+/// assert!((d - core::f64::consts::E).abs() < 1e-12);
+/// ```
+pub fn derivative5<F: Float<f64>>(mut f: impl FnMut(F) -> F, x: F, h: F) -> F {
+    let h = exact(x, h);
+    let near = f(x + h) - f(x - h);
+    let far = f(x + h * 2.0) - f(x - h * 2.0);
+    (near * 8.0 - far) / (h * 12.0)
+}
+
+/// The second derivative of `f` at `x`, by the central difference `(f(x + h) - 2f(x) + f(x - h)) / h²`, with a error of `O(h²)`.
+///
+/// Refer to [`step`] for choosing `h`.
+/// ```
+/// # use umath::diff::{second_derivative, step};
+/// let d = second_derivative(|x: f64| x.sin(), 1.0, step(1.0, 4));
+/// assert!((d + 1f64.sin()).abs() < 1e-7);
+/// ```
+pub fn second_derivative<F: Float<f64>>(mut f: impl FnMut(F) -> F, x: F, h: F) -> F {
+    let h = exact(x, h);
+    (f(x + h) - f(x) * 2.0 + f(x - h)) / (h * h)
+}
+
+/// The derivative of `f` at `x`, and a estimate of its error, by Ridders' method:
+/// central differences with the step shrinking from `h` (by 1.4 each time), extrapolated toward a step of 0 with Richardson's method,
+/// and stopping once the rounding error starts to dominate.
+///
+/// Unlike for the plain stencils, `h` should be large: about the distance over which `f` changes significantly.
+/// This takes up to 20 evaluations of `f`.
+/// ```
+/// # use umath::diff::richardson;
+/// let (d, error) = richardson(|x: f64| x.exp() * x.sin(), 1.0, 0.5);
+/// let exact = 1f64.exp() * (1f64.sin() + 1f64.cos());
+/// assert!((d - exact).abs() < 1e-12);
+/// assert!(error < 1e-11);
+/// ```
+pub fn richardson<F: Float<f64>>(mut f: impl FnMut(F) -> F, x: F, h: F) -> (F, F) {
+    const SHRINK: f64 = 1.4;
+    const STEPS: usize = 10;
+    let mut h = exact(x, h);
+    let mut central = |h: F| (f(x + h) - f(x - h)) / (h * 2.0);
+    // the last column of the extrapolation tableau: column[j] is the j times extrapolated derivative.
+    let mut column = vec![central(h)];
+    let mut best = column[0];
+    // SAFETY: MAX is finite
+    let mut error = unsafe { <F as Constructors>::max() };
+    for i in 1..STEPS {
+        h = exact(x, h / SHRINK);
+        let mut next = Vec::with_capacity(i + 1);
+        next.push(central(h));
+        let mut factor = SHRINK * SHRINK;
+        for j in 1..=i {
+            // eliminates the h^2j term of the error.
+            let extrapolated = (next[j - 1] * factor - column[j - 1]) / (factor - 1.0);
+            factor *= SHRINK * SHRINK;
+            let change = (extrapolated - next[j - 1])
+                .abs()
+                .max((extrapolated - column[j - 1]).abs());
+            if change <= error {
+                (best, error) = (extrapolated, change);
+            }
+            next.push(extrapolated);
+        }
+        // the highest order got worse (by a good margin): rounding has taken over.
+        let worse = (next[i] - column[i - 1]).abs() >= error * 2.0;
+        column = next;
+        if worse {
+            break;
+        }
+    }
+    (best, error)
+}
+
+#[test]
+fn differentiated() {
+    let f = |x: f64| x.powi(4) - x * 3.0;
+    // f' = 4x³ - 3, f'' = 12x²
+    for x in [-2.0f64, 0.0, 0.5, 300.0] {
+        let slope = 4.0 * x * x * x - 3.0;
+        let scale = 1.0 + slope.abs();
+        assert!((derivative(f, x, step(x, 3)) - slope).abs() < 1e-8 * scale);
+        assert!((derivative5(f, x, step(x, 5)) - slope).abs() < 1e-11 * scale);
+        let (d, error) = richardson(f, x, 1.0 + x.abs());
+        assert!((d - slope).abs() < 1e-11 * scale, "{x} {d} {slope}");
+        assert!(error < 1e-10 * scale);
+        let curvature = 12.0 * x * x;
+        let scale = 1.0 + curvature;
+        assert!((second_derivative(f, x, step(x, 4)) - curvature).abs() < 1e-6 * scale);
+    }
+    // a kink is not differentiable: the central difference averages the sides.
+    assert!(derivative(f64::abs, 0.0, 1e-3).abs() < 1e-12);
+}
@@ -0,0 +1,480 @@
+//! forward mode automatic differentiation.
+//!
+//! A [`Dual`] number carries a value, and its derivative with respect to some input.
+//! As it implements [`Float`], any function written against <code>[Float]<[f32]></code> can be differentiated, without changes.
+//! The value part is still computed with whatever float you put in, so a <code>[Dual]<[FFloat]></code> keeps the fast math.
+//! ```
+//! # use umath::{FFloat, Float, dual::differentiate};
+//! fn f<F: Float<f32>>(x: F) -> F {
+//!     x * x * 3.0 + x.sin()
+//! }
+//! let (y, dy) = differentiate(f, unsafe { FFloat::new(0.0f32) });
+//! assert_eq!(*y, 0.0);
+//! assert_eq!(*dy, 1.0); // 6x + cos(x)
+//! ```
+//!
+//! ## Safety Notice
+//!
+//! The derivative is held in the same float type as the value, so for a [`FFloat`] it must also never be [`NAN`] | [`INF`].
+//! Take care at points where the derivative does not exist, such as `sqrt(0)`.
+use crate::generic_float::{
+    lit, Constants, Constructors, Float, FloatAlone, FloatExt, FloatMethods, Log, Rounding, Trig,
+};
+#[cfg(doc)]
+use crate::FFloat;
+use core::cmp::Ordering;
+use core::ops::{
+    Add, AddAssign, Div, DivAssign, Mul, MulAssign, Neg, Rem, RemAssign, Sub, SubAssign,
+};
+#[cfg(doc)]
+use std::f32::{INFINITY as INF, NAN};
+
+/// A dual number, `value + derivative·ε`. Refer to the [module level documentation](self).
+///
+/// Comparisons only look at the value.
+#[derive(Copy, Clone, Debug, Default)]
+pub struct Dual<F> {
+    value: F,
+    derivative: F,
+}
+
+/// Computes `f(x)`, and its derivative `f'(x)`.
+pub fn differentiate<F: FloatAlone>(f: impl FnOnce(Dual<F>) -> Dual<F>, x: F) -> (F, F) {
+    let y = f(Dual::variable(x));
+    (y.value, y.derivative)
+}
+
+impl<F: FloatAlone> Dual<F> {
+    /// Creates a new [`Dual`] number from its parts.
+    pub fn new(value: F, derivative: F) -> Self {
+        Self { value, derivative }
+    }
+
+    /// Creates a constant, (derivative 0).
+    pub fn constant(value: F) -> Self {
+        Self::new(value, unsafe { F::zero() })
+    }
+
+    /// Creates the variable being differentiated for, (derivative 1).
+    pub fn variable(value: F) -> Self {
+        Self::new(value, unsafe { F::one() })
+    }
+
+    /// The value.
+    pub fn value(self) -> F {
+        self.value
+    }
+
+    /// The derivative.
+    pub fn derivative(self) -> F {
+        self.derivative
+    }
+
+    /// Applies the chain rule: `f(self)`, where `f'(self) = slope`.
+    #[inline(always)]
+    fn chain(self, value: F, slope: F) -> Self {
+        Self::new(value, self.derivative * slope)
+    }
+}
+
+impl<F: FloatAlone> From<F> for Dual<F> {
+    fn from(value: F) -> Self {
+        Self::constant(value)
+    }
+}
+
+impl<F: PartialEq> PartialEq for Dual<F> {
+    fn eq(&self, other: &Self) -> bool {
+        self.value == other.value
+    }
+}
+
+impl<F: PartialOrd> PartialOrd for Dual<F> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        self.value.partial_cmp(&other.value)
+    }
+}
+
+impl<F: FloatAlone> Add for Dual<F> {
+    type Output = Self;
+    fn add(self, rhs: Self) -> Self {
+        Self::new(self.value + rhs.value, self.derivative + rhs.derivative)
+    }
+}
+
+impl<F: FloatAlone> Sub for Dual<F> {
+    type Output = Self;
+    fn sub(self, rhs: Self) -> Self {
+        Self::new(self.value - rhs.value, self.derivative - rhs.derivative)
+    }
+}
+
+impl<F: FloatAlone> Mul for Dual<F> {
+    type Output = Self;
+    fn mul(self, rhs: Self) -> Self {
+        Self::new(
+            self.value * rhs.value,
+            self.derivative * rhs.value + self.value * rhs.derivative,
+        )
+    }
+}
+
+impl<F: FloatAlone> Div for Dual<F> {
+    type Output = Self;
+    fn div(self, rhs: Self) -> Self {
+        let value = self.value / rhs.value;
+        Self::new(
+            value,
+            (self.derivative - value * rhs.derivative) / rhs.value,
+        )
+    }
+}
+
+impl<F: FloatAlone> Rem for Dual<F> {
+    type Output = Self;
+    fn rem(self, rhs: Self) -> Self {
+        Self::new(
+            self.value % rhs.value,
+            self.derivative - (self.value / rhs.value).trunc() * rhs.derivative,
+        )
+    }
+}
+
+impl<F: FloatAlone> Neg for Dual<F> {
+    type Output = Self;
+    fn neg(self) -> Self {
+        Self::new(-self.value, -self.derivative)
+    }
+}
+
+macro_rules! assign {
+    ($($trait:ident, $fn:ident => $op:ident);+ $(;)?) => {
+        $(
+            impl<F: FloatAlone> $trait for Dual<F> {
+                fn $fn(&mut self, rhs: Self) {
+                    *self = (*self).$op(rhs);
+                }
+            }
+        )+
+    };
+}
+
+assign! {
+    AddAssign, add_assign => add;
+    SubAssign, sub_assign => sub;
+    MulAssign, mul_assign => mul;
+    DivAssign, div_assign => div;
+    RemAssign, rem_assign => rem;
+}
+
+impl<F: FloatAlone> Constants for Dual<F> {
+    unsafe fn π() -> Self {
+        Self::constant(F::π())
+    }
+    unsafe fn ε() -> Self {
+        Self::constant(F::ε())
+    }
+    unsafe fn e() -> Self {
+        Self::constant(F::e())
+    }
+}
+
+impl<F: FloatAlone> Constructors for Dual<F> {
+    unsafe fn zero() -> Self {
+        Self::constant(F::zero())
+    }
+    unsafe fn one() -> Self {
+        Self::constant(F::one())
+    }
+    unsafe fn min() -> Self {
+        Self::constant(<F as Constructors>::min())
+    }
+    unsafe fn max() -> Self {
+        Self::constant(<F as Constructors>::max())
+    }
+}
+
+impl<F: FloatAlone> Trig for Dual<F> {
+    fn sin(self) -> Self {
+        self.chain(self.value.sin(), self.value.cos())
+    }
+    fn asin(self) -> Self {
+        let x = self.value;
+        self.chain(x.asin(), (lit::<F>(1.0) - x * x).sqrt().recip())
+    }
+    fn sinh(self) -> Self {
+        self.chain(self.value.sinh(), self.value.cosh())
+    }
+    fn asinh(self) -> Self {
+        let x = self.value;
+        self.chain(x.asinh(), (x * x + lit(1.0)).sqrt().recip())
+    }
+    fn cos(self) -> Self {
+        self.chain(self.value.cos(), -self.value.sin())
+    }
+    fn acos(self) -> Self {
+        let x = self.value;
+        self.chain(x.acos(), -(lit::<F>(1.0) - x * x).sqrt().recip())
+    }
+    fn cosh(self) -> Self {
+        self.chain(self.value.cosh(), self.value.sinh())
+    }
+    fn acosh(self) -> Self {
+        let x = self.value;
+        self.chain(x.acosh(), (x * x - lit(1.0)).sqrt().recip())
+    }
+    fn tan(self) -> Self {
+        let t = self.value.tan();
+        self.chain(t, t * t + lit(1.0))
+    }
+    fn atan(self) -> Self {
+        let x = self.value;
+        self.chain(x.atan(), (x * x + lit(1.0)).recip())
+    }
+    /// Refer to [`f32::atan2`]. `self` is `y`, and `other` is `x`.
+    fn atan2(self, other: Self) -> Self {
+        let (y, x) = (self.value, other.value);
+        Self::new(
+            y.atan2(x),
+            (x * self.derivative - y * other.derivative) / (x * x + y * y),
+        )
+    }
+    fn tanh(self) -> Self {
+        let t = self.value.tanh();
+        self.chain(t, lit::<F>(1.0) - t * t)
+    }
+    fn atanh(self) -> Self {
+        let x = self.value;
+        self.chain(x.atanh(), (lit::<F>(1.0) - x * x).recip())
+    }
+}
+
+impl<F: FloatAlone> Rounding for Dual<F> {
+    fn floor(self) -> Self {
+        Self::constant(self.value.floor())
+    }
+    fn ceil(self) -> Self {
+        Self::constant(self.value.ceil())
+    }
+    fn round(self) -> Self {
+        Self::constant(self.value.round())
+    }
+}
+
+impl<F: FloatAlone> Log for Dual<F> {
+    fn log(self, base: Self) -> Self {
+        self.ln() / base.ln()
+    }
+    fn log2(self) -> Self {
+        let x = self.value;
+        self.chain(x.log2(), (x * lit(core::f64::consts::LN_2)).recip())
+    }
+    fn log10(self) -> Self {
+        let x = self.value;
+        self.chain(x.log10(), (x * lit(core::f64::consts::LN_10)).recip())
+    }
+    fn ln(self) -> Self {
+        self.chain(self.value.ln(), self.value.recip())
+    }
+}
+
+impl<F: FloatAlone> FloatMethods for Dual<F> {
+    fn trunc(self) -> Self {
+        Self::constant(self.value.trunc())
+    }
+    fn fract(self) -> Self {
+        Self::new(self.value.fract(), self.derivative)
+    }
+    fn abs(self) -> Self {
+        if self.value < unsafe { F::zero() } {
+            -self
+        } else {
+            self
+        }
+    }
+    fn powi(self, n: i32) -> Self {
+        if n == 0 {
+            return Self::constant(self.value.powi(0));
+        }
+        let x = self.value;
+        self.chain(x.powi(n), x.powi(n - 1) * lit(f64::from(n)))
+    }
+    /// Refer to [`f32::powf`]. The `ln(self)` term is only computed when `n` has a derivative.
+    fn powf(self, n: Self) -> Self {
+        let value = self.value.powf(n.value);
+        let mut derivative = self.derivative * n.value * self.value.powf(n.value - lit(1.0));
+        if n.derivative != unsafe { F::zero() } {
+            derivative += n.derivative * value * self.value.ln();
+        }
+        Self::new(value, derivative)
+    }
+    fn sqrt(self) -> Self {
+        let s = self.value.sqrt();
+        self.chain(s, (s * lit(2.0)).recip())
+    }
+    fn cbrt(self) -> Self {
+        let c = self.value.cbrt();
+        self.chain(c, (c * c * lit(3.0)).recip())
+    }
+    fn hypot(self, other: Self) -> Self {
+        let h = self.value.hypot(other.value);
+        Self::new(
+            h,
+            (self.value * self.derivative + other.value * other.derivative) / h,
+        )
+    }
+    fn exp2(self) -> Self {
+        let e = self.value.exp2();
+        self.chain(e, e * lit(core::f64::consts::LN_2))
+    }
+    fn min(self, other: Self) -> Self {
+        if other.value < self.value {
+            other
+        } else {
+            self
+        }
+    }
+    fn max(self, other: Self) -> Self {
+        if other.value > self.value {
+            other
+        } else {
+            self
+        }
+    }
+}
+
+impl<F: FloatAlone> FloatExt for Dual<F> {
+    unsafe fn from_f64(x: f64) -> Self {
+        Self::constant(F::from_f64(x))
+    }
+    fn exp(self) -> Self {
+        let e = self.value.exp();
+        self.chain(e, e)
+    }
+    fn next_up(self) -> Self {
+        Self::new(self.value.next_up(), self.derivative)
+    }
+    fn next_down(self) -> Self {
+        Self::new(self.value.next_down(), self.derivative)
+    }
+}
+
+/// `1 / x`.
+trait Recip {
+    fn recip(self) -> Self;
+}
+
+impl<F: FloatAlone> Recip for F {
+    #[inline(always)]
+    fn recip(self) -> Self {
+        lit::<F>(1.0) / self
+    }
+}
+
+macro_rules! base {
+    ($base:ty) => {
+        impl<F: Float<$base>> PartialEq<$base> for Dual<F> {
+            fn eq(&self, other: &$base) -> bool {
+                self.value == *other
+            }
+        }
+
+        impl<F: Float<$base>> PartialOrd<$base> for Dual<F> {
+            fn partial_cmp(&self, other: &$base) -> Option<Ordering> {
+                self.value.partial_cmp(other)
+            }
+        }
+
+        impl<F: Float<$base>> Add<$base> for Dual<F> {
+            type Output = Self;
+            fn add(self, rhs: $base) -> Self {
+                Self::new(self.value + rhs, self.derivative)
+            }
+        }
+
+        impl<F: Float<$base>> Sub<$base> for Dual<F> {
+            type Output = Self;
+            fn sub(self, rhs: $base) -> Self {
+                Self::new(self.value - rhs, self.derivative)
+            }
+        }
+
+        impl<F: Float<$base>> Mul<$base> for Dual<F> {
+            type Output = Self;
+            fn mul(self, rhs: $base) -> Self {
+                Self::new(self.value * rhs, self.derivative * rhs)
+            }
+        }
+
+        impl<F: Float<$base>> Div<$base> for Dual<F> {
+            type Output = Self;
+            fn div(self, rhs: $base) -> Self {
+                Self::new(self.value / rhs, self.derivative / rhs)
+            }
+        }
+
+        impl<F: Float<$base>> Rem<$base> for Dual<F> {
+            type Output = Self;
+            fn rem(self, rhs: $base) -> Self {
+                Self::new(self.value % rhs, self.derivative)
+            }
+        }
+
+        impl<F: Float<$base>> AddAssign<$base> for Dual<F> {
+            fn add_assign(&mut self, rhs: $base) {
+                *self = *self + rhs;
+            }
+        }
+
+        impl<F: Float<$base>> SubAssign<$base> for Dual<F> {
+            fn sub_assign(&mut self, rhs: $base) {
+                *self = *self - rhs;
+            }
+        }
+
+        impl<F: Float<$base>> MulAssign<$base> for Dual<F> {
+            fn mul_assign(&mut self, rhs: $base) {
+                *self = *self * rhs;
+            }
+        }
+
+        impl<F: Float<$base>> DivAssign<$base> for Dual<F> {
+            fn div_assign(&mut self, rhs: $base) {
+                *self = *self / rhs;
+            }
+        }
+
+        impl<F: Float<$base>> RemAssign<$base> for Dual<F> {
+            fn rem_assign(&mut self, rhs: $base) {
+                *self = *self % rhs;
+            }
+        }
+
+        impl<F: Float<$base>> Float<$base> for Dual<F> {
+            /// Creates a constant [`Dual`].
+            #[doc = include_str!("refer.md")]
+            unsafe fn new(from: $base) -> Self {
+                Self::constant(F::new(from))
+            }
+
+            /// Returns the value.
+            fn take(self) -> $base {
+                self.value.take()
+            }
+        }
+    };
+}
+
+base!(f32);
+base!(f64);
+
+#[test]
+#[allow(clippy::float_cmp)]
+fn chain() {
+    let (y, dy) = differentiate(|x| x.exp() * x.ln(), 1.0f64);
+    assert_eq!((y, dy), (0.0, core::f64::consts::E));
+    let (_, dy) = differentiate(|x: Dual<f64>| x.sqrt().powi(3), 4.0);
+    assert_eq!(dy, 3.0);
+    let (_, dy) = differentiate(|x| x.atan2(Dual::constant(1.0f32)), 0.0);
+    assert_eq!(dy, 1.0);
+}
@@ -0,0 +1,183 @@
+//! easing functions: the standard curves for tweening, mapping the progress of a animation, from 0 to 1, to how far along its value is.
+//!
+//! Each [`Easing`] is a curve, eased [in](Mode::In) (starting slowly), [out](Mode::Out) (ending slowly), or [in and out](Mode::InOut).
+//! They are generic over [`FloatAlone`], so they work on plain floats, [`FFloat`](crate::FFloat)s, and the other wrappers;
+//! a [`UnitFloat`] progress can be [eased](UnitFloat::ease) directly, which keeps the result in `0..=1` (clipping the overshoot of [`Back`](Easing::Back) and [`Elastic`](Easing::Elastic)).
+//! ```
+//! # use umath::{FFloat, UnitFloat, easing::{Easing, Mode}};
+//! let t = unsafe { FFloat::new(0.5f32) };
+//! assert_eq!(*Easing::Quad.ease(Mode::In, t), 0.25);
+//! assert_eq!(*Easing::Quad.ease(Mode::Out, t), 0.75);
+//! let progress = UnitFloat::new(0.9f32).unwrap();
+//! assert_eq!(*progress.ease(Easing::Back, Mode::Out), 1.0);
+//! ```
+use crate::generic_float::{lit, Constructors, FloatAlone};
+use crate::r#trait::FastFloat;
+use crate::UnitFloat;
+
+/// A easing curve (named as in css, and most tweening libraries). The descriptions are of the [in](Mode::In) form.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub enum Easing {
+    /// `t`: no easing.
+    #[default]
+    Linear,
+    /// `t²`
+    Quad,
+    /// `t³`
+    Cubic,
+    /// `t⁴`
+    Quart,
+    /// `t⁵`
+    Quint,
+    /// `1 - cos(t π / 2)`: a quarter of a cosine wave.
+    Sine,
+    /// `2^(10 (t - 1))` (and exactly 0 at 0).
+    Expo,
+    /// `1 - sqrt(1 - t²)`: a quarter circle.
+    Circ,
+    /// A cubic that first backs off (to about -0.1), before accelerating toward 1.
+    Back,
+    /// A exponentially growing sine wave, like a spring being released.
+    Elastic,
+    /// Bouncing, with decreasing height, and landing at 1.
+    Bounce,
+}
+
+/// Which end of the animation a [`Easing`] is applied to.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub enum Mode {
+    /// Starts slowly: the curve itself.
+    #[default]
+    In,
+    /// Ends slowly: the curve rotated by 180°, `1 - in(1 - t)`.
+    Out,
+    /// Starts and ends slowly: the curve for the first half, and its out form for the second.
+    InOut,
+}
+
+impl Easing {
+    /// The curve, eased in.
+    fn ease_in<F: FloatAlone>(self, t: F) -> F {
+        // SAFETY: 0, 1, and π are finite
+        let (zero, one, π) = unsafe { (F::zero(), F::one(), F::π()) };
+        match self {
+            Self::Linear => t,
+            Self::Quad => t * t,
+            Self::Cubic => t * t * t,
+            Self::Quart => (t * t) * (t * t),
+            Self::Quint => (t * t) * (t * t) * t,
+            Self::Sine => one - (t * π * lit(0.5)).cos(),
+            Self::Expo if t == zero => zero,
+            Self::Expo => ((t - one) * lit(10.0)).exp2(),
+            Self::Circ => one - (one - t * t).max(zero).sqrt(),
+            Self::Back => {
+                const OVERSHOOT: f64 = 1.70158;
+                t * t * (t * lit(OVERSHOOT + 1.0) - lit(OVERSHOOT))
+            }
+            Self::Elastic if t == zero || t == one => t,
+            Self::Elastic => {
+                let x = (t - one) * lit(10.0);
+                -x.exp2() * ((x - lit(0.75)) * π * lit(2.0 / 3.0)).sin()
+            }
+            Self::Bounce => one - bounce(one - t),
+        }
+    }
+
+    /// Eases the progress `t` (from 0 to 1), with `mode`. Every curve goes from 0 (at `t = 0`) to 1 (at `t = 1`).
+    /// ```
+    /// # use umath::easing::{Easing, Mode};
+    /// for easing in [Easing::Cubic, Easing::Expo, Easing::Elastic, Easing::Bounce] {
+    ///     for mode in [Mode::In, Mode::Out, Mode::InOut] {
+    ///         assert!(easing.ease(mode, 0.0f64).abs() < 1e-12);
+    ///         assert!((easing.ease(mode, 1.0f64) - 1.0).abs() < 1e-12);
+    ///     }
+    ///     assert!((easing.ease(Mode::InOut, 0.5f64) - 0.5).abs() < 1e-12);
+    /// }
+    /// ```
+    #[must_use]
+    pub fn ease<F: FloatAlone>(self, mode: Mode, t: F) -> F {
+        // SAFETY: 1 is a valid float
+        let one = unsafe { F::one() };
+        let half = lit::<F>(0.5);
+        match mode {
+            Mode::In => self.ease_in(t),
+            Mode::Out => one - self.ease_in(one - t),
+            Mode::InOut if t < half => self.ease_in(t * lit(2.0)) * half,
+            Mode::InOut => one - self.ease_in((one - t) * lit(2.0)) * half,
+        }
+    }
+}
+
+/// The bounce, eased out: four parabolas, each a quarter the height of the last.
+fn bounce<F: FloatAlone>(t: F) -> F {
+    const SCALE: f64 = 7.5625;
+    const WIDTH: f64 = 2.75;
+    let parabola = |center: f64, floor: f64| {
+        let x = t - lit(center / WIDTH);
+        x * x * lit(SCALE) + lit(floor)
+    };
+    if t < lit(1.0 / WIDTH) {
+        t * t * lit(SCALE)
+    } else if t < lit(2.0 / WIDTH) {
+        parabola(1.5, 0.75)
+    } else if t < lit(2.5 / WIDTH) {
+        parabola(2.25, 0.9375)
+    } else {
+        parabola(2.625, 0.984_375)
+    }
+}
+
+impl<T: FastFloat + Constructors + FloatAlone> UnitFloat<T> {
+    /// Eases this progress with `easing` and `mode` (refer to [`Easing::ease`]), saturating the result into `0..=1`.
+    ///
+    /// The curve is computed on the inner float (so intermediate values may leave `0..=1`), and only the result is saturated.
+    #[must_use]
+    pub fn ease(self, easing: Easing, mode: Mode) -> Self {
+        Self::saturating(easing.ease(mode, *self))
+    }
+}
+
+#[test]
+fn eased() {
+    let curves = [
+        Easing::Linear,
+        Easing::Quad,
+        Easing::Cubic,
+        Easing::Quart,
+        Easing::Quint,
+        Easing::Sine,
+        Easing::Expo,
+        Easing::Circ,
+        Easing::Back,
+        Easing::Elastic,
+        Easing::Bounce,
+    ];
+    for easing in curves {
+        for mode in [Mode::In, Mode::Out, Mode::InOut] {
+            let ease = |t: f64| easing.ease(mode, t);
+            assert!(ease(0.0).abs() < 1e-12, "{easing:?} {mode:?}");
+            assert!((ease(1.0) - 1.0).abs() < 1e-12, "{easing:?} {mode:?}");
+            // continuous (there are no jumps, even at the pieces of bounce, and the middle of in-out)
+            for i in 0..1000u16 {
+                let t = f64::from(i) / 1000.0;
+                assert!(
+                    (ease(t + 0.001) - ease(t)).abs() < 0.05,
+                    "{easing:?} {mode:?} {t}"
+                );
+            }
+        }
+        // in-out is symmetric about the middle
+        for t in [0.1f64, 0.3, 0.45] {
+            let sum = easing.ease(Mode::InOut, t) + easing.ease(Mode::InOut, 1.0 - t);
+            assert!((sum - 1.0).abs() < 1e-12, "{easing:?}");
+        }
+    }
+    assert!((Easing::Sine.ease(Mode::In, 0.5f64) - (1.0 - 0.5f64.sqrt())).abs() < 1e-12);
+    // back undershoots
+    assert!(Easing::Back.ease(Mode::In, 0.3f64) < 0.0);
+    let early = UnitFloat::new(0.3f64).unwrap();
+    assert_eq!(
+        early.ease(Easing::Back, Mode::In),
+        UnitFloat::new(0.0).unwrap()
+    );
+}
@@ -0,0 +1,502 @@
+//! hardware reciprocal | reciprocal square root estimates.
+use crate::policy::Policy;
+use crate::r#trait::Wrapper;
+use crate::FFloat;
+use core::simd::{Simd, SimdElement};
+
+/// The estimate instructions, or the exact result where there are none.
+trait Estimate: Sized {
+    fn recip_estimate(self) -> Self;
+    fn rsqrt_estimate(self) -> Self;
+}
+
+#[cfg(any(
+    target_arch = "x86_64",
+    all(target_arch = "x86", target_feature = "sse")
+))]
+impl Estimate for f32 {
+    #[inline(always)]
+    fn recip_estimate(self) -> Self {
+        #[cfg(target_arch = "x86")]
+        use core::arch::x86::{_mm_cvtss_f32, _mm_rcp_ss, _mm_set_ss};
+        #[cfg(target_arch = "x86_64")]
+        use core::arch::x86_64::{_mm_cvtss_f32, _mm_rcp_ss, _mm_set_ss};
+        // SAFETY: sse is enabled (it is part of the x86_64 baseline).
+        unsafe { _mm_cvtss_f32(_mm_rcp_ss(_mm_set_ss(self))) }
+    }
+
+    #[inline(always)]
+    fn rsqrt_estimate(self) -> Self {
+        #[cfg(target_arch = "x86")]
+        use core::arch::x86::{_mm_cvtss_f32, _mm_rsqrt_ss, _mm_set_ss};
+        #[cfg(target_arch = "x86_64")]
+        use core::arch::x86_64::{_mm_cvtss_f32, _mm_rsqrt_ss, _mm_set_ss};
+        // SAFETY: sse is enabled (it is part of the x86_64 baseline).
+        unsafe { _mm_cvtss_f32(_mm_rsqrt_ss(_mm_set_ss(self))) }
+    }
+}
+
+#[cfg(target_arch = "x86_64")]
+impl Estimate for f64 {
+    #[inline(always)]
+    fn recip_estimate(self) -> Self {
+        if avx512() {
+            // SAFETY: the cpu supports avx512f.
+            unsafe { rcp14(self) }
+        } else {
+            1.0 / self
+        }
+    }
+
+    #[inline(always)]
+    fn rsqrt_estimate(self) -> Self {
+        if avx512() {
+            // SAFETY: the cpu supports avx512f.
+            unsafe { rsqrt14(self) }
+        } else {
+            1.0 / self.sqrt()
+        }
+    }
+}
+
+/// Whether the `avx512f` estimates can be used: known where the target enables it, and probed (once) at runtime otherwise.
+#[cfg(target_arch = "x86_64")]
+#[inline(always)]
+fn avx512() -> bool {
+    use crate::slice::dispatch::{level, Level};
+    cfg!(target_feature = "avx512f") || level() == Level::Avx512
+}
+
+#[cfg(target_arch = "x86_64")]
+#[target_feature(enable = "avx512f")]
+unsafe fn rcp14(x: f64) -> f64 {
+    use core::arch::x86_64::{_mm_cvtsd_f64, _mm_rcp14_sd, _mm_set_sd};
+    let x = _mm_set_sd(x);
+    _mm_cvtsd_f64(_mm_rcp14_sd(x, x))
+}
+
+#[cfg(target_arch = "x86_64")]
+#[target_feature(enable = "avx512f")]
+unsafe fn rsqrt14(x: f64) -> f64 {
+    use core::arch::x86_64::{_mm_cvtsd_f64, _mm_rsqrt14_sd, _mm_set_sd};
+    let x = _mm_set_sd(x);
+    _mm_cvtsd_f64(_mm_rsqrt14_sd(x, x))
+}
+
+#[cfg(target_arch = "aarch64")]
+impl Estimate for f32 {
+    #[inline(always)]
+    fn recip_estimate(self) -> Self {
+        // SAFETY: neon is part of the aarch64 baseline.
+        unsafe { core::arch::aarch64::vrecpes_f32(self) }
+    }
+
+    #[inline(always)]
+    fn rsqrt_estimate(self) -> Self {
+        // SAFETY: neon is part of the aarch64 baseline.
+        unsafe { core::arch::aarch64::vrsqrtes_f32(self) }
+    }
+}
+
+#[cfg(target_arch = "aarch64")]
+impl Estimate for f64 {
+    #[inline(always)]
+    fn recip_estimate(self) -> Self {
+        // SAFETY: neon is part of the aarch64 baseline.
+        unsafe { core::arch::aarch64::vrecped_f64(self) }
+    }
+
+    #[inline(always)]
+    fn rsqrt_estimate(self) -> Self {
+        // SAFETY: neon is part of the aarch64 baseline.
+        unsafe { core::arch::aarch64::vrsqrted_f64(self) }
+    }
+}
+
+/// Implements [`Estimate`] with the exact operations.
+#[cfg(not(any(target_arch = "x86_64", target_arch = "aarch64")))]
+macro_rules! exact {
+    ($t:ty) => {
+        impl Estimate for $t {
+            #[inline(always)]
+            fn recip_estimate(self) -> Self {
+                1.0 / self
+            }
+
+            #[inline(always)]
+            fn rsqrt_estimate(self) -> Self {
+                1.0 / self.sqrt()
+            }
+        }
+    };
+}
+
+#[cfg(not(any(
+    target_arch = "x86_64",
+    all(target_arch = "x86", target_feature = "sse"),
+    target_arch = "aarch64"
+)))]
+exact!(f32);
+#[cfg(not(any(target_arch = "x86_64", target_arch = "aarch64")))]
+exact!(f64);
+
+/// Estimates each `W` lane chunk of `x` with `packed`, and the lanes left over with `scalar`.
+#[cfg(any(
+    target_arch = "x86_64",
+    all(target_arch = "x86", target_feature = "sse"),
+    target_arch = "aarch64"
+))]
+#[inline(always)]
+fn chunked<T: SimdElement, const N: usize, const W: usize>(
+    x: Simd<T, N>,
+    packed: impl Fn(Simd<T, W>) -> Simd<T, W>,
+    scalar: impl Fn(T) -> T,
+) -> Simd<T, N> {
+    let mut x = x.to_array();
+    let (chunks, rest) = x.as_chunks_mut::<W>();
+    for chunk in chunks {
+        *chunk = packed(Simd::from_array(*chunk)).to_array();
+    }
+    for x in rest {
+        *x = scalar(*x);
+    }
+    Simd::from_array(x)
+}
+
+/// Implements [`Estimate`] for vectors with the packed instructions, which take `$w` lanes at a time.
+#[cfg(any(
+    target_arch = "x86_64",
+    all(target_arch = "x86", target_feature = "sse"),
+    target_arch = "aarch64"
+))]
+macro_rules! packed {
+    ($t:ident, $w:literal, $recip:path, $rsqrt:path) => {
+        impl<const N: usize> Estimate for Simd<$t, N> {
+            #[inline(always)]
+            fn recip_estimate(self) -> Self {
+                // SAFETY: the instruction set is part of the target.
+                chunked::<_, N, $w>(
+                    self,
+                    |x| unsafe { $recip(x.into()) }.into(),
+                    $t::recip_estimate,
+                )
+            }
+
+            #[inline(always)]
+            fn rsqrt_estimate(self) -> Self {
+                // SAFETY: the instruction set is part of the target.
+                chunked::<_, N, $w>(
+                    self,
+                    |x| unsafe { $rsqrt(x.into()) }.into(),
+                    $t::rsqrt_estimate,
+                )
+            }
+        }
+    };
+}
+
+/// Implements [`Estimate`] for vectors lane by lane, where there are no packed instructions.
+#[cfg(not(any(target_arch = "x86_64", target_arch = "aarch64")))]
+macro_rules! lanes {
+    ($t:ident) => {
+        impl<const N: usize> Estimate for Simd<$t, N> {
+            #[inline(always)]
+            fn recip_estimate(self) -> Self {
+                Simd::from_array(self.to_array().map($t::recip_estimate))
+            }
+
+            #[inline(always)]
+            fn rsqrt_estimate(self) -> Self {
+                Simd::from_array(self.to_array().map($t::rsqrt_estimate))
+            }
+        }
+    };
+}
+
+#[cfg(all(target_arch = "x86_64", target_feature = "avx"))]
+packed!(
+    f32,
+    8,
+    core::arch::x86_64::_mm256_rcp_ps,
+    core::arch::x86_64::_mm256_rsqrt_ps
+);
+#[cfg(all(target_arch = "x86_64", not(target_feature = "avx")))]
+packed!(
+    f32,
+    4,
+    core::arch::x86_64::_mm_rcp_ps,
+    core::arch::x86_64::_mm_rsqrt_ps
+);
+#[cfg(all(target_arch = "x86", target_feature = "sse"))]
+packed!(
+    f32,
+    4,
+    core::arch::x86::_mm_rcp_ps,
+    core::arch::x86::_mm_rsqrt_ps
+);
+#[cfg(target_arch = "aarch64")]
+packed!(
+    f32,
+    4,
+    core::arch::aarch64::vrecpeq_f32,
+    core::arch::aarch64::vrsqrteq_f32
+);
+#[cfg(target_arch = "aarch64")]
+packed!(
+    f64,
+    2,
+    core::arch::aarch64::vrecpeq_f64,
+    core::arch::aarch64::vrsqrteq_f64
+);
+#[cfg(not(any(
+    target_arch = "x86_64",
+    all(target_arch = "x86", target_feature = "sse"),
+    target_arch = "aarch64"
+)))]
+lanes!(f32);
+#[cfg(not(any(target_arch = "x86_64", target_arch = "aarch64")))]
+lanes!(f64);
+
+#[cfg(target_arch = "x86_64")]
+impl<const N: usize> Estimate for Simd<f64, N> {
+    #[inline(always)]
+    fn recip_estimate(self) -> Self {
+        if avx512() {
+            // SAFETY: the cpu supports avx512f.
+            unsafe { rcp14_pd(self) }
+        } else {
+            Simd::from_array(self.to_array().map(|x| 1.0 / x))
+        }
+    }
+
+    #[inline(always)]
+    fn rsqrt_estimate(self) -> Self {
+        if avx512() {
+            // SAFETY: the cpu supports avx512f.
+            unsafe { rsqrt14_pd(self) }
+        } else {
+            Simd::from_array(self.to_array().map(|x| 1.0 / x.sqrt()))
+        }
+    }
+}
+
+#[cfg(target_arch = "x86_64")]
+#[target_feature(enable = "avx512f")]
+unsafe fn rcp14_pd<const N: usize>(x: Simd<f64, N>) -> Simd<f64, N> {
+    use core::arch::x86_64::_mm512_rcp14_pd;
+    // SAFETY: avx512f is enabled here too.
+    chunked::<_, N, 8>(
+        x,
+        |x| _mm512_rcp14_pd(x.into()).into(),
+        |x| unsafe { rcp14(x) },
+    )
+}
+
+#[cfg(target_arch = "x86_64")]
+#[target_feature(enable = "avx512f")]
+unsafe fn rsqrt14_pd<const N: usize>(x: Simd<f64, N>) -> Simd<f64, N> {
+    use core::arch::x86_64::_mm512_rsqrt14_pd;
+    // SAFETY: avx512f is enabled here too.
+    chunked::<_, N, 8>(
+        x,
+        |x| _mm512_rsqrt14_pd(x.into()).into(),
+        |x| unsafe { rsqrt14(x) },
+    )
+}
+
+macro_rules! estimate {
+    ($t:ident, $packed:ident) => {
+        impl<P: Policy> FFloat<$t, P> {
+            /// Approximates `1 / self`, with a single hardware estimate instruction
+            /// (`rcpss` on x86, with around 12 correct bits; `frecpe` on aarch64, with around 8).
+            /// Where there is no such instruction (or, for `f64` on x86, on a cpu without `avx512f`, which is detected at runtime), this is the exact reciprocal.
+            ///
+            /// Use this when the result only needs to be roughly right (normalizing a direction for a heuristic, a initial guess), and refer to [`recip_refined`](Self::recip_refined) for more precision.
+            #[doc = include_str!("ffloat_safety_notice.md")]
+            /// ```
+            /// # use umath::FFloat;
+            #[doc = concat!("let x = unsafe { FFloat::new(3.0", stringify!($t), ") };")]
+            /// assert!((*x.recip_estimate() - 1.0 / 3.0).abs() < 1.0 / 256.0);
+            /// ```
+            #[inline(always)]
+            pub fn recip_estimate(self) -> Self {
+                unsafe { Self::wrap(self.raw().recip_estimate()) }
+            }
+
+            /// Approximates `1 / self.sqrt()`, with a single hardware estimate instruction
+            /// (`rsqrtss` on x86, with around 12 correct bits; `frsqrte` on aarch64, with around 8).
+            /// Where there is no such instruction (or, for `f64` on x86, on a cpu without `avx512f`, which is detected at runtime), this is exact (up to the two roundings).
+            ///
+            /// Refer to [`rsqrt_refined`](Self::rsqrt_refined) for more precision.
+            #[doc = include_str!("ffloat_safety_notice.md")]
+            /// ```
+            /// # use umath::FFloat;
+            #[doc = concat!("let x = unsafe { FFloat::new(4.0", stringify!($t), ") };")]
+            /// assert!((*x.rsqrt_estimate() - 0.5).abs() < 0.5 / 256.0);
+            /// ```
+            #[inline(always)]
+            pub fn rsqrt_estimate(self) -> Self {
+                unsafe { Self::wrap(self.raw().rsqrt_estimate()) }
+            }
+
+            /// [`recip_estimate`](Self::recip_estimate), followed by one newton step (`r * (2 - self * r)`), which roughly doubles the correct bits:
+            /// within a few ulps of `1 / self` for `f32` on x86, and around 16 bits on aarch64.
+            /// This is still cheaper than a division, where the division is not pipelined (and much cheaper, for packed floats).
+            #[doc = include_str!("ffloat_safety_notice.md")]
+            /// ```
+            /// # use umath::FFloat;
+            #[doc = concat!("let x = unsafe { FFloat::new(3.0", stringify!($t), ") };")]
+            /// assert!((*x.recip_refined() - 1.0 / 3.0).abs() < 1e-4);
+            /// ```
+            #[inline(always)]
+            pub fn recip_refined(self) -> Self {
+                let x = self.raw();
+                let r = x.recip_estimate();
+                unsafe { Self::wrap(r * (2.0 - x * r)) }
+            }
+
+            /// [`rsqrt_estimate`](Self::rsqrt_estimate), followed by one newton step (`r * (1.5 - self / 2 * r * r)`), which roughly doubles the correct bits.
+            #[doc = include_str!("ffloat_safety_notice.md")]
+            /// ```
+            /// # use umath::FFloat;
+            #[doc = concat!("let x = unsafe { FFloat::new(4.0", stringify!($t), ") };")]
+            /// assert!((*x.rsqrt_refined() - 0.5).abs() < 1e-4);
+            /// ```
+            #[inline(always)]
+            pub fn rsqrt_refined(self) -> Self {
+                let x = self.raw();
+                let r = x.rsqrt_estimate();
+                unsafe { Self::wrap(r * (1.5 - 0.5 * x * r * r)) }
+            }
+        }
+
+        /// Packed estimates, with the packed versions of the instructions (`rcpps` | `rsqrtps` on x86, 8 lanes at a time with `avx`; `frecpe` | `frsqrte` on aarch64),
+        /// and the scalar ones for any lanes left over. The precision is that of the scalar estimates.
+        /// ```
+        #[doc = concat!("# use umath::{FFloat, ", stringify!($packed), "};")]
+        #[doc = concat!("let f = |x: ", stringify!($t), "| unsafe { FFloat::new(x) };")]
+        #[doc = concat!("let x = ", stringify!($packed), "::from([f(1.0), f(2.0), f(4.0), f(8.0)]);")]
+        /// let r = x.rsqrt_refined().to_array();
+        /// assert!((*r[2] - 0.5).abs() < 1e-4);
+        /// ```
+        impl<const N: usize, P: Policy> FFloat<Simd<$t, N>, P> {
+            /// Approximates `1 / self` in every lane. Refer to [`FFloat::recip_estimate`].
+            #[doc = include_str!("ffloat_safety_notice.md")]
+            #[inline(always)]
+            pub fn recip_estimate(self) -> Self {
+                unsafe { Self::wrap(self.raw().recip_estimate()) }
+            }
+
+            /// Approximates `1 / self.sqrt()` in every lane. Refer to [`FFloat::rsqrt_estimate`].
+            #[doc = include_str!("ffloat_safety_notice.md")]
+            #[inline(always)]
+            pub fn rsqrt_estimate(self) -> Self {
+                unsafe { Self::wrap(self.raw().rsqrt_estimate()) }
+            }
+
+            /// [`recip_estimate`](Self::recip_estimate), followed by one newton step in every lane. Refer to [`FFloat::recip_refined`].
+            #[doc = include_str!("ffloat_safety_notice.md")]
+            #[inline(always)]
+            pub fn recip_refined(self) -> Self {
+                let x = self.raw();
+                let r = x.recip_estimate();
+                unsafe { Self::wrap(r * (Simd::splat(2.0) - x * r)) }
+            }
+
+            /// [`rsqrt_estimate`](Self::rsqrt_estimate), followed by one newton step in every lane. Refer to [`FFloat::rsqrt_refined`].
+            #[doc = include_str!("ffloat_safety_notice.md")]
+            #[inline(always)]
+            pub fn rsqrt_refined(self) -> Self {
+                let x = self.raw();
+                let r = x.rsqrt_estimate();
+                unsafe { Self::wrap(r * (Simd::splat(1.5) - Simd::splat(0.5) * x * r * r)) }
+            }
+        }
+    };
+}
+
+estimate!(f32, FF32x4);
+estimate!(f64, FF64x4);
+
+/// The documented relative errors: of a estimate, and of a refined one.
+#[cfg(test)]
+const ERROR: [f64; 2] = if cfg!(target_arch = "aarch64") {
+    [1.0 / 256.0, 1.0 / 32768.0]
+} else {
+    [1.5 / 4096.0, 1.0 / 1_048_576.0]
+};
+
+#[cfg(test)]
+fn error(x: f64, approx: f64, exact: f64) -> f64 {
+    assert!(approx.is_finite(), "{x}");
+    ((approx - exact) / exact).abs()
+}
+
+#[test]
+fn bits() {
+    for i in 1..2000u16 {
+        let y = f32::from(i) * 0.37;
+        let x = f64::from(y);
+        let a = unsafe { FFloat::<f32>::new(y) };
+        let b = unsafe { FFloat::<f64>::new(x) };
+        for (approx, refined) in [
+            (
+                f64::from(*a.recip_estimate()),
+                f64::from(*a.recip_refined()),
+            ),
+            (*b.recip_estimate(), *b.recip_refined()),
+        ] {
+            assert!(error(x, approx, 1.0 / x) < ERROR[0]);
+            assert!(error(x, refined, 1.0 / x) < ERROR[1]);
+        }
+        for (approx, refined) in [
+            (
+                f64::from(*a.rsqrt_estimate()),
+                f64::from(*a.rsqrt_refined()),
+            ),
+            (*b.rsqrt_estimate(), *b.rsqrt_refined()),
+        ] {
+            assert!(error(x, approx, x.sqrt().recip()) < ERROR[0]);
+            assert!(error(x, refined, x.sqrt().recip()) < ERROR[1]);
+        }
+    }
+}
+
+#[test]
+#[allow(clippy::cast_precision_loss)]
+fn packed() {
+    fn check<const N: usize>(x: [f64; N], estimates: [[f64; N]; 4]) {
+        let [recip, rsqrt, recip_refined, rsqrt_refined] = estimates;
+        for i in 0..N {
+            let rs = x[i].sqrt().recip();
+            assert!(error(x[i], recip[i], 1.0 / x[i]) < ERROR[0]);
+            assert!(error(x[i], rsqrt[i], rs) < ERROR[0]);
+            assert!(error(x[i], recip_refined[i], 1.0 / x[i]) < ERROR[1]);
+            assert!(error(x[i], rsqrt_refined[i], rs) < ERROR[1]);
+        }
+    }
+    macro_rules! lanes {
+        ($t:ident, $n:literal, $i:ident) => {{
+            let x: [$t; $n] = core::array::from_fn(|j| ($i * $n + j + 1) as $t * 0.37);
+            let v = unsafe { FFloat::<Simd<$t, $n>>::new(Simd::from_array(x)) };
+            let get = |v: FFloat<Simd<$t, $n>>| v.to_array().map(|x| f64::from(*x));
+            check(
+                x.map(f64::from),
+                [
+                    get(v.recip_estimate()),
+                    get(v.rsqrt_estimate()),
+                    get(v.recip_refined()),
+                    get(v.rsqrt_refined()),
+                ],
+            );
+        }};
+    }
+    // the widths cover whole packed chunks, and lanes left over.
+    for i in 0..200usize {
+        lanes!(f32, 2, i);
+        lanes!(f32, 4, i);
+        lanes!(f32, 16, i);
+        lanes!(f64, 2, i);
+        lanes!(f64, 8, i);
+    }
+}
@@ -0,0 +1,286 @@
+//! scoped control of the floating point environment: flushing denormals to zero, and the rounding mode.
+//!
+//! The environment is a per thread control register (MXCSR on x86, FPCR on aarch64), so the guards set it for the current thread only,
+//! restore it when dropped, and are not [`Send`]. On other targets, the guards do nothing (check [`SUPPORTED`]).
+//!
+//! Rust (and LLVM) assume the default environment: operations may be evaluated at compile time (constant folded), or moved across the guards, ignoring these settings.
+//! So creating a guard is `unsafe`: the code it covers must not rely on the results of float operations being those of the default environment.
+//! ```
+//! # use umath::fenv::{self, DenormalGuard};
+//! use std::hint::black_box;
+//! let tiny = black_box(f32::MIN_POSITIVE);
+//! {
+//!     // SAFETY: nothing here relies on subnormal results.
+//!     let _guard = unsafe { DenormalGuard::new() };
+//!     // the (subnormal) result is flushed to zero
+//!     assert!(!fenv::SUPPORTED || black_box(tiny / 2.0) == 0.0);
+//! }
+//! assert_ne!(black_box(tiny / 2.0), 0.0);
+//! ```
+use core::marker::PhantomData;
+
+#[cfg(any(
+    target_arch = "x86_64",
+    all(target_arch = "x86", target_feature = "sse")
+))]
+mod register {
+    use core::arch::asm;
+
+    pub type Register = u32;
+    pub const SUPPORTED: bool = true;
+    /// FTZ (flush results to zero) and DAZ (treat inputs as zero), in MXCSR.
+    pub const DENORMALS: Register = 1 << 15 | 1 << 6;
+    /// RC, in MXCSR.
+    pub const ROUNDING: Register = 3 << 13;
+    /// The RC of each [`RoundingMode`](super::RoundingMode).
+    pub const MODES: [Register; 4] = [0, 3 << 13, 2 << 13, 1 << 13];
+
+    #[inline(always)]
+    pub fn get() -> Register {
+        let mut x: Register = 0;
+        // SAFETY: stmxcsr only writes x.
+        unsafe {
+            asm!("stmxcsr [{}]", in(reg) &raw mut x, options(nostack, preserves_flags));
+        }
+        x
+    }
+
+    #[inline(always)]
+    pub fn set(x: Register) {
+        // SAFETY: ldmxcsr only reads x; the reserved bits stay as read by `get`.
+        unsafe {
+            asm!("ldmxcsr [{}]", in(reg) &raw const x, options(nostack, preserves_flags));
+        }
+    }
+}
+
+#[cfg(target_arch = "aarch64")]
+mod register {
+    use core::arch::asm;
+
+    pub type Register = u64;
+    pub const SUPPORTED: bool = true;
+    /// FZ (flush inputs and results to zero), in FPCR.
+    pub const DENORMALS: Register = 1 << 24;
+    /// RMode, in FPCR.
+    pub const ROUNDING: Register = 3 << 22;
+    /// The RMode of each [`RoundingMode`](super::RoundingMode).
+    pub const MODES: [Register; 4] = [0, 3 << 22, 1 << 22, 2 << 22];
+
+    #[inline(always)]
+    pub fn get() -> Register {
+        let x: Register;
+        // SAFETY: reading FPCR has no side effects.
+        unsafe { asm!("mrs {}, fpcr", out(reg) x, options(nomem, nostack, preserves_flags)) };
+        x
+    }
+
+    #[inline(always)]
+    pub fn set(x: Register) {
+        // SAFETY: the reserved bits stay as read by `get`.
+        unsafe { asm!("msr fpcr, {}", in(reg) x, options(nostack, preserves_flags)) };
+    }
+}
+
+#[cfg(not(any(
+    target_arch = "x86_64",
+    all(target_arch = "x86", target_feature = "sse"),
+    target_arch = "aarch64"
+)))]
+mod register {
+    pub type Register = u32;
+    pub const SUPPORTED: bool = false;
+    pub const DENORMALS: Register = 0;
+    pub const ROUNDING: Register = 0;
+    pub const MODES: [Register; 4] = [0; 4];
+
+    pub fn get() -> Register {
+        0
+    }
+
+    pub fn set(_: Register) {}
+}
+
+use register::{get, set, Register, DENORMALS, MODES, ROUNDING};
+
+/// Whether the guards have an effect on this target (x86 with SSE, and aarch64).
+pub const SUPPORTED: bool = register::SUPPORTED;
+
+/// Flushes denormal (subnormal) floats to zero, on the current thread, until dropped.
+///
+/// Once a signal decays into the subnormal range (a reverb tail, a filter with no input, a simulation settling), every operation on it can take a hundred times longer,
+/// as most cpus handle subnormals in microcode. With this guard, subnormal inputs are treated as 0, and subnormal results are flushed to 0 (FTZ and DAZ on x86, FZ on aarch64),
+/// which is inaudible | invisible in those cases, and keeps the cost constant.
+///
+/// Guards can be nested: each one restores the state it found.
+#[must_use = "the denormals are only flushed until the guard is dropped"]
+pub struct DenormalGuard {
+    previous: Register,
+    /// the environment belongs to this thread.
+    thread: PhantomData<*const ()>,
+}
+
+impl DenormalGuard {
+    /// Starts flushing denormals to zero.
+    ///
+    /// # Safety
+    ///
+    /// LLVM assumes subnormals are not flushed, and may evaluate (or move) float operations accordingly, so while the guard lives,
+    /// the results of float operations may be either those with, or without, flushing.
+    /// No code that runs on this thread until it is dropped may rely on either for soundness
+    /// (such as a invariant of a [`FFloat`](crate::FFloat) being upheld by a computation whose result is subnormal, or 0).
+    pub unsafe fn new() -> Self {
+        let previous = get();
+        set(previous | DENORMALS);
+        Self {
+            previous,
+            thread: PhantomData,
+        }
+    }
+
+    /// Checks if denormals are currently being flushed to zero, on this thread.
+    #[must_use]
+    pub fn active() -> bool {
+        SUPPORTED && get() & DENORMALS == DENORMALS
+    }
+}
+
+impl Drop for DenormalGuard {
+    fn drop(&mut self) {
+        set(self.previous);
+    }
+}
+
+impl core::fmt::Debug for DenormalGuard {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("DenormalGuard").finish_non_exhaustive()
+    }
+}
+
+/// How the result of a operation is rounded to a float.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub enum RoundingMode {
+    /// To the nearest float, ties to even: the default.
+    #[default]
+    Nearest,
+    /// Toward zero (truncation).
+    TowardZero,
+    /// Toward +[`INF`](f32::INFINITY).
+    Up,
+    /// Toward -[`INF`](f32::INFINITY).
+    Down,
+}
+
+impl RoundingMode {
+    /// The current rounding mode, of this thread. This is always [`Nearest`](Self::Nearest) on unsupported targets.
+    #[must_use]
+    pub fn current() -> Self {
+        let bits = get() & ROUNDING;
+        [Self::Nearest, Self::TowardZero, Self::Up, Self::Down]
+            .into_iter()
+            .find(|&mode| MODES[mode as usize] == bits)
+            .unwrap_or_default()
+    }
+}
+
+/// Sets the hardware rounding mode, on the current thread, until dropped.
+///
+/// Directed rounding makes a single operation round its result down (or up), so that a lower (upper) bound is tight,
+/// instead of being widened by a ulp afterwards (as [`Interval`](crate::interval::Interval) does, to be correct in any mode);
+/// and running the same computation in each mode is a cheap estimate of how sensitive it is to rounding error.
+///
+/// As LLVM assumes the default mode, a operation the compiler evaluates itself (with constant operands) is always rounded to nearest;
+/// pass the operands through [`black_box`](core::hint::black_box) to be sure.
+/// ```
+/// # use umath::fenv::{self, RoundingGuard, RoundingMode};
+/// use std::hint::black_box;
+/// let third = |mode| {
+///     // SAFETY: only a plain division runs in the other mode.
+///     let _guard = unsafe { RoundingGuard::new(mode) };
+///     black_box(1.0f32) / black_box(3.0)
+/// };
+/// if fenv::SUPPORTED {
+///     assert!(third(RoundingMode::Down) < third(RoundingMode::Up));
+///     assert_eq!(third(RoundingMode::Down), third(RoundingMode::TowardZero));
+/// }
+/// assert_eq!(RoundingMode::current(), RoundingMode::Nearest);
+/// ```
+#[must_use = "the rounding mode is only set until the guard is dropped"]
+pub struct RoundingGuard {
+    previous: Register,
+    /// the environment belongs to this thread.
+    thread: PhantomData<*const ()>,
+}
+
+impl RoundingGuard {
+    /// Starts rounding with `mode`.
+    ///
+    /// # Safety
+    ///
+    /// LLVM assumes the default mode, and may evaluate (or move) float operations accordingly, so while the guard lives,
+    /// the results of float operations may be rounded either with `mode`, or to nearest.
+    /// No code that runs on this thread until it is dropped may rely on either for soundness;
+    /// in particular, nothing that relies on operations being correctly rounded to nearest, such as the exact arithmetic of the [`predicates`](crate::predicates).
+    pub unsafe fn new(mode: RoundingMode) -> Self {
+        let previous = get();
+        set(previous & !ROUNDING | MODES[mode as usize]);
+        Self {
+            previous,
+            thread: PhantomData,
+        }
+    }
+}
+
+impl Drop for RoundingGuard {
+    fn drop(&mut self) {
+        set(self.previous);
+    }
+}
+
+impl core::fmt::Debug for RoundingGuard {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("RoundingGuard").finish_non_exhaustive()
+    }
+}
+
+#[test]
+#[allow(clippy::float_cmp)]
+fn nested() {
+    use core::hint::black_box;
+    let tiny = black_box(f64::MIN_POSITIVE);
+    assert!(!DenormalGuard::active());
+    {
+        // SAFETY: only plain multiplications run with the guards.
+        let _outer = unsafe { DenormalGuard::new() };
+        {
+            let _inner = unsafe { DenormalGuard::new() };
+        }
+        assert_eq!(DenormalGuard::active(), SUPPORTED);
+        if SUPPORTED {
+            assert_eq!(black_box(tiny) * black_box(0.25), 0.0);
+        }
+    }
+    assert!(!DenormalGuard::active());
+    assert!(black_box(tiny) * black_box(0.25) > 0.0);
+}
+
+#[test]
+#[allow(clippy::float_cmp)]
+fn directed() {
+    use core::hint::black_box;
+    let tenth = |mode| {
+        // SAFETY: only a plain division runs in the other mode.
+        let _guard = unsafe { RoundingGuard::new(mode) };
+        assert!(!SUPPORTED || RoundingMode::current() == mode);
+        -black_box(1.0f64) / black_box(10.0)
+    };
+    let nearest = tenth(RoundingMode::Nearest);
+    assert_eq!(nearest, -0.1);
+    if SUPPORTED {
+        // the nearest f64 to 0.1 is above it
+        assert_eq!(tenth(RoundingMode::Down), nearest);
+        assert_eq!(tenth(RoundingMode::Up), nearest.next_up());
+        assert_eq!(tenth(RoundingMode::TowardZero), nearest.next_up());
+    }
+    assert_eq!(RoundingMode::current(), RoundingMode::Nearest);
+}
@@ -0,0 +1,402 @@
+//! fast fourier transforms, of power of two sizes.
+//!
+//! A [`Fft`] (or [`RealFft`]) is a plan for one size: it precomputes the twiddle factors and the bit reversal permutation once, and then transforms any number of buffers in place.
+//! The transforms are generic over [`FloatAlone`], and work on [`Complex`] numbers, so a <code>[Fft]<[FFloat]></code> runs every butterfly with the fast intrinsics.
+//!
+//! For a few frequencies, a [`Goertzel`] detector is cheaper than a whole transform.
+//!
+//! The forward transform is `X[k] = Σ x[j] e^(-2πijk / n)`, unscaled; the inverse is scaled by `1 / n`, so that it undoes the forward transform.
+//! ```
+//! # use umath::{FFloat, complex::Complex, fft::Fft};
+//! let f = |x| unsafe { FFloat::new(x) };
+//! let fft = Fft::new(8);
+//! // a cosine, of 1 cycle per 8 samples
+//! let mut x: Vec<_> = (0..8)
+//!     .map(|j| Complex::from(f((core::f64::consts::TAU * f64::from(j) / 8.0).cos())))
+//!     .collect();
+//! fft.forward(&mut x);
+//! assert!((*x[1].re - 4.0).abs() < 1e-12);
+//! assert!((*x[7].re - 4.0).abs() < 1e-12);
+//! assert!(*x[2].norm() < 1e-12);
+//! fft.inverse(&mut x);
+//! assert!((*x[0].re - 1.0).abs() < 1e-12);
+//! ```
+use crate::complex::Complex;
+use crate::generic_float::{lit, FloatAlone};
+
+/// A complex fast fourier transform of a power of two size: radix 4 butterflies, with one radix 2 pass when the size is not a power of 4.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Fft<F> {
+    /// `e^(-2πij / n)`, for each `j < n`.
+    twiddles: Vec<Complex<F>>,
+    /// The pairs to swap for the bit reversal permutation.
+    swaps: Vec<(usize, usize)>,
+}
+
+/// `e^(-2πij / n)`, computed in [`f64`].
+#[allow(clippy::cast_precision_loss)]
+fn twiddle<F: FloatAlone>(j: usize, n: usize) -> Complex<F> {
+    let angle = -core::f64::consts::TAU * j as f64 / n as f64;
+    Complex::new(lit(angle.cos()), lit(angle.sin()))
+}
+
+impl<F: FloatAlone> Fft<F> {
+    /// Plans a transform of `size` points.
+    ///
+    /// # Panics
+    ///
+    /// If `size` is not a power of two.
+    #[must_use]
+    #[track_caller]
+    pub fn new(size: usize) -> Self {
+        assert!(
+            size.is_power_of_two(),
+            "the fft size must be a power of two"
+        );
+        let bits = size.trailing_zeros();
+        let swaps = (0..size)
+            .filter_map(|i| {
+                let j = i
+                    .reverse_bits()
+                    .checked_shr(usize::BITS - bits)
+                    .unwrap_or(0);
+                (i < j).then_some((i, j))
+            })
+            .collect();
+        Self {
+            twiddles: (0..size).map(|j| twiddle(j, size)).collect(),
+            swaps,
+        }
+    }
+
+    /// The number of points transformed.
+    #[must_use]
+    pub fn size(&self) -> usize {
+        self.twiddles.len()
+    }
+
+    /// Transforms `buffer` in place, from the time domain to the frequency domain.
+    ///
+    /// # Panics
+    ///
+    /// If `buffer` is not [`size`](Self::size) long.
+    #[track_caller]
+    #[allow(clippy::many_single_char_names)]
+    pub fn forward(&self, buffer: &mut [Complex<F>]) {
+        let n = self.size();
+        assert_eq!(buffer.len(), n, "the buffer must be as long as the fft");
+        for &(i, j) in &self.swaps {
+            buffer.swap(i, j);
+        }
+        // the transforms of length m, in consecutive blocks, are combined 4 at a time.
+        let mut m = 1;
+        if n.trailing_zeros() % 2 == 1 {
+            for pair in buffer.chunks_exact_mut(2) {
+                let (a, b) = (pair[0], pair[1]);
+                (pair[0], pair[1]) = (a + b, a - b);
+            }
+            m = 2;
+        }
+        while m < n {
+            let stride = n / (4 * m);
+            for block in buffer.chunks_exact_mut(4 * m) {
+                for k in 0..m {
+                    // the blocks hold the transforms of the elements ≡ 0, 2, 1, 3 (mod 4), in that order.
+                    let a = block[k];
+                    let b = block[k + m] * self.twiddles[2 * k * stride];
+                    let c = block[k + 2 * m] * self.twiddles[k * stride];
+                    let d = block[k + 3 * m] * self.twiddles[3 * k * stride];
+                    let (even, odd) = (a + b, a - b);
+                    let (sum, difference) = (c + d, (c - d).mul_i());
+                    block[k] = even + sum;
+                    block[k + m] = odd - difference;
+                    block[k + 2 * m] = even - sum;
+                    block[k + 3 * m] = odd + difference;
+                }
+            }
+            m *= 4;
+        }
+    }
+
+    /// Transforms `buffer` in place, from the frequency domain back to the time domain (scaled by `1 / n`, so that this undoes [`forward`](Self::forward)).
+    ///
+    /// # Panics
+    ///
+    /// If `buffer` is not [`size`](Self::size) long.
+    #[track_caller]
+    #[allow(clippy::cast_precision_loss)]
+    pub fn inverse(&self, buffer: &mut [Complex<F>]) {
+        // the inverse transform is the conjugate of the forward transform of the conjugate.
+        for x in &mut *buffer {
+            *x = x.conj();
+        }
+        self.forward(buffer);
+        let scale = lit::<F>(1.0 / self.size() as f64);
+        for x in buffer {
+            *x = x.conj() * scale;
+        }
+    }
+}
+
+/// A fast fourier transform of real signals, of a power of two size `n`:
+/// the `n` real samples are packed into a complex transform of half the size, so this is about twice as fast as a [`Fft`] of the signal.
+///
+/// As the transform of a real signal is conjugate symmetric (`X[n - k] = conj(X[k])`), only the `n / 2 + 1` bins from 0 to the nyquist frequency are produced
+/// (and [`X[0]`](Complex::re) and `X[n / 2]` are real).
+/// ```
+/// # use umath::{FFloat, complex::Complex, fft::RealFft};
+/// let f = |x| unsafe { FFloat::new(x) };
+/// let fft = RealFft::new(8);
+/// let signal = [1.0, 2.0, 3.0, 4.0, 0.0, 0.0, 0.0, 0.0].map(f);
+/// let mut spectrum = vec![Complex::from(f(0.0f32)); 5];
+/// fft.forward(&signal, &mut spectrum);
+/// assert_eq!(*spectrum[0].re, 10.0);
+/// let mut back = [f(0.0); 8];
+/// fft.inverse(&mut spectrum, &mut back);
+/// assert!(back.iter().zip(signal).all(|(x, y)| (**x - *y).abs() < 1e-6));
+/// ```
+#[derive(Clone, Debug, PartialEq)]
+pub struct RealFft<F> {
+    half: Fft<F>,
+    /// `e^(-2πik / n)`, for each `k <= n / 2`.
+    twiddles: Vec<Complex<F>>,
+}
+
+impl<F: FloatAlone> RealFft<F> {
+    /// Plans a transform of `size` real samples.
+    ///
+    /// # Panics
+    ///
+    /// If `size` is not a power of two, or is 1.
+    #[must_use]
+    #[track_caller]
+    pub fn new(size: usize) -> Self {
+        assert!(
+            size.is_power_of_two() && size > 1,
+            "the fft size must be a power of two, and more than 1"
+        );
+        Self {
+            half: Fft::new(size / 2),
+            twiddles: (0..=size / 2).map(|k| twiddle(k, size)).collect(),
+        }
+    }
+
+    /// The number of real samples transformed.
+    #[must_use]
+    pub fn size(&self) -> usize {
+        self.half.size() * 2
+    }
+
+    /// Transforms the real `input` (of [`size`](Self::size) samples) to its spectrum, the `size / 2 + 1` bins from 0 to the nyquist frequency.
+    ///
+    /// # Panics
+    ///
+    /// If `input` is not [`size`](Self::size) long, or `output` is not `size / 2 + 1` long.
+    #[track_caller]
+    pub fn forward(&self, input: &[F], output: &mut [Complex<F>]) {
+        let half = self.half.size();
+        assert_eq!(
+            input.len(),
+            half * 2,
+            "the input must be as long as the fft"
+        );
+        assert_eq!(
+            output.len(),
+            half + 1,
+            "the output must be half as long as the fft, plus one"
+        );
+        // z[j] = x[2j] + x[2j + 1] i
+        for (z, pair) in output.iter_mut().zip(input.chunks_exact(2)) {
+            *z = Complex::new(pair[0], pair[1]);
+        }
+        self.half.forward(&mut output[..half]);
+        let z0 = output[0];
+        output[0] = Complex::from(z0.re + z0.im);
+        output[half] = Complex::from(z0.re - z0.im);
+        let halve = lit::<F>(0.5);
+        // the transforms of the even and odd samples are the conjugate symmetric and antisymmetric parts of Z, and X[k] = E[k] + e^(-2πik / n) O[k].
+        for k in 1..=half / 2 {
+            let (z, mirror) = (output[k], output[half - k].conj());
+            let even = (z + mirror) * halve;
+            let odd = (z - mirror).mul_i() * -halve;
+            output[k] = even + odd * self.twiddles[k];
+            output[half - k] = (even - odd * self.twiddles[k]).conj();
+        }
+    }
+
+    /// Transforms the spectrum `input` (the `size / 2 + 1` bins from 0 to the nyquist frequency) back to the real signal, scaled by `1 / n` (so that this undoes [`forward`](Self::forward)).
+    ///
+    /// `input` is used as scratch space, and left unspecified. The imaginary parts of the first and last bins are ignored.
+    ///
+    /// # Panics
+    ///
+    /// If `input` is not `size / 2 + 1` long, or `output` is not [`size`](Self::size) long.
+    #[track_caller]
+    pub fn inverse(&self, input: &mut [Complex<F>], output: &mut [F]) {
+        let half = self.half.size();
+        assert_eq!(
+            input.len(),
+            half + 1,
+            "the input must be half as long as the fft, plus one"
+        );
+        assert_eq!(
+            output.len(),
+            half * 2,
+            "the output must be as long as the fft"
+        );
+        let halve = lit::<F>(0.5);
+        let (first, last) = (input[0].re, input[half].re);
+        // undoes the last step of the forward transform: Z[k] = E[k] + i O[k]
+        input[0] = Complex::new(first + last, first - last) * halve;
+        for k in 1..=half / 2 {
+            let (x, mirror) = (input[k], input[half - k].conj());
+            let even = (x + mirror) * halve;
+            let odd = (x - mirror) * self.twiddles[k].conj() * halve;
+            input[k] = even + odd.mul_i();
+            input[half - k] = (even - odd.mul_i()).conj();
+        }
+        self.half.inverse(&mut input[..half]);
+        for (pair, z) in output.chunks_exact_mut(2).zip(&*input) {
+            (pair[0], pair[1]) = (z.re, z.im);
+        }
+    }
+}
+
+/// The goertzel algorithm: the power of a single frequency, over a stream of samples, with one multiply add (and no complex math) per sample.
+///
+/// A fft computes every bin in `O(log n)` per bin; this computes one (at any frequency, not only the bins of a power of two size) in `O(1)` per sample,
+/// so it is the cheaper choice for detecting a handful of known frequencies, such as dtmf tones.
+/// ```
+/// # use umath::{FFloat, fft::Goertzel};
+/// let f = |x| unsafe { FFloat::new(x) };
+/// let tone = |frequency: f64| (0..400).map(move |j| f((core::f64::consts::TAU * frequency * f64::from(j)).sin()));
+/// let mut detector = Goertzel::new(f(0.1));
+/// detector.process_block(&tone(0.1).collect::<Vec<_>>());
+/// // a sine of amplitude 1, over 400 samples
+/// assert!((*detector.magnitude() - 200.0).abs() < 1e-9);
+/// detector.reset();
+/// detector.process_block(&tone(0.15).collect::<Vec<_>>());
+/// assert!(*detector.magnitude() < 1e-9);
+/// ```
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Goertzel<F> {
+    /// `2 cos(2π frequency)`
+    coefficient: F,
+    /// The last two outputs of the resonator.
+    state: [F; 2],
+}
+
+impl<F: FloatAlone> Goertzel<F> {
+    /// Creates a detector for `frequency`, in cycles per sample (the frequency in Hz divided by the sample rate).
+    #[must_use]
+    pub fn new(frequency: F) -> Self {
+        // SAFETY: 0 and π are finite
+        let (zero, π) = unsafe { (F::zero(), F::π()) };
+        Self {
+            coefficient: (frequency * π * lit(2.0)).cos() * lit(2.0),
+            state: [zero; 2],
+        }
+    }
+
+    /// Forgets the samples so far.
+    pub fn reset(&mut self) {
+        // SAFETY: 0 is a valid float
+        self.state = [unsafe { F::zero() }; 2];
+    }
+
+    /// Pushes one sample.
+    #[inline]
+    pub fn process(&mut self, x: F) {
+        let [s1, s2] = self.state;
+        self.state = [x + self.coefficient * s1 - s2, s1];
+    }
+
+    /// Pushes a block of samples.
+    pub fn process_block(&mut self, block: &[F]) {
+        for &x in block {
+            self.process(x);
+        }
+    }
+
+    /// The power of the frequency, over the samples so far: `|X|²`, where `X = Σ x[j] e^(-2πi frequency j)`.
+    ///
+    /// A sine of amplitude `a` at the frequency, over `n` samples, has a power of about `(a n / 2)²`.
+    #[must_use]
+    pub fn power(&self) -> F {
+        let [s1, s2] = self.state;
+        s1 * s1 + s2 * s2 - self.coefficient * s1 * s2
+    }
+
+    /// The magnitude of the frequency, `|X|` (the square root of the [`power`](Self::power)).
+    #[must_use]
+    pub fn magnitude(&self) -> F {
+        // SAFETY: 0 is a valid float
+        self.power().max(unsafe { F::zero() }).sqrt()
+    }
+}
+
+#[test]
+#[allow(clippy::cast_precision_loss)]
+fn transformed() {
+    let signal = |n: usize| -> Vec<Complex<f64>> {
+        (0..n)
+            .map(|j| Complex::new((j as f64 * 0.7).sin() + 0.25, (j as f64 * 1.3).cos()))
+            .collect()
+    };
+    let close = |x: Complex<f64>, y: Complex<f64>| (x - y).norm() < 1e-9;
+    for bits in 0..10 {
+        let n = 1 << bits;
+        let x = signal(n);
+        let mut fast = x.clone();
+        let fft = Fft::new(n);
+        fft.forward(&mut fast);
+        for (k, &fast) in fast.iter().enumerate() {
+            let slow = x
+                .iter()
+                .enumerate()
+                .fold(Complex::from(0.0), |acc, (j, &x)| {
+                    acc + x * twiddle(j * k % n, n)
+                });
+            assert!(close(fast, slow), "{n} {k}");
+        }
+        fft.inverse(&mut fast);
+        assert!(fast.iter().zip(&x).all(|(&a, &b)| close(a, b)), "{n}");
+        if n > 1 {
+            // the real transform matches the complex transform of the real parts.
+            let real: Vec<f64> = x.iter().map(|z| z.re).collect();
+            let mut full: Vec<_> = real.iter().map(|&x| Complex::from(x)).collect();
+            fft.forward(&mut full);
+            let rfft = RealFft::new(n);
+            let mut spectrum = vec![Complex::from(0.0); n / 2 + 1];
+            rfft.forward(&real, &mut spectrum);
+            assert!(
+                spectrum.iter().zip(&full).all(|(&a, &b)| close(a, b)),
+                "{n}"
+            );
+            let mut back = vec![0.0; n];
+            rfft.inverse(&mut spectrum, &mut back);
+            assert!(
+                back.iter().zip(&real).all(|(a, b)| (a - b).abs() < 1e-9),
+                "{n}"
+            );
+        }
+    }
+    // goertzel matches the dft, at any frequency
+    let x = signal(50);
+    for frequency in [0.0, 0.1, 0.123, 0.5] {
+        let mut goertzel = Goertzel::new(frequency);
+        for sample in &x {
+            goertzel.process(sample.re);
+        }
+        let dft = x
+            .iter()
+            .enumerate()
+            .fold(Complex::from(0.0), |acc, (j, x)| {
+                acc + Complex::from_polar(x.re, -core::f64::consts::TAU * frequency * j as f64)
+            });
+        assert!(
+            (goertzel.power() - dft.norm_sqr()).abs() < 1e-9,
+            "{frequency}"
+        );
+    }
+}
@@ -0,0 +1,451 @@
+//! digital filters, for streams of [`FFloat`] samples.
+//!
+//! A [`Fir`] convolves with a (finite) impulse response, and a [`Biquad`] is the standard recursive building block of equalizers and crossovers.
+//! Filters keep their state between calls, so a signal can be processed a sample at a time, or in blocks of any size, with the same result.
+//! ```
+//! # use umath::{FFloat, filter::Fir};
+//! let f = |x| unsafe { FFloat::new(x) };
+//! // a moving average of 4 samples
+//! let mut average = Fir::new(vec![f(0.25f32); 4]);
+//! let mut block = [4.0, 4.0, 4.0, 4.0, 8.0].map(f);
+//! average.process_in_place(&mut block);
+//! assert_eq!(block, [1.0, 2.0, 3.0, 4.0, 5.0]);
+//! ```
+use crate::complex::Complex;
+use crate::generic_float::{lit, Constructors, FloatAlone};
+use crate::policy::Policy;
+use crate::r#trait::FastFloat;
+use crate::{audio, slice, FFloat};
+
+/// A finite impulse response filter: each output is `y[n] = Σ h[j] * x[n - j]`, over the coefficients `h`, and the last `h.len()` inputs.
+///
+/// Every output is a [`dot`](slice::dot) product of the coefficients with the delay line, so the taps are summed with the vectorized (and, under [`Fast`](crate::policy::Fast), fused) kernel.
+/// The delay line is stored twice over, so that the last `h.len()` inputs are always contiguous, and a sample costs one write (not a shift of the whole line).
+#[derive(Clone, Debug, PartialEq)]
+pub struct Fir<F> {
+    /// The coefficients, reversed (the oldest input first), to line up with the delay line.
+    taps: Vec<F>,
+    /// A ring of the last `taps.len()` inputs, with every sample written at `i` and `i + taps.len()`.
+    line: Vec<F>,
+    /// Where the newest input is.
+    position: usize,
+    /// The history and a block of input, for [`process_in_place`](Self::process_in_place).
+    scratch: Vec<F>,
+}
+
+impl<T: FastFloat + Constructors, P: Policy> Fir<FFloat<T, P>> {
+    /// Creates a filter with the `coefficients` (the impulse response, `h[0]` first), and a delay line of zeros.
+    ///
+    /// # Panics
+    ///
+    /// If there are no `coefficients`.
+    #[must_use]
+    #[track_caller]
+    pub fn new(mut coefficients: Vec<FFloat<T, P>>) -> Self {
+        assert!(
+            !coefficients.is_empty(),
+            "a filter needs at least 1 coefficient"
+        );
+        coefficients.reverse();
+        // SAFETY: 0 is a valid float
+        let zero = unsafe { FFloat::zero() };
+        Self {
+            line: vec![zero; coefficients.len() * 2],
+            position: 0,
+            taps: coefficients,
+            scratch: Vec::new(),
+        }
+    }
+
+    /// The coefficients, `h[0]` first.
+    #[must_use]
+    pub fn coefficients(&self) -> impl DoubleEndedIterator<Item = FFloat<T, P>> + '_ {
+        self.taps.iter().rev().copied()
+    }
+
+    /// Clears the delay line, as if every input so far was zero.
+    pub fn reset(&mut self) {
+        // SAFETY: 0 is a valid float
+        self.line.fill(unsafe { FFloat::zero() });
+    }
+
+    /// Filters one sample.
+    #[inline]
+    pub fn process(&mut self, x: FFloat<T, P>) -> FFloat<T, P> {
+        let taps = self.taps.len();
+        self.position = (self.position + 1) % taps;
+        self.line[self.position] = x;
+        self.line[self.position + taps] = x;
+        slice::dot(
+            &self.line[self.position + 1..self.position + 1 + taps],
+            &self.taps,
+        )
+    }
+
+    /// Filters a block of samples, in place.
+    ///
+    /// The block is filtered in one pass over a contiguous copy of the history and the block, which avoids updating the delay line for every sample.
+    pub fn process_in_place(&mut self, buffer: &mut [FFloat<T, P>]) {
+        if buffer.is_empty() {
+            return;
+        }
+        let taps = self.taps.len();
+        self.scratch.clear();
+        // the last `taps - 1` inputs, oldest first.
+        self.scratch
+            .extend_from_slice(&self.line[self.position + 2..self.position + 1 + taps]);
+        self.scratch.extend_from_slice(buffer);
+        for (i, y) in buffer.iter_mut().enumerate() {
+            *y = slice::dot(&self.scratch[i..i + taps], &self.taps);
+        }
+        for i in self.scratch.len() - taps..self.scratch.len() {
+            self.position = (self.position + 1) % taps;
+            self.line[self.position] = self.scratch[i];
+            self.line[self.position + taps] = self.scratch[i];
+        }
+    }
+
+    /// Filters the block `input` into `output`.
+    ///
+    /// # Panics
+    ///
+    /// If `input` and `output` have different lengths.
+    #[track_caller]
+    pub fn process_block(&mut self, input: &[FFloat<T, P>], output: &mut [FFloat<T, P>]) {
+        assert_eq!(input.len(), output.len(), "slice lengths differ");
+        output.copy_from_slice(input);
+        self.process_in_place(output);
+    }
+}
+
+/// A second order infinite impulse response filter (two poles and two zeros), in the transposed direct form II:
+/// two state variables, and five multiply adds per sample, which is the most accurate of the direct forms in floating point.
+///
+/// The constructors design the filters of the [audio eq cookbook](https://www.w3.org/TR/audio-eq-cookbook/) (by Robert Bristow-Johnson),
+/// from a `frequency` in cycles per sample (the frequency in Hz divided by the sample rate, so below `0.5`, the nyquist frequency), and a quality factor `q`
+/// (`1 / √2` is the flattest; higher is more resonant, and narrower).
+/// Unlike a [`Fir`], it is generic over [`FloatAlone`], so it also runs on plain floats.
+/// ```
+/// # use umath::{FFloat, filter::Biquad};
+/// let f = |x| unsafe { FFloat::new(x) };
+/// // 1 kHz, at 48 kHz
+/// let mut lowpass = Biquad::lowpass(f(1000.0 / 48000.0), f(core::f64::consts::FRAC_1_SQRT_2));
+/// assert!((*lowpass.response(f(0.0)).norm() - 1.0).abs() < 1e-12);
+/// assert!((*lowpass.response(f(1000.0 / 48000.0)).norm() - 0.5f64.sqrt()).abs() < 1e-12);
+/// // a constant passes through, once the filter settles
+/// let settled = (0..1000).map(|_| lowpass.process(f(2.0))).last().unwrap();
+/// assert!((*settled - 2.0).abs() < 1e-9);
+/// ```
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Biquad<F> {
+    /// The numerator, `b0 + b1 z⁻¹ + b2 z⁻²`, divided by `a0`.
+    feedforward: [F; 3],
+    /// The denominator, `1 + a1 z⁻¹ + a2 z⁻²`, divided by `a0`.
+    feedback: [F; 2],
+    state: [F; 2],
+}
+
+impl<F: FloatAlone> Biquad<F> {
+    /// Creates a filter with the transfer function `(b0 + b1 z⁻¹ + b2 z⁻²) / (a0 + a1 z⁻¹ + a2 z⁻²)`, and a state of zeros.
+    ///
+    /// # Panics
+    ///
+    /// If `a0` is 0.
+    #[must_use]
+    #[track_caller]
+    pub fn new(feedforward: [F; 3], [a0, a1, a2]: [F; 3]) -> Self {
+        // SAFETY: 0 is a valid float
+        let zero = unsafe { F::zero() };
+        assert!(a0 != zero, "a0 must not be 0");
+        Self {
+            feedforward: feedforward.map(|b| b / a0),
+            feedback: [a1 / a0, a2 / a0],
+            state: [zero; 2],
+        }
+    }
+
+    /// The numerator and denominator coefficients, `([b0, b1, b2], [a0, a1, a2])`, normalized so that `a0` is 1.
+    #[must_use]
+    pub fn coefficients(&self) -> ([F; 3], [F; 3]) {
+        // SAFETY: 1 is a valid float
+        (
+            self.feedforward,
+            [unsafe { F::one() }, self.feedback[0], self.feedback[1]],
+        )
+    }
+
+    /// `(cos(ω), alpha)`, for the cookbook designs.
+    #[track_caller]
+    fn design(frequency: F, q: F) -> (F, F) {
+        // SAFETY: 0 and π are finite
+        let (zero, τ) = unsafe { (F::zero(), F::π() * lit(2.0)) };
+        assert!(
+            frequency > zero && frequency < lit(0.5),
+            "the frequency must be between 0 and 0.5 (the nyquist frequency)"
+        );
+        assert!(q > zero, "q must be positive");
+        let ω = frequency * τ;
+        (ω.cos(), ω.sin() / (q * lit(2.0)))
+    }
+
+    /// A lowpass filter, with a gain of -3 dB at `frequency` (when `q` is `1 / √2`), falling off at 12 dB per octave above it.
+    ///
+    /// # Panics
+    ///
+    /// If `frequency` is not between 0 and 0.5, or `q` is not positive.
+    #[must_use]
+    #[track_caller]
+    pub fn lowpass(frequency: F, q: F) -> Self {
+        let (cos, alpha) = Self::design(frequency, q);
+        // SAFETY: 1 is a valid float
+        let one = unsafe { F::one() };
+        let b1 = one - cos;
+        let b0 = b1 * lit(0.5);
+        Self::new([b0, b1, b0], [one + alpha, cos * lit(-2.0), one - alpha])
+    }
+
+    /// A highpass filter, with a gain of -3 dB at `frequency` (when `q` is `1 / √2`), falling off at 12 dB per octave below it.
+    ///
+    /// # Panics
+    ///
+    /// If `frequency` is not between 0 and 0.5, or `q` is not positive.
+    #[must_use]
+    #[track_caller]
+    pub fn highpass(frequency: F, q: F) -> Self {
+        let (cos, alpha) = Self::design(frequency, q);
+        // SAFETY: 1 is a valid float
+        let one = unsafe { F::one() };
+        let b0 = (one + cos) * lit(0.5);
+        Self::new(
+            [b0, -(one + cos), b0],
+            [one + alpha, cos * lit(-2.0), one - alpha],
+        )
+    }
+
+    /// A bandpass filter, with a gain of 0 dB at `frequency`, and a bandwidth of `frequency / q`.
+    ///
+    /// # Panics
+    ///
+    /// If `frequency` is not between 0 and 0.5, or `q` is not positive.
+    #[must_use]
+    #[track_caller]
+    pub fn bandpass(frequency: F, q: F) -> Self {
+        let (cos, alpha) = Self::design(frequency, q);
+        // SAFETY: 0 and 1 are valid floats
+        let (zero, one) = unsafe { (F::zero(), F::one()) };
+        Self::new(
+            [alpha, zero, -alpha],
+            [one + alpha, cos * lit(-2.0), one - alpha],
+        )
+    }
+
+    /// A notch (band stop) filter, silencing `frequency`, with a bandwidth of `frequency / q`.
+    ///
+    /// # Panics
+    ///
+    /// If `frequency` is not between 0 and 0.5, or `q` is not positive.
+    #[must_use]
+    #[track_caller]
+    pub fn notch(frequency: F, q: F) -> Self {
+        let (cos, alpha) = Self::design(frequency, q);
+        // SAFETY: 1 is a valid float
+        let one = unsafe { F::one() };
+        let b1 = cos * lit(-2.0);
+        Self::new([one, b1, one], [one + alpha, b1, one - alpha])
+    }
+
+    /// A peaking (bell) filter, boosting (or, with a negative `gain`, cutting) the frequencies around `frequency` by `gain` decibels.
+    ///
+    /// # Panics
+    ///
+    /// If `frequency` is not between 0 and 0.5, or `q` is not positive.
+    #[must_use]
+    #[track_caller]
+    pub fn peaking(frequency: F, q: F, gain: F) -> Self {
+        let (cos, alpha) = Self::design(frequency, q);
+        // SAFETY: 1 is a valid float
+        let one = unsafe { F::one() };
+        let amplitude = audio::from_db(gain * lit(0.5));
+        let b1 = cos * lit(-2.0);
+        Self::new(
+            [one + alpha * amplitude, b1, one - alpha * amplitude],
+            [one + alpha / amplitude, b1, one - alpha / amplitude],
+        )
+    }
+
+    /// A low shelf filter, boosting (or, with a negative `gain`, cutting) the frequencies below `frequency` by `gain` decibels.
+    ///
+    /// # Panics
+    ///
+    /// If `frequency` is not between 0 and 0.5, or `q` is not positive.
+    #[must_use]
+    #[track_caller]
+    pub fn low_shelf(frequency: F, q: F, gain: F) -> Self {
+        Self::shelf(frequency, q, gain, lit(1.0))
+    }
+
+    /// A high shelf filter, boosting (or, with a negative `gain`, cutting) the frequencies above `frequency` by `gain` decibels.
+    ///
+    /// # Panics
+    ///
+    /// If `frequency` is not between 0 and 0.5, or `q` is not positive.
+    #[must_use]
+    #[track_caller]
+    pub fn high_shelf(frequency: F, q: F, gain: F) -> Self {
+        Self::shelf(frequency, q, gain, lit(-1.0))
+    }
+
+    /// The low (`side = 1`) and high (`side = -1`) shelves, which differ only in the sign of the odd terms.
+    #[track_caller]
+    fn shelf(frequency: F, q: F, gain: F, side: F) -> Self {
+        let (cos, alpha) = Self::design(frequency, q);
+        // SAFETY: 1 is a valid float
+        let one = unsafe { F::one() };
+        let amplitude = audio::from_db(gain * lit(0.5));
+        let (plus, minus) = (amplitude + one, amplitude - one);
+        let cos = cos * side;
+        let slope = amplitude.sqrt() * alpha * lit(2.0);
+        Self::new(
+            [
+                amplitude * (plus - minus * cos + slope),
+                amplitude * (minus - plus * cos) * side * lit(2.0),
+                amplitude * (plus - minus * cos - slope),
+            ],
+            [
+                plus + minus * cos + slope,
+                (minus + plus * cos) * side * lit(-2.0),
+                plus + minus * cos - slope,
+            ],
+        )
+    }
+
+    /// The frequency response at `frequency` (in cycles per sample): the gain is its [`norm`](Complex::norm), and the phase shift its [`arg`](Complex::arg).
+    #[must_use]
+    pub fn response(&self, frequency: F) -> Complex<F> {
+        // SAFETY: 1 and π are finite
+        let (one, τ) = unsafe { (F::one(), F::π() * lit(2.0)) };
+        let z = Complex::from_polar(one, -(frequency * τ));
+        let [b0, b1, b2] = self.feedforward;
+        let [a1, a2] = self.feedback;
+        // horner, in z⁻¹
+        let numerator = (z * b2 + Complex::from(b1)) * z + Complex::from(b0);
+        let denominator = (z * a2 + Complex::from(a1)) * z + Complex::from(one);
+        numerator / denominator
+    }
+
+    /// Clears the state, as if every input so far was zero.
+    pub fn reset(&mut self) {
+        // SAFETY: 0 is a valid float
+        self.state = [unsafe { F::zero() }; 2];
+    }
+
+    /// Filters one sample.
+    #[inline]
+    pub fn process(&mut self, x: F) -> F {
+        let [b0, b1, b2] = self.feedforward;
+        let [a1, a2] = self.feedback;
+        let [s1, s2] = self.state;
+        let y = b0 * x + s1;
+        self.state = [b1 * x - a1 * y + s2, b2 * x - a2 * y];
+        y
+    }
+
+    /// Filters a block of samples, in place.
+    pub fn process_in_place(&mut self, buffer: &mut [F]) {
+        for x in buffer {
+            *x = self.process(*x);
+        }
+    }
+
+    /// Filters the block `input` into `output`.
+    ///
+    /// # Panics
+    ///
+    /// If `input` and `output` have different lengths.
+    #[track_caller]
+    pub fn process_block(&mut self, input: &[F], output: &mut [F]) {
+        assert_eq!(input.len(), output.len(), "slice lengths differ");
+        for (y, &x) in output.iter_mut().zip(input) {
+            *y = self.process(x);
+        }
+    }
+}
+
+#[test]
+#[allow(clippy::float_cmp)]
+fn filtered() {
+    let f = |x| unsafe { FFloat::<f64>::new(x) };
+    let h = [0.5, -1.0, 2.0, 0.25, 3.0].map(f);
+    let mut fir = Fir::new(h.to_vec());
+    assert!(fir.coefficients().eq(h));
+    // the impulse response is the coefficients, followed by zeros
+    let response: Vec<_> = [1.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0]
+        .into_iter()
+        .map(|x| *fir.process(f(x)))
+        .collect();
+    assert_eq!(response, [0.5, -1.0, 2.0, 0.25, 3.0, 0.0, 0.0]);
+    // blocks of any size match sample at a time processing, and convolution
+    let signal: Vec<_> = (0..40u8).map(|i| f((f64::from(i) * 0.9).sin())).collect();
+    let expected = crate::convolution::convolve(&signal, &h, crate::convolution::Mode::Full);
+    for size in [1, 2, 3, 7, 40] {
+        fir.reset();
+        let mut output = vec![f(0.0); signal.len()];
+        for (input, output) in signal.chunks(size).zip(output.chunks_mut(size)) {
+            fir.process_block(input, output);
+        }
+        assert!(
+            output
+                .iter()
+                .zip(&expected)
+                .all(|(x, y)| (**x - **y).abs() < 1e-12),
+            "{size}"
+        );
+    }
+    fir.reset();
+    assert!(signal
+        .iter()
+        .zip(&expected)
+        .all(|(&x, y)| (*fir.process(x) - **y).abs() < 1e-12));
+    // the biquads have the gain they were designed for
+    let corner = 0.05;
+    let flat = core::f64::consts::FRAC_1_SQRT_2;
+    let gain = |biquad: &Biquad<f64>, frequency| audio::to_db(biquad.response(frequency).norm());
+    let close = |x: f64, y: f64| (x - y).abs() < 1e-9;
+    assert!(close(
+        gain(&Biquad::lowpass(corner, flat), corner),
+        -10.0 * 2f64.log10()
+    ));
+    assert!(close(
+        gain(&Biquad::highpass(corner, flat), corner),
+        -10.0 * 2f64.log10()
+    ));
+    assert!(close(gain(&Biquad::highpass(corner, flat), 0.5), 0.0));
+    assert!(close(gain(&Biquad::bandpass(corner, 2.0), corner), 0.0));
+    assert!(Biquad::notch(corner, flat).response(corner).norm() < 1e-12);
+    assert!(close(
+        gain(&Biquad::peaking(corner, flat, 6.0), corner),
+        6.0
+    ));
+    assert!(close(gain(&Biquad::peaking(corner, flat, -6.0), 0.0), 0.0));
+    for shelf in [6.0, -12.0] {
+        let (low, high) = (
+            Biquad::low_shelf(corner, flat, shelf),
+            Biquad::high_shelf(corner, flat, shelf),
+        );
+        assert!(close(gain(&low, 0.0), shelf) && close(gain(&low, 0.5), 0.0));
+        assert!(close(gain(&high, 0.5), shelf) && close(gain(&high, 0.0), 0.0));
+        // halfway (in decibels) at the corner
+        assert!(close(gain(&low, corner), shelf / 2.0) && close(gain(&high, corner), shelf / 2.0));
+    }
+    // the impulse response matches the difference equation, y[n] = Σ b[j] x[n - j] - Σ a[j] y[n - j]
+    let mut peaking = Biquad::peaking(0.1, 3.0, 4.0);
+    let ([b0, b1, b2], [_, a1, a2]) = peaking.coefficients();
+    let mut history = [0.0f64; 2];
+    for n in 0..20 {
+        let impulse = |j: usize| if n == j { 1.0 } else { 0.0 };
+        let expected =
+            b0 * impulse(0) + b1 * impulse(1) + b2 * impulse(2) - a1 * history[0] - a2 * history[1];
+        history = [expected, history[0]];
+        assert!(close(peaking.process(impulse(0)), expected), "{n}");
+    }
+}
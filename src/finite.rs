@@ -0,0 +1,99 @@
+use crate::policy::Ieee;
+use crate::r#trait::{FastFloat, Show, Wrapper};
+use crate::Float;
+use core::ops::Deref;
+#[cfg(doc)]
+use {
+    crate::{policy::Policy, FFloat},
+    std::f32::{INFINITY as INF, NAN},
+};
+
+/// Safe float wrapper that is never [`NAN`] | [`INF`], without any fast math.
+/// This float also implements [`Ord`], [`Hash`], and [`Eq`].
+///
+/// Unlike [`FFloat`], breaking the invariant is not UB: [`Finite::new`] is checked, and any operation that would produce [`NAN`] | [`INF`] panics (in every build).
+/// The operations are ordinary IEEE operations (the [`Policy`] is [`Ieee`]).
+///
+/// As [`Finite`] shares its trait implementations with [`FFloat`], you can switch between the two with a type alias:
+/// ```
+/// # use umath::{Finite, Float};
+/// // type Real = umath::FF64;
+/// type Real = Finite<f64>;
+/// fn area<F: Float<f64>>(r: F) -> F {
+///     r * r * std::f64::consts::PI
+/// }
+/// let r = Real::new(2.0).unwrap();
+/// assert!((12.56..12.57).contains(&*area(r)));
+/// assert_eq!(Real::new(f64::NAN), None);
+/// ```
+///
+/// `Finite<F>` is guaranteed to have the same memory layout and ABI as F.
+#[repr(transparent)]
+#[derive(Copy, Clone, PartialEq)]
+pub struct Finite<T>(T);
+
+impl<T: FastFloat> Finite<T> {
+    /// Create a new [`Finite`] from your {[`f32`], [`f64`]}, if it is not [`NAN`] | [`INF`].
+    pub fn new(from: T) -> Option<Self> {
+        (!from.bad::<Ieee>()).then_some(Self(from))
+    }
+
+    /// Create a new [`Finite`] from your {[`f32`], [`f64`]}.
+    ///
+    /// # Panics
+    ///
+    /// If `from` is [`NAN`] | [`INF`].
+    #[track_caller]
+    pub fn must(from: T) -> Self {
+        match Self::new(from) {
+            Some(x) => x,
+            None => panic!("{} is NAN | INF.", Show(from)),
+        }
+    }
+
+    /// Create a new [`Finite`], without checking it, for conversions from wrappers that already promise it is not [`NAN`] | [`INF`].
+    pub(crate) const fn unchecked(from: T) -> Self {
+        Self(from)
+    }
+}
+
+impl<T: FastFloat> Wrapper for Finite<T> {
+    type Inner = T;
+    type Policy = Ieee;
+
+    #[inline(always)]
+    fn raw(self) -> T {
+        self.0
+    }
+
+    #[inline(always)]
+    unsafe fn wrap(x: T) -> Self {
+        // the invariant is checked here, so the (unchecked) `check` is fine.
+        Self::must(x)
+    }
+}
+
+impl<T> Deref for Finite<T> {
+    type Target = T;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl<F: FastFloat + Float<F>> Float<F> for Finite<F> {
+    /// Create a new [`Finite`] from your {[`f32`], [`f64`]}. This function is safe to call, but panics if `from` is [`NAN`] | [`INF`].
+    unsafe fn new(from: F) -> Self {
+        Self::must(from)
+    }
+
+    fn take(self) -> F {
+        self.0
+    }
+}
+
+#[test]
+#[should_panic = "inf is NAN | INF."]
+fn overflow() {
+    let _ = Finite::must(f32::MAX) * 2.0;
+}
@@ -0,0 +1,54 @@
+use crate::policy::Policy;
+use crate::r#trait::Wrapper;
+use crate::FFloat;
+
+macro_rules! fixed {
+    ($t:ident) => {
+        impl<P: Policy> FFloat<$t, P> {
+            /// `2^FRAC_BITS`
+            #[inline(always)]
+            #[allow(clippy::cast_precision_loss)]
+            fn scale<const FRAC_BITS: u32>() -> $t {
+                const { assert!(FRAC_BITS < 32, "FRAC_BITS must be less than 32") };
+                (1u64 << FRAC_BITS) as $t
+            }
+
+            /// Converts to a signed Q-format fixed point number, with `FRAC_BITS` fractional bits (rounding to nearest).
+            ///
+            /// Values out of range saturate to [`i32::MIN`] | [`i32::MAX`].
+            /// ```
+            /// # use umath::FFloat;
+            /// let x = unsafe { FFloat::new(0.5f32) };
+            /// assert_eq!(x.to_fixed::<15>(), 16384); // Q15
+            /// assert_eq!((x * 1e9).to_fixed::<16>(), i32::MAX);
+            /// ```
+            #[allow(clippy::cast_possible_truncation)]
+            pub fn to_fixed<const FRAC_BITS: u32>(self) -> i32 {
+                // this is not fast math, as the scaled value may overflow (`as` saturates INF).
+                (self.raw() * Self::scale::<FRAC_BITS>()).round() as i32
+            }
+
+            /// Converts from a signed Q-format fixed point number, with `FRAC_BITS` fractional bits.
+            /// ```
+            /// # use umath::FFloat;
+            #[doc = concat!("assert_eq!(*FFloat::<", stringify!($t), ">::from_fixed::<8>(-384), -1.5);")]
+            /// ```
+            #[allow(clippy::cast_precision_loss, clippy::cast_lossless)]
+            pub fn from_fixed<const FRAC_BITS: u32>(x: i32) -> Self {
+                // SAFETY: this is always finite.
+                unsafe { Self::wrap(x as $t / Self::scale::<FRAC_BITS>()) }
+            }
+        }
+    };
+}
+
+fixed!(f32);
+fixed!(f64);
+
+#[test]
+fn q31() {
+    let x = unsafe { FFloat::new(-1.0f64) };
+    assert_eq!(x.to_fixed::<31>(), i32::MIN);
+    assert_eq!((-x).to_fixed::<31>(), i32::MAX);
+    assert_eq!(FFloat::<f64>::from_fixed::<31>(i32::MIN), x);
+}
@@ -6,9 +6,9 @@
 //! /// with no external implementations, this can take either f32 or FFloat<f32>.
 //! fn takes_float<F: Float<f32>>(f: F) {}
 //! ```
-use crate::{FFloat, FastFloat};
+use crate::{policy::Policy, r#trait::Wrapper, FFloat, FastFloat};
 use core::ops::{
-    Add, AddAssign, Deref, Div, DivAssign, Mul, MulAssign, Neg, Rem, RemAssign, Sub, SubAssign,
+    Add, AddAssign, Div, DivAssign, Mul, MulAssign, Neg, Rem, RemAssign, Sub, SubAssign,
 };
 #[cfg(doc)]
 use std::f32::{INFINITY as INF, NAN};
@@ -25,10 +25,10 @@ macro_rules! simp {
 
         impl $trat for f32 { $(fn $name(self $(, $arg: Self)?) -> Self { self.$name($($arg)?) })+ }
         impl $trat for f64 { $(fn $name(self $(, $arg: Self)?) -> Self { self.$name($($arg)?) })+ }
-        impl<T: FastFloat + Trig + Rounding + Log> $trat for FFloat<T> {
+        impl<W: Wrapper> $trat for W where W::Inner: $trat {
             $(
                 #[doc = include_str!("ffloat_safety_notice.md")]
-                fn $name(self $(, $arg: Self)?) -> Self { unsafe { FFloat::new(self.deref().$name($(*$arg)?)) } }
+                fn $name(self $(, $arg: Self)?) -> Self { unsafe { W::wrap(self.raw().$name($($arg.raw())?)) } }
             )+
         }
     };
@@ -111,6 +111,14 @@ pub trait Constructors {
     unsafe fn max() -> Self;
 }
 
+/// Creates a float from a literal.
+/// Only use this with finite literals that fit in a [`f32`].
+#[inline(always)]
+pub(crate) fn lit<F: FloatExt>(x: f64) -> F {
+    // SAFETY: the caller passes a literal that is representable by every float.
+    unsafe { F::from_f64(x) }
+}
+
 /// Methods on a float.
 /// If there is a method you would like to see on this trait, please open a issue.
 ///
@@ -151,6 +159,29 @@ pub trait FloatMethods: Trig + Rounding + Log {
     fn max(self, other: Self) -> Self;
 }
 
+/// Float constructors and methods added after `0.0.7`.
+///
+/// These are kept out of [`Constructors`] and [`FloatMethods`], so that implementations of those outside this crate keep compiling.
+/// [`FloatAlone`] needs this as well though, so a float type outside this crate has to implement it to stay a [`FloatAlone`].
+///
+/// The wrappers implement these by wrapping the result like any other operation, so (for example) a [`UnitFloat`](crate::UnitFloat) saturates the results into `0..=1`,
+/// and a [`NonNegative`](crate::NonNegative) panics on `next_down(0)`, or if [`exp`](Self::exp) overflows.
+pub trait FloatExt: Constructors {
+    /// Returns the given [`f64`], converted (possibly lossily) to this float.
+    #[doc = include_str!("refer.md")]
+    #[must_use]
+    unsafe fn from_f64(x: f64) -> Self;
+
+    /// Refer to [`f32::exp`]
+    fn exp(self) -> Self;
+
+    /// Refer to [`f32::next_up`]
+    fn next_up(self) -> Self;
+
+    /// Refer to [`f32::next_down`]
+    fn next_down(self) -> Self;
+}
+
 /// Completely stand-alone [`Float`].
 /// This is comparable to something like [num_traits::Float](https://docs.rs/num-traits/latest/num_traits/float/trait.Float.html).
 pub trait FloatAlone:
@@ -160,6 +191,7 @@ pub trait FloatAlone:
     + Constructors
     + Constants
     + FloatMethods
+    + FloatExt
     + Add<Self, Output = Self>
     + Sub<Self, Output = Self>
     + Mul<Self, Output = Self>
@@ -180,6 +212,7 @@ impl<
             + Copy
             + Constructors
             + FloatMethods
+            + FloatExt
             + Constants
             + Add<T, Output = T>
             + Sub<T, Output = T>
@@ -196,7 +229,7 @@ impl<
 {
 }
 
-/// Generic float trait, implemented by {[`FFloat`], [`f32`], [`f64`]}. Takes a "base" argument, intended to be set to {[`f32`], [`f64`]}.
+/// Generic float trait, implemented by {[`FFloat`], [`AFloat`](crate::AFloat), [`NotNan`](crate::NotNan), [`Finite`](crate::Finite), [`UnitFloat`](crate::UnitFloat), [`NonNegative`](crate::NonNegative), [`Dual`](crate::dual::Dual), [`f32`], [`f64`]}. Takes a "base" argument, intended to be set to {[`f32`], [`f64`]}.
 /// The main purpose of this is to be taken (generically) by optionally fast functions.
 ///
 ///
@@ -275,50 +308,72 @@ macro_rules! impf {
                 self.max(other)
             }
         }
+        impl FloatExt for $for {
+            #[doc = concat!("Returns the input value, as a [`", stringify!($for), "`]. This function is safe to call")]
+            #[allow(clippy::cast_possible_truncation)]
+            unsafe fn from_f64(x: f64) -> $for {
+                x as $for
+            }
+            fn exp(self) -> $for {
+                self.exp()
+            }
+            fn next_up(self) -> $for {
+                self.next_up()
+            }
+            fn next_down(self) -> $for {
+                self.next_down()
+            }
+        }
     };
 }
 
 impf!(f32);
 impf!(f64);
 
-impl<F: FastFloat + Constants> Constants for FFloat<F> {
+impl<W: Wrapper> Constants for W
+where
+    W::Inner: Constants,
+{
     /// Create a new [`FFloat`] representing the machine epsilon.
     #[doc = include_str!("ffloat_safety_noconstr.md")]
     unsafe fn ε() -> Self {
-        Self::new(F::ε())
+        W::wrap(W::Inner::ε())
     }
     /// Create a new [`FFloat`] representing π.
     #[doc = include_str!("ffloat_safety_noconstr.md")]
     unsafe fn π() -> Self {
-        Self::new(F::π())
+        W::wrap(W::Inner::π())
     }
     /// Create a new [`FFloat`] representing eulers number.
     #[doc = include_str!("ffloat_safety_noconstr.md")]
     unsafe fn e() -> Self {
-        Self::new(F::e())
+        W::wrap(W::Inner::e())
     }
 }
 
-impl<F: FastFloat + Constructors> Constructors for FFloat<F> {
+impl<W: Wrapper> Constructors for W
+where
+    W::Inner: Constructors,
+{
     /// Create a new [`FFloat`] representing `0.0`.
     #[doc = include_str!("ffloat_safety_noconstr.md")]
     unsafe fn zero() -> Self {
-        Self::new(F::zero())
+        W::wrap(W::Inner::zero())
     }
     /// Create a new [`FFloat`] representing `1.0`.
     #[doc = include_str!("ffloat_safety_noconstr.md")]
     unsafe fn one() -> Self {
-        Self::new(F::one())
+        W::wrap(W::Inner::one())
     }
     /// Create a new [`FFloat`] representing the minimum value for the inner float.
     #[doc = include_str!("ffloat_safety_noconstr.md")]
     unsafe fn min() -> Self {
-        Self::new(F::min())
+        W::wrap(W::Inner::min())
     }
     /// Create a new [`FFloat`] representing the maximum value for the inner float.
     #[doc = include_str!("ffloat_safety_noconstr.md")]
     unsafe fn max() -> Self {
-        Self::new(F::max())
+        W::wrap(W::Inner::max())
     }
 }
 
@@ -327,17 +382,16 @@ macro_rules! reuse {
         #[doc = concat!("Refer to [`f32::", stringify!($name), "`]")]
         #[doc = include_str!("ffloat_safety_notice.md")]
         fn $name(self) -> Self {
-            self.check();
-            unsafe { Self::new(self.0.$name()) }
+            unsafe { W::wrap(self.raw().$name()) }
         }
     };
 }
 
-impl<F: FastFloat + Float<F>> Float<F> for FFloat<F> {
+impl<F: FastFloat + Float<F>, P: Policy> Float<F> for FFloat<F, P> {
     /// Create a new [`FFloat`] from your {[`f32`], [`f64`]}
     #[doc = include_str!("ffloat_safety.md")]
     unsafe fn new(from: F) -> Self {
-        Self::new(from)
+        Self::with_policy(from)
     }
 
     fn take(self) -> F {
@@ -345,7 +399,10 @@ impl<F: FastFloat + Float<F>> Float<F> for FFloat<F> {
     }
 }
 
-impl<F: FloatMethods + FastFloat + Float<F>> FloatMethods for FFloat<F> {
+impl<W: Wrapper> FloatMethods for W
+where
+    W::Inner: FloatMethods,
+{
     reuse!(fn trunc);
     reuse!(fn fract);
     reuse!(fn abs);
@@ -353,14 +410,13 @@ impl<F: FloatMethods + FastFloat + Float<F>> FloatMethods for FFloat<F> {
     /// Refer to [`f32::powi`]
     #[doc = include_str!("ffloat_safety_notice.md")]
     fn powi(self, n: i32) -> Self {
-        unsafe { Self::new(self.0.powi(n)) }
+        unsafe { W::wrap(self.raw().powi(n)) }
     }
 
     /// Refer to [`f32::powf`]
     #[doc = include_str!("ffloat_safety_notice.md")]
     fn powf(self, n: Self) -> Self {
-        self.check();
-        unsafe { Self::new(self.0.powf(*n)) }
+        unsafe { W::wrap(self.raw().powf(n.raw())) }
     }
 
     reuse!(fn sqrt);
@@ -368,27 +424,39 @@ impl<F: FloatMethods + FastFloat + Float<F>> FloatMethods for FFloat<F> {
     /// Refer to [`f32::hypot`]
     #[doc = include_str!("ffloat_safety_notice.md")]
     fn hypot(self, other: Self) -> Self {
-        self.check();
-        unsafe { Self::new(self.0.hypot(*other)) }
+        unsafe { W::wrap(self.raw().hypot(other.raw())) }
     }
     reuse!(fn exp2);
 
     /// Refer to [`f32::min`]
     #[doc = include_str!("ffloat_safety_notice.md")]
     fn min(self, other: Self) -> Self {
-        self.check();
-        unsafe { Self::new(self.0.min(*other)) }
+        unsafe { W::wrap(self.raw().min(other.raw())) }
     }
 
     /// Refer to [`f32::max`]
     #[doc = include_str!("ffloat_safety_notice.md")]
     fn max(self, other: Self) -> Self {
-        self.check();
-        unsafe { Self::new(self.0.max(*other)) }
+        unsafe { W::wrap(self.raw().max(other.raw())) }
     }
 }
 
+impl<W: Wrapper> FloatExt for W
+where
+    W::Inner: FloatExt,
+{
+    /// Create a new [`FFloat`] from a [`f64`].
+    #[doc = include_str!("ffloat_safety.md")]
+    unsafe fn from_f64(x: f64) -> Self {
+        W::wrap(W::Inner::from_f64(x))
+    }
+    reuse!(fn exp);
+    reuse!(fn next_up);
+    reuse!(fn next_down);
+}
+
 #[test]
+#[allow(clippy::approx_constant, clippy::needless_return)]
 fn usable() {
     fn cos<F: Float<f32>>(x: F) -> F {
         let mut y = x * (1.0 / 6.283);
@@ -399,3 +467,15 @@ fn usable() {
     assert!((0.995..0.996).contains(&cos(0.1)));
     assert!((0.995..0.996).contains(&*cos(unsafe { FFloat::new(0.1) })));
 }
+
+#[test]
+#[allow(clippy::float_cmp)]
+fn wrapped() {
+    use crate::{NonNegative, UnitFloat};
+    let one = UnitFloat::new(1.0f64).unwrap();
+    assert_eq!(*one.next_up(), 1.0);
+    assert_eq!(*one.exp(), 1.0);
+    let zero = NonNegative::new(0.0f64).unwrap();
+    assert_eq!(*zero.exp(), 1.0);
+    assert!(std::panic::catch_unwind(|| zero.next_down()).is_err());
+}
@@ -0,0 +1,373 @@
+//! interval arithmetic.
+//!
+//! A [`Interval`] is a pair of bounds, `lo..=hi`, which is guaranteed to contain the true (real valued) result of its operations.
+//! Every operation rounds its bounds outward by a ulp, so the usual rounding error (even with fast math) can not escape the interval.
+//! A lower bound of `MIN`, or a upper bound of `MAX`, is unbounded: a bound that would overflow saturates to it (instead of overflowing the float),
+//! and the interval stays unbounded on that side, through every later operation. So `MIN..=MAX` is the entire real line,
+//! which is also the result of a division by a interval that contains 0.
+//! ```
+//! # use umath::{FFloat, interval::Interval};
+//! let x = Interval::new(unsafe { FFloat::new(1.0f64) }, unsafe { FFloat::new(2.0) });
+//! let y = x * x - x;
+//! assert!(*y.lo() <= -1.0 && *y.hi() >= 3.0);
+//! assert!(y.contains(unsafe { FFloat::new(0.0) }));
+//! ```
+use crate::generic_float::{lit, Constructors, FloatAlone};
+use core::ops::{Add, Div, Mul, Neg, Sub};
+
+/// Rounds `x` up by a ulp, without overflowing to infinity.
+fn up<F: FloatAlone>(x: F) -> F {
+    if x < unsafe { <F as Constructors>::max() } {
+        x.next_up()
+    } else {
+        x
+    }
+}
+
+/// Rounds `x` down by a ulp, without overflowing to infinity.
+fn down<F: FloatAlone>(x: F) -> F {
+    if x > unsafe { <F as Constructors>::min() } {
+        x.next_down()
+    } else {
+        x
+    }
+}
+
+/// A bound, extended with the infinities (which are its values past the range of the float), so that the arithmetic on bounds can not overflow.
+#[derive(Copy, Clone, PartialEq, PartialOrd)]
+enum End<F> {
+    Below,
+    Finite(F),
+    Above,
+}
+
+impl<F: FloatAlone> End<F> {
+    /// A lower bound: `MIN` is unbounded.
+    fn lower(x: F) -> Self {
+        // SAFETY: MIN is finite
+        if x == unsafe { <F as Constructors>::min() } {
+            Self::Below
+        } else {
+            Self::Finite(x)
+        }
+    }
+
+    /// A upper bound: `MAX` is unbounded.
+    fn upper(x: F) -> Self {
+        // SAFETY: MAX is finite
+        if x == unsafe { <F as Constructors>::max() } {
+            Self::Above
+        } else {
+            Self::Finite(x)
+        }
+    }
+
+    /// Back to a lower bound, rounded down (an infinity saturates to the end of the range).
+    fn to_lower(self) -> F {
+        // SAFETY: MIN and MAX are finite
+        match self {
+            Self::Below => unsafe { <F as Constructors>::min() },
+            Self::Finite(x) => down(x),
+            Self::Above => unsafe { <F as Constructors>::max() },
+        }
+    }
+
+    /// Back to a upper bound, rounded up (an infinity saturates to the end of the range).
+    fn to_upper(self) -> F {
+        // SAFETY: MIN and MAX are finite
+        match self {
+            Self::Below => unsafe { <F as Constructors>::min() },
+            Self::Finite(x) => up(x),
+            Self::Above => unsafe { <F as Constructors>::max() },
+        }
+    }
+
+    /// The infinity on the `positive` side.
+    fn infinity(positive: bool) -> Self {
+        if positive {
+            Self::Above
+        } else {
+            Self::Below
+        }
+    }
+
+    /// Whether this is more than 0.
+    fn positive(self) -> bool {
+        // SAFETY: 0 is a valid float
+        self > Self::Finite(unsafe { F::zero() })
+    }
+
+    fn neg(self) -> Self {
+        match self {
+            Self::Below => Self::Above,
+            Self::Finite(x) => Self::Finite(-x),
+            Self::Above => Self::Below,
+        }
+    }
+
+    /// The sum; the two infinities are never added together, as a sum of bounds only adds bounds on the same side.
+    fn add(self, other: Self) -> Self {
+        let (Self::Finite(a), Self::Finite(b)) = (self, other) else {
+            return if matches!(self, Self::Finite(_)) {
+                other
+            } else {
+                self
+            };
+        };
+        // SAFETY: 0, MIN, and MAX are finite
+        let (zero, min, max) = unsafe {
+            (
+                F::zero(),
+                <F as Constructors>::min(),
+                <F as Constructors>::max(),
+            )
+        };
+        // compared with a ulp of margin, so that a sum that passes is rounded to at most `MAX`.
+        if a > zero && b > zero && a > down(max - b) {
+            Self::Above
+        } else if a < zero && b < zero && a < up(min - b) {
+            Self::Below
+        } else {
+            Self::Finite(a + b)
+        }
+    }
+
+    /// The product, where 0 times a infinity is 0 (as the values of a interval are finite).
+    fn mul(self, other: Self) -> Self {
+        // SAFETY: 0, 1, and MAX are finite
+        let (zero, one, max) = unsafe { (F::zero(), F::one(), <F as Constructors>::max()) };
+        if self == Self::Finite(zero) || other == Self::Finite(zero) {
+            return Self::Finite(zero);
+        }
+        let positive = self.positive() == other.positive();
+        match (self, other) {
+            // `max / b` is only formed when it can not overflow (a factor of at most 1 can not grow the product).
+            (Self::Finite(a), Self::Finite(b))
+                if b.abs() <= one || a.abs() <= down(max / b.abs()) =>
+            {
+                Self::Finite(a * b)
+            }
+            _ => Self::infinity(positive),
+        }
+    }
+
+    /// The quotient, by a divisor that is not 0, where a finite value divided by a infinity is 0.
+    fn div(self, other: Self) -> Self {
+        // SAFETY: 0, 1, and MAX are finite
+        let (zero, one, max) = unsafe { (F::zero(), F::one(), <F as Constructors>::max()) };
+        let positive = self.positive() == other.positive();
+        match (self, other) {
+            (Self::Finite(_), Self::Below | Self::Above) => Self::Finite(zero),
+            (Self::Finite(a), Self::Finite(b))
+                if b.abs() >= one || a.abs() <= down(max * b.abs()) =>
+            {
+                Self::Finite(a / b)
+            }
+            _ if self == Self::Finite(zero) => self,
+            _ => Self::infinity(positive),
+        }
+    }
+}
+
+/// A closed interval `lo..=hi`, with outward rounded arithmetic. Refer to the [module level documentation](self).
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Interval<F> {
+    lo: F,
+    hi: F,
+}
+
+impl<F: FloatAlone> Interval<F> {
+    /// Creates a new [`Interval`] spanning `lo..=hi`.
+    ///
+    /// # Panics
+    ///
+    /// If `lo > hi`.
+    #[track_caller]
+    pub fn new(lo: F, hi: F) -> Self {
+        assert!(lo <= hi, "interval bounds out of order");
+        Self { lo, hi }
+    }
+
+    /// Creates a (degenerate) [`Interval`] containing only `x`.
+    pub fn point(x: F) -> Self {
+        Self { lo: x, hi: x }
+    }
+
+    /// The lower bound.
+    pub fn lo(self) -> F {
+        self.lo
+    }
+
+    /// The upper bound.
+    pub fn hi(self) -> F {
+        self.hi
+    }
+
+    /// The width of the interval (`hi - lo`), rounded up (to `MAX`, if it is unbounded, or would overflow).
+    pub fn width(self) -> F {
+        End::upper(self.hi)
+            .add(End::lower(self.lo).neg())
+            .to_upper()
+    }
+
+    /// The midpoint of the interval. This is always contained in the interval.
+    pub fn midpoint(self) -> F {
+        // SAFETY: 0 is a valid float
+        let zero = unsafe { F::zero() };
+        let (lo, hi) = (self.lo, self.hi);
+        // `lo * 0.5 + hi * 0.5` does not help, as the fast math flags may factor it back into `(lo + hi) * 0.5`: instead,
+        // the sum of bounds of opposite signs can not overflow (however it is reordered), and neither can the difference of bounds of the same sign,
+        // which is passed through `up` (a bit operation, which the fast math flags can not see through), so that it is not reordered back into a sum.
+        let mid = if lo <= zero && hi >= zero {
+            (lo + hi) * lit(0.5)
+        } else {
+            lo + up(hi - lo) * lit(0.5)
+        };
+        mid.max(lo).min(hi)
+    }
+
+    /// Checks if `x` lies within the interval.
+    pub fn contains(self, x: F) -> bool {
+        self.lo <= x && x <= self.hi
+    }
+
+    /// The overlap of two intervals, or [`None`] if they are disjoint.
+    pub fn intersection(self, other: Self) -> Option<Self> {
+        let lo = self.lo.max(other.lo);
+        let hi = self.hi.min(other.hi);
+        (lo <= hi).then_some(Self { lo, hi })
+    }
+
+    /// The smallest interval containing both intervals (their hull).
+    pub fn union(self, other: Self) -> Self {
+        Self {
+            lo: self.lo.min(other.lo),
+            hi: self.hi.max(other.hi),
+        }
+    }
+}
+
+impl<F: FloatAlone> From<F> for Interval<F> {
+    fn from(x: F) -> Self {
+        Self::point(x)
+    }
+}
+
+impl<F: FloatAlone> Add for Interval<F> {
+    type Output = Self;
+    fn add(self, rhs: Self) -> Self {
+        Self {
+            lo: End::lower(self.lo).add(End::lower(rhs.lo)).to_lower(),
+            hi: End::upper(self.hi).add(End::upper(rhs.hi)).to_upper(),
+        }
+    }
+}
+
+impl<F: FloatAlone> Sub for Interval<F> {
+    type Output = Self;
+    fn sub(self, rhs: Self) -> Self {
+        Self {
+            lo: End::lower(self.lo).add(End::upper(rhs.hi).neg()).to_lower(),
+            hi: End::upper(self.hi).add(End::lower(rhs.lo).neg()).to_upper(),
+        }
+    }
+}
+
+impl<F: FloatAlone> Mul for Interval<F> {
+    type Output = Self;
+    fn mul(self, rhs: Self) -> Self {
+        let (lo, hi) = extremes(self, rhs, End::mul);
+        Self {
+            lo: lo.to_lower(),
+            hi: hi.to_upper(),
+        }
+    }
+}
+
+/// The smallest, and largest, of `op` on each pair of bounds.
+fn extremes<F: FloatAlone>(
+    a: Interval<F>,
+    b: Interval<F>,
+    op: fn(End<F>, End<F>) -> End<F>,
+) -> (End<F>, End<F>) {
+    let (a, b) = (
+        [End::lower(a.lo), End::upper(a.hi)],
+        [End::lower(b.lo), End::upper(b.hi)],
+    );
+    let [first, rest @ ..] = [
+        op(a[0], b[0]),
+        op(a[0], b[1]),
+        op(a[1], b[0]),
+        op(a[1], b[1]),
+    ];
+    rest.into_iter().fold((first, first), |(lo, hi), x| {
+        (if x < lo { x } else { lo }, if x > hi { x } else { hi })
+    })
+}
+
+impl<F: FloatAlone> Div for Interval<F> {
+    type Output = Self;
+    /// Divides the intervals. If `rhs` contains zero, the result is the entire (unbounded) range, `MIN..=MAX`.
+    fn div(self, rhs: Self) -> Self {
+        if rhs.contains(unsafe { F::zero() }) {
+            // SAFETY: MIN and MAX are finite
+            return unsafe { Self::new(<F as Constructors>::min(), <F as Constructors>::max()) };
+        }
+        let (lo, hi) = extremes(self, rhs, End::div);
+        Self {
+            lo: lo.to_lower(),
+            hi: hi.to_upper(),
+        }
+    }
+}
+
+impl<F: FloatAlone> Neg for Interval<F> {
+    type Output = Self;
+    fn neg(self) -> Self {
+        Self {
+            lo: -self.hi,
+            hi: -self.lo,
+        }
+    }
+}
+
+#[test]
+#[allow(clippy::float_cmp)]
+fn certify() {
+    let third = Interval::point(1.0f32) / Interval::point(3.0);
+    assert!(third.lo() < third.hi());
+    let one = third * Interval::point(3.0);
+    assert!(one.contains(1.0));
+    let x = Interval::new(-1.0f32, 2.0);
+    assert_eq!(x / x, Interval::new(f32::MIN, f32::MAX));
+    assert_eq!(x.intersection(Interval::point(5.0)), None);
+    assert_eq!(x.union(Interval::point(5.0)).hi(), 5.0);
+    // bounds that would overflow saturate, and stay unbounded
+    let big = Interval::new(f32::MAX / 2.0, f32::MAX / 2.0);
+    let sum = big + big + big;
+    assert!(sum.lo() >= f32::MAX / 2.0 && sum.hi() == f32::MAX);
+    assert_eq!((sum - big).hi(), f32::MAX);
+    assert_eq!((big * -big).lo(), f32::MIN);
+    let entire = x / x;
+    let zero = entire * Interval::point(0.0);
+    assert!(zero.contains(0.0) && zero.width() < 1e-40);
+    assert_eq!((entire - entire).width(), f32::MAX);
+    assert_eq!(
+        Interval::point(1.0f32) / Interval::new(1e-40, 1.0),
+        Interval::new(1.0f32.next_down(), f32::MAX)
+    );
+    let tiny = Interval::point(1.0) / Interval::new(f32::MIN, -1.0);
+    assert!(tiny.contains(-1.0) && tiny.contains(-1e-30) && tiny.hi() > 0.0);
+    // without overflowing a fast float
+    let f = |x| unsafe { crate::FFloat::new(x) };
+    let huge = Interval::point(f(f64::MAX)) * Interval::point(f(2.0)) + Interval::point(f(1.0));
+    assert_eq!(*huge.hi(), f64::MAX);
+    let whole = Interval::new(f(f64::MIN), f(f64::MAX));
+    assert_eq!(*whole.midpoint(), 0.0);
+    let top = Interval::new(f(f64::MAX * 0.75), f(f64::MAX)).midpoint();
+    assert!(*top >= f64::MAX * 0.75 && (*top / f64::MAX - 0.875).abs() < 1e-12);
+    let bottom = Interval::new(f(f64::MIN), f(f64::MIN * 0.75)).midpoint();
+    assert!((*bottom / f64::MIN - 0.875).abs() < 1e-12);
+    let small = Interval::point(f(1.0)) * Interval::point(f(1e-310));
+    assert!(small.contains(f(1e-310)) && *small.hi() < 1e-300);
+}
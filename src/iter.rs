@@ -0,0 +1,100 @@
+use crate::generic_float::FloatExt;
+use crate::policy::Policy;
+use crate::r#trait::{FastFloat, Wrapper};
+use crate::FFloat;
+
+/// Aggregates of iterators of [`FFloat`]s, without collecting them into a slice first
+/// (for slices, refer to the kernels in [`slice`](crate::slice)).
+///
+/// The elements are read raw, and only the result is checked (like a [`Unchecked`](crate::Unchecked) expression),
+/// and the operations carry the policies flags, so that (with [`Fast`](crate::policy::Fast)) the sum may be reassociated, and vectorized.
+///
+/// As a [`FFloat`] is never [`NAN`](f32::NAN), the minimum and maximum are always defined,
+/// and need no [`Option`]: like [`Summary`](crate::slice::Summary), the minimum of no elements is the maximum float (and vice versa).
+/// ```
+/// # use umath::{FFloat, FFloatIterExt};
+/// let x = [3.0f32, 1.0, 4.0, 1.0, 5.0].map(|x| unsafe { FFloat::new(x) });
+/// assert_eq!(x.iter().copied().sum_fast(), 14.0);
+/// assert_eq!(x.iter().map(|&x| x * 2.0).max_fast(), 10.0);
+/// assert_eq!(x.into_iter().mean_fast().unwrap(), 2.8);
+/// assert_eq!(core::iter::empty::<FFloat<f32>>().min_fast(), f32::MAX);
+/// ```
+pub trait FFloatIterExt<T, P>: Iterator<Item = FFloat<T, P>> + Sized {
+    /// The smallest element, or the maximum float if there are none.
+    fn min_fast(self) -> FFloat<T, P>;
+    /// The largest element, or the minimum float (`-MAX`) if there are none.
+    fn max_fast(self) -> FFloat<T, P>;
+    /// The sum of the elements (0 if there are none), added in any order the policy allows.
+    fn sum_fast(self) -> FFloat<T, P>;
+    /// The mean of the elements, or [`None`] if there are none.
+    fn mean_fast(self) -> Option<FFloat<T, P>>;
+}
+
+impl<T: FastFloat + FloatExt, P: Policy, I: Iterator<Item = FFloat<T, P>>> FFloatIterExt<T, P>
+    for I
+{
+    fn min_fast(self) -> FFloat<T, P> {
+        // SAFETY: MAX is finite
+        let min = self.fold(unsafe { T::max() }, |acc, x| {
+            let x = x.raw();
+            if x < acc {
+                x
+            } else {
+                acc
+            }
+        });
+        unsafe { FFloat::wrap(min) }
+    }
+
+    fn max_fast(self) -> FFloat<T, P> {
+        // SAFETY: MIN is finite
+        let max = self.fold(unsafe { T::min() }, |acc, x| {
+            let x = x.raw();
+            if x > acc {
+                x
+            } else {
+                acc
+            }
+        });
+        unsafe { FFloat::wrap(max) }
+    }
+
+    fn sum_fast(self) -> FFloat<T, P> {
+        // SAFETY: 0 is a valid float
+        let sum = self.fold(unsafe { T::zero() }, |acc, x| unsafe {
+            T::add::<P>(acc, x.raw())
+        });
+        unsafe { FFloat::wrap(sum) }
+    }
+
+    #[allow(clippy::cast_precision_loss)]
+    fn mean_fast(self) -> Option<FFloat<T, P>> {
+        // SAFETY: 0 is a valid float
+        let (sum, count) = self.fold((unsafe { T::zero() }, 0usize), |(acc, n), x| {
+            (unsafe { T::add::<P>(acc, x.raw()) }, n + 1)
+        });
+        (count != 0).then(|| unsafe { FFloat::wrap(T::div::<P>(sum, T::from_f64(count as f64))) })
+    }
+}
+
+#[test]
+#[allow(clippy::float_cmp)]
+fn aggregates() {
+    use crate::policy::Ieee;
+    let x: Vec<_> = (1..=100)
+        .map(|i| unsafe { FFloat::<f64, Ieee>::with_policy(f64::from((i * 37) % 101) - 50.0) })
+        .collect();
+    let s = crate::slice::summary(&x);
+    assert_eq!(x.iter().copied().min_fast(), s.min);
+    assert_eq!(x.iter().copied().max_fast(), s.max);
+    assert_eq!(
+        x.iter().copied().sum_fast(),
+        x.iter().map(|x| **x).sum::<f64>()
+    );
+    assert_eq!(x.iter().copied().mean_fast(), s.mean());
+    let none = core::iter::empty::<FFloat<f64, Ieee>>;
+    assert_eq!(none().min_fast(), f64::MAX);
+    assert_eq!(none().max_fast(), f64::MIN);
+    assert_eq!(none().sum_fast(), 0.0);
+    assert_eq!(none().mean_fast(), None);
+}
@@ -0,0 +1,146 @@
+use crate::policy::{Fast, Policy};
+use crate::r#trait::{FastFloat, Wrapper};
+use crate::FFloat;
+use core::marker::PhantomData;
+use core::ops::{Add as add, Div as div, Mul as mul, Neg, Rem as rem, Sub as sub};
+#[cfg(doc)]
+use std::f32::{INFINITY as INF, NAN};
+
+/// A intermediate [`FFloat`] value, which is not checked after each operation.
+///
+/// Create one with [`FFloat::lazy`], chain your operations, and call [`Unchecked::finish`] to check the final result (once).
+/// This gives the optimizer the most freedom, and keeps debug builds fast.
+/// ```
+/// # use umath::FFloat;
+/// let [a, b, c, d] = [1.0, 2.0, 3.0, 4.0].map(|x| unsafe { FFloat::new(x) });
+/// let x = (a.lazy() * b + c.lazy() * d - a).finish();
+/// assert_eq!(*x, 13.0);
+/// ```
+///
+/// ## Safety Notice
+///
+/// Not checking the intermediates does not make them any less fast: a [`Unchecked`] carries the same promise as the [`FFloat`] it came from,
+/// and producing [`NAN`] | [`INF`] at any point in the chain is still UB.
+#[repr(transparent)]
+pub struct Unchecked<T, P = Fast>(T, PhantomData<P>);
+
+impl<T: Copy, P> Clone for Unchecked<T, P> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<T: Copy, P> Copy for Unchecked<T, P> {}
+
+impl<T: FastFloat, P: Policy> FFloat<T, P> {
+    /// Starts a lazily checked expression. Refer to [`Unchecked`].
+    #[inline(always)]
+    pub fn lazy(self) -> Unchecked<T, P> {
+        Unchecked(self.raw(), PhantomData)
+    }
+}
+
+impl<T: FastFloat, P: Policy> Unchecked<T, P> {
+    /// Checks the result, turning it back into a [`FFloat`].
+    #[inline(always)]
+    pub fn finish(self) -> FFloat<T, P> {
+        unsafe { FFloat::with_policy(self.0) }
+    }
+}
+
+impl<T: FastFloat, P: Policy> From<Unchecked<T, P>> for FFloat<T, P> {
+    fn from(value: Unchecked<T, P>) -> Self {
+        value.finish()
+    }
+}
+
+macro_rules! op {
+    ($name:ident) => {
+        impl<T: FastFloat, P: Policy> $name for Unchecked<T, P> {
+            type Output = Self;
+            #[inline(always)]
+            fn $name(self, rhs: Self) -> Self {
+                Self(unsafe { T::$name::<P>(self.0, rhs.0) }, PhantomData)
+            }
+        }
+
+        impl<T: FastFloat, P: Policy> $name<T> for Unchecked<T, P> {
+            type Output = Self;
+            #[inline(always)]
+            fn $name(self, rhs: T) -> Self {
+                Self(unsafe { T::$name::<P>(self.0, rhs) }, PhantomData)
+            }
+        }
+
+        impl<T: FastFloat, P: Policy> $name<FFloat<T, P>> for Unchecked<T, P> {
+            type Output = Self;
+            #[inline(always)]
+            fn $name(self, rhs: FFloat<T, P>) -> Self {
+                Self(unsafe { T::$name::<P>(self.0, rhs.raw()) }, PhantomData)
+            }
+        }
+
+        impl<T: FastFloat, P: Policy> $name<Unchecked<T, P>> for FFloat<T, P> {
+            type Output = Unchecked<T, P>;
+            #[inline(always)]
+            fn $name(self, rhs: Unchecked<T, P>) -> Unchecked<T, P> {
+                Unchecked(unsafe { T::$name::<P>(self.raw(), rhs.0) }, PhantomData)
+            }
+        }
+    };
+}
+
+op!(add);
+op!(div);
+op!(mul);
+op!(rem);
+op!(sub);
+
+macro_rules! unchecked {
+    ($($name:ident => $op:ident, $verb:literal);+ $(;)?) => {
+        impl<T: FastFloat, P: Policy> FFloat<T, P> {
+            $(
+                #[doc = concat!("Raw fast ", $verb, ", skipping every check (on `self`, `rhs`, and the result).")]
+                ///
+                /// # Safety
+                ///
+                /// The result MUST NOT be anything the policy forbids (for [`Fast`], [`NAN`] | [`INF`]).
+                #[inline(always)]
+                pub unsafe fn $name(self, rhs: Self) -> Self {
+                    Self(T::$op::<P>(self.0, rhs.0), PhantomData)
+                }
+            )+
+        }
+    };
+}
+
+unchecked! {
+    add_unchecked => add, "addition";
+    sub_unchecked => sub, "subtraction";
+    mul_unchecked => mul, "multiplication";
+    div_unchecked => div, "division";
+    rem_unchecked => rem, "remainder";
+}
+
+impl<T: FastFloat, P: Policy> Neg for Unchecked<T, P> {
+    type Output = Self;
+    #[inline(always)]
+    fn neg(self) -> Self {
+        Self(-self.0, PhantomData)
+    }
+}
+
+impl<T: FastFloat, P> core::fmt::Debug for Unchecked<T, P> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(f, "{:?}", self.0)
+    }
+}
+
+#[test]
+#[allow(clippy::float_cmp)]
+fn mixed() {
+    let a = unsafe { FFloat::new(3.0f64) };
+    let x = (a * a.lazy() / 3.0 - -a.lazy()).finish();
+    assert_eq!(*x, 6.0);
+    assert_eq!(*unsafe { a.mul_unchecked(a).sub_unchecked(a) }, 6.0);
+}
@@ -7,9 +7,10 @@
 //! assert_eq!(*f, 35.0);
 //! # }
 //! ```
-#![feature(core_intrinsics)]
+#![feature(core_intrinsics, portable_simd)]
 #![warn(clippy::pedantic, clippy::dbg_macro, missing_docs)]
 #![allow(
+    internal_features,
     clippy::return_self_not_must_use,
     mixed_script_confusables,
     clippy::inline_always,
@@ -24,20 +25,92 @@ use core::ops::{
 #[cfg(doc)]
 use std::f32::{INFINITY as INF, NAN};
 use std::hash::Hash;
+use std::marker::PhantomData;
 
 /// Type alias for <code>[FFloat]<[f32]></code>. (fast float 32 bits)
 pub type FF32 = FFloat<f32>;
 /// Type alias for <code>[FFloat]<[f64]></code>. (fast float 64 bits)
 pub type FF64 = FFloat<f64>;
+/// Type alias for <code>[FFloat]<[f32x4](std::simd::f32x4)></code>. (4 packed fast float 32s)
+pub type FF32x4 = FFloat<std::simd::f32x4>;
+/// Type alias for <code>[FFloat]<[f32x8](std::simd::f32x8)></code>. (8 packed fast float 32s)
+pub type FF32x8 = FFloat<std::simd::f32x8>;
+/// Type alias for <code>[FFloat]<[f64x4](std::simd::f64x4)></code>. (4 packed fast float 64s)
+pub type FF64x4 = FFloat<std::simd::f64x4>;
 
+mod afloat;
+mod atomic;
+pub mod audio;
+pub mod bounds;
+mod cell;
+mod checked;
+pub mod complex;
+pub mod convert;
+pub mod convolution;
+pub mod curve;
+pub mod diff;
+pub mod dual;
+pub mod easing;
+mod estimate;
+pub mod fenv;
+pub mod fft;
+pub mod filter;
+mod finite;
+mod fixed;
 pub mod generic_float;
+pub mod interval;
+mod iter;
+mod lazy;
+pub mod lut;
+pub mod logfloat;
+pub mod matrix;
+pub mod measured;
+pub mod noise;
+pub mod ode;
+mod nonneg;
+mod notnan;
+pub mod policy;
+pub mod polynomial;
+pub mod predicates;
+pub mod prob;
+pub mod quadrature;
+pub mod quaternion;
+pub mod random;
+pub mod ray;
+pub mod resample;
+pub mod roots;
+mod saturating;
+mod simd;
+pub mod slice;
+mod solve;
+pub mod spline;
+pub mod stats;
+mod unit;
+pub mod units;
+pub mod vector;
+pub mod window;
 mod r#trait;
+pub use afloat::AFloat;
+pub use atomic::{AtomicFF32, AtomicFF64};
+pub use cell::FCell;
+pub use finite::Finite;
+pub use iter::FFloatIterExt;
+pub use lazy::Unchecked;
+pub use nonneg::NonNegative;
+pub use notnan::NotNan;
+pub use saturating::Saturating;
+pub use unit::UnitFloat;
 #[doc(inline)]
 pub use generic_float::Float;
-use r#trait::FastFloat;
+use generic_float::Constructors;
+use policy::{Fast, NoNan, Policy};
+use r#trait::{FastFloat, Show, Wrapper};
 
 /// Float wrapper that uses `ffast-math`. This float also implements [`Ord`], [`Hash`], and [`Eq`], as it is not allowed to be [`NAN`].
 ///
+/// The applied fast-math flags may be chosen with the [`Policy`] parameter, which defaults to [`Fast`] (all of them).
+/// Only policies which forbid [`NAN`] (see [`NoNan`]) get [`Ord`], [`Hash`], and [`Eq`].
+///
 /// `FFloat<F>` is guaranteed to have the same memory layout and ABI as F.
 /// ```
 /// # use umath::FFloat;
@@ -47,22 +120,34 @@ use r#trait::FastFloat;
 /// # }
 /// ```
 ///
+/// With the `portable_simd` feature, `FFloat<Simd<f32, N>>` | `FFloat<Simd<f64, N>>` apply the fast math flags to every lane.
+/// The invariant is checked lane wise: if any lane is [`NAN`] | [`INF`], the whole vector is.
+/// ```
+/// #![feature(portable_simd)]
+/// # use umath::FFloat;
+/// use std::simd::f32x4;
+/// let x = unsafe { FFloat::new(f32x4::from_array([1.0, 2.0, 3.0, 4.0])) };
+/// let y = x * f32x4::splat(2.0) + x;
+/// assert_eq!(y.to_array(), [3.0, 6.0, 9.0, 12.0]);
+/// ```
+///
 /// ## Safety Notice (for transmuters)
 ///
-/// A [`FFloat`] is _never_ allowed to be [`NAN`] | [`INF`].
+/// A [`FFloat`] is _never_ allowed to be [`NAN`] | [`INF`] (or, for other policies, whatever the policy forbids).
 #[repr(transparent)]
-#[derive(Copy, Clone, PartialEq)]
-pub struct FFloat<T>(T);
+pub struct FFloat<T, P = Fast>(T, PhantomData<P>);
 
-impl<T: FastFloat> core::fmt::Debug for FFloat<T> {
-    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
-        write!(f, "{:?}", self.0)
+impl<T: Clone, P> Clone for FFloat<T, P> {
+    fn clone(&self) -> Self {
+        Self(self.0.clone(), PhantomData)
     }
 }
 
-impl<T: FastFloat> core::fmt::Display for FFloat<T> {
-    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
-        write!(f, "{}", self.0)
+impl<T: Copy, P> Copy for FFloat<T, P> {}
+
+impl<T: PartialEq, P> PartialEq for FFloat<T, P> {
+    fn eq(&self, other: &Self) -> bool {
+        self.0 == other.0
     }
 }
 
@@ -75,28 +160,43 @@ impl<T: FastFloat> FFloat<T> {
     /// let f = unsafe { FFloat::new(7.0) };
     /// ```
     pub unsafe fn new(from: T) -> Self {
-        let new = Self(from);
-        new.check();
-        new
+        Self::with_policy(from)
     }
+}
 
-    /// Checks if somebody else made a mistake, cause UB or panic if so.
+impl<T: FastFloat, P: Policy> FFloat<T, P> {
+    /// Create a new [`FFloat`], with any [`Policy`], from your {[`f32`], [`f64`]}.
+    ///
     /// # Safety
     ///
-    /// This can never cause UB unless someone else made a mistake, therefore ub has already occured.
+    /// Same as [`FFloat::new`], except that you must only uphold what the policy forbids (for [`Fast`], [`NAN`] | [`INF`]).
+    /// ```
+    /// # use umath::{FFloat, policy::NoInf};
+    /// let f = unsafe { FFloat::<_, NoInf>::with_policy(f32::NAN) };
+    /// ```
+    pub unsafe fn with_policy(from: T) -> Self {
+        let new = Self(from, PhantomData);
+        new.check();
+        new
+    }
+}
+
+impl<T: FastFloat, P: Policy> Wrapper for FFloat<T, P> {
+    type Inner = T;
+    type Policy = P;
+
     #[inline(always)]
-    fn check(self) {
-        if self.bad() {
-            if cfg!(debug_assertions) {
-                panic!("{self} is NAN | INF.");
-            } else {
-                unsafe { core::hint::unreachable_unchecked() };
-            }
-        }
+    fn raw(self) -> T {
+        self.0
+    }
+
+    #[inline(always)]
+    unsafe fn wrap(x: T) -> Self {
+        Self::with_policy(x)
     }
 }
 
-impl<T> Deref for FFloat<T> {
+impl<T, P> Deref for FFloat<T, P> {
     type Target = T;
 
     fn deref(&self) -> &Self::Target {
@@ -104,142 +204,176 @@ impl<T> Deref for FFloat<T> {
     }
 }
 
-impl<T> DerefMut for FFloat<T> {
+impl<T, P> DerefMut for FFloat<T, P> {
     fn deref_mut(&mut self) -> &mut Self::Target {
         &mut self.0
     }
 }
 
+// every value is checked when it is created (by `wrap`), so the operations only check their result.
 macro_rules! op {
-    ($name:ident) => {
-        impl<T: FastFloat> $name<T> for FFloat<T> {
-            type Output = FFloat<T>;
+    ([$($g:tt)*] $ty:ty, $t:ident, $name:ident) => {
+        impl<$($g)*> $name<$t> for $ty {
+            type Output = $ty;
 
-            fn $name(self, rhs: T) -> Self::Output {
-                self.check();
-                unsafe { Self::new(T::$name(self.0, rhs)) }
+            fn $name(self, rhs: $t) -> Self::Output {
+                unsafe { Self::wrap(<$t as FastFloat>::$name::<<Self as Wrapper>::Policy>(self.raw(), rhs)) }
             }
         }
 
-        impl<T: FastFloat> $name<&T> for FFloat<T> {
-            type Output = FFloat<T>;
+        impl<$($g)*> $name<&$t> for $ty {
+            type Output = $ty;
 
-            fn $name(self, rhs: &T) -> Self::Output {
-                self.check();
-                unsafe { Self::new(T::$name(self.0, *rhs)) }
+            fn $name(self, rhs: &$t) -> Self::Output {
+                unsafe { Self::wrap(<$t as FastFloat>::$name::<<Self as Wrapper>::Policy>(self.raw(), *rhs)) }
             }
         }
 
-        impl<T: FastFloat> $name for FFloat<T> {
-            type Output = FFloat<T>;
-            fn $name(self, FFloat(rhs): FFloat<T>) -> Self::Output {
-                self.check();
-                unsafe { Self::new(T::$name(self.0, rhs)) }
+        impl<$($g)*> $name for $ty {
+            type Output = $ty;
+            fn $name(self, rhs: $ty) -> Self::Output {
+                unsafe { Self::wrap(<$t as FastFloat>::$name::<<Self as Wrapper>::Policy>(self.raw(), rhs.raw())) }
             }
         }
 
-        impl<T: FastFloat> $name<&FFloat<T>> for FFloat<T> {
-            type Output = FFloat<T>;
-            fn $name(self, FFloat(rhs): &FFloat<T>) -> Self::Output {
-                self.check();
-                unsafe { Self::new(T::$name(self.0, *rhs)) }
+        impl<$($g)*> $name<&$ty> for $ty {
+            type Output = $ty;
+            fn $name(self, rhs: &$ty) -> Self::Output {
+                unsafe { Self::wrap(<$t as FastFloat>::$name::<<Self as Wrapper>::Policy>(self.raw(), rhs.raw())) }
             }
         }
     };
 }
 
-op!(add);
-op!(div);
-op!(mul);
-op!(rem);
-op!(sub);
-
 macro_rules! assign {
-    ($name:ident, $op:ident) => {
-        impl<T: FastFloat> $name<T> for FFloat<T> {
-            fn $name(&mut self, rhs: T) {
-                self.check();
-                *self = unsafe { Self::new(T::$op(self.0, rhs)) };
+    ([$($g:tt)*] $ty:ty, $t:ident, $name:ident, $op:ident) => {
+        impl<$($g)*> $name<$t> for $ty {
+            fn $name(&mut self, rhs: $t) {
+                *self = unsafe { Self::wrap(<$t as FastFloat>::$op::<<Self as Wrapper>::Policy>(self.raw(), rhs)) };
+            }
+        }
+
+        impl<$($g)*> $name<&$t> for $ty {
+            fn $name(&mut self, rhs: &$t) {
+                *self = unsafe { Self::wrap(<$t as FastFloat>::$op::<<Self as Wrapper>::Policy>(self.raw(), *rhs)) };
+            }
+        }
+
+        impl<$($g)*> $name for $ty {
+            fn $name(&mut self, rhs: $ty) {
+                *self = unsafe { Self::wrap(<$t as FastFloat>::$op::<<Self as Wrapper>::Policy>(self.raw(), rhs.raw())) };
+            }
+        }
+
+        impl<$($g)*> $name<&$ty> for $ty {
+            fn $name(&mut self, rhs: &$ty) {
+                *self = unsafe { Self::wrap(<$t as FastFloat>::$op::<<Self as Wrapper>::Policy>(self.raw(), rhs.raw())) };
+            }
+        }
+    };
+}
+
+/// Implements the operators, comparisons, and formatting for a [`Wrapper`].
+macro_rules! wrapper {
+    ([$($g:tt)*] $ty:ty, $t:ident) => {
+        op!([$($g)*] $ty, $t, add);
+        op!([$($g)*] $ty, $t, div);
+        op!([$($g)*] $ty, $t, mul);
+        op!([$($g)*] $ty, $t, rem);
+        op!([$($g)*] $ty, $t, sub);
+
+        assign!([$($g)*] $ty, $t, add_assign, add);
+        assign!([$($g)*] $ty, $t, div_assign, div);
+        assign!([$($g)*] $ty, $t, mul_assign, mul);
+        assign!([$($g)*] $ty, $t, rem_assign, rem);
+        assign!([$($g)*] $ty, $t, sub_assign, sub);
+
+        // convenience
+        impl<$($g)*> Neg for $ty {
+            type Output = Self;
+            fn neg(self) -> Self::Output {
+                unsafe { Self::wrap(-self.raw()) }
             }
         }
 
-        impl<T: FastFloat> $name<&T> for FFloat<T> {
-            fn $name(&mut self, rhs: &T) {
+        impl<$($g)*> PartialEq<$t> for $ty {
+            fn eq(&self, other: &$t) -> bool {
                 self.check();
-                *self = unsafe { Self::new(T::$op(self.0, *rhs)) };
+                self.raw().eq(other)
             }
         }
 
-        impl<T: FastFloat> $name for FFloat<T> {
-            fn $name(&mut self, FFloat(rhs): FFloat<T>) {
+        #[allow(clippy::non_canonical_partial_ord_impl)]
+        impl<$($g)*> PartialOrd for $ty {
+            fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
                 self.check();
-                *self = unsafe { Self::new(T::$op(self.0, rhs)) };
+                other.check();
+                if <<Self as Wrapper>::Policy as Policy>::NNAN {
+                    // SAFETY: neither side may be NAN.
+                    Some(unsafe { self.raw().partial_cmp(&other.raw()).unwrap_unchecked() })
+                } else {
+                    self.raw().partial_cmp(&other.raw())
+                }
             }
         }
 
-        impl<T: FastFloat> $name<&FFloat<T>> for FFloat<T> {
-            fn $name(&mut self, FFloat(rhs): &FFloat<T>) {
+        impl<$($g)*> PartialOrd<$t> for $ty {
+            fn partial_cmp(&self, other: &$t) -> Option<Ordering> {
                 self.check();
-                *self = unsafe { Self::new(T::$op(self.0, *rhs)) };
+                self.raw().partial_cmp(other)
+            }
+        }
+
+        impl<$($g)*> core::fmt::Debug for $ty {
+            fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+                write!(f, "{:?}", self.raw())
+            }
+        }
+
+        impl<$($g)*> core::fmt::Display for $ty {
+            fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+                write!(f, "{}", Show(self.raw()))
             }
         }
     };
 }
-assign!(add_assign, add);
-assign!(div_assign, div);
-assign!(mul_assign, mul);
-assign!(rem_assign, rem);
-assign!(sub_assign, sub);
-
-// convenience
-impl<T: FastFloat> Neg for FFloat<T> {
-    type Output = Self;
-    fn neg(self) -> Self::Output {
-        self.check();
-        unsafe { Self::new(-self.0) }
-    }
-}
 
-impl<T: FastFloat> PartialEq<T> for FFloat<T> {
-    fn eq(&self, other: &T) -> bool {
-        self.check();
-        self.0.eq(other)
-    }
-}
-impl<T: FastFloat> Eq for FFloat<T> {}
-impl<T: FastFloat> PartialOrd for FFloat<T> {
-    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
-        Some(self.cmp(other))
-    }
-}
-impl<T: FastFloat> PartialOrd<T> for FFloat<T> {
-    fn partial_cmp(&self, other: &T) -> Option<Ordering> {
-        self.check();
-        self.0.partial_cmp(other)
-    }
-}
-impl<T: FastFloat> Ord for FFloat<T> {
-    fn cmp(&self, other: &Self) -> Ordering {
-        self.check();
-        unsafe { self.0.partial_cmp(&other.0).unwrap_unchecked() }
-    }
-}
+wrapper!([T: FastFloat, P: Policy] FFloat<T, P>, T);
+wrapper!([T: FastFloat] AFloat<T>, T);
+wrapper!([T: FastFloat] NotNan<T>, T);
+wrapper!([T: FastFloat] Finite<T>, T);
+wrapper!([T: FastFloat + Constructors] UnitFloat<T>, T);
+wrapper!([T: FastFloat + Constructors] NonNegative<T>, T);
 
-impl Hash for FFloat<f32> {
-    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
-        self.check();
-        state.write_u32((self.0 + 0.0).to_bits());
-    }
-}
+/// Implements [`Eq`], [`Ord`], and [`Hash`] for a [`Wrapper`] that is never [`NAN`].
+macro_rules! total {
+    ([$($g:tt)*] $ty:ty) => {
+        impl<$($g)*> Eq for $ty {}
 
-impl Hash for FFloat<f64> {
-    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
-        self.check();
-        state.write_u64((self.0 + 0.0).to_bits());
-    }
+        impl<$($g)*> Ord for $ty {
+            fn cmp(&self, other: &Self) -> Ordering {
+                self.check();
+                unsafe { self.raw().partial_cmp(&other.raw()).unwrap_unchecked() }
+            }
+        }
+
+        impl<$($g)*> Hash for $ty {
+            fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+                self.check();
+                self.raw().hash(state);
+            }
+        }
+    };
 }
 
+total!([T: FastFloat, P: NoNan] FFloat<T, P>);
+total!([T: FastFloat] NotNan<T>);
+total!([T: FastFloat] Finite<T>);
+total!([T: FastFloat + Constructors] UnitFloat<T>);
+total!([T: FastFloat + Constructors] NonNegative<T>);
+
 #[cfg(test)]
+#[allow(clippy::float_cmp)]
 mod tests {
     use std::collections::HashMap;
 
@@ -251,13 +385,44 @@ mod tests {
     }
 
     #[test]
+    #[cfg(any(
+        feature = "strict-checks",
+        all(debug_assertions, not(feature = "trusted"))
+    ))]
+    #[should_panic = "NaN is NAN | INF."]
+    fn checked() {
+        let _ = unsafe { FFloat::new(f32::NAN) };
+    }
+
+    #[test]
+    #[cfg(any(
+        feature = "strict-checks",
+        all(debug_assertions, not(feature = "trusted"))
+    ))]
+    #[should_panic = "inf is INF."]
+    fn checked_inf() {
+        let _ = unsafe { FFloat::<_, policy::NoInf>::with_policy(f32::INFINITY) };
+    }
+
+    #[test]
+    #[cfg(any(
+        feature = "strict-checks",
+        all(debug_assertions, not(feature = "trusted"))
+    ))]
+    #[should_panic = "NaN is NAN."]
+    fn checked_nan() {
+        let _ = unsafe { NotNan::new(f64::NAN) };
+    }
+
+    #[test]
+    #[allow(clippy::manual_assert_eq)]
     fn hashing() {
         let mut map = HashMap::new();
-        map.insert(FFloat(2.0), "hi");
-        map.insert(FFloat(7.0), "bye");
-        map.insert(FFloat(-0.0), "edge");
-        assert!(map[&FFloat(2.0)] == "hi");
-        assert!(map[&FFloat(7.0)] == "bye");
-        assert!(map[&FFloat(0.0)] == "edge");
+        map.insert(FFloat::<f64>(2.0, PhantomData), "hi");
+        map.insert(FFloat::<f64>(7.0, PhantomData), "bye");
+        map.insert(FFloat::<f64>(-0.0, PhantomData), "edge");
+        assert!(map[&FFloat::<f64>(2.0, PhantomData)] == "hi");
+        assert!(map[&FFloat::<f64>(7.0, PhantomData)] == "bye");
+        assert!(map[&FFloat::<f64>(0.0, PhantomData)] == "edge");
     }
 }
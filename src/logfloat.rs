@@ -0,0 +1,258 @@
+//! log domain floats.
+//!
+//! A [`LogFloat`] stores the natural logarithm of a non negative number, so very large and very small magnitudes
+//! (like the product of thousands of probabilities) neither underflow to zero, nor overflow to infinity.
+//! Multiplication becomes addition, and addition uses log-sum-exp.
+//! ```
+//! # use umath::{FFloat, logfloat::LogFloat};
+//! let tiny = LogFloat::new(unsafe { FFloat::new(1e-30f64) });
+//! let product: LogFloat<_> = std::iter::repeat(tiny).take(1000).product();
+//! assert!((-69078.0..-69077.0).contains(&*product.ln())); // 1e-30000
+//! assert_eq!(*product.value(), 0.0); // underflows as a plain float
+//! let sum = product + product;
+//! assert!((*sum.ln() - *product.ln() - 2f64.ln()).abs() < 1e-9);
+//! ```
+//!
+//! Zero is stored as the minimum float (instead of -[`INF`]), so that this can be used with [`FFloat`].
+use crate::generic_float::{lit, Constructors, FloatAlone};
+use core::cmp::Ordering;
+use core::iter::{Product, Sum};
+use core::ops::{Add, Div, Mul, Sub};
+#[cfg(doc)]
+use {crate::FFloat, std::f32::INFINITY as INF};
+
+/// A non negative number, stored as its natural logarithm. Refer to the [module level documentation](self).
+#[derive(Copy, Clone, Debug)]
+pub struct LogFloat<F> {
+    ln: F,
+}
+
+impl<F: FloatAlone> LogFloat<F> {
+    /// Creates a new [`LogFloat`] from a value. The magnitude of `x` is used, and 0 is supported.
+    pub fn new(x: F) -> Self {
+        let x = x.abs();
+        if x == unsafe { F::zero() } {
+            Self::zero()
+        } else {
+            Self { ln: x.ln() }
+        }
+    }
+
+    /// Creates a new [`LogFloat`] from the natural logarithm of its value.
+    pub fn from_ln(ln: F) -> Self {
+        Self { ln }
+    }
+
+    /// Zero.
+    #[must_use]
+    pub fn zero() -> Self {
+        Self {
+            ln: unsafe { <F as Constructors>::min() },
+        }
+    }
+
+    /// One.
+    #[must_use]
+    pub fn one() -> Self {
+        Self {
+            ln: unsafe { F::zero() },
+        }
+    }
+
+    /// Checks if this is zero.
+    pub fn is_zero(self) -> bool {
+        self.ln == unsafe { <F as Constructors>::min() }
+    }
+
+    /// The natural logarithm of the value.
+    pub fn ln(self) -> F {
+        self.ln
+    }
+
+    /// The value, as a plain float. This underflows to 0, and saturates at the maximum float.
+    pub fn value(self) -> F {
+        if self.is_zero() {
+            return unsafe { F::zero() };
+        }
+        // the logarithm of MAX is rounded, so step below it.
+        let limit = unsafe { <F as Constructors>::max() }.ln().next_down();
+        self.ln.min(limit).exp()
+    }
+
+    /// Raises to the power of `n` (`n` must be positive). Like the other operations, this saturates at zero and at the largest value.
+    ///
+    /// # Panics
+    ///
+    /// If `n` is not finite.
+    #[track_caller]
+    pub fn powf(self, n: F) -> Self {
+        // SAFETY: MAX is finite
+        let max = unsafe { <F as Constructors>::max() };
+        assert!(n.abs() <= max, "the power must be finite");
+        if self.is_zero() {
+            return self;
+        }
+        if n.abs() > lit(1.0) && self.ln.abs() > max / n.abs() {
+            // SAFETY: 0 is a valid float
+            let zero = unsafe { F::zero() };
+            return if (self.ln > zero) == (n > zero) {
+                Self::from_ln(max)
+            } else {
+                Self::zero()
+            };
+        }
+        Self { ln: self.ln * n }
+    }
+
+    /// `e^(a + b)`, saturating instead of overflowing the logarithm.
+    fn exp_sum(a: F, b: F) -> Self {
+        // SAFETY: 0 is a valid float
+        let zero = unsafe { F::zero() };
+        // SAFETY: MIN and MAX are finite
+        let (min, max) = unsafe { (<F as Constructors>::min(), <F as Constructors>::max()) };
+        if a > zero && b > max - a {
+            Self::from_ln(max)
+        } else if a < zero && b < min - a {
+            Self::zero()
+        } else {
+            Self { ln: a + b }
+        }
+    }
+
+    /// `e^(a - b) == 0`, where `a < b`: the difference would overflow, so the exponential would underflow anyway.
+    fn negligible(a: F, b: F) -> bool {
+        // SAFETY: 0 is a valid float
+        b > unsafe { F::zero() } && a < unsafe { <F as Constructors>::min() } + b
+    }
+
+    /// The square root.
+    pub fn sqrt(self) -> Self {
+        self.powf(lit(0.5))
+    }
+}
+
+impl<F: FloatAlone> Default for LogFloat<F> {
+    fn default() -> Self {
+        Self::zero()
+    }
+}
+
+impl<F: FloatAlone> PartialEq for LogFloat<F> {
+    fn eq(&self, other: &Self) -> bool {
+        self.ln == other.ln
+    }
+}
+
+impl<F: FloatAlone> PartialOrd for LogFloat<F> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        self.ln.partial_cmp(&other.ln)
+    }
+}
+
+impl<F: FloatAlone> Mul for LogFloat<F> {
+    type Output = Self;
+    fn mul(self, rhs: Self) -> Self {
+        if self.is_zero() || rhs.is_zero() {
+            return Self::zero();
+        }
+        Self::exp_sum(self.ln, rhs.ln)
+    }
+}
+
+impl<F: FloatAlone> Div for LogFloat<F> {
+    type Output = Self;
+    /// Divides.
+    ///
+    /// # Panics
+    ///
+    /// If the divisor is zero.
+    #[track_caller]
+    fn div(self, rhs: Self) -> Self {
+        assert!(!rhs.is_zero(), "division by zero");
+        if self.is_zero() {
+            return self;
+        }
+        Self::exp_sum(self.ln, -rhs.ln)
+    }
+}
+
+impl<F: FloatAlone> Add for LogFloat<F> {
+    type Output = Self;
+    /// Adds, with log-sum-exp: `max + ln(1 + e^(min - max))`.
+    fn add(self, rhs: Self) -> Self {
+        let (hi, lo) = if self >= rhs {
+            (self, rhs)
+        } else {
+            (rhs, self)
+        };
+        if lo.is_zero() || Self::negligible(lo.ln, hi.ln) {
+            return hi;
+        }
+        Self::exp_sum(hi.ln, (lit::<F>(1.0) + (lo.ln - hi.ln).exp()).ln())
+    }
+}
+
+impl<F: FloatAlone> Sub for LogFloat<F> {
+    type Output = Self;
+    /// Subtracts: `self + ln(1 - e^(rhs - self))`. As this can not be negative, it saturates at zero.
+    fn sub(self, rhs: Self) -> Self {
+        if rhs.is_zero() {
+            return self;
+        }
+        if self <= rhs {
+            return Self::zero();
+        }
+        if Self::negligible(rhs.ln, self.ln) {
+            return self;
+        }
+        let d = lit::<F>(1.0) - (rhs.ln - self.ln).exp();
+        if d <= unsafe { F::zero() } {
+            return Self::zero();
+        }
+        Self::exp_sum(self.ln, d.ln())
+    }
+}
+
+impl<F: FloatAlone> Sum for LogFloat<F> {
+    fn sum<I: Iterator<Item = Self>>(iter: I) -> Self {
+        iter.fold(Self::zero(), Add::add)
+    }
+}
+
+impl<F: FloatAlone> Product for LogFloat<F> {
+    fn product<I: Iterator<Item = Self>>(iter: I) -> Self {
+        iter.fold(Self::one(), Mul::mul)
+    }
+}
+
+#[test]
+fn zero() {
+    let two = LogFloat::new(2.0f32);
+    assert!((two * LogFloat::zero()).is_zero());
+    assert_eq!(two + LogFloat::zero(), two);
+    assert!((two - two).is_zero());
+    assert!(((two - LogFloat::one()).value() - 1.0).abs() < 1e-6);
+    assert!((two.sqrt().value() - core::f32::consts::SQRT_2).abs() < 1e-6);
+}
+
+#[test]
+#[allow(clippy::float_cmp)]
+fn saturating() {
+    let (min, max) = (LogFloat::from_ln(f64::MIN), LogFloat::from_ln(f64::MAX));
+    assert!(min.is_zero());
+    assert_eq!(max * max, max);
+    assert_eq!(max + max, max);
+    assert_eq!(max + LogFloat::one(), max);
+    assert_eq!(max - LogFloat::one(), max);
+    assert!((LogFloat::from_ln(-f64::MAX) / max).is_zero());
+    assert!((LogFloat::from_ln(-f64::MAX) * LogFloat::from_ln(-f64::MAX)).is_zero());
+    assert_eq!(LogFloat::from_ln(-f64::MAX) + max, max);
+    assert_eq!(max.powf(2.0), max);
+    assert!(max.powf(-2.0).is_zero());
+    assert!(LogFloat::from_ln(-f64::MAX).powf(3.0).is_zero());
+    assert_eq!(max.sqrt().ln(), f64::MAX / 2.0);
+    assert!(std::panic::catch_unwind(|| max.powf(f64::INFINITY)).is_err());
+    assert!(std::panic::catch_unwind(|| max / min).is_err());
+    let fast = LogFloat::from_ln(unsafe { crate::FFloat::new(f64::MAX) });
+    assert_eq!(*(fast * fast + fast).ln(), f64::MAX);
+}
@@ -0,0 +1,184 @@
+//! lookup tables: cheap approximations of expensive functions, by interpolating between precomputed samples.
+use crate::generic_float::{lit, FloatExt};
+use crate::policy::Policy;
+use crate::r#trait::{FastFloat, Wrapper};
+use crate::FFloat;
+
+/// A function sampled at evenly spaced points over `[start, end]`, evaluated by interpolating the samples.
+///
+/// The sample index is found with a multiply by the inverse spacing (no division, and no search), and inputs outside of the range are clamped to it (with min | max, not branches),
+/// so a evaluation is a handful of instructions, and two (or, [cubic](Self::eval_cubic), four) loads.
+/// The error of linear interpolation is about `h² f'' / 8`, for a spacing of `h`: so each doubling of the table quarters it.
+/// ```
+/// # use umath::{FFloat, generic_float::Trig, lut::Lut};
+/// let f = |x| unsafe { FFloat::new(x) };
+/// let sin = Lut::new(|x: FFloat<f32>| x.sin(), f(0.0), f(core::f32::consts::PI), 256);
+/// assert!((*sin.eval(f(1.0)) - 1f32.sin()).abs() < 1e-5);
+/// assert!((*sin.eval_cubic(f(1.0)) - 1f32.sin()).abs() < 1e-6);
+/// // clamped
+/// assert_eq!(*sin.eval(f(-5.0)), 0.0);
+/// ```
+#[derive(Clone, Debug, PartialEq)]
+pub struct Lut<F> {
+    table: Vec<F>,
+    start: F,
+    end: F,
+    /// `(table.len() - 1) / (end / 2 - start / 2)`, in [`f64`], so that neither the span, nor the offset of a input, can overflow.
+    scale: f64,
+}
+
+impl<T: FastFloat + FloatExt + Into<f64>, P: Policy> Lut<FFloat<T, P>> {
+    /// Samples `f` at `size` evenly spaced points from `start` to `end` (inclusive).
+    ///
+    /// # Panics
+    ///
+    /// If `size` is less than 2, or `start` is not less than `end`.
+    #[must_use]
+    #[track_caller]
+    #[allow(clippy::cast_precision_loss)]
+    pub fn new(
+        mut f: impl FnMut(FFloat<T, P>) -> FFloat<T, P>,
+        start: FFloat<T, P>,
+        end: FFloat<T, P>,
+        size: usize,
+    ) -> Self {
+        assert!(size > 1, "a table needs at least 2 entries");
+        assert!(start < end, "start must be less than end");
+        let last = size - 1;
+        let (low, high): (f64, f64) = (start.raw().into(), end.raw().into());
+        let table = (0..size)
+            .map(|i| {
+                let t = i as f64 / last as f64;
+                // the last sample exactly at `end`; the others interpolated in f64 (without the span, which can overflow), and clamped to the range.
+                f(if i == last {
+                    end
+                } else {
+                    // SAFETY: between start and end, which are finite.
+                    unsafe {
+                        FFloat::wrap(T::from_f64((low * (1.0 - t) + high * t).max(low).min(high)))
+                    }
+                })
+            })
+            .collect();
+        Self::from_table(table, start, end)
+    }
+
+    /// Uses `table` as samples at evenly spaced points from `start` to `end` (inclusive).
+    ///
+    /// # Panics
+    ///
+    /// If `table` has less than 2 entries, or `start` is not less than `end`.
+    #[must_use]
+    #[track_caller]
+    #[allow(clippy::cast_precision_loss)]
+    pub fn from_table(table: Vec<FFloat<T, P>>, start: FFloat<T, P>, end: FFloat<T, P>) -> Self {
+        assert!(table.len() > 1, "a table needs at least 2 entries");
+        assert!(start < end, "start must be less than end");
+        let last = (table.len() - 1) as f64;
+        let (low, high): (f64, f64) = (start.raw().into(), end.raw().into());
+        Self {
+            scale: last / (high * 0.5 - low * 0.5),
+            table,
+            start,
+            end,
+        }
+    }
+
+    /// The samples.
+    #[must_use]
+    pub fn table(&self) -> &[FFloat<T, P>] {
+        &self.table
+    }
+
+    /// The start of the range.
+    #[must_use]
+    pub fn start(&self) -> FFloat<T, P> {
+        self.start
+    }
+
+    /// The end of the range.
+    #[must_use]
+    pub fn end(&self) -> FFloat<T, P> {
+        self.end
+    }
+
+    /// The index of the sample at or below `x` (clamped to the range, and at most the second to last), and the position past it, from 0 to 1.
+    #[inline(always)]
+    #[allow(
+        clippy::cast_precision_loss,
+        clippy::cast_possible_truncation,
+        clippy::cast_sign_loss
+    )]
+    fn locate(&self, x: FFloat<T, P>) -> (usize, FFloat<T, P>) {
+        let last = self.table.len() - 1;
+        let (x, start, end): (f64, f64, f64) = (
+            x.raw().into(),
+            self.start.raw().into(),
+            self.end.raw().into(),
+        );
+        // clamped first, so that the offset is at most the span; u is then at most `last`, up to rounding (or NAN, if the scale overflowed, which max turns into 0).
+        let x = x.max(start).min(end);
+        let u = ((x * 0.5 - start * 0.5) * self.scale)
+            .max(0.0)
+            .min(last as f64);
+        let i = (u as usize).min(last - 1);
+        // SAFETY: u - i is in [0, 1].
+        (i, unsafe { FFloat::wrap(T::from_f64(u - i as f64)) })
+    }
+
+    /// The value at `x`, by linear interpolation between the nearest samples.
+    #[inline]
+    #[must_use]
+    pub fn eval(&self, x: FFloat<T, P>) -> FFloat<T, P> {
+        let (i, t) = self.locate(x);
+        let (low, high) = (self.table[i], self.table[i + 1]);
+        low + (high - low) * t
+    }
+
+    /// The value at `x`, by cubic (Catmull-Rom) interpolation between the nearest four samples (repeating the first | last sample at the ends).
+    ///
+    /// This is exact for quadratics (away from the ends), and its error shrinks as `h³`.
+    #[inline]
+    #[must_use]
+    pub fn eval_cubic(&self, x: FFloat<T, P>) -> FFloat<T, P> {
+        let (i, t) = self.locate(x);
+        let last = self.table.len() - 1;
+        let p0 = self.table[i.saturating_sub(1)];
+        let (p1, p2) = (self.table[i], self.table[i + 1]);
+        let p3 = self.table[(i + 2).min(last)];
+        let [half, two, three, four, five] = [0.5, 2.0, 3.0, 4.0, 5.0].map(lit::<FFloat<T, P>>);
+        let c1 = p2 - p0;
+        let c2 = p0 * two - p1 * five + p2 * four - p3;
+        let c3 = (p1 - p2) * three + p3 - p0;
+        p1 + half * t * (c1 + t * (c2 + t * c3))
+    }
+}
+
+#[test]
+#[allow(clippy::float_cmp)]
+fn interpolates() {
+    let f = |x| unsafe { FFloat::<f64>::new(x) };
+    let square = Lut::new(|x| x * x, f(-1.0), f(3.0), 9);
+    assert_eq!(square.table().len(), 9);
+    // the samples are exact, and linear interpolation is close in between
+    assert_eq!(*square.eval(f(2.5)), 6.25);
+    assert!((*square.eval(f(2.25)) - 5.0625).abs() <= 0.0625);
+    // cubic is exact for quadratics, away from the ends
+    for i in 0..=20u8 {
+        let x = f(f64::from(i) * 0.1);
+        assert!((*square.eval_cubic(x) - *x * *x).abs() < 1e-12, "{x}");
+    }
+    // clamped at both ends
+    assert_eq!(*square.eval(f(-100.0)), 1.0);
+    assert_eq!(*square.eval(f(100.0)), 9.0);
+    assert_eq!(*square.eval_cubic(f(3.0)), 9.0);
+    let table = Lut::from_table(vec![f(0.0), f(10.0)], f(0.0), f(1.0));
+    assert_eq!(*table.eval(f(0.25)), 2.5);
+    // far outside of a tiny range, and over the whole range of the float, nothing overflows
+    let tiny = Lut::new(|x| x, f(0.0), f(1e-30), 16);
+    assert_eq!(*tiny.eval(f(1e10)), 1e-30);
+    assert_eq!(*tiny.eval(f(-f64::MAX)), 0.0);
+    let whole = Lut::new(|x| x, f(-f64::MAX), f(f64::MAX), 17);
+    assert_eq!(*whole.eval(f(0.0)), 0.0);
+    assert_eq!(*whole.eval(f(f64::MAX)), f64::MAX);
+}
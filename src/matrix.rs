@@ -0,0 +1,427 @@
+//! small square matrices: [`Mat2`], [`Mat3`], and [`Mat4`].
+//!
+//! The matrices are column major, as arrays of column [vectors](crate::vector), and multiply column vectors (on their right), as in most graphics apis.
+//! Each product is a sum of columns scaled by components, `Σ columns[i] · v[i]`: a chain of `a * b + c`, which a [`Policy`](crate::policy::Policy) that allows contraction (such as [`Fast`](crate::policy::Fast)) turns into fused multiply adds.
+//! ```
+//! # use umath::{FFloat, matrix::Mat4, vector::{Vec3, Vec4}};
+//! let f = |x| unsafe { FFloat::new(x) };
+//! let v = |x, y, z| Vec3::new(f(x), f(y), f(z));
+//! // a scale by 2, then a translation by (1, 2, 3)
+//! let transform = Mat4::from_columns([
+//!     Vec4::new(f(2.0f32), f(0.0), f(0.0), f(0.0)),
+//!     Vec4::new(f(0.0), f(2.0), f(0.0), f(0.0)),
+//!     Vec4::new(f(0.0), f(0.0), f(2.0), f(0.0)),
+//!     Vec4::new(f(1.0), f(2.0), f(3.0), f(1.0)),
+//! ]);
+//! assert_eq!(transform.transform_point(v(1.0, 1.0, 1.0)), v(3.0, 4.0, 5.0));
+//! assert_eq!(transform.transform_vector(v(1.0, 1.0, 1.0)), v(2.0, 2.0, 2.0));
+//! assert_eq!(*transform.determinant(), 8.0);
+//! let inverse = transform.inverse().unwrap();
+//! assert_eq!(inverse.transform_point(v(3.0, 4.0, 5.0)), v(1.0, 1.0, 1.0));
+//! ```
+use crate::generic_float::{lit, Constructors, FloatAlone, FloatMethods};
+use crate::vector::{Vec2, Vec3, Vec4};
+use core::ops::{Add, AddAssign, Mul, MulAssign, Neg, Sub, SubAssign};
+
+/// The largest power of two at most `x` (which must be positive, and finite).
+///
+/// This multiplies by powers of two, which is exact, instead of using [`log2`](crate::generic_float::FloatMethods::log2) and [`exp2`](crate::generic_float::FloatMethods::exp2),
+/// which the fast math flags may approximate; the powers are squared, so that it takes a few dozen steps at most, not one per binade.
+fn power_of_two<F: FloatAlone>(x: F) -> F {
+    let (one, two, half) = (lit::<F>(1.0), lit::<F>(2.0), lit::<F>(0.5));
+    let mut power = one;
+    // down, until at most x (the step is squared while the power stays above x; it may underflow to 0, which stops it)
+    while power > x {
+        let mut step = half;
+        while power * step * step > x {
+            step *= step;
+        }
+        power *= step;
+    }
+    // then up, while it stays at most x (`x / power ≥ step` is `power · step² ≤ x`, without overflowing)
+    while x / power >= two {
+        let mut step = two;
+        while x / (power * step) >= step {
+            step *= step;
+        }
+        power *= step;
+    }
+    power
+}
+
+macro_rules! matrix {
+    ($(#[$meta:meta])* $name:ident, $vector:ident, $n:literal, [$($index:literal),+]) => {
+        $(#[$meta])*
+        #[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Hash)]
+        pub struct $name<F> {
+            /// The columns.
+            pub columns: [$vector<F>; $n],
+        }
+
+        impl<F: FloatAlone> $name<F> {
+            #[doc = concat!("Creates a new [`", stringify!($name), "`] from its columns.")]
+            pub const fn from_columns(columns: [$vector<F>; $n]) -> Self {
+                Self { columns }
+            }
+
+            #[doc = concat!("Creates a new [`", stringify!($name), "`] from its rows.")]
+            pub fn from_rows(rows: [$vector<F>; $n]) -> Self {
+                Self { columns: rows }.transpose()
+            }
+
+            /// A matrix with `diagonal` on its diagonal, and zeros everywhere else: a scale.
+            pub fn from_diagonal(diagonal: $vector<F>) -> Self {
+                let diagonal = diagonal.to_array();
+                // SAFETY: 0 is a valid float
+                let zero = unsafe { F::zero() };
+                Self {
+                    columns: core::array::from_fn(|j| {
+                        $vector::from(core::array::from_fn(|i| if i == j { diagonal[i] } else { zero }))
+                    }),
+                }
+            }
+
+            /// The identity matrix, which leaves every vector unchanged.
+            pub fn identity() -> Self {
+                // SAFETY: 1 is a valid float
+                Self::from_diagonal($vector::splat(unsafe { F::one() }))
+            }
+
+            /// The row `i`.
+            ///
+            /// # Panics
+            ///
+            #[doc = concat!("If `i` is not less than ", $n, ".")]
+            #[track_caller]
+            pub fn row(self, i: usize) -> $vector<F> {
+                $vector::from(self.columns.map(|column| column.to_array()[i]))
+            }
+
+            /// The transpose, with the rows as columns.
+            pub fn transpose(self) -> Self {
+                let columns = self.columns.map($vector::to_array);
+                Self {
+                    columns: core::array::from_fn(|j| {
+                        $vector::from(core::array::from_fn(|i| columns[i][j]))
+                    }),
+                }
+            }
+
+            /// The inverse, such that `self * inverse` is the identity;
+            /// or [`None`], if `self` is singular (or so close to it that the inverse would not be meaningful, or finite).
+            ///
+            /// The matrix is divided by a power of two (which is exact) to a largest component between 1 and 2 first, so the inverse does not overflow on the way.
+            pub fn inverse(self) -> Option<Self> {
+                // SAFETY: 0, 1, MAX, and ε are finite
+                let (zero, one, ε, max) = unsafe { (F::zero(), F::one(), F::ε(), <F as Constructors>::max()) };
+                let largest = |m: Self| {
+                    m.columns
+                        .iter()
+                        .flat_map(|column| column.to_array())
+                        .fold(zero, |m, x| FloatMethods::max(m, x.abs()))
+                };
+                let biggest = largest(self);
+                if biggest == zero {
+                    return None;
+                }
+                let scale = power_of_two(biggest);
+                let scaled = Self { columns: self.columns.map(|column| column / scale) };
+                let determinant = scaled.determinant();
+                if determinant.abs() <= ε * lit(f64::from($n)) {
+                    return None;
+                }
+                // the inverse of the scaled matrix is finite (its determinant is not tiny), but dividing it by a small scale may overflow.
+                let inverse = scaled.adjugate() * (one / determinant);
+                if scale < one && largest(inverse) > scale * max {
+                    return None;
+                }
+                Some(Self { columns: inverse.columns.map(|column| column / scale) })
+            }
+        }
+
+        impl<F> From<[[F; $n]; $n]> for $name<F> {
+            /// From the columns.
+            fn from(columns: [[F; $n]; $n]) -> Self {
+                Self { columns: columns.map($vector::from) }
+            }
+        }
+
+        impl<F> From<$name<F>> for [[F; $n]; $n] {
+            /// To the columns.
+            fn from(m: $name<F>) -> Self {
+                m.columns.map(<[F; $n]>::from)
+            }
+        }
+
+        impl<F: FloatAlone> Mul<$vector<F>> for $name<F> {
+            type Output = $vector<F>;
+            fn mul(self, v: $vector<F>) -> $vector<F> {
+                let v = v.to_array();
+                matrix!(@sum $(self.columns[$index] * v[$index]),+)
+            }
+        }
+
+        impl<F: FloatAlone> Mul for $name<F> {
+            type Output = Self;
+            fn mul(self, rhs: Self) -> Self {
+                Self { columns: rhs.columns.map(|column| self * column) }
+            }
+        }
+
+        impl<F: FloatAlone> Mul<F> for $name<F> {
+            type Output = Self;
+            fn mul(self, rhs: F) -> Self {
+                Self { columns: self.columns.map(|column| column * rhs) }
+            }
+        }
+
+        impl<F: FloatAlone> Add for $name<F> {
+            type Output = Self;
+            fn add(self, rhs: Self) -> Self {
+                Self { columns: [$(self.columns[$index] + rhs.columns[$index]),+] }
+            }
+        }
+
+        impl<F: FloatAlone> Sub for $name<F> {
+            type Output = Self;
+            fn sub(self, rhs: Self) -> Self {
+                Self { columns: [$(self.columns[$index] - rhs.columns[$index]),+] }
+            }
+        }
+
+        impl<F: FloatAlone> Neg for $name<F> {
+            type Output = Self;
+            fn neg(self) -> Self {
+                Self { columns: self.columns.map(Neg::neg) }
+            }
+        }
+
+        impl<F: FloatAlone> MulAssign for $name<F> {
+            fn mul_assign(&mut self, rhs: Self) {
+                *self = *self * rhs;
+            }
+        }
+
+        impl<F: FloatAlone> MulAssign<F> for $name<F> {
+            fn mul_assign(&mut self, rhs: F) {
+                *self = *self * rhs;
+            }
+        }
+
+        impl<F: FloatAlone> AddAssign for $name<F> {
+            fn add_assign(&mut self, rhs: Self) {
+                *self = *self + rhs;
+            }
+        }
+
+        impl<F: FloatAlone> SubAssign for $name<F> {
+            fn sub_assign(&mut self, rhs: Self) {
+                *self = *self - rhs;
+            }
+        }
+    };
+    (@sum $first:expr $(, $rest:expr)*) => {
+        $first $(+ $rest)*
+    };
+}
+
+matrix! {
+    /// A 2 × 2 matrix: a linear transform of the plane. Refer to the [module level documentation](self).
+    Mat2, Vec2, 2, [0, 1]
+}
+
+matrix! {
+    /// A 3 × 3 matrix: a linear transform of space, or a affine transform of the plane (in homogeneous coordinates). Refer to the [module level documentation](self).
+    Mat3, Vec3, 3, [0, 1, 2]
+}
+
+matrix! {
+    /// A 4 × 4 matrix: a affine (or projective) transform of space, in homogeneous coordinates. Refer to the [module level documentation](self).
+    Mat4, Vec4, 4, [0, 1, 2, 3]
+}
+
+impl<F: FloatAlone> Mat2<F> {
+    /// The determinant: the (signed) factor the transform scales areas by.
+    pub fn determinant(self) -> F {
+        let [a, b] = self.columns;
+        a.perp_dot(b)
+    }
+
+    /// The transpose of the cofactor matrix, which is the inverse times the determinant.
+    fn adjugate(self) -> Self {
+        let [a, b] = self.columns;
+        Self::from_columns([Vec2::new(b.y, -a.y), Vec2::new(-b.x, a.x)])
+    }
+}
+
+impl<F: FloatAlone> Mat3<F> {
+    /// The determinant: the (signed) factor the transform scales volumes by.
+    ///
+    /// This is the scalar triple product of the columns, `a · (b × c)`.
+    pub fn determinant(self) -> F {
+        let [a, b, c] = self.columns;
+        a.dot(b.cross(c))
+    }
+
+    /// The transpose of the cofactor matrix, which is the inverse times the determinant: its rows are the cross products of the columns.
+    fn adjugate(self) -> Self {
+        let [a, b, c] = self.columns;
+        Self::from_rows([b.cross(c), c.cross(a), a.cross(b)])
+    }
+
+    /// Transforms the point `p` of the plane, as `[x, y, 1]`, so that it is translated by the last column.
+    /// The last row is taken to be `[0, 0, 1]` (as it is, for a affine transform).
+    pub fn transform_point(self, p: Vec2<F>) -> Vec2<F> {
+        let [x_axis, y_axis, origin] = self.columns;
+        let moved = x_axis * p.x + y_axis * p.y + origin;
+        Vec2::new(moved.x, moved.y)
+    }
+
+    /// Transforms the direction `v` of the plane, as `[x, y, 0]`, so that it is not translated.
+    pub fn transform_vector(self, v: Vec2<F>) -> Vec2<F> {
+        let [x_axis, y_axis, _] = self.columns;
+        let moved = x_axis * v.x + y_axis * v.y;
+        Vec2::new(moved.x, moved.y)
+    }
+}
+
+/// The first three components of `v`.
+fn xyz<F: FloatAlone>(v: Vec4<F>) -> Vec3<F> {
+    Vec3::new(v.x, v.y, v.z)
+}
+
+impl<F: FloatAlone> Mat4<F> {
+    /// The parts of the columns `[a, b, c, d]` that the determinant and the adjugate are built from (refer to lengyel, "foundations of game engine development", volume 1):
+    /// `(s, t, u, v) = (a × b, c × d, a·y - b·x, c·w - d·z)`, where the 3 dimensional `a`, `b`, `c`, and `d` are the tops of the columns, and `x`, `y`, `z`, `w` is the bottom row.
+    #[allow(clippy::many_single_char_names)]
+    fn parts(self) -> [Vec3<F>; 4] {
+        let [a, b, c, d] = self.columns.map(xyz);
+        let [x, y, z, w] = self.row(3).to_array();
+        [a.cross(b), c.cross(d), a * y - b * x, c * w - d * z]
+    }
+
+    /// The determinant: the (signed) factor the transform scales 4 dimensional volumes by (and, for a affine transform, 3 dimensional volumes).
+    pub fn determinant(self) -> F {
+        let [s, t, u, v] = self.parts();
+        s.dot(v) + t.dot(u)
+    }
+
+    /// The transpose of the cofactor matrix, which is the inverse times the determinant.
+    #[allow(clippy::many_single_char_names)]
+    fn adjugate(self) -> Self {
+        let [s, t, u, v] = self.parts();
+        let [a, b, c, d] = self.columns.map(xyz);
+        let [x, y, z, w] = self.row(3).to_array();
+        let row = |top: Vec3<F>, last| Vec4::new(top.x, top.y, top.z, last);
+        Self::from_rows([
+            row(b.cross(v) + t * y, -b.dot(t)),
+            row(v.cross(a) - t * x, a.dot(t)),
+            row(d.cross(u) + s * w, -d.dot(s)),
+            row(u.cross(c) - s * z, c.dot(s)),
+        ])
+    }
+
+    /// Transforms the point `p`, as `[x, y, z, 1]`, so that it is translated by the last column.
+    /// The last row is taken to be `[0, 0, 0, 1]` (as it is, for a affine transform); refer to [`project_point`](Self::project_point) for projections.
+    pub fn transform_point(self, p: Vec3<F>) -> Vec3<F> {
+        let [x_axis, y_axis, z_axis, origin] = self.columns;
+        xyz(x_axis * p.x + y_axis * p.y + z_axis * p.z + origin)
+    }
+
+    /// Transforms the direction `v`, as `[x, y, z, 0]`, so that it is not translated.
+    pub fn transform_vector(self, v: Vec3<F>) -> Vec3<F> {
+        let [x_axis, y_axis, z_axis, _] = self.columns;
+        xyz(x_axis * v.x + y_axis * v.y + z_axis * v.z)
+    }
+
+    /// Transforms the point `p`, as `[x, y, z, 1]`, and divides the result by its `w` (the perspective divide).
+    ///
+    /// The transformed `w` must not be 0: `p` must not be on the plane of the eye of a perspective projection.
+    pub fn project_point(self, p: Vec3<F>) -> Vec3<F> {
+        // SAFETY: 1 is a valid float
+        let one = unsafe { F::one() };
+        let moved = self * Vec4::new(p.x, p.y, p.z, one);
+        xyz(moved) * (one / moved.w)
+    }
+}
+
+#[test]
+#[allow(clippy::float_cmp)]
+fn matrices() {
+    let close = |a: Mat4<f64>, b: Mat4<f64>| {
+        let (a, b) = (<[[f64; 4]; 4]>::from(a), <[[f64; 4]; 4]>::from(b));
+        a.iter()
+            .flatten()
+            .zip(b.iter().flatten())
+            .all(|(x, y)| (x - y).abs() < 1e-12)
+    };
+    let m = Mat4::from([
+        [2.0, 1.0, 0.0, 3.0],
+        [-1.0, 4.0, 2.0, 0.0],
+        [0.5, 0.0, 1.0, -2.0],
+        [1.0, 2.0, 3.0, 1.0],
+    ]);
+    assert!(close(m.transpose().transpose(), m));
+    assert_eq!(m.row(1), Vec4::new(1.0, 4.0, 0.0, 2.0));
+    assert_eq!(m * Mat4::identity(), m);
+    let inverse = m.inverse().unwrap();
+    assert!(close(m * inverse, Mat4::identity()) && close(inverse * m, Mat4::identity()));
+    // the determinant of a product is the product of the determinants
+    assert!(((m * m.transpose()).determinant() - m.determinant() * m.determinant()).abs() < 1e-9);
+    assert!((m.determinant() * inverse.determinant() - 1.0).abs() < 1e-12);
+    let three = Mat3::from_rows([
+        Vec3::new(0.0, -1.0, 5.0),
+        Vec3::new(1.0, 0.0, -2.0),
+        Vec3::new(0.0, 0.0, 1.0),
+    ]);
+    assert_eq!(three.determinant(), 1.0);
+    assert_eq!(
+        three.transform_point(Vec2::new(1.0, 0.0)),
+        Vec2::new(5.0, -1.0)
+    );
+    assert_eq!(
+        three.transform_vector(Vec2::new(1.0, 0.0)),
+        Vec2::new(0.0, 1.0)
+    );
+    let back = three.inverse().unwrap();
+    assert_eq!(
+        back.transform_point(Vec2::new(5.0, -1.0)),
+        Vec2::new(1.0, 0.0)
+    );
+    let two = Mat2::from_diagonal(Vec2::new(2.0, 4.0)) + Mat2::from([[0.0, 0.0], [1.0, 0.0]]);
+    assert_eq!(two.determinant(), 8.0);
+    assert_eq!(
+        two.inverse().unwrap() * Vec2::new(3.0, 4.0),
+        Vec2::new(1.0, 1.0)
+    );
+    // singular, however it is scaled
+    for scale in [1e-200, 1.0, 1e200] {
+        assert_eq!(
+            (Mat2::from([[1.0, 2.0], [2.0, 4.0]]) * scale).inverse(),
+            None
+        );
+        let flat = Mat3::from_diagonal(Vec3::new(1.0, 1.0, 0.0)) * scale;
+        assert_eq!(flat.inverse(), None);
+        let uniform = Mat3::<f64>::identity() * scale;
+        assert_eq!(uniform.inverse(), Some(Mat3::identity() * (1.0 / scale)));
+    }
+    assert_eq!(Mat4::<f64>::default().inverse(), None);
+    // a tiny matrix, whose inverse does not fit in the float, and a small one, whose does (with exact powers of two, even below the normals)
+    let f = |x| unsafe { crate::FFloat::<f32>::new(x) };
+    assert_eq!((Mat2::identity() * f(1e-39)).inverse(), None);
+    let small = Mat2::identity() * f(1e-37);
+    assert_eq!(small.inverse(), Some(Mat2::identity() * f(1e37)));
+    assert_eq!(power_of_two(f(1e-39)), f(f32::from_bits(1 << 19)));
+    assert_eq!(power_of_two(f(3e38)), f(2f32.powi(127)));
+    assert_eq!(power_of_two(3.0), 2.0);
+    let perspective = Mat4::from_rows([
+        Vec4::new(1.0, 0.0, 0.0, 0.0),
+        Vec4::new(0.0, 1.0, 0.0, 0.0),
+        Vec4::new(0.0, 0.0, 1.0, 0.0),
+        Vec4::new(0.0, 0.0, -1.0, 0.0),
+    ]);
+    assert_eq!(
+        perspective.project_point(Vec3::new(2.0, 4.0, -2.0)),
+        Vec3::new(1.0, 2.0, -1.0)
+    );
+}
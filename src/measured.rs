@@ -0,0 +1,176 @@
+//! values with uncertainty.
+//!
+//! A [`Measured`] is a value ± its standard error. Arithmetic propagates the error with the usual first order (linear) approximation,
+//! assuming the operands are independent.
+//! ```
+//! # use umath::{FFloat, measured::Measured};
+//! let m = |v, e| unsafe { Measured::new(FFloat::new(v), FFloat::new(e)) };
+//! let length = m(10.0f64, 0.6);
+//! let width = m(5.0, 0.4);
+//! let area = length * width;
+//! assert_eq!(*area.value(), 50.0);
+//! assert!((4.99..5.01).contains(&*area.error())); // hypot(5 * 0.6, 10 * 0.4)
+//! ```
+use crate::generic_float::{lit, FloatAlone};
+use core::fmt::{Display, Formatter, Result};
+use core::ops::{Add, Div, Mul, Neg, Sub};
+
+/// A value, with a (non negative) standard error. Refer to the [module level documentation](self).
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Measured<F> {
+    value: F,
+    error: F,
+}
+
+impl<F: FloatAlone> Measured<F> {
+    /// Creates a new [`Measured`] value, `value ± |error|`.
+    pub fn new(value: F, error: F) -> Self {
+        Self {
+            value,
+            error: error.abs(),
+        }
+    }
+
+    /// Creates a exact value (with no error).
+    pub fn exact(value: F) -> Self {
+        Self {
+            value,
+            error: unsafe { F::zero() },
+        }
+    }
+
+    /// The (central) value.
+    pub fn value(self) -> F {
+        self.value
+    }
+
+    /// The standard error.
+    pub fn error(self) -> F {
+        self.error
+    }
+
+    /// The error relative to the value (`error / |value|`).
+    ///
+    /// The value must not be zero.
+    pub fn relative_error(self) -> F {
+        self.error / self.value.abs()
+    }
+
+    /// Raises to a integer power. The error becomes `|n * value^(n - 1)| * error`.
+    pub fn powi(self, n: i32) -> Self {
+        if n == 0 {
+            // avoid computing value^-1
+            return Self::exact(self.value.powi(0));
+        }
+        Self {
+            value: self.value.powi(n),
+            error: (self.value.powi(n - 1) * lit(f64::from(n))).abs() * self.error,
+        }
+    }
+
+    /// Takes the square root. The error becomes `error / (2 * sqrt(value))`.
+    ///
+    /// The value must be positive.
+    pub fn sqrt(self) -> Self {
+        let value = self.value.sqrt();
+        Self {
+            value,
+            error: self.error / (value * lit(2.0)),
+        }
+    }
+}
+
+impl<F: FloatAlone> From<F> for Measured<F> {
+    fn from(value: F) -> Self {
+        Self::exact(value)
+    }
+}
+
+impl<F: FloatAlone> Add for Measured<F> {
+    type Output = Self;
+    fn add(self, rhs: Self) -> Self {
+        Self {
+            value: self.value + rhs.value,
+            error: self.error.hypot(rhs.error),
+        }
+    }
+}
+
+impl<F: FloatAlone> Sub for Measured<F> {
+    type Output = Self;
+    fn sub(self, rhs: Self) -> Self {
+        Self {
+            value: self.value - rhs.value,
+            error: self.error.hypot(rhs.error),
+        }
+    }
+}
+
+impl<F: FloatAlone> Mul for Measured<F> {
+    type Output = Self;
+    fn mul(self, rhs: Self) -> Self {
+        Self {
+            value: self.value * rhs.value,
+            error: (rhs.value * self.error).hypot(self.value * rhs.error),
+        }
+    }
+}
+
+impl<F: FloatAlone> Div for Measured<F> {
+    type Output = Self;
+    /// Divides the values. The divisor's value must not be zero.
+    fn div(self, rhs: Self) -> Self {
+        let value = self.value / rhs.value;
+        Self {
+            value,
+            error: self.error.hypot(value * rhs.error) / rhs.value.abs(),
+        }
+    }
+}
+
+impl<F: FloatAlone> Mul<F> for Measured<F> {
+    type Output = Self;
+    fn mul(self, rhs: F) -> Self {
+        Self {
+            value: self.value * rhs,
+            error: self.error * rhs.abs(),
+        }
+    }
+}
+
+impl<F: FloatAlone> Div<F> for Measured<F> {
+    type Output = Self;
+    fn div(self, rhs: F) -> Self {
+        Self {
+            value: self.value / rhs,
+            error: self.error / rhs.abs(),
+        }
+    }
+}
+
+impl<F: FloatAlone> Neg for Measured<F> {
+    type Output = Self;
+    fn neg(self) -> Self {
+        Self {
+            value: -self.value,
+            error: self.error,
+        }
+    }
+}
+
+impl<F: Display> Display for Measured<F> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+        write!(f, "{} ± {}", self.value, self.error)
+    }
+}
+
+#[test]
+#[allow(clippy::float_cmp)]
+fn propagate() {
+    let x = Measured::new(4.0f64, -0.4);
+    assert_eq!(x.error(), 0.4);
+    assert_eq!((x + x).error(), 0.4f64.hypot(0.4));
+    assert_eq!(x.sqrt(), Measured::new(2.0, 0.1));
+    assert_eq!(x.powi(2), Measured::new(16.0, 3.2));
+    assert_eq!((x / 2.0).to_string(), "2 ± 0.2");
+}
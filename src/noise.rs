@@ -0,0 +1,480 @@
+//! gradient (perlin, and simplex) noise, for procedural textures and terrain.
+//!
+//! [`Perlin`] noise is smooth pseudo random noise: a random gradient at every integer point of the lattice, smoothly interpolated in between,
+//! so it is 0 at the lattice points, varies on a scale of about 1, and is about `[-1, 1]` in range.
+//! The same generator also makes [simplex](Perlin::simplex2) noise, which is cheaper in 3 dimensions, and has no axis aligned artifacts.
+//! Layering octaves of it, each at a higher frequency and lower amplitude (with [`Fbm`]), gives fractal detail, as in terrain.
+//!
+//! The noise is generic over <code>[Float]\<B\></code> (for both bases, [`f32`] and [`f64`]), so a [`FFloat`](crate::FFloat) evaluates it with fast math.
+//! ```
+//! # use umath::{FFloat, noise::{Fbm, Perlin}};
+//! let f = |x| unsafe { FFloat::new(x) };
+//! let perlin = Perlin::new(42);
+//! let height = perlin.noise2(f(1.5f32), f(2.25));
+//! assert!((-1.0..=1.0).contains(&*height));
+//! // 0 on the lattice
+//! assert_eq!(*perlin.noise2(f(3.0f32), f(-7.0)), 0.0);
+//! let terrain = perlin.fbm2(Fbm::DEFAULT.with_octaves(4), f(1.5f32), f(2.25));
+//! assert!((-1.0..=1.0).contains(&*terrain));
+//! ```
+use crate::generic_float::{lit, Float, FloatAlone};
+
+/// The scale of [`Perlin::simplex2`], so that it reaches about ±1.
+const SCALE2: f64 = 70.0;
+/// The scale of [`Perlin::simplex3`], so that it reaches about ±1.
+const SCALE3: f64 = 76.0;
+
+/// A seeded perlin noise generator ("improved noise", with the quintic fade, from Ken Perlin's 2002 reference), in 1, 2, and 3 dimensions; and simplex noise, in 2 and 3.
+///
+/// The noise repeats every 256 units, along each axis.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Perlin {
+    /// A permutation of `0..256`, repeated twice, so that the hashes of neighbouring lattice points can be looked up without wrapping.
+    permutation: [u8; 512],
+}
+
+/// The octaves of fractal brownian motion: the sum of `octaves` layers of noise, each at `lacunarity` times the frequency, and `gain` times the amplitude, of the last.
+/// ```
+/// # use umath::noise::Fbm;
+/// let rough = Fbm::DEFAULT.with_octaves(8).with_gain(0.6);
+/// assert_eq!(rough.octaves(), 8);
+/// ```
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Fbm {
+    octaves: u32,
+    lacunarity: f64,
+    gain: f64,
+}
+
+impl Fbm {
+    /// The defaults: 6 octaves, each at twice the frequency, and half the amplitude, of the last.
+    pub const DEFAULT: Self = Self {
+        octaves: 6,
+        lacunarity: 2.0,
+        gain: 0.5,
+    };
+
+    /// Sets the number of layers of noise.
+    ///
+    /// # Panics
+    ///
+    /// If `octaves` is 0.
+    #[must_use]
+    #[track_caller]
+    pub const fn with_octaves(self, octaves: u32) -> Self {
+        assert!(octaves > 0, "fbm needs at least 1 octave");
+        Self { octaves, ..self }
+    }
+
+    /// Sets the frequency multiplier between octaves (usually about 2).
+    ///
+    /// # Panics
+    ///
+    /// If `lacunarity` is not positive, and finite.
+    #[must_use]
+    #[track_caller]
+    pub const fn with_lacunarity(self, lacunarity: f64) -> Self {
+        assert!(
+            lacunarity > 0.0 && lacunarity.is_finite(),
+            "the lacunarity must be positive, and finite"
+        );
+        Self { lacunarity, ..self }
+    }
+
+    /// Sets the amplitude multiplier between octaves (usually about 0.5; higher is rougher).
+    ///
+    /// # Panics
+    ///
+    /// If `gain` is not positive, and finite.
+    #[must_use]
+    #[track_caller]
+    pub const fn with_gain(self, gain: f64) -> Self {
+        assert!(
+            gain > 0.0 && gain.is_finite(),
+            "the gain must be positive, and finite"
+        );
+        Self { gain, ..self }
+    }
+
+    /// The number of layers of noise.
+    #[must_use]
+    pub const fn octaves(self) -> u32 {
+        self.octaves
+    }
+
+    /// The frequency multiplier between octaves.
+    #[must_use]
+    pub const fn lacunarity(self) -> f64 {
+        self.lacunarity
+    }
+
+    /// The amplitude multiplier between octaves.
+    #[must_use]
+    pub const fn gain(self) -> f64 {
+        self.gain
+    }
+
+    /// Sums the octaves of `noise` (which takes the frequency), normalized by the total amplitude, so that the result stays in about `[-1, 1]`.
+    ///
+    /// Octaves with a frequency over `1 / ε` (where the lattice is finer than the resolution of the float, so they would only add rounding noise) are left out,
+    /// so that every frequency is finite in `F`.
+    fn sum<F: Float<B>, B: Into<f64>>(self, mut noise: impl FnMut(F) -> F) -> F {
+        // SAFETY: 0 and ε are finite
+        let (mut sum, ε) = unsafe { (F::zero(), F::ε()) };
+        let finest = 1.0 / ε.take().into();
+        let (mut octaves, mut frequency) = (0, 1.0);
+        while octaves < self.octaves && frequency <= finest {
+            octaves += 1;
+            frequency *= self.lacunarity;
+        }
+        // the amplitudes, relative to the largest (the first, or with a gain over 1, the last), so that they (and their total) are finite.
+        let relative = |octave: u32| {
+            if self.gain <= 1.0 {
+                self.gain.powf(f64::from(octave))
+            } else {
+                self.gain.recip().powf(f64::from(octaves - 1 - octave))
+            }
+        };
+        let total: f64 = (0..octaves).map(relative).sum();
+        let mut frequency = 1.0;
+        for octave in 0..octaves {
+            // SAFETY: the frequency is at most `1 / ε`, and the weight is in [0, 1].
+            let (scale, weight) = unsafe {
+                (
+                    F::from_f64(frequency),
+                    F::from_f64(relative(octave) / total),
+                )
+            };
+            sum += noise(scale) * weight;
+            frequency *= self.lacunarity;
+        }
+        sum
+    }
+}
+
+impl Default for Fbm {
+    fn default() -> Self {
+        Self::DEFAULT
+    }
+}
+
+/// `6t⁵ - 15t⁴ + 10t³`: a s curve from 0 to 1, flat (to the second derivative) at both ends, so that the noise is smooth across the lattice.
+fn fade<F: FloatAlone>(t: F) -> F {
+    t * t * t * (t * (t * lit(6.0) - lit(15.0)) + lit(10.0))
+}
+
+fn lerp<F: FloatAlone>(t: F, from: F, to: F) -> F {
+    from + (to - from) * t
+}
+
+/// The lattice index of the integer `x`, wrapped to `0..256`.
+#[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+fn index<F: Float<B>, B: Into<f64>>(x: F) -> usize {
+    let index: f64 = x.take().into();
+    // `as` saturates, and the two's complement wraps negative cells.
+    (index as i64 & 255) as usize
+}
+
+/// The lattice cell of `x`, wrapped to `0..256`, and the position within it, from 0 to 1.
+fn cell<F: Float<B>, B: Into<f64>>(x: F) -> (usize, F) {
+    let floor = x.floor();
+    (index(floor), x - floor)
+}
+
+/// The dot product of `(x, y, z)` with one of the 12 gradients toward the edges of a cube, (±1, ±1, 0) and its rotations
+/// (with 4 repeated, so that 16 can be picked from), by `hash`.
+#[allow(clippy::many_single_char_names)]
+fn gradient3<F: FloatAlone>(hash: usize, x: F, y: F, z: F) -> F {
+    let h = hash & 15;
+    let u = if h < 8 { x } else { y };
+    let v = match h {
+        0..4 => y,
+        12 | 14 => x,
+        _ => z,
+    };
+    (if h & 1 == 0 { u } else { -u }) + (if h & 2 == 0 { v } else { -v })
+}
+
+/// The contribution of a corner of a simplex, at the offset `distance²` from it: `(1/2 - distance²)⁴ · gradient`, or 0, outside of its radius.
+fn falloff<F: FloatAlone>(distance: F, gradient: F) -> F {
+    let t = lit::<F>(0.5) - distance;
+    // SAFETY: 0 is a valid float
+    if t <= unsafe { F::zero() } {
+        return unsafe { F::zero() };
+    }
+    let t = t * t;
+    t * t * gradient
+}
+
+impl Perlin {
+    /// Creates a generator, with a permutation shuffled by `seed`.
+    #[must_use]
+    #[allow(clippy::cast_possible_truncation)]
+    pub fn new(seed: u64) -> Self {
+        let mut table: [u8; 256] = core::array::from_fn(|i| i as u8);
+        let mut state = seed;
+        // fisher-yates, with splitmix64
+        for i in (1..256).rev() {
+            state = state.wrapping_add(0x9e37_79b9_7f4a_7c15);
+            let mut z = state;
+            z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+            z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+            z ^= z >> 31;
+            table.swap(i, (z % (i as u64 + 1)) as usize);
+        }
+        Self {
+            permutation: core::array::from_fn(|i| table[i % 256]),
+        }
+    }
+
+    fn hash(&self, i: usize) -> usize {
+        usize::from(self.permutation[i])
+    }
+
+    /// One dimensional noise, in about `[-1, 1]`.
+    #[must_use]
+    pub fn noise1<F: Float<B>, B: Into<f64>>(&self, x: F) -> F {
+        let (i, x) = cell(x);
+        // SAFETY: 1 is a valid float
+        let one = unsafe { F::one() };
+        // gradients of ±1
+        let gradient = |hash: usize, x: F| if hash & 1 == 0 { x } else { -x };
+        let a = gradient(self.hash(i), x);
+        let b = gradient(self.hash(i + 1), x - one);
+        lerp(fade(x), a, b) * lit::<F>(2.0)
+    }
+
+    /// Two dimensional noise, in about `[-1, 1]`.
+    #[must_use]
+    #[allow(clippy::many_single_char_names)]
+    pub fn noise2<F: Float<B>, B: Into<f64>>(&self, x: F, y: F) -> F {
+        let ((i, x), (j, y)) = (cell(x), cell(y));
+        // SAFETY: 1 is a valid float
+        let one = unsafe { F::one() };
+        // the diagonal gradients, (±1, ±1)
+        let gradient = |hash: usize, x: F, y: F| {
+            let x = if hash & 1 == 0 { x } else { -x };
+            let y = if hash & 2 == 0 { y } else { -y };
+            x + y
+        };
+        let (a, b) = (self.hash(i) + j, self.hash(i + 1) + j);
+        let (u, v) = (fade(x), fade(y));
+        lerp(
+            v,
+            lerp(
+                u,
+                gradient(self.hash(a), x, y),
+                gradient(self.hash(b), x - one, y),
+            ),
+            lerp(
+                u,
+                gradient(self.hash(a + 1), x, y - one),
+                gradient(self.hash(b + 1), x - one, y - one),
+            ),
+        )
+    }
+
+    /// Three dimensional noise, in about `[-1, 1]`.
+    #[must_use]
+    #[allow(clippy::many_single_char_names)]
+    pub fn noise3<F: Float<B>, B: Into<f64>>(&self, x: F, y: F, z: F) -> F {
+        let ((i, x), (j, y), (k, z)) = (cell(x), cell(y), cell(z));
+        // SAFETY: 1 is a valid float
+        let one = unsafe { F::one() };
+        let gradient = gradient3;
+        let (a, b) = (self.hash(i) + j, self.hash(i + 1) + j);
+        let (aa, ab) = (self.hash(a) + k, self.hash(a + 1) + k);
+        let (ba, bb) = (self.hash(b) + k, self.hash(b + 1) + k);
+        let (u, v, w) = (fade(x), fade(y), fade(z));
+        let (x1, y1, z1) = (x - one, y - one, z - one);
+        let near = lerp(
+            v,
+            lerp(
+                u,
+                gradient(self.hash(aa), x, y, z),
+                gradient(self.hash(ba), x1, y, z),
+            ),
+            lerp(
+                u,
+                gradient(self.hash(ab), x, y1, z),
+                gradient(self.hash(bb), x1, y1, z),
+            ),
+        );
+        let far = lerp(
+            v,
+            lerp(
+                u,
+                gradient(self.hash(aa + 1), x, y, z1),
+                gradient(self.hash(ba + 1), x1, y, z1),
+            ),
+            lerp(
+                u,
+                gradient(self.hash(ab + 1), x, y1, z1),
+                gradient(self.hash(bb + 1), x1, y1, z1),
+            ),
+        );
+        lerp(w, near, far)
+    }
+
+    /// Two dimensional simplex noise, in about `[-1, 1]`.
+    ///
+    /// Simplex noise (Perlin's successor to his noise, after Gustavson's "simplex noise demystified") sums a radial contribution from each corner of the simplex (here, the triangle) around the point,
+    /// instead of interpolating the corners of a square: so it needs 3 gradients instead of 4 (and, in 3 dimensions, 4 instead of 8), and has no axis aligned artifacts.
+    /// Like the perlin noise, it is 0 on the lattice (of the skewed grid), and repeats every 256 units (of the skewed grid, along its axes).
+    #[must_use]
+    pub fn simplex2<F: Float<B>, B: Into<f64>>(&self, x: F, y: F) -> F {
+        // skewing (x, y) by (√3 - 1) / 2 maps the triangles onto half squares; unskewing is by (3 - √3) / 6.
+        const SKEW: f64 = 0.366_025_403_784_438_6;
+        const UNSKEW: f64 = 0.211_324_865_405_187_1;
+        let skew = (x + y) * lit::<F>(SKEW);
+        let (sx, sy) = ((x + skew).floor(), (y + skew).floor());
+        let unskew = (sx + sy) * lit::<F>(UNSKEW);
+        // the offset from the first corner, and which triangle of the square it is in
+        let (x0, y0) = (x - sx + unskew, y - sy + unskew);
+        let (i1, j1) = if x0 > y0 { (1, 0) } else { (0, 1) };
+        // SAFETY: 0 and 1 are valid floats
+        let (zero, one) = unsafe { (F::zero(), F::one()) };
+        let step = |i: usize| if i == 1 { one } else { zero };
+        let (x1, y1) = (
+            x0 - step(i1) + lit::<F>(UNSKEW),
+            y0 - step(j1) + lit::<F>(UNSKEW),
+        );
+        let (x2, y2) = (
+            x0 - one + lit::<F>(2.0 * UNSKEW),
+            y0 - one + lit::<F>(2.0 * UNSKEW),
+        );
+        let (i, j) = (index(sx), index(sy));
+        let corner = |di: usize, dj: usize, x: F, y: F| {
+            let hash = self.hash(i + di + self.hash(j + dj));
+            falloff(x * x + y * y, gradient3(hash, x, y, zero))
+        };
+        (corner(0, 0, x0, y0) + corner(i1, j1, x1, y1) + corner(1, 1, x2, y2)) * lit::<F>(SCALE2)
+    }
+
+    /// Three dimensional simplex noise, in about `[-1, 1]`. Refer to [`simplex2`](Self::simplex2).
+    #[must_use]
+    #[allow(clippy::many_single_char_names)]
+    pub fn simplex3<F: Float<B>, B: Into<f64>>(&self, x: F, y: F, z: F) -> F {
+        // skewing by 1 / 3 maps the tetrahedra onto sixths of cubes; unskewing is by 1 / 6.
+        const UNSKEW: f64 = 1.0 / 6.0;
+        let skew = (x + y + z) * lit::<F>(1.0 / 3.0);
+        let (sx, sy, sz) = ((x + skew).floor(), (y + skew).floor(), (z + skew).floor());
+        let unskew = (sx + sy + sz) * lit::<F>(UNSKEW);
+        let (x0, y0, z0) = (x - sx + unskew, y - sy + unskew, z - sz + unskew);
+        // the second and third corners, by the order of the offsets
+        let (first, second) = match (x0 >= y0, y0 >= z0, x0 >= z0) {
+            (true, true, _) => ([1, 0, 0], [1, 1, 0]),
+            (true, false, true) => ([1, 0, 0], [1, 0, 1]),
+            (true, false, false) => ([0, 0, 1], [1, 0, 1]),
+            (false, false, _) => ([0, 0, 1], [0, 1, 1]),
+            (false, true, false) => ([0, 1, 0], [0, 1, 1]),
+            (false, true, true) => ([0, 1, 0], [1, 1, 0]),
+        };
+        // SAFETY: 0 and 1 are valid floats
+        let (zero, one) = unsafe { (F::zero(), F::one()) };
+        let (i, j, k) = (index(sx), index(sy), index(sz));
+        let corner = |[di, dj, dk]: [usize; 3], n: u8| {
+            let step = |d: usize| if d == 1 { one } else { zero };
+            let unskew = lit::<F>(f64::from(n) * UNSKEW);
+            let (x, y, z) = (
+                x0 - step(di) + unskew,
+                y0 - step(dj) + unskew,
+                z0 - step(dk) + unskew,
+            );
+            let hash = self.hash(i + di + self.hash(j + dj + self.hash(k + dk)));
+            falloff(x * x + y * y + z * z, gradient3(hash, x, y, z))
+        };
+        (corner([0, 0, 0], 0) + corner(first, 1) + corner(second, 2) + corner([1, 1, 1], 3))
+            * lit::<F>(SCALE3)
+    }
+
+    /// [`noise1`](Self::noise1), in `octaves`.
+    #[must_use]
+    pub fn fbm1<F: Float<B>, B: Into<f64>>(&self, octaves: Fbm, x: F) -> F {
+        octaves.sum(|frequency| self.noise1(x * frequency))
+    }
+
+    /// [`noise2`](Self::noise2), in `octaves`.
+    #[must_use]
+    pub fn fbm2<F: Float<B>, B: Into<f64>>(&self, octaves: Fbm, x: F, y: F) -> F {
+        octaves.sum(|frequency| self.noise2(x * frequency, y * frequency))
+    }
+
+    /// [`noise3`](Self::noise3), in `octaves`.
+    #[must_use]
+    pub fn fbm3<F: Float<B>, B: Into<f64>>(&self, octaves: Fbm, x: F, y: F, z: F) -> F {
+        octaves.sum(|frequency| self.noise3(x * frequency, y * frequency, z * frequency))
+    }
+
+    /// [`simplex2`](Self::simplex2), in `octaves`.
+    #[must_use]
+    pub fn fbm_simplex2<F: Float<B>, B: Into<f64>>(&self, octaves: Fbm, x: F, y: F) -> F {
+        octaves.sum(|frequency| self.simplex2(x * frequency, y * frequency))
+    }
+
+    /// [`simplex3`](Self::simplex3), in `octaves`.
+    #[must_use]
+    pub fn fbm_simplex3<F: Float<B>, B: Into<f64>>(&self, octaves: Fbm, x: F, y: F, z: F) -> F {
+        octaves.sum(|frequency| self.simplex3(x * frequency, y * frequency, z * frequency))
+    }
+}
+
+impl Default for Perlin {
+    /// A generator seeded with 0.
+    fn default() -> Self {
+        Self::new(0)
+    }
+}
+
+#[test]
+#[allow(clippy::float_cmp)]
+fn noisy() {
+    let perlin = Perlin::new(7);
+    let mut sorted = perlin.permutation[..256].to_vec();
+    sorted.sort_unstable();
+    assert!(sorted.iter().copied().eq(0..=255));
+    assert_ne!(Perlin::new(8), perlin);
+    let (mut low, mut high) = (0.0f64, 0.0f64);
+    for i in -200..200 {
+        for j in -20..20 {
+            let (x, y) = (f64::from(i) * 0.173, f64::from(j) * 0.311);
+            for n in [
+                perlin.noise1(x),
+                perlin.noise2(x, y),
+                perlin.noise3(x, y, x - y),
+                perlin.fbm3(Fbm::DEFAULT, x, y, 0.5),
+                perlin.simplex2(x, y),
+                perlin.simplex3(x, y, x - y),
+                perlin.fbm_simplex3(Fbm::DEFAULT, x, y, 0.5),
+            ] {
+                (low, high) = (low.min(n), high.max(n));
+            }
+            // continuous
+            let step = 1e-6;
+            assert!((perlin.noise3(x + step, y, 0.3) - perlin.noise3(x, y, 0.3)).abs() < 1e-5);
+            assert!((perlin.noise2(x, y + step) - perlin.noise2(x, y)).abs() < 1e-5);
+            assert!((perlin.simplex2(x + step, y) - perlin.simplex2(x, y)).abs() < 1e-5);
+            assert!((perlin.simplex3(x, y, step) - perlin.simplex3(x, y, 0.0)).abs() < 1e-5);
+        }
+        // 0 on the lattice, and repeating every 256
+        assert_eq!(perlin.noise3(f64::from(i), 3.0, -2.0), 0.0);
+        let x = f64::from(i) * 0.37;
+        assert!((perlin.noise2(x, 0.5) - perlin.noise2(x + 256.0, 0.5)).abs() < 1e-9);
+    }
+    assert!(low < -0.5 && high > 0.5, "{low} {high}");
+    assert!(low >= -1.1 && high <= 1.1, "{low} {high}");
+    // the simplex noise is 0 on its lattice too
+    assert_eq!(perlin.simplex2(0.0, 0.0), 0.0);
+    assert_eq!(perlin.simplex3(0.0, 0.0, 0.0), 0.0);
+    // f32 works too
+    assert!(perlin.noise3(0.5f32, 0.25, 0.75).abs() <= 1.1);
+    assert!(perlin.fbm_simplex2(Fbm::DEFAULT, 0.5f32, 0.25).abs() <= 1.1);
+    // far too many octaves (for f32), and a gain over 1, stay finite
+    let many = Fbm::DEFAULT.with_octaves(200);
+    assert!(perlin.fbm2(many, 0.3f32, 0.7).abs() <= 1.1);
+    let rising = Fbm::DEFAULT
+        .with_gain(4.0)
+        .with_lacunarity(3.0)
+        .with_octaves(100);
+    assert!(perlin.fbm3(rising, 0.3f64, 0.7, 0.1).abs() <= 1.1);
+}
@@ -0,0 +1,106 @@
+use crate::generic_float::{Constructors, FloatAlone};
+use crate::policy::Ieee;
+use crate::r#trait::{FastFloat, Show, Wrapper};
+use crate::{Float, NotNan};
+use core::ops::Deref;
+#[cfg(doc)]
+use std::f32::{INFINITY as INF, NAN};
+
+/// Safe float wrapper that is always finite, and not negative.
+/// This float also implements [`Ord`], [`Hash`], and [`Eq`].
+///
+/// Like [`Finite`](crate::Finite), breaking the invariant is not UB: any operation that would produce a negative number, or [`NAN`] | [`INF`], panics.
+/// In exchange, [`NonNegative::sqrt`], [`NonNegative::ln`], and [`NonNegative::log2`] need no domain checks, as they can never produce [`NAN`].
+/// ```
+/// # use umath::NonNegative;
+/// let variance = NonNegative::new(4.0f64).unwrap();
+/// assert_eq!(*variance.sqrt(), 2.0);
+/// assert_eq!(*NonNegative::new(0.0f64).unwrap().ln(), f64::NEG_INFINITY);
+/// assert_eq!(NonNegative::new(-1.0f64), None);
+/// ```
+///
+/// `NonNegative<F>` is guaranteed to have the same memory layout and ABI as F.
+#[repr(transparent)]
+#[derive(Copy, Clone, PartialEq)]
+pub struct NonNegative<T>(T);
+
+impl<T: FastFloat + Constructors> NonNegative<T> {
+    /// Create a new [`NonNegative`] from your {[`f32`], [`f64`]}, if it is finite, and not negative.
+    pub fn new(from: T) -> Option<Self> {
+        // SAFETY: 0 is a valid float
+        (!from.bad::<Ieee>() && from >= unsafe { T::zero() }).then_some(Self(from))
+    }
+
+    /// Create a new [`NonNegative`] from your {[`f32`], [`f64`]}.
+    ///
+    /// # Panics
+    ///
+    /// If `from` is negative, or [`NAN`] | [`INF`].
+    #[track_caller]
+    pub fn must(from: T) -> Self {
+        match Self::new(from) {
+            Some(x) => x,
+            None => panic!("{} is negative | NAN | INF.", Show(from)),
+        }
+    }
+}
+
+impl<T: FastFloat + FloatAlone> NonNegative<T> {
+    /// Refer to [`f32::sqrt`]. This can not fail.
+    pub fn sqrt(self) -> Self {
+        Self(self.0.sqrt())
+    }
+
+    /// Refer to [`f32::ln`]. This can not be [`NAN`], but is -[`INF`] for 0.
+    pub fn ln(self) -> NotNan<T> {
+        // SAFETY: the logarithm of a non negative number is never NAN.
+        unsafe { NotNan::new(self.0.ln()) }
+    }
+
+    /// Refer to [`f32::log2`]. This can not be [`NAN`], but is -[`INF`] for 0.
+    pub fn log2(self) -> NotNan<T> {
+        // SAFETY: the logarithm of a non negative number is never NAN.
+        unsafe { NotNan::new(self.0.log2()) }
+    }
+}
+
+impl<T: FastFloat + Constructors> Wrapper for NonNegative<T> {
+    type Inner = T;
+    type Policy = Ieee;
+
+    #[inline(always)]
+    fn raw(self) -> T {
+        self.0
+    }
+
+    #[inline(always)]
+    unsafe fn wrap(x: T) -> Self {
+        // the invariant is checked here, so the (unchecked) `check` is fine.
+        Self::must(x)
+    }
+}
+
+impl<T> Deref for NonNegative<T> {
+    type Target = T;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl<F: FastFloat + Float<F>> Float<F> for NonNegative<F> {
+    /// Create a new [`NonNegative`] from your {[`f32`], [`f64`]}. This function is safe to call, but panics if `from` is negative, or [`NAN`] | [`INF`].
+    unsafe fn new(from: F) -> Self {
+        Self::must(from)
+    }
+
+    fn take(self) -> F {
+        self.0
+    }
+}
+
+#[test]
+#[should_panic = "-1 is negative | NAN | INF."]
+fn negative() {
+    let _ = NonNegative::must(1.0f32) - 2.0;
+}
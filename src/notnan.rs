@@ -0,0 +1,97 @@
+use crate::policy::Flags;
+use crate::r#trait::{FastFloat, Wrapper};
+use crate::Float;
+use core::ops::{Deref, DerefMut};
+#[cfg(doc)]
+use {
+    crate::{policy::Policy, FFloat},
+    std::f32::{INFINITY as INF, NAN},
+};
+
+/// Float wrapper that is not allowed to be [`NAN`], but (unlike [`FFloat`]) may be [`INF`].
+/// This float also implements [`Ord`], [`Hash`], and [`Eq`].
+///
+/// Rust can only apply `nnan` together with every other fast-math flag, so the operations themselves are ordinary IEEE operations;
+/// the `nnan` part comes from the checks, which (in release) let the optimizer assume that no value is [`NAN`].
+/// The [`Policy`] used only sets `nnan`.
+///
+/// `NotNan<F>` is guaranteed to have the same memory layout and ABI as F.
+/// ```
+/// # use umath::NotNan;
+/// # use std::collections::BTreeSet;
+/// let mut set = BTreeSet::new();
+/// set.insert(unsafe { NotNan::new(f32::INFINITY) });
+/// set.insert(unsafe { NotNan::new(2.0) });
+/// assert_eq!(*set.pop_first().unwrap(), 2.0);
+/// ```
+///
+/// ## Safety Notice (for transmuters)
+///
+/// A [`NotNan`] is _never_ allowed to be [`NAN`].
+#[repr(transparent)]
+#[derive(Copy, Clone, PartialEq)]
+pub struct NotNan<T>(T);
+
+impl<T: FastFloat> NotNan<T> {
+    /// Create a new [`NotNan`] from your {[`f32`], [`f64`]}.
+    ///
+    /// # Safety
+    ///
+    /// - You MUST NEVER call this function with [`NAN`]
+    /// - You MUST NEVER make the produced [`NotNan`] [`NAN`]
+    /// - You MUST NEVER combine this or any [`NotNan`] with any other {[`NotNan`], [`f32`], [`f64`]}, if it will produce [`NAN`] (such as `INF - INF`, or `0 * INF`)
+    pub unsafe fn new(from: T) -> Self {
+        let new = Self(from);
+        new.check();
+        new
+    }
+}
+
+impl<T: FastFloat> Wrapper for NotNan<T> {
+    type Inner = T;
+    type Policy = Flags<true, false, false, false, false, false>;
+
+    #[inline(always)]
+    fn raw(self) -> T {
+        self.0
+    }
+
+    #[inline(always)]
+    unsafe fn wrap(x: T) -> Self {
+        Self::new(x)
+    }
+}
+
+impl<T> Deref for NotNan<T> {
+    type Target = T;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl<T> DerefMut for NotNan<T> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.0
+    }
+}
+
+impl<F: FastFloat + Float<F>> Float<F> for NotNan<F> {
+    /// Create a new [`NotNan`] from your {[`f32`], [`f64`]}.
+    /// Refer to [`NotNan::new`]'s safety documentation.
+    unsafe fn new(from: F) -> Self {
+        Self::new(from)
+    }
+
+    fn take(self) -> F {
+        self.0
+    }
+}
+
+#[test]
+fn infinite() {
+    let inf = unsafe { NotNan::new(f64::INFINITY) };
+    assert!((inf * 2.0).is_infinite());
+    assert!(-inf < unsafe { NotNan::new(f64::MIN) });
+    assert_eq!(inf.max(unsafe { NotNan::new(1.0) }), inf);
+}
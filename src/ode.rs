@@ -0,0 +1,191 @@
+//! fixed step integrators for ordinary differential equations `y' = f(t, y)`, for any <code>[Float]<[f64]></code>.
+//!
+//! The state `y` is anything that is a slice of floats, and can be cloned (for the intermediate stages): a array (`[F; N]`, which needs no allocation), or a [`Vec`].
+//! The derivative is a closure `f(t, y, dy)`, which writes `y'` at `(t, y)` into `dy`.
+//! ```
+//! # use umath::{FFloat, ode::{self, Method}};
+//! // a harmonic oscillator: x'' = -x, as (x, v)' = (v, -x)
+//! let f = |_, y: &[FFloat<f64>], dy: &mut [FFloat<f64>]| {
+//!     dy[0] = y[1];
+//!     dy[1] = -y[0];
+//! };
+//! let mut y = [1.0, 0.0].map(|x| unsafe { FFloat::new(x) });
+//! let t = ode::integrate(Method::Rk4, f, unsafe { FFloat::new(0.0) }, &mut y, unsafe { FFloat::new(0.01) }, 628);
+//! // about one period later
+//! assert!((*t - 6.28).abs() < 1e-9);
+//! assert!((*y[0] - 6.28f64.cos()).abs() < 1e-9);
+//! ```
+//!
+//! ## Safety Notice
+//!
+//! For a [`FFloat`](crate::FFloat), the state, and `f`, must stay finite: a step that is too large for a stiff system blows up.
+use crate::generic_float::Float;
+
+/// A explicit Runge-Kutta method.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub enum Method {
+    /// The forward euler method: `y += h f(t, y)`. First order, and only stable for very small steps.
+    Euler,
+    /// The midpoint method: a euler step to the middle of the step, and then a full step with the derivative there. Second order.
+    Rk2,
+    /// The classic fourth order Runge-Kutta method, with four evaluations of `f` per step.
+    #[default]
+    Rk4,
+}
+
+impl Method {
+    /// The order of the method: halving the step divides the global error by `2^order`.
+    #[must_use]
+    pub const fn order(self) -> u32 {
+        match self {
+            Self::Euler => 1,
+            Self::Rk2 => 2,
+            Self::Rk4 => 4,
+        }
+    }
+}
+
+/// `y = base + h * k`, elementwise.
+fn offset<F: Float<f64>>(y: &mut [F], base: &[F], h: F, k: &[F]) {
+    for ((y, &base), &k) in y.iter_mut().zip(base).zip(k) {
+        *y = base + h * k;
+    }
+}
+
+/// Advances `y` by one step, with the scratch buffers (each as long as `y`).
+fn advance<F: Float<f64>>(
+    method: Method,
+    f: &mut impl FnMut(F, &[F], &mut [F]),
+    t: F,
+    y: &mut [F],
+    h: F,
+    [k1, k2, k3, k4, stage]: [&mut [F]; 5],
+) {
+    let half = h * 0.5;
+    f(t, y, k1);
+    match method {
+        Method::Euler => {
+            for (y, &k1) in y.iter_mut().zip(&*k1) {
+                *y += h * k1;
+            }
+        }
+        Method::Rk2 => {
+            offset(stage, y, half, k1);
+            f(t + half, stage, k2);
+            for (y, &k2) in y.iter_mut().zip(&*k2) {
+                *y += h * k2;
+            }
+        }
+        Method::Rk4 => {
+            offset(stage, y, half, k1);
+            f(t + half, stage, k2);
+            offset(stage, y, half, k2);
+            f(t + half, stage, k3);
+            offset(stage, y, h, k3);
+            f(t + h, stage, k4);
+            let sixth = h / 6.0;
+            for (i, y) in y.iter_mut().enumerate() {
+                *y += sixth * (k1[i] + (k2[i] + k3[i]) * 2.0 + k4[i]);
+            }
+        }
+    }
+}
+
+/// Advances `y` from `t` to `t + h`, with one step of `method`.
+///
+/// The intermediate stages are clones of `y`; to integrate many steps of a [`Vec`] state without allocating each step, use [`integrate`].
+/// ```
+/// # use umath::ode::{self, Method};
+/// // y' = y
+/// let mut y = [1.0f64];
+/// ode::step(Method::Euler, |_, y, dy| dy[0] = y[0], 0.0, &mut y, 0.5);
+/// assert_eq!(y, [1.5]);
+/// ```
+pub fn step<F: Float<f64>, S: Clone + AsRef<[F]> + AsMut<[F]>>(
+    method: Method,
+    mut f: impl FnMut(F, &[F], &mut [F]),
+    t: F,
+    y: &mut S,
+    h: F,
+) {
+    let mut scratch: [S; 5] = core::array::from_fn(|_| y.clone());
+    advance(
+        method,
+        &mut f,
+        t,
+        y.as_mut(),
+        h,
+        scratch.each_mut().map(AsMut::as_mut),
+    );
+}
+
+/// Advances `y` from `t` by `steps` steps of `h`, with `method`, and returns the final time.
+///
+/// The time is computed as `t + i h` for each step (instead of adding `h` up), so that it does not drift.
+/// ```
+/// # use umath::ode::{self, Method};
+/// // y' = -2ty, so y = exp(-t²)
+/// let mut y = vec![1.0f64];
+/// let t = ode::integrate(Method::Rk4, |t, y, dy| dy[0] = -2.0 * t * y[0], 0.0, &mut y, 0.001, 1000);
+/// assert!((t - 1.0).abs() < 1e-12);
+/// assert!((y[0] - (-1f64).exp()).abs() < 1e-12);
+/// ```
+#[allow(clippy::cast_precision_loss)]
+pub fn integrate<F: Float<f64>, S: Clone + AsRef<[F]> + AsMut<[F]>>(
+    method: Method,
+    mut f: impl FnMut(F, &[F], &mut [F]),
+    t: F,
+    y: &mut S,
+    h: F,
+    steps: usize,
+) -> F {
+    let mut scratch: [S; 5] = core::array::from_fn(|_| y.clone());
+    for i in 0..steps {
+        let now = t + h * i as f64;
+        advance(
+            method,
+            &mut f,
+            now,
+            y.as_mut(),
+            h,
+            scratch.each_mut().map(AsMut::as_mut),
+        );
+    }
+    t + h * steps as f64
+}
+
+#[test]
+fn converges() {
+    // y' = cos(t) y, so y = exp(sin(t))
+    let f = |t: f64, y: &[f64], dy: &mut [f64]| dy[0] = t.cos() * y[0];
+    let exact = 2f64.sin().exp();
+    for method in [Method::Euler, Method::Rk2, Method::Rk4] {
+        let error = |steps: u32| {
+            let mut y = [1.0];
+            integrate(
+                method,
+                f,
+                0.0,
+                &mut y,
+                2.0 / f64::from(steps),
+                steps as usize,
+            );
+            (y[0] - exact).abs()
+        };
+        // halving the step divides the error by 2^order
+        let ratio = error(100) / error(200);
+        let expected = f64::from(1 << method.order());
+        assert!((ratio / expected - 1.0).abs() < 0.1, "{method:?} {ratio}");
+    }
+    // a state in a vec, and a step at a time
+    let mut y = vec![1.0, 0.0];
+    let oscillator = |_, y: &[f64], dy: &mut [f64]| {
+        dy[0] = y[1];
+        dy[1] = -y[0];
+    };
+    for i in 0..100u8 {
+        step(Method::Rk4, oscillator, f64::from(i) * 0.01, &mut y, 0.01);
+    }
+    assert!((y[0] - 1f64.cos()).abs() < 1e-10);
+    assert!((y[1] + 1f64.sin()).abs() < 1e-10);
+}
@@ -0,0 +1,125 @@
+//! fast-math policies, for choosing which `ffast-math` flags a [`FFloat`] applies.
+//!
+//! The flags mirror LLVM's:
+//! - `nnan`: assume no argument or result is [`NAN`]
+//! - `ninf`: assume no argument or result is [`INF`]
+//! - `nsz`: ignore the sign of zero
+//! - `arcp`: allow `x / y` to become `x * (1 / y)`
+//! - `contract`: allow fusing (`a * b + c` => `fma(a, b, c)`)
+//! - `reassoc`: allow reassociation (`(a + b) + c` => `a + (b + c)`)
+//!
+//! `nnan` and `ninf` also become the invariant of the [`FFloat`]: with them set, it is checked (and assumed) that the float is not [`NAN`] | [`INF`].
+//!
+//! Rust does not expose each flag on its own, so a policy is lowered to the most permissive set of operations that does not apply any flag the policy did not ask for:
+//! - every flag set: [`fadd_fast`](core::intrinsics::fadd_fast) and friends.
+//! - `nsz`, `arcp`, `contract`, and `reassoc` set: [`fadd_algebraic`](core::intrinsics::fadd_algebraic) and friends.
+//! - anything less: ordinary IEEE operations.
+//!
+//! Set `nnan` | `ninf` are still upheld through the checks, even if the operations themselves do not carry the flag.
+//! ```
+//! # use umath::{FFloat, policy::Algebraic};
+//! // reassociation and contraction, but NAN stays legal.
+//! let f: FFloat<f32, Algebraic> = unsafe { FFloat::with_policy(f32::NAN) };
+//! assert!((f * 2.0).is_nan());
+//! ```
+#[cfg(doc)]
+use crate::FFloat;
+#[cfg(doc)]
+use std::f32::{INFINITY as INF, NAN};
+
+/// A set of fast-math flags. Refer to the [module level documentation](self).
+///
+/// Usually you want to use [`Flags`].
+pub trait Policy: Copy + 'static {
+    /// `nnan`: assume no argument or result is [`NAN`].
+    const NNAN: bool;
+    /// `ninf`: assume no argument or result is [`INF`].
+    const NINF: bool;
+    /// `nsz`: ignore the sign of zero.
+    const NSZ: bool;
+    /// `arcp`: allow the use of reciprocals.
+    const ARCP: bool;
+    /// `contract`: allow floating point contraction (fma).
+    const CONTRACT: bool;
+    /// `reassoc`: allow reassociation.
+    const REASSOC: bool;
+}
+
+/// Which operations a [`Policy`] is lowered to.
+/// This is derived from the flags (through the blanket implementation), so a policy can not claim more than it allows.
+pub(crate) trait Lowering: Policy {
+    /// `nsz`, `arcp`, `contract`, and `reassoc` set: the algebraic operations.
+    const ALGEBRAIC: bool;
+    /// Every flag set: the fast operations.
+    const FAST: bool;
+}
+
+impl<P: Policy> Lowering for P {
+    const ALGEBRAIC: bool = P::NSZ && P::ARCP && P::CONTRACT && P::REASSOC;
+    const FAST: bool = P::NNAN && P::NINF && Self::ALGEBRAIC;
+}
+
+/// Marker for policies that forbid [`NAN`], letting the [`FFloat`] be totally ordered ([`Ord`], [`Eq`], [`Hash`]).
+///
+/// # Safety
+///
+/// [`Policy::NNAN`] must be `true`.
+pub unsafe trait NoNan: Policy {}
+
+/// A zero sized [`Policy`], with each flag as a const parameter, in the order `nnan`, `ninf`, `nsz`, `arcp`, `contract`, `reassoc`.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct Flags<
+    const NNAN: bool,
+    const NINF: bool,
+    const NSZ: bool,
+    const ARCP: bool,
+    const CONTRACT: bool,
+    const REASSOC: bool,
+>;
+
+impl<
+        const NNAN: bool,
+        const NINF: bool,
+        const NSZ: bool,
+        const ARCP: bool,
+        const CONTRACT: bool,
+        const REASSOC: bool,
+    > Policy for Flags<NNAN, NINF, NSZ, ARCP, CONTRACT, REASSOC>
+{
+    const NNAN: bool = NNAN;
+    const NINF: bool = NINF;
+    const NSZ: bool = NSZ;
+    const ARCP: bool = ARCP;
+    const CONTRACT: bool = CONTRACT;
+    const REASSOC: bool = REASSOC;
+}
+
+unsafe impl<
+        const NINF: bool,
+        const NSZ: bool,
+        const ARCP: bool,
+        const CONTRACT: bool,
+        const REASSOC: bool,
+    > NoNan for Flags<true, NINF, NSZ, ARCP, CONTRACT, REASSOC>
+{
+}
+
+/// Every flag. This is the default policy.
+pub type Fast = Flags<true, true, true, true, true, true>;
+/// Reassociation, contraction, reciprocals, and no signed zeros. [`NAN`] and [`INF`] are legal.
+pub type Algebraic = Flags<false, false, true, true, true, true>;
+/// Forbids [`NAN`] and [`INF`], but otherwise uses IEEE semantics.
+pub type Ieee = Flags<true, true, false, false, false, false>;
+/// Only forbids [`INF`], with IEEE semantics.
+pub type NoInf = Flags<false, true, false, false, false, false>;
+
+#[test]
+#[allow(clippy::float_cmp)]
+fn lowering() {
+    use crate::FFloat;
+    let a = unsafe { FFloat::<f64, Ieee>::with_policy(0.1) };
+    assert_eq!(*(a + 0.2), 0.1 + 0.2);
+    let n = unsafe { FFloat::<f32, NoInf>::with_policy(f32::NAN) };
+    assert!((n * 0.0).is_nan());
+    assert_eq!(n.partial_cmp(&n), None);
+}
@@ -0,0 +1,246 @@
+//! dense polynomials.
+//!
+//! A [`Polynomial`] is evaluated with horner's method: a chain of multiply adds, which (with the fast math flags of a [`FFloat`]) are contracted into FMAs.
+//! ```
+//! # use umath::{FFloat, polynomial::Polynomial};
+//! let f = |x| unsafe { FFloat::new(x) };
+//! // 1 - 2x + x²
+//! let p = Polynomial::new(vec![f(1.0f64), f(-2.0), f(1.0)]);
+//! assert_eq!(*p.eval(f(3.0)), 4.0);
+//! assert_eq!(p.derivative(), Polynomial::new(vec![f(-2.0), f(2.0)]));
+//! let q = &p * &Polynomial::new(vec![f(1.0), f(1.0)]);
+//! assert_eq!(q.degree(), 3);
+//! ```
+//!
+//! The roots can be found with [`Polynomial::real_roots`] | [`Polynomial::complex_roots`].
+use crate::generic_float::FloatAlone;
+#[cfg(doc)]
+use crate::FFloat;
+use core::ops::{Add, Mul, Neg, Sub};
+
+mod roots;
+
+/// A polynomial `c₀ + c₁x + c₂x² + ..`, stored as its coefficients, constant first. Refer to the [module level documentation](self).
+///
+/// Trailing zero coefficients are removed, so equal polynomials compare equal, and the zero polynomial has no coefficients.
+#[derive(Clone, Debug, PartialEq, Default)]
+pub struct Polynomial<F> {
+    coefficients: Vec<F>,
+}
+
+impl<F: FloatAlone> Polynomial<F> {
+    /// Creates a polynomial from its coefficients, constant first.
+    #[must_use]
+    pub fn new(mut coefficients: Vec<F>) -> Self {
+        // SAFETY: 0 is a valid float.
+        let zero = unsafe { F::zero() };
+        while coefficients.last() == Some(&zero) {
+            coefficients.pop();
+        }
+        Self { coefficients }
+    }
+
+    /// The zero polynomial.
+    #[must_use]
+    pub fn zero() -> Self {
+        Self {
+            coefficients: Vec::new(),
+        }
+    }
+
+    /// The coefficients, constant first.
+    #[must_use]
+    pub fn coefficients(&self) -> &[F] {
+        &self.coefficients
+    }
+
+    /// The degree (the power of the highest nonzero term), which is 0 for constants (and the zero polynomial).
+    #[must_use]
+    pub fn degree(&self) -> usize {
+        self.coefficients.len().saturating_sub(1)
+    }
+
+    /// Evaluates the polynomial at `x`, with horner's method.
+    #[inline]
+    #[must_use]
+    pub fn eval(&self, x: F) -> F {
+        // SAFETY: 0 is a valid float.
+        let zero = unsafe { F::zero() };
+        self.coefficients
+            .iter()
+            .rev()
+            .fold(zero, |acc, &c| acc * x + c)
+    }
+
+    /// Evaluates the polynomial at each of `x`, into `out`.
+    ///
+    /// The elements are independent, so the horner chains of several of them are interleaved (and vectorized).
+    /// ```
+    /// # use umath::{FFloat, polynomial::Polynomial};
+    /// let f = |x| unsafe { FFloat::new(x) };
+    /// let square = Polynomial::new(vec![f(0.0f32), f(0.0), f(1.0)]);
+    /// let mut out = [f(0.0); 3];
+    /// square.eval_slice(&[f(1.0), f(2.0), f(3.0)], &mut out);
+    /// assert_eq!(out, [1.0, 4.0, 9.0]);
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// If the lengths differ.
+    #[track_caller]
+    pub fn eval_slice(&self, x: &[F], out: &mut [F]) {
+        assert_eq!(x.len(), out.len(), "slice lengths differ");
+        // SAFETY: 0 is a valid float.
+        out.fill(unsafe { F::zero() });
+        // one coefficient at a time, over every element, so the inner loop has no dependency chain.
+        for &c in self.coefficients.iter().rev() {
+            for (out, &x) in out.iter_mut().zip(x) {
+                *out = *out * x + c;
+            }
+        }
+    }
+
+    /// The derivative.
+    #[must_use]
+    #[allow(clippy::cast_precision_loss)]
+    pub fn derivative(&self) -> Self {
+        Self::new(
+            self.coefficients
+                .iter()
+                .enumerate()
+                .skip(1)
+                // SAFETY: the power is finite.
+                .map(|(k, &c)| c * unsafe { F::from_f64(k as f64) })
+                .collect(),
+        )
+    }
+
+    /// The antiderivative whose value at 0 is `constant`.
+    /// ```
+    /// # use umath::{FFloat, polynomial::Polynomial};
+    /// let f = |x| unsafe { FFloat::new(x) };
+    /// let p = Polynomial::new(vec![f(3.0f64), f(4.0)]);
+    /// let integral = p.integral(f(1.0));
+    /// assert_eq!(integral, Polynomial::new(vec![f(1.0), f(3.0), f(2.0)]));
+    /// assert_eq!(integral.derivative(), p);
+    /// ```
+    #[must_use]
+    #[allow(clippy::cast_precision_loss)]
+    pub fn integral(&self, constant: F) -> Self {
+        Self::new(
+            core::iter::once(constant)
+                .chain(
+                    self.coefficients
+                        .iter()
+                        .enumerate()
+                        // SAFETY: the power is finite.
+                        .map(|(k, &c)| c / unsafe { F::from_f64((k + 1) as f64) }),
+                )
+                .collect(),
+        )
+    }
+}
+
+impl<F: FloatAlone> From<Vec<F>> for Polynomial<F> {
+    fn from(coefficients: Vec<F>) -> Self {
+        Self::new(coefficients)
+    }
+}
+
+/// Combines the coefficients of each power, with a missing coefficient as 0.
+fn zip<F: FloatAlone>(a: &[F], b: &[F], f: impl Fn(F, F) -> F) -> Polynomial<F> {
+    // SAFETY: 0 is a valid float.
+    let zero = unsafe { F::zero() };
+    Polynomial::new(
+        (0..a.len().max(b.len()))
+            .map(|i| {
+                f(
+                    a.get(i).copied().unwrap_or(zero),
+                    b.get(i).copied().unwrap_or(zero),
+                )
+            })
+            .collect(),
+    )
+}
+
+impl<F: FloatAlone> Add for &Polynomial<F> {
+    type Output = Polynomial<F>;
+    fn add(self, rhs: Self) -> Polynomial<F> {
+        zip(&self.coefficients, &rhs.coefficients, |a, b| a + b)
+    }
+}
+
+impl<F: FloatAlone> Sub for &Polynomial<F> {
+    type Output = Polynomial<F>;
+    fn sub(self, rhs: Self) -> Polynomial<F> {
+        zip(&self.coefficients, &rhs.coefficients, |a, b| a - b)
+    }
+}
+
+impl<F: FloatAlone> Mul for &Polynomial<F> {
+    type Output = Polynomial<F>;
+    fn mul(self, rhs: Self) -> Polynomial<F> {
+        let (a, b) = (&self.coefficients, &rhs.coefficients);
+        if a.is_empty() || b.is_empty() {
+            return Polynomial::zero();
+        }
+        // SAFETY: 0 is a valid float.
+        let mut product = vec![unsafe { F::zero() }; a.len() + b.len() - 1];
+        for (i, &x) in a.iter().enumerate() {
+            for (out, &y) in product[i..].iter_mut().zip(b) {
+                *out += x * y;
+            }
+        }
+        Polynomial::new(product)
+    }
+}
+
+impl<F: FloatAlone> Neg for &Polynomial<F> {
+    type Output = Polynomial<F>;
+    fn neg(self) -> Polynomial<F> {
+        Polynomial {
+            coefficients: self.coefficients.iter().map(|&c| -c).collect(),
+        }
+    }
+}
+
+macro_rules! owned {
+    ($($trait:ident $name:ident),+) => {
+        $(
+            impl<F: FloatAlone> $trait for Polynomial<F> {
+                type Output = Self;
+                fn $name(self, rhs: Self) -> Self {
+                    (&self).$name(&rhs)
+                }
+            }
+        )+
+    };
+}
+owned!(Add add, Sub sub, Mul mul);
+
+impl<F: FloatAlone> Neg for Polynomial<F> {
+    type Output = Self;
+    fn neg(self) -> Self {
+        -&self
+    }
+}
+
+#[test]
+#[allow(clippy::float_cmp)]
+fn arithmetic() {
+    let p = Polynomial::new(vec![1.0f64, -3.0, 0.0, 2.0]);
+    let q = Polynomial::new(vec![0.5, 4.0, 0.0]);
+    assert_eq!(q.degree(), 1);
+    let x = [-2.0, -0.5, 0.0, 1.25, 3.0];
+    let mut out = [0.0; 5];
+    (&p * &q).eval_slice(&x, &mut out);
+    for (&x, out) in x.iter().zip(out) {
+        assert_eq!((&p + &q).eval(x), p.eval(x) + q.eval(x));
+        assert!((out - p.eval(x) * q.eval(x)).abs() < 1e-12);
+        assert_eq!((-&p).eval(x), -p.eval(x));
+    }
+    assert_eq!(&p - &p, Polynomial::zero());
+    assert_eq!(p.clone() * Polynomial::zero(), Polynomial::zero());
+    assert_eq!(Polynomial::<f64>::zero().eval(2.0), 0.0);
+    assert_eq!(p.integral(0.0).derivative(), p);
+}
@@ -0,0 +1,295 @@
+use super::Polynomial;
+use crate::complex::Complex;
+use crate::generic_float::{lit, Constructors, FloatAlone};
+use core::cmp::Ordering;
+
+/// A monic polynomial `y^n + b[n - 1] y^(n - 1) + .. + b[0]`, whose roots are those of the original divided by `scale`.
+///
+/// The scale is chosen so that every `|b[i]| <= 1`, so that every root has `|y| < 2`, and nothing computed with it can overflow.
+struct Scaled<F> {
+    b: Vec<F>,
+    scale: F,
+}
+
+impl<F: FloatAlone> Scaled<F> {
+    /// Scales `coefficients` (constant first), or [`None`] if it is (numerically) a constant.
+    #[allow(clippy::cast_precision_loss)]
+    fn new(coefficients: &[F]) -> Option<Self> {
+        // SAFETY: 0 and MAX are finite
+        let (zero, max) = unsafe { (F::zero(), <F as Constructors>::max()) };
+        let largest = coefficients.iter().fold(zero, |m, &x| m.max(x.abs()));
+        // dividing by a leading coefficient this small would overflow.
+        let floor = largest / max;
+        let n = coefficients
+            .iter()
+            .rposition(|&x| x != zero && x.abs() >= floor)?;
+        if n == 0 {
+            return None;
+        }
+        let lead = coefficients[n];
+        let monic: Vec<F> = coefficients[..n].iter().map(|&x| x / lead).collect();
+        // the largest root is less than twice this (Fujiwara's bound)
+        let scale = monic.iter().enumerate().fold(zero, |bound, (i, &x)| {
+            bound.max(x.abs().powf(lit(1.0 / (n - i) as f64)))
+        });
+        // x^n: every root is 0.
+        if scale == zero {
+            return Some(Self {
+                b: monic,
+                scale: lit(1.0),
+            });
+        }
+        // b[i] = a[i] / scale^(n - i), divided one factor at a time, so it can only shrink.
+        let b = monic
+            .iter()
+            .enumerate()
+            .map(|(i, &x)| (i..n).fold(x, |x, _| x / scale))
+            .collect();
+        Some(Self { b, scale })
+    }
+
+    fn eval(&self, y: F) -> F {
+        self.b.iter().rev().fold(lit(1.0), |acc, &c| acc * y + c)
+    }
+
+    /// A few newton steps on a root, each kept only if it gets closer.
+    #[allow(clippy::cast_precision_loss)]
+    fn polish(&self, mut y: F) -> F {
+        let n = self.b.len();
+        let mut value = self.eval(y);
+        for _ in 0..4 {
+            // the derivative, by horner on (k + 1) b[k + 1], with the leading n
+            let slope = self.b[1..]
+                .iter()
+                .enumerate()
+                .rev()
+                .fold(lit::<F>(n as f64), |acc, (k, &c)| {
+                    acc * y + c * lit((k + 1) as f64)
+                });
+            if slope.abs() == unsafe { F::zero() } {
+                break;
+            }
+            let step = value / slope;
+            if step.abs().partial_cmp(&lit(1.0)) != Some(Ordering::Less) {
+                break;
+            }
+            let next = self.eval(y - step);
+            if next.abs().partial_cmp(&value.abs()) != Some(Ordering::Less) {
+                break;
+            }
+            (y, value) = (y - step, next);
+        }
+        y
+    }
+
+    /// The real roots, in closed form for degrees up to 3, with [`Self::durand_kerner`] otherwise.
+    fn real(&self) -> Vec<F> {
+        // SAFETY: 0 and π are finite
+        let (zero, π) = unsafe { (F::zero(), F::π()) };
+        let third = lit::<F>(1.0 / 3.0);
+        if self.b.iter().all(|&x| x == zero) {
+            return vec![zero];
+        }
+        match *self.b {
+            [b0] => vec![-b0],
+            [b0, b1] => {
+                let disc = b1 * b1 - b0 * lit(4.0);
+                if disc < zero {
+                    return vec![];
+                }
+                // the larger root (in magnitude) first, so that the sum does not cancel, and then the other from the product.
+                let big = (b1 + disc.sqrt() * sign(b1)) * lit(-0.5);
+                if big == zero {
+                    vec![zero]
+                } else {
+                    vec![big, b0 / big]
+                }
+            }
+            [c0, c1, c2] => {
+                // depressed to t³ + dp t + dq, with y = t - c2 / 3.
+                let dp = c1 - c2 * c2 * third;
+                let dq = c2 * c2 * c2 * lit(2.0 / 27.0) - c2 * c1 * third + c0;
+                let half = dq * lit(-0.5);
+                let disc = half * half + dp * dp * dp * lit(1.0 / 27.0);
+                let amplitude = (dp * -third).max(zero).sqrt() * lit(2.0);
+                let depressed = if disc > zero {
+                    let root = disc.sqrt();
+                    vec![(half + root).cbrt() + (half - root).cbrt()]
+                } else if dp * amplitude == zero {
+                    vec![(-dq).cbrt()]
+                } else {
+                    // three real roots: the trigonometric form.
+                    let cos = (dq * lit(3.0) / (dp * amplitude))
+                        .max(lit(-1.0))
+                        .min(lit(1.0));
+                    let θ = cos.acos() * third;
+                    (0..3)
+                        .map(|k| amplitude * (θ - π * lit(2.0 * f64::from(k) / 3.0)).cos())
+                        .collect()
+                };
+                depressed.into_iter().map(|t| t - c2 * third).collect()
+            }
+            _ => {
+                let tolerance = unsafe { F::ε() }.cbrt();
+                self.durand_kerner()
+                    .into_iter()
+                    .filter(|z| z.im.abs() <= tolerance)
+                    .map(|z| z.re)
+                    .collect()
+            }
+        }
+    }
+
+    /// Every root, with the Durand-Kerner (Weierstrass) iteration: each root estimate is moved by `p(z) / Π (z - other roots)`, simultaneously.
+    fn durand_kerner(&self) -> Vec<Complex<F>> {
+        let n = self.b.len();
+        if self.b.iter().all(|&x| x == unsafe { F::zero() }) {
+            return vec![Complex::from(unsafe { F::zero() }); n];
+        }
+        let one = Complex::from(lit::<F>(1.0));
+        // SAFETY: ε is finite
+        let ε = unsafe { F::ε() };
+        let eval = |z: Complex<F>| {
+            self.b
+                .iter()
+                .rev()
+                .fold(one, |acc, &c| acc * z + Complex::from(c))
+        };
+        // not on the real line, nor a root of unity, so no two estimates (or their conjugates) start out symmetric.
+        let seed = Complex::new(lit(0.4), lit(0.9));
+        let mut z: Vec<_> = core::iter::successors(Some(seed), |&w| Some(w * seed))
+            .take(n)
+            .collect();
+        for _ in 0..500 {
+            let mut moved = unsafe { F::zero() };
+            for i in 0..n {
+                let denominator = (0..n)
+                    .filter(|&j| j != i)
+                    .fold(one, |acc, j| acc * (z[i] - z[j]));
+                // (nearly) coincident estimates: leave this one for the next round.
+                if denominator.norm_sqr().partial_cmp(&(ε * ε)) != Some(Ordering::Greater) {
+                    continue;
+                }
+                let step = eval(z[i]) / denominator;
+                // every root is within 2 of the origin, so a longer step is not converging.
+                if step.norm_sqr().partial_cmp(&lit(16.0)) != Some(Ordering::Less) {
+                    continue;
+                }
+                z[i] -= step;
+                moved = moved.max(step.norm_sqr());
+            }
+            if moved <= ε * ε {
+                break;
+            }
+        }
+        z
+    }
+}
+
+/// `1` or `-1`, with the sign of `x` (`1` for 0).
+fn sign<F: FloatAlone>(x: F) -> F {
+    if x < unsafe { F::zero() } {
+        lit(-1.0)
+    } else {
+        lit(1.0)
+    }
+}
+
+impl<F: FloatAlone> Polynomial<F> {
+    /// The distinct real roots, ascending.
+    ///
+    /// Degrees up to 3 are solved in closed form (the quadratic formula, written so it does not cancel; and Cardano's | the trigonometric method);
+    /// higher degrees find every complex root with [`complex_roots`](Self::complex_roots), and keep those with a negligible imaginary part.
+    /// Each root is then refined with a few newton steps.
+    ///
+    /// Before any of that, the polynomial is made monic, and its variable scaled so that every root is within 2 of the origin,
+    /// so that no intermediate can overflow, even for coefficients near the limits of the float (which matters, as a [`FFloat`](crate::FFloat) must stay finite).
+    /// Leading coefficients so small that dividing by them would overflow are ignored, and roots closer together than about `∛ε` (relative to the largest root) are reported once.
+    /// Constants (including the zero polynomial) have no roots.
+    /// ```
+    /// # use umath::{FFloat, polynomial::Polynomial};
+    /// let f = |x| unsafe { FFloat::new(x) };
+    /// // (x - 1)(x - 2)(x + 3) = x³ - 7x + 6
+    /// let p = Polynomial::new(vec![f(6.0f64), f(-7.0), f(0.0), f(1.0)]);
+    /// let roots = p.real_roots();
+    /// assert_eq!(roots.len(), 3);
+    /// for (root, expect) in roots.iter().zip([-3.0, 1.0, 2.0]) {
+    ///     assert!((**root - expect).abs() < 1e-12);
+    /// }
+    /// // x² + 1
+    /// assert!(Polynomial::new(vec![f(1.0), f(0.0), f(1.0)]).real_roots().is_empty());
+    /// ```
+    #[must_use]
+    pub fn real_roots(&self) -> Vec<F> {
+        let Some(scaled) = Scaled::new(&self.coefficients) else {
+            return vec![];
+        };
+        let mut roots: Vec<F> = scaled
+            .real()
+            .into_iter()
+            .map(|y| scaled.polish(y))
+            .collect();
+        roots.sort_unstable_by(|a, b| a.partial_cmp(b).unwrap_or(Ordering::Equal));
+        // SAFETY: ε is finite
+        let tolerance = unsafe { F::ε() }.cbrt();
+        roots.dedup_by(|b, a| *b - *a <= tolerance);
+        roots.into_iter().map(|y| y * scaled.scale).collect()
+    }
+
+    /// Every complex root (repeated by multiplicity), found with the Durand-Kerner iteration, in no particular order.
+    ///
+    /// The polynomial is scaled as in [`real_roots`](Self::real_roots). Repeated roots converge slowly, and only to about `ε^(1 / multiplicity)`.
+    /// ```
+    /// # use umath::{FFloat, polynomial::Polynomial};
+    /// let f = |x| unsafe { FFloat::new(x) };
+    /// // x⁴ - 1
+    /// let p = Polynomial::new(vec![f(-1.0f64), f(0.0), f(0.0), f(0.0), f(1.0)]);
+    /// let roots = p.complex_roots();
+    /// assert_eq!(roots.len(), 4);
+    /// assert!(roots.iter().all(|z| (*z.norm() - 1.0).abs() < 1e-12));
+    /// ```
+    #[must_use]
+    pub fn complex_roots(&self) -> Vec<Complex<F>> {
+        let Some(scaled) = Scaled::new(&self.coefficients) else {
+            return vec![];
+        };
+        scaled
+            .durand_kerner()
+            .into_iter()
+            .map(|z| z * scaled.scale)
+            .collect()
+    }
+}
+
+#[test]
+fn found() {
+    let roots = |c: Vec<f64>| Polynomial::new(c).real_roots();
+    let close = |a: &[f64], b: &[f64]| {
+        a.len() == b.len()
+            && a.iter()
+                .zip(b)
+                .all(|(a, b)| (a - b).abs() < 1e-9 * (1.0 + b.abs()))
+    };
+    assert!(close(&roots(vec![-3.0, 2.0]), &[1.5]));
+    assert!(close(&roots(vec![1.0, -2.0, 1.0]), &[1.0]));
+    // huge, and tiny, coefficients
+    assert!(close(&roots(vec![-2e300, -1e300, 1e300]), &[-1.0, 2.0]));
+    assert!(close(&roots(vec![2e-300, -3e-300, 1e-300]), &[1.0, 2.0]));
+    assert!(close(&roots(vec![1e200, 1.0]), &[-1e200]));
+    // cubics: one real root, three, and a triple root
+    assert!(close(&roots(vec![-2.0, 0.0, 0.0, 1.0]), &[2f64.cbrt()]));
+    assert!(close(&roots(vec![-6.0, 11.0, -6.0, 1.0]), &[1.0, 2.0, 3.0]));
+    assert_eq!(roots(vec![-1.0, 3.0, -3.0, 1.0]).len(), 1);
+    // (x + 2)(x - 1)(x - 3.5)(x² + 1)
+    let p = &(&Polynomial::new(vec![2.0, 1.0]) * &Polynomial::new(vec![-1.0, 1.0]))
+        * &(&Polynomial::new(vec![-3.5, 1.0]) * &Polynomial::new(vec![1.0, 0.0, 1.0]));
+    assert!(close(&p.real_roots(), &[-2.0, 1.0, 3.5]));
+    assert_eq!(p.complex_roots().len(), 5);
+    assert!(roots(vec![0.0, 0.0, 0.0, 0.0, 1.0])
+        .iter()
+        .all(|&x| x == 0.0));
+    assert!(roots(vec![5.0]).is_empty());
+    let f32s = Polynomial::new(vec![-6.0f32, 11.0, -6.0, 1.0]).real_roots();
+    assert_eq!(f32s.len(), 3);
+    assert!((f32s[2] - 3.0).abs() < 1e-4);
+}
@@ -0,0 +1,278 @@
+//! robust geometric predicates: [`orient2d`], and [`incircle`].
+//!
+//! A predicate is the sign of a determinant, which the rounding of a plain float computation gets wrong for (nearly) degenerate inputs,
+//! such as three (nearly) collinear points, which breaks the invariants of convex hulls and delaunay triangulations.
+//! These are adaptive, after shewchuk ("adaptive precision floating-point arithmetic and fast robust geometric predicates"):
+//! the determinant is computed with plain floats first, and only if it is too close to 0 for its sign to be trusted (within a bound on its rounding error),
+//! it is computed again exactly, with floating point expansions.
+//!
+//! The points can be of any [`Float`] (such as [`FFloat`](crate::FFloat)); they are converted to [`f64`], and both computations are in plain (strict) [`f64`],
+//! as the error bound, and the exact arithmetic, rely on correctly rounded operations, which the fast math flags do not promise.
+//! The result is exact for every input, as long as no product of four coordinates overflows or underflows: for (nonzero) coordinates within about `10^±70`, or any `f32`s.
+//! ```
+//! # use umath::{FFloat, predicates::orient2d, vector::Vec2};
+//! # use core::cmp::Ordering;
+//! let f = |x| unsafe { FFloat::new(x) };
+//! let p = |x, y| Vec2::new(f(x), f(y));
+//! // collinear, to the last bit
+//! assert_eq!(orient2d(p(0.5f64, 0.5), p(12.0, 12.0), p(24.0, 24.0)), Ordering::Equal);
+//! // and just below the line, so the triangle is clockwise
+//! assert_eq!(orient2d(p(0.5f64.next_up(), 0.5), p(12.0, 12.0), p(24.0, 24.0)), Ordering::Less);
+//! ```
+use crate::generic_float::Float;
+use crate::vector::Vec2;
+use core::cmp::Ordering;
+
+/// The unit roundoff of [`f64`], `2^-53`.
+const EPSILON: f64 = f64::EPSILON / 2.0;
+/// The bound on the relative error of the plain orientation determinant (shewchuk's `ccwerrboundA`).
+const ORIENT_BOUND: f64 = (3.0 + 16.0 * EPSILON) * EPSILON;
+/// The bound on the relative error of the plain in circle determinant (shewchuk's `iccerrboundA`).
+const INCIRCLE_BOUND: f64 = (10.0 + 96.0 * EPSILON) * EPSILON;
+
+/// `a + b`, exactly, as the rounded sum, and its error (knuth's two sum).
+fn two_sum(a: f64, b: f64) -> (f64, f64) {
+    let sum = a + b;
+    let virtual_b = sum - a;
+    let virtual_a = sum - virtual_b;
+    (sum, (a - virtual_a) + (b - virtual_b))
+}
+
+/// [`two_sum`], for `|a| ≥ |b|` (dekker's fast two sum).
+fn fast_two_sum(a: f64, b: f64) -> (f64, f64) {
+    let sum = a + b;
+    (sum, b - (sum - a))
+}
+
+/// `a · b`, exactly, as the rounded product, and its error (which a fused multiply add computes exactly).
+fn two_product(a: f64, b: f64) -> (f64, f64) {
+    let product = a * b;
+    (product, a.mul_add(b, -product))
+}
+
+/// `a - b`, exactly, as a expansion.
+fn difference(a: f64, b: f64) -> Vec<f64> {
+    let (sum, error) = two_sum(a, -b);
+    [error, sum].into_iter().filter(|&x| x != 0.0).collect()
+}
+
+/// The exact sum of the expansions `e` and `f` (shewchuk's `fast_expansion_sum_zeroelim`).
+///
+/// A expansion is a sum of floats that do not overlap, in order of increasing magnitude, without zeros (so 0 is empty).
+#[allow(clippy::many_single_char_names)]
+fn sum(e: &[f64], f: &[f64]) -> Vec<f64> {
+    // the components of both, in order of increasing magnitude.
+    let mut merged = Vec::with_capacity(e.len() + f.len());
+    let (mut i, mut j) = (0, 0);
+    while i < e.len() && j < f.len() {
+        if e[i].abs() <= f[j].abs() {
+            merged.push(e[i]);
+            i += 1;
+        } else {
+            merged.push(f[j]);
+            j += 1;
+        }
+    }
+    merged.extend_from_slice(&e[i..]);
+    merged.extend_from_slice(&f[j..]);
+    let [first, second, rest @ ..] = &merged[..] else {
+        return merged;
+    };
+    let mut h = Vec::with_capacity(merged.len());
+    let (mut q, error) = fast_two_sum(*second, *first);
+    h.push(error);
+    for &g in rest {
+        let error;
+        (q, error) = two_sum(q, g);
+        h.push(error);
+    }
+    h.push(q);
+    h.retain(|&x| x != 0.0);
+    h
+}
+
+/// The exact product of the expansion `e` and `b` (shewchuk's `scale_expansion_zeroelim`).
+fn scale(e: &[f64], b: f64) -> Vec<f64> {
+    let Some((&first, rest)) = e.split_first() else {
+        return Vec::new();
+    };
+    let mut h = Vec::with_capacity(e.len() * 2);
+    let (mut q, error) = two_product(first, b);
+    h.push(error);
+    for &x in rest {
+        let (product, product_error) = two_product(x, b);
+        let (partial, error) = two_sum(q, product_error);
+        h.push(error);
+        let error;
+        (q, error) = fast_two_sum(product, partial);
+        h.push(error);
+    }
+    h.push(q);
+    h.retain(|&x| x != 0.0);
+    h
+}
+
+/// The exact product of the expansions `e` and `f`.
+fn product(e: &[f64], f: &[f64]) -> Vec<f64> {
+    f.iter().fold(Vec::new(), |h, &x| sum(&h, &scale(e, x)))
+}
+
+/// The negation of the expansion `e`.
+fn negate(mut e: Vec<f64>) -> Vec<f64> {
+    for x in &mut e {
+        *x = -*x;
+    }
+    e
+}
+
+/// The sign of the expansion `e`: the sign of its largest component.
+fn sign(e: &[f64]) -> Ordering {
+    e.last().map_or(Ordering::Equal, |x| x.total_cmp(&0.0))
+}
+
+/// The sign of `x`, which is not NAN.
+fn sign_of(x: f64) -> Ordering {
+    if x > 0.0 {
+        Ordering::Greater
+    } else if x < 0.0 {
+        Ordering::Less
+    } else {
+        Ordering::Equal
+    }
+}
+
+/// `p`, as [`f64`]s.
+fn widen<F: Float<B>, B: Into<f64>>(p: Vec2<F>) -> [f64; 2] {
+    [p.x.take().into(), p.y.take().into()]
+}
+
+/// The orientation of the triangle `a`, `b`, `c`: [`Greater`](Ordering::Greater) if it is counterclockwise (`c` is to the left of the line from `a` to `b`),
+/// [`Less`](Ordering::Less) if it is clockwise, and [`Equal`](Ordering::Equal) if the points are collinear, exactly.
+///
+/// This is the sign of the determinant `(a - c) × (b - c)`, twice the signed area of the triangle. Refer to the [module level documentation](self).
+#[must_use]
+pub fn orient2d<F: Float<B>, B: Into<f64>>(a: Vec2<F>, b: Vec2<F>, c: Vec2<F>) -> Ordering {
+    let ([ax, ay], [bx, by], [cx, cy]) = (widen(a), widen(b), widen(c));
+    let left = (ax - cx) * (by - cy);
+    let right = (ay - cy) * (bx - cx);
+    let determinant = left - right;
+    // if the terms have different signs (or one is 0), there is no cancellation, and the sign is right.
+    if (left > 0.0 && right <= 0.0) || (left < 0.0 && right >= 0.0) || left == 0.0 {
+        return sign_of(determinant);
+    }
+    let bound = ORIENT_BOUND * (left.abs() + right.abs());
+    if determinant.abs() > bound {
+        return sign_of(determinant);
+    }
+    let left = product(&difference(ax, cx), &difference(by, cy));
+    let right = product(&difference(ay, cy), &difference(bx, cx));
+    sign(&sum(&left, &negate(right)))
+}
+
+/// Whether `d` is in the circle through `a`, `b`, and `c`, which must be in counterclockwise order (refer to [`orient2d`]):
+/// [`Greater`](Ordering::Greater) if it is inside, [`Less`](Ordering::Less) if it is outside, and [`Equal`](Ordering::Equal) if it is on the circle, exactly.
+/// (If they are clockwise, the result is reversed.)
+///
+/// This is the sign of the determinant of the rows `[x, y, x² + y²]` of `a - d`, `b - d`, and `c - d`. Refer to the [module level documentation](self).
+/// ```
+/// # use umath::{predicates::incircle, vector::Vec2};
+/// # use core::cmp::Ordering;
+/// let (a, b, c) = (Vec2::new(5.0f64, 0.0), Vec2::new(0.0, 5.0), Vec2::new(-5.0, 0.0));
+/// assert_eq!(incircle(a, b, c, Vec2::new(3.0, 4.0)), Ordering::Equal);
+/// assert_eq!(incircle(a, b, c, Vec2::new(3.0, 4.0f64.next_down())), Ordering::Greater);
+/// assert_eq!(incircle(a, b, c, Vec2::new(3.0, -4.0f64.next_up())), Ordering::Less);
+/// ```
+#[must_use]
+pub fn incircle<F: Float<B>, B: Into<f64>>(
+    a: Vec2<F>,
+    b: Vec2<F>,
+    c: Vec2<F>,
+    d: Vec2<F>,
+) -> Ordering {
+    let ([ax, ay], [bx, by], [cx, cy], [dx, dy]) = (widen(a), widen(b), widen(c), widen(d));
+    let (adx, ady, bdx, bdy, cdx, cdy) = (ax - dx, ay - dy, bx - dx, by - dy, cx - dx, cy - dy);
+    let (bc_left, bc_right) = (bdx * cdy, cdx * bdy);
+    let (ca_left, ca_right) = (cdx * ady, adx * cdy);
+    let (ab_left, ab_right) = (adx * bdy, bdx * ady);
+    let alift = adx * adx + ady * ady;
+    let blift = bdx * bdx + bdy * bdy;
+    let clift = cdx * cdx + cdy * cdy;
+    let determinant =
+        alift * (bc_left - bc_right) + blift * (ca_left - ca_right) + clift * (ab_left - ab_right);
+    let permanent = (bc_left.abs() + bc_right.abs()) * alift
+        + (ca_left.abs() + ca_right.abs()) * blift
+        + (ab_left.abs() + ab_right.abs()) * clift;
+    if determinant.abs() > INCIRCLE_BOUND * permanent {
+        return sign_of(determinant);
+    }
+    let [adx, ady, bdx, bdy, cdx, cdy] = [
+        difference(ax, dx),
+        difference(ay, dy),
+        difference(bx, dx),
+        difference(by, dy),
+        difference(cx, dx),
+        difference(cy, dy),
+    ];
+    let lift = |x: &[f64], y: &[f64]| sum(&product(x, x), &product(y, y));
+    let cross = |x0: &[f64], y1: &[f64], x1: &[f64], y0: &[f64]| {
+        sum(&product(x0, y1), &negate(product(x1, y0)))
+    };
+    let a = product(&lift(&adx, &ady), &cross(&bdx, &cdy, &cdx, &bdy));
+    let b = product(&lift(&bdx, &bdy), &cross(&cdx, &ady, &adx, &cdy));
+    let c = product(&lift(&cdx, &cdy), &cross(&adx, &bdy, &bdx, &ady));
+    sign(&sum(&sum(&a, &b), &c))
+}
+
+#[test]
+#[allow(
+    clippy::cast_precision_loss,
+    clippy::cast_possible_truncation,
+    clippy::many_single_char_names
+)]
+fn robust() {
+    use crate::FFloat;
+    let p = |x, y| Vec2::new(x, y);
+    // a grid of points around 0.5, one ulp apart, against the line through (12, 12) and (24, 24);
+    // scaled by 2^53, everything is a integer, so the orientation can be checked exactly with i128s.
+    let ulp = 0.5f64.next_up() - 0.5;
+    let scale = |x: f64| (x * (1u64 << 53) as f64) as i128;
+    for i in -8..8 {
+        for j in -8..8 {
+            let a = p(0.5 + f64::from(i) * ulp, 0.5 + f64::from(j) * ulp);
+            let (b, c) = (p(12.0, 12.0), p(24.0, 24.0));
+            let [ax, ay, bx, by, cx, cy] = [a.x, a.y, b.x, b.y, c.x, c.y].map(scale);
+            let exact = ((ax - cx) * (by - cy) - (ay - cy) * (bx - cx)).cmp(&0);
+            assert_eq!(orient2d(a, b, c), exact, "{i} {j}");
+            assert_eq!(orient2d(b, c, a), exact);
+            assert_eq!(orient2d(b, a, c), exact.reverse());
+        }
+    }
+    // obvious cases take the fast path, and fast floats work
+    let f = |x, y| unsafe { Vec2::new(FFloat::new(x), FFloat::new(y)) };
+    assert_eq!(
+        orient2d(f(0.0f32, 0.0), f(1.0, 0.0), f(0.0, 1.0)),
+        Ordering::Greater
+    );
+    assert_eq!(
+        orient2d(f(0.0f32, 0.0), f(0.0, 1.0), f(1.0, 0.0)),
+        Ordering::Less
+    );
+    assert_eq!(
+        orient2d(f(1.0f32, 1.0), f(1.0, 1.0), f(3.0, 7.0)),
+        Ordering::Equal
+    );
+    // cocircular points, and far from the origin, where the plain determinant cancels
+    let (a, b, c) = (p(5.0, 0.0), p(0.0, 5.0), p(-5.0, 0.0));
+    let moved = |q: Vec2<f64>| q + Vec2::splat(1_073_741_824.0);
+    assert_eq!(incircle(a, b, c, p(-3.0, -4.0)), Ordering::Equal);
+    assert_eq!(incircle(a, b, c, p(0.0, 0.0)), Ordering::Greater);
+    assert_eq!(incircle(a, b, c, p(6.0, 0.0)), Ordering::Less);
+    assert_eq!(incircle(c, b, a, p(0.0, 0.0)), Ordering::Less);
+    let on = incircle(moved(a), moved(b), moved(c), moved(p(3.0, 4.0)));
+    let inside = incircle(moved(a), moved(b), moved(c), moved(p(3.0, 3.999_999)));
+    assert_eq!((on, inside), (Ordering::Equal, Ordering::Greater));
+    assert_eq!(
+        incircle(f(5.0f32, 0.0), f(0.0, 5.0), f(-5.0, 0.0), f(0.0, -5.0)),
+        Ordering::Equal
+    );
+}
@@ -0,0 +1,128 @@
+//! probabilities.
+//!
+//! A [`Prob`] is a [`UnitFloat`] with the operations of probability theory, where the conditional operations are validated:
+//! they return [`None`] instead of producing a meaningless probability.
+//! ```
+//! # use umath::prob::Prob;
+//! let p = |x| Prob::new(x).unwrap();
+//! // a test that is 99% sensitive, with a 5% false positive rate, for a disease with a 1% prevalence.
+//! let prior = p(0.01f64);
+//! let evidence = p(0.99).and(prior).or_exclusive(p(0.05).and(!prior));
+//! let posterior = prior.bayes(p(0.99), evidence).unwrap();
+//! assert!((0.16..0.17).contains(&*posterior));
+//! ```
+use crate::generic_float::{Constructors, FloatAlone};
+use crate::r#trait::FastFloat;
+use crate::UnitFloat;
+use core::ops::{Deref, Not};
+
+/// A probability, within `0..=1`. Refer to the [module level documentation](self).
+#[repr(transparent)]
+#[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Prob<T: FastFloat + Constructors>(UnitFloat<T>);
+
+impl<T: FastFloat + FloatAlone> Prob<T> {
+    /// Creates a new [`Prob`], if `p` is within `0..=1`.
+    pub fn new(p: T) -> Option<Self> {
+        UnitFloat::new(p).map(Self)
+    }
+
+    /// Creates a new [`Prob`], clamping `p` into `0..=1`.
+    pub fn saturating(p: T) -> Self {
+        Self(UnitFloat::saturating(p))
+    }
+
+    /// The probability of both events, assuming they are independent (`p·q`).
+    pub fn and(self, other: Self) -> Self {
+        Self(self.0 * other.0)
+    }
+
+    /// The probability of either event, assuming they are independent (`p + q - p·q`).
+    pub fn or(self, other: Self) -> Self {
+        Self::saturating(*self + *other - *self * *other)
+    }
+
+    /// The probability of either event, assuming they are mutually exclusive (`p + q`).
+    pub fn or_exclusive(self, other: Self) -> Self {
+        Self(self.0 + other.0)
+    }
+
+    /// The conditional probability `P(A | B)`, where `self` is the joint probability `P(A ∧ B)`, and `condition` is `P(B)`.
+    ///
+    /// Returns [`None`] if `P(B)` is 0, or the joint probability exceeds it.
+    pub fn given(self, condition: Self) -> Option<Self> {
+        (*condition > unsafe { T::zero() } && self <= condition)
+            .then(|| Self::saturating(*self / *condition))
+    }
+
+    /// Bayes' rule: the posterior `P(A | B)`, where `self` is the prior `P(A)`, `likelihood` is `P(B | A)`, and `evidence` is `P(B)`.
+    ///
+    /// Returns [`None`] under the same conditions as [`Prob::given`].
+    pub fn bayes(self, likelihood: Self, evidence: Self) -> Option<Self> {
+        likelihood.and(self).given(evidence)
+    }
+
+    /// The odds in favour, `p / (1 - p)`. Returns [`None`] if `p` is 1.
+    pub fn odds(self) -> Option<T> {
+        let not = *!self;
+        (not > unsafe { T::zero() }).then(|| *self / not)
+    }
+
+    /// Creates a [`Prob`] from the odds in favour, `o / (1 + o)`. Returns [`None`] if `odds` is negative.
+    pub fn from_odds(odds: T) -> Option<Self> {
+        (odds >= unsafe { T::zero() })
+            .then(|| Self::saturating(odds / (odds + unsafe { T::one() })))
+    }
+
+    /// The log odds (logit), `ln(p / (1 - p))`. Returns [`None`] if `p` is 0 or 1.
+    pub fn log_odds(self) -> Option<T> {
+        let odds = self.odds()?;
+        (odds > unsafe { T::zero() }).then(|| odds.ln())
+    }
+
+    /// Creates a [`Prob`] from log odds (the logistic function), `1 / (1 + e^-l)`.
+    pub fn from_log_odds(l: T) -> Self {
+        let one = unsafe { T::one() };
+        Self::saturating(one / (one + (-l).exp()))
+    }
+}
+
+impl<T: FastFloat + FloatAlone> Not for Prob<T> {
+    type Output = Self;
+    /// The probability of the event not happening (`1 - p`).
+    fn not(self) -> Self {
+        Self(self.0.complement())
+    }
+}
+
+impl<T: FastFloat + Constructors> From<Prob<T>> for UnitFloat<T> {
+    fn from(value: Prob<T>) -> Self {
+        value.0
+    }
+}
+
+impl<T: FastFloat + Constructors> From<UnitFloat<T>> for Prob<T> {
+    fn from(value: UnitFloat<T>) -> Self {
+        Self(value)
+    }
+}
+
+impl<T: FastFloat + Constructors> Deref for Prob<T> {
+    type Target = T;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+#[test]
+#[allow(clippy::float_cmp)]
+fn validated() {
+    let half = Prob::new(0.5f32).unwrap();
+    assert_eq!(*half.or(half), 0.75);
+    assert_eq!(half.given(half.and(half)), None);
+    assert_eq!(half.and(half).given(half), Some(half));
+    assert_eq!(half.odds(), Some(1.0));
+    assert_eq!((!Prob::new(0.0).unwrap()).log_odds(), None);
+    assert_eq!(Prob::from_log_odds(0.0f32), half);
+}
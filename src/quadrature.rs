@@ -0,0 +1,252 @@
+//! numerical integration of closures, for any <code>[Float]<[f64]></code>.
+//!
+//! [`simpson`] and [`gauss_legendre`] evaluate `f` at a fixed set of points, chosen up front: Simpson's rule is exact for cubics,
+//! and `n` point Gauss-Legendre for polynomials of degree `2n - 1` (so it is far more accurate, per evaluation, for smooth `f`).
+//! [`adaptive`] keeps splitting the subinterval with the largest error until the [`Integral`] is within the tolerance,
+//! and so handles peaks, kinks, and (integrable) singularities at the ends.
+//! ```
+//! # use umath::{FFloat, quadrature};
+//! let f = |x: FFloat<f64>| x * x;
+//! let [a, b] = [0.0, 3.0].map(|x| unsafe { FFloat::new(x) });
+//! assert!((*quadrature::simpson(f, a, b, 2) - 9.0).abs() < 1e-12);
+//! assert!((*quadrature::gauss_legendre(f, a, b, 2) - 9.0).abs() < 1e-12);
+//! let integral = quadrature::adaptive(f, a, b, 1e-10);
+//! assert!((*integral.value - 9.0).abs() <= *integral.error + 1e-12);
+//! ```
+//!
+//! ## Safety Notice
+//!
+//! For a [`FFloat`](crate::FFloat), `f` must be finite wherever it is evaluated: [`simpson`] evaluates it at both ends, the others only strictly between them.
+use crate::generic_float::{lit, Float};
+
+/// The result of [`adaptive`]: an estimate of the integral, and of its (absolute) error.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Integral<F> {
+    /// The estimate of the integral.
+    pub value: F,
+    /// The estimated absolute error of [`value`](Self::value) (which is usually pessimistic, for smooth integrands).
+    pub error: F,
+}
+
+/// Integrates `f` over `[a, b]` with the composite Simpson's rule, on `intervals` equal subintervals (rounded up to an even number).
+///
+/// The error is proportional to `h⁴ f⁗`, where `h` is the width of a subinterval.
+///
+/// # Panics
+///
+/// If `intervals` is 0.
+/// ```
+/// # use umath::quadrature::simpson;
+/// let area = simpson(|x: f64| x.sin(), 0.0, core::f64::consts::PI, 64);
+/// assert!((area - 2.0).abs() < 1e-6);
+/// ```
+#[track_caller]
+#[allow(clippy::cast_precision_loss)]
+pub fn simpson<F: Float<f64>>(mut f: impl FnMut(F) -> F, a: F, b: F, intervals: usize) -> F {
+    assert!(intervals != 0, "there must be at least one interval");
+    let count = intervals.next_multiple_of(2);
+    let width = (b - a) / count as f64;
+    let (mut odd, mut even) = (lit::<F>(0.0), lit::<F>(0.0));
+    for i in 1..count {
+        let value = f(a + width * i as f64);
+        if i % 2 == 1 {
+            odd += value;
+        } else {
+            even += value;
+        }
+    }
+    (f(a) + f(b) + odd * 4.0 + even * 2.0) * width / 3.0
+}
+
+/// The nodes (in `[0, 1)`, ascending) and weights of the `n` point Gauss-Legendre rule on `[-1, 1]`,
+/// for the nonnegative half (the rule is symmetric): the roots of the legendre polynomial `Pₙ`, by newton's method.
+#[allow(clippy::cast_precision_loss)]
+fn legendre(n: usize) -> Vec<(f64, f64)> {
+    let order = n as f64;
+    let mut rule: Vec<(f64, f64)> = (0..n.div_ceil(2))
+        .map(|i| {
+            // a close approximation of the i-th root (from the top).
+            let mut z = (core::f64::consts::PI * (i as f64 + 0.75) / (order + 0.5)).cos();
+            let mut slope = 1.0;
+            for _ in 0..100 {
+                // Pₙ(z), and Pₙ₋₁(z), by the recurrence (k + 1) Pₖ₊₁ = (2k + 1) z Pₖ - k Pₖ₋₁
+                let (mut p, mut previous) = (1.0, 0.0);
+                for k in 0..n {
+                    let k = k as f64;
+                    (p, previous) = (((2.0 * k + 1.0) * z * p - k * previous) / (k + 1.0), p);
+                }
+                slope = order * (z * p - previous) / (z * z - 1.0);
+                let step = p / slope;
+                z -= step;
+                if step.abs() <= f64::EPSILON {
+                    break;
+                }
+            }
+            (z, 2.0 / ((1.0 - z * z) * slope * slope))
+        })
+        .collect();
+    rule.reverse();
+    rule
+}
+
+/// Integrates `f` over `[a, b]` with the `points` point Gauss-Legendre rule, which is exact for polynomials of degree up to `2 points - 1`.
+///
+/// The nodes are computed for each call (in `O(points²)`), so for many integrals of a high order, prefer splitting the interval up instead.
+///
+/// # Panics
+///
+/// If `points` is 0.
+/// ```
+/// # use umath::quadrature::gauss_legendre;
+/// // exact for x⁵, with 3 points
+/// let x5 = gauss_legendre(|x: f64| x.powi(5), -1.0, 2.0, 3);
+/// assert!((x5 - 10.5).abs() < 1e-12);
+/// let e = gauss_legendre(|x: f64| x.exp(), 0.0, 1.0, 8);
+/// assert!((e - (core::f64::consts::E - 1.0)).abs() < 1e-14);
+/// ```
+#[track_caller]
+pub fn gauss_legendre<F: Float<f64>>(mut f: impl FnMut(F) -> F, a: F, b: F, points: usize) -> F {
+    assert!(points != 0, "there must be at least one point");
+    let half = (b - a) * 0.5;
+    let mid = a + half;
+    let mut sum = lit::<F>(0.0);
+    for (z, weight) in legendre(points) {
+        sum += f(mid + half * z) * weight;
+        if z != 0.0 {
+            sum += f(mid - half * z) * weight;
+        }
+    }
+    sum * half
+}
+
+/// The nodes (in `(0, 1]`), weights of the 15 point Kronrod rule, and of the embedded 7 point Gauss rule (on every other node), on `[-1, 1]`;
+/// the center node is last.
+const KRONROD: [(f64, f64, f64); 8] = [
+    (0.991_455_371_120_812_6, 0.022_935_322_010_529_22, 0.0),
+    (
+        0.949_107_912_342_758_5,
+        0.063_092_092_629_978_55,
+        0.129_484_966_168_869_7,
+    ),
+    (0.864_864_423_359_769_1, 0.104_790_010_322_250_2, 0.0),
+    (
+        0.741_531_185_599_394_4,
+        0.140_653_259_715_525_9,
+        0.279_705_391_489_276_7,
+    ),
+    (0.586_087_235_467_691_1, 0.169_004_726_639_267_9, 0.0),
+    (
+        0.405_845_151_377_397_2,
+        0.190_350_578_064_785_4,
+        0.381_830_050_505_118_9,
+    ),
+    (0.207_784_955_007_898_5, 0.204_432_940_075_298_9, 0.0),
+    (0.0, 0.209_482_141_084_727_8, 0.417_959_183_673_469_4),
+];
+
+/// The 15 point Gauss-Kronrod estimate of the integral over `[a, b]`, with the difference from the 7 point Gauss estimate as its error.
+fn kronrod<F: Float<f64>>(f: &mut impl FnMut(F) -> F, a: F, b: F) -> Integral<F> {
+    let half = (b - a) * 0.5;
+    let mid = a + half;
+    let (mut kronrod, mut gauss) = (lit::<F>(0.0), lit::<F>(0.0));
+    for (z, weight, gauss_weight) in KRONROD {
+        let y = if z == 0.0 {
+            f(mid)
+        } else {
+            f(mid - half * z) + f(mid + half * z)
+        };
+        kronrod += y * weight;
+        gauss += y * gauss_weight;
+    }
+    Integral {
+        value: kronrod * half,
+        error: ((kronrod - gauss) * half).abs(),
+    }
+}
+
+/// The most subintervals [`adaptive`] will split `[a, b]` into.
+const LIMIT: usize = 1000;
+
+/// Integrates `f` over `[a, b]` adaptively, with 15 point Gauss-Kronrod rules:
+/// the subinterval with the largest estimated error is halved, until the total error is below `tolerance` (absolute, or relative to the integral, whichever is larger).
+///
+/// This gives up once `[a, b]` is split into 1000 subintervals, (or a subinterval can not be split any more),
+/// so check the [`error`](Integral::error) if `f` may be badly behaved.
+///
+/// # Panics
+///
+/// If `tolerance` is negative (or [`NAN`](f64::NAN)).
+/// ```
+/// # use umath::quadrature::adaptive;
+/// // a singularity at 0
+/// let integral = adaptive(|x: f64| 1.0 / x.sqrt(), 0.0, 1.0, 1e-9);
+/// assert!((integral.value - 2.0).abs() < 1e-8);
+/// assert!(integral.error < 1e-8);
+/// ```
+#[track_caller]
+pub fn adaptive<F: Float<f64>>(
+    mut f: impl FnMut(F) -> F,
+    a: F,
+    b: F,
+    tolerance: f64,
+) -> Integral<F> {
+    assert!(tolerance >= 0.0, "the tolerance must not be negative");
+    let mut parts = vec![(a, b, kronrod(&mut f, a, b))];
+    loop {
+        let total: Integral<F> = parts.iter().fold(
+            Integral {
+                value: lit(0.0),
+                error: lit(0.0),
+            },
+            |acc, (_, _, part)| Integral {
+                value: acc.value + part.value,
+                error: acc.error + part.error,
+            },
+        );
+        if total.error <= (total.value.abs() * tolerance).max(lit(tolerance))
+            || parts.len() >= LIMIT
+        {
+            return total;
+        }
+        let worst = (0..parts.len())
+            .reduce(|worst, i| {
+                if parts[i].2.error > parts[worst].2.error {
+                    i
+                } else {
+                    worst
+                }
+            })
+            .unwrap_or(0);
+        let (low, high, _) = parts[worst];
+        let mid = low + (high - low) * 0.5;
+        if mid == low || mid == high {
+            return total;
+        }
+        parts[worst] = (low, mid, kronrod(&mut f, low, mid));
+        parts.push((mid, high, kronrod(&mut f, mid, high)));
+    }
+}
+
+#[test]
+fn integrated() {
+    use core::f64::consts::PI;
+    let cubic = |x: f64| x * x * x - x * 2.0 + 1.0;
+    // ∫ from -1 to 3 = 20 - 8 + 4
+    assert!((simpson(cubic, -1.0, 3.0, 1) - 16.0).abs() < 1e-12);
+    assert!((gauss_legendre(cubic, -1.0, 3.0, 2) - 16.0).abs() < 1e-12);
+    assert!((gauss_legendre(cubic, 3.0, -1.0, 2) + 16.0).abs() < 1e-12);
+    // the nodes and weights of a high order
+    for points in [1, 2, 7, 20, 64] {
+        assert!((gauss_legendre(|_: f64| 1.0, 0.0, 1.0, points) - 1.0).abs() < 1e-13);
+        assert!((gauss_legendre(|x: f64| x.sin(), 0.0, PI, points.max(12)) - 2.0).abs() < 1e-13);
+    }
+    // a sharp peak
+    let peak = |x: f64| 1.0 / (1e-4 + x * x);
+    let integral = adaptive(peak, -1.0, 1.0, 1e-10);
+    let exact = 2.0 * 100.0 * (100.0f64).atan();
+    assert!((integral.value - exact).abs() <= integral.error.max(1e-12 * exact));
+    assert!(integral.error <= 1e-10 * exact);
+    let kink = adaptive(|x: f64| x.abs(), -1.0, 2.0, 1e-12);
+    assert!((kink.value - 2.5).abs() <= kink.error);
+    assert!((simpson(|x: f64| x.sin(), 0.0, PI, 1000) - 2.0).abs() < 1e-11);
+}
@@ -0,0 +1,368 @@
+//! quaternions, for rotations in space.
+//!
+//! A unit [`Quat`] is a rotation: cheaper to compose than a [matrix](crate::matrix), and smoothly interpolable (by [`slerp`](Quat::slerp)), which is why animation uses them.
+//! ```
+//! # use umath::{FFloat, quaternion::Quat, vector::Vec3};
+//! let f = |x| unsafe { FFloat::new(x) };
+//! let z = Vec3::new(f(0.0f64), f(0.0), f(1.0));
+//! let quarter = Quat::from_axis_angle(z, f(core::f64::consts::FRAC_PI_2));
+//! let y = quarter.rotate(Vec3::new(f(1.0), f(0.0), f(0.0)));
+//! assert!((y - Vec3::new(f(0.0), f(1.0), f(0.0))).length() < 1e-12);
+//! // halfway to a quarter turn is an eighth of a turn
+//! let eighth = Quat::identity().slerp(quarter, f(0.5));
+//! assert!((*eighth.to_axis_angle().1 - core::f64::consts::FRAC_PI_4).abs() < 1e-12);
+//! ```
+use crate::generic_float::{lit, FloatAlone};
+use crate::matrix::{Mat3, Mat4};
+use crate::vector::{Vec3, Vec4};
+use core::ops::{Add, Mul, Neg, Sub};
+
+/// A quaternion, `w + xi + yj + zk`: a rotation of `2·acos(w)` radians about the axis `[x, y, z]`, if it has a length of 1.
+/// Refer to the [module level documentation](self).
+///
+/// `q` and `-q` are the same rotation.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Quat<F> {
+    /// The `i` component.
+    pub x: F,
+    /// The `j` component.
+    pub y: F,
+    /// The `k` component.
+    pub z: F,
+    /// The real (scalar) component.
+    pub w: F,
+}
+
+impl<F: FloatAlone> Quat<F> {
+    /// Creates a new [`Quat`], `w + xi + yj + zk`.
+    pub const fn new(x: F, y: F, z: F, w: F) -> Self {
+        Self { x, y, z, w }
+    }
+
+    /// The identity, which does not rotate anything.
+    #[must_use]
+    pub fn identity() -> Self {
+        // SAFETY: 0 and 1 are valid floats
+        let (zero, one) = unsafe { (F::zero(), F::one()) };
+        Self::new(zero, zero, zero, one)
+    }
+
+    /// The rotation by `angle` radians about `axis` (counterclockwise, looking from its tip), which must have a length of 1.
+    pub fn from_axis_angle(axis: Vec3<F>, angle: F) -> Self {
+        let half = angle * lit::<F>(0.5);
+        let v = axis * half.sin();
+        Self::new(v.x, v.y, v.z, half.cos())
+    }
+
+    /// The axis (with a length of 1), and the angle (in radians, in `0..=2π`) of the rotation.
+    /// `self` must have a length of 1.
+    ///
+    /// The axis of the identity is arbitrary: it is `[1, 0, 0]`.
+    pub fn to_axis_angle(self) -> (Vec3<F>, F) {
+        // SAFETY: 0, 1, and ε are finite
+        let (zero, one, ε) = unsafe { (F::zero(), F::one(), F::ε()) };
+        // the sine of half of the angle; the arctangent is accurate at every angle (where the arccosine of `w` is not, near 0).
+        let sin = self.vector().length();
+        let angle = sin.atan2(self.w) * lit::<F>(2.0);
+        if sin <= ε {
+            return (Vec3::new(one, zero, zero), angle);
+        }
+        (self.vector() * (one / sin), angle)
+    }
+
+    /// The rotation of a (pure rotation) matrix: the inverse of [`to_mat3`](Self::to_mat3).
+    ///
+    /// This is shepperd's method, which takes the square root of the largest of the four candidates, so it stays accurate for every rotation.
+    pub fn from_mat3(m: Mat3<F>) -> Self {
+        let [c0, c1, c2] = m.columns;
+        let (m00, m11, m22) = (c0.x, c1.y, c2.z);
+        // SAFETY: 0 and 1 are valid floats
+        let (zero, one) = unsafe { (F::zero(), F::one()) };
+        let (two, quarter) = (lit::<F>(2.0), lit::<F>(0.25));
+        let trace = m00 + m11 + m22;
+        if trace > zero {
+            let s = (trace + one).sqrt() * two;
+            Self::new(
+                (c1.z - c2.y) / s,
+                (c2.x - c0.z) / s,
+                (c0.y - c1.x) / s,
+                s * quarter,
+            )
+        } else if m00 > m11 && m00 > m22 {
+            let s = (one + m00 - m11 - m22).sqrt() * two;
+            Self::new(
+                s * quarter,
+                (c1.x + c0.y) / s,
+                (c2.x + c0.z) / s,
+                (c1.z - c2.y) / s,
+            )
+        } else if m11 > m22 {
+            let s = (one + m11 - m00 - m22).sqrt() * two;
+            Self::new(
+                (c1.x + c0.y) / s,
+                s * quarter,
+                (c2.y + c1.z) / s,
+                (c2.x - c0.z) / s,
+            )
+        } else {
+            let s = (one + m22 - m00 - m11).sqrt() * two;
+            Self::new(
+                (c2.x + c0.z) / s,
+                (c2.y + c1.z) / s,
+                s * quarter,
+                (c0.y - c1.x) / s,
+            )
+        }
+    }
+
+    /// The rotation matrix. `self` must have a length of 1.
+    pub fn to_mat3(self) -> Mat3<F> {
+        let Self { x, y, z, w } = self;
+        // SAFETY: 1 is a valid float
+        let one = unsafe { F::one() };
+        let two = lit::<F>(2.0);
+        Mat3::from_columns([
+            Vec3::new(
+                one - two * (y * y + z * z),
+                two * (x * y + w * z),
+                two * (x * z - w * y),
+            ),
+            Vec3::new(
+                two * (x * y - w * z),
+                one - two * (x * x + z * z),
+                two * (y * z + w * x),
+            ),
+            Vec3::new(
+                two * (x * z + w * y),
+                two * (y * z - w * x),
+                one - two * (x * x + y * y),
+            ),
+        ])
+    }
+
+    /// The rotation matrix, in homogeneous coordinates (refer to [`to_mat3`](Self::to_mat3)).
+    pub fn to_mat4(self) -> Mat4<F> {
+        // SAFETY: 0 and 1 are valid floats
+        let (zero, one) = unsafe { (F::zero(), F::one()) };
+        let [a, b, c] = self.to_mat3().columns;
+        Mat4::from_columns([
+            Vec4::new(a.x, a.y, a.z, zero),
+            Vec4::new(b.x, b.y, b.z, zero),
+            Vec4::new(c.x, c.y, c.z, zero),
+            Vec4::new(zero, zero, zero, one),
+        ])
+    }
+
+    /// The vector (imaginary) part, `[x, y, z]`.
+    pub fn vector(self) -> Vec3<F> {
+        Vec3::new(self.x, self.y, self.z)
+    }
+
+    /// The dot product, `Σ self[i] · rhs[i]`: the cosine of half of the angle between two rotations.
+    pub fn dot(self, rhs: Self) -> F {
+        self.x * rhs.x + self.y * rhs.y + self.z * rhs.z + self.w * rhs.w
+    }
+
+    /// The squared length, `self · self`. This is cheaper than [`length`](Self::length).
+    pub fn length_squared(self) -> F {
+        self.dot(self)
+    }
+
+    /// The length, `√(self · self)`.
+    pub fn length(self) -> F {
+        self.length_squared().sqrt()
+    }
+
+    /// The quaternion scaled to a length of 1 (a rotation), such as to remove the drift of many multiplications.
+    ///
+    /// `self` must not be zero.
+    pub fn normalize(self) -> Self {
+        // SAFETY: 1 is a valid float
+        self * (unsafe { F::one() } / self.length())
+    }
+
+    /// The conjugate, `w - xi - yj - zk`: the inverse rotation, for a quaternion with a length of 1.
+    pub fn conjugate(self) -> Self {
+        Self::new(-self.x, -self.y, -self.z, self.w)
+    }
+
+    /// The inverse, `conjugate / length²`, such that `self * inverse` is the identity. Prefer [`conjugate`](Self::conjugate), for rotations.
+    ///
+    /// `self` must not be zero.
+    pub fn inverse(self) -> Self {
+        // SAFETY: 1 is a valid float
+        self.conjugate() * (unsafe { F::one() } / self.length_squared())
+    }
+
+    /// Rotates `v` by `self`, which must have a length of 1.
+    ///
+    /// This is `self · v · self*`, expanded to `v + w·t + u × t`, where `u` is the [vector part](Self::vector) and `t = 2·(u × v)`.
+    pub fn rotate(self, v: Vec3<F>) -> Vec3<F> {
+        let u = self.vector();
+        let t = u.cross(v) * lit::<F>(2.0);
+        v + t * self.w + u.cross(t)
+    }
+
+    /// The normalized linear interpolation from `self` (at `t = 0`) to `other` (at `t = 1`), along the shorter way around.
+    ///
+    /// This is cheaper than [`slerp`](Self::slerp), and follows the same path, but not at a constant angular speed (it is fastest in the middle).
+    pub fn nlerp(self, other: Self, t: F) -> Self {
+        // SAFETY: 0 is a valid float
+        let other = if self.dot(other) < unsafe { F::zero() } {
+            -other
+        } else {
+            other
+        };
+        (self + (other - self) * t).normalize()
+    }
+
+    /// The spherical linear interpolation from `self` (at `t = 0`) to `other` (at `t = 1`), along the shorter way around, at a constant angular speed.
+    /// Both must have a length of 1.
+    ///
+    /// When the rotations are nearly the same, this is [`nlerp`](Self::nlerp) (which is then as accurate, and does not divide by the sine of a tiny angle).
+    pub fn slerp(self, other: Self, t: F) -> Self {
+        // SAFETY: 0 and 1 are valid floats
+        let (zero, one) = unsafe { (F::zero(), F::one()) };
+        let (cos, other) = match self.dot(other) {
+            cos if cos < zero => (-cos, -other),
+            cos => (cos, other),
+        };
+        if cos > lit::<F>(0.9995) {
+            return (self + (other - self) * t).normalize();
+        }
+        let angle = cos.min(one).acos();
+        let sin = angle.sin();
+        self * (((one - t) * angle).sin() / sin) + other * ((t * angle).sin() / sin)
+    }
+}
+
+impl<F: FloatAlone> Default for Quat<F> {
+    /// The [identity](Quat::identity).
+    fn default() -> Self {
+        Self::identity()
+    }
+}
+
+impl<F: FloatAlone> From<Quat<F>> for Vec4<F> {
+    fn from(q: Quat<F>) -> Self {
+        Vec4::new(q.x, q.y, q.z, q.w)
+    }
+}
+
+impl<F: FloatAlone> From<Vec4<F>> for Quat<F> {
+    fn from(v: Vec4<F>) -> Self {
+        Self::new(v.x, v.y, v.z, v.w)
+    }
+}
+
+impl<F: FloatAlone> Mul for Quat<F> {
+    type Output = Self;
+    /// The hamilton product: the rotation by `rhs`, and then by `self`.
+    fn mul(self, rhs: Self) -> Self {
+        let (a, b) = (self, rhs);
+        Self::new(
+            a.w * b.x + a.x * b.w + a.y * b.z - a.z * b.y,
+            a.w * b.y - a.x * b.z + a.y * b.w + a.z * b.x,
+            a.w * b.z + a.x * b.y - a.y * b.x + a.z * b.w,
+            a.w * b.w - a.x * b.x - a.y * b.y - a.z * b.z,
+        )
+    }
+}
+
+impl<F: FloatAlone> Mul<Vec3<F>> for Quat<F> {
+    type Output = Vec3<F>;
+    /// Refer to [`Quat::rotate`].
+    fn mul(self, rhs: Vec3<F>) -> Vec3<F> {
+        self.rotate(rhs)
+    }
+}
+
+impl<F: FloatAlone> Mul<F> for Quat<F> {
+    type Output = Self;
+    fn mul(self, rhs: F) -> Self {
+        Self::new(self.x * rhs, self.y * rhs, self.z * rhs, self.w * rhs)
+    }
+}
+
+impl<F: FloatAlone> Add for Quat<F> {
+    type Output = Self;
+    fn add(self, rhs: Self) -> Self {
+        Self::new(
+            self.x + rhs.x,
+            self.y + rhs.y,
+            self.z + rhs.z,
+            self.w + rhs.w,
+        )
+    }
+}
+
+impl<F: FloatAlone> Sub for Quat<F> {
+    type Output = Self;
+    fn sub(self, rhs: Self) -> Self {
+        Self::new(
+            self.x - rhs.x,
+            self.y - rhs.y,
+            self.z - rhs.z,
+            self.w - rhs.w,
+        )
+    }
+}
+
+impl<F: FloatAlone> Neg for Quat<F> {
+    type Output = Self;
+    fn neg(self) -> Self {
+        Self::new(-self.x, -self.y, -self.z, -self.w)
+    }
+}
+
+#[test]
+#[allow(clippy::float_cmp)]
+fn rotations() {
+    use core::f64::consts::{FRAC_PI_2, PI};
+    let close = |p: Vec3<f64>, q: Vec3<f64>| (p - q).length() < 1e-12;
+    let axis = Vec3::new(1.0, 2.0, 2.0) * (1.0 / 3.0);
+    let turn = Quat::from_axis_angle(axis, 2.0);
+    let (back, angle) = turn.to_axis_angle();
+    assert!(close(back, axis) && (angle - 2.0).abs() < 1e-12);
+    assert_eq!(Quat::<f64>::default().to_axis_angle().1, 0.0);
+    // the axis is left alone, and the rotation matches the matrix
+    assert!(close(turn.rotate(axis), axis));
+    let point = Vec3::new(0.5, -1.0, 4.0);
+    assert!(close(turn * point, turn.to_mat3() * point));
+    assert!(close(turn.conjugate() * (turn * point), point));
+    assert!(close(turn.inverse().rotate(turn.rotate(point)), point));
+    assert!(close(turn.to_mat4().transform_point(point), turn * point));
+    // composition, and the round trip through the matrix, from every branch of shepperd's method
+    let quarter = |axis| Quat::from_axis_angle(axis, FRAC_PI_2);
+    let [x, y, z] = [
+        Vec3::new(1.0, 0.0, 0.0),
+        Vec3::new(0.0, 1.0, 0.0),
+        Vec3::new(0.0, 0.0, 1.0),
+    ];
+    assert!(close(
+        (quarter(z) * quarter(x)) * y,
+        quarter(z) * (quarter(x) * y)
+    ));
+    for r in [
+        turn,
+        -turn,
+        quarter(x),
+        Quat::from_axis_angle(x, PI),
+        Quat::from_axis_angle(y, PI),
+        Quat::from_axis_angle(z, PI),
+    ] {
+        let back = Quat::from_mat3(r.to_mat3());
+        assert!((back.dot(r).abs() - 1.0).abs() < 1e-12, "{r:?} {back:?}");
+    }
+    // interpolation, the shorter way, at a constant speed
+    let start = Quat::identity();
+    for t in [0.0, 0.25, 0.5, 1.0] {
+        let between = start.slerp(quarter(z), t);
+        assert!((between.to_axis_angle().1 - t * FRAC_PI_2).abs() < 1e-12);
+        let flipped = start.slerp(-quarter(z), t);
+        assert!((flipped.dot(between).abs() - 1.0).abs() < 1e-12);
+        assert!((start.nlerp(quarter(z), t).length() - 1.0).abs() < 1e-12);
+    }
+    let near = Quat::from_axis_angle(z, 1e-9);
+    assert!((start.slerp(near, 0.5).to_axis_angle().1 - 5e-10).abs() < 1e-15);
+}
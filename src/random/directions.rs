@@ -0,0 +1,97 @@
+use super::distributions::bits;
+use super::Source;
+use crate::generic_float::{lit, FloatAlone};
+use crate::vector::{Vec2, Vec3};
+use core::f64::consts::TAU;
+
+/// A uniform random direction in the plane: a point on the unit circle.
+/// ```
+/// # use umath::{FFloat, random::{self, Rng}, vector::Vec2};
+/// let v: Vec2<FFloat<f32>> = random::unit_circle(&mut Rng::new(1));
+/// assert!((*v.length() - 1.0).abs() < 1e-6);
+/// ```
+pub fn unit_circle<F: FloatAlone>(source: &mut (impl Source + ?Sized)) -> Vec2<F> {
+    let (sin, cos) = (bits(source) * TAU).sin_cos();
+    Vec2::new(lit(cos), lit(sin))
+}
+
+/// A uniform random point in the unit disk (with `x² + y² < 1`): the radius is the square root of a uniform value, so that the area is covered evenly.
+pub fn unit_disk<F: FloatAlone>(source: &mut (impl Source + ?Sized)) -> Vec2<F> {
+    let radius = bits(source).sqrt();
+    let (sin, cos) = (bits(source) * TAU).sin_cos();
+    Vec2::new(lit(radius * cos), lit(radius * sin))
+}
+
+/// A uniform random direction in space: a point on the unit sphere.
+///
+/// By archimedes' hat-box theorem, `z` is uniform in `[-1, 1]` (and the angle around the z axis is uniform).
+/// ```
+/// # use umath::{FFloat, random::{self, Rng}, vector::Vec3};
+/// let v: Vec3<FFloat<f64>> = random::unit_sphere(&mut Rng::new(1));
+/// assert!((*v.length() - 1.0).abs() < 1e-12);
+/// ```
+pub fn unit_sphere<F: FloatAlone>(source: &mut (impl Source + ?Sized)) -> Vec3<F> {
+    let z = 1.0 - 2.0 * bits(source);
+    around(source, z)
+}
+
+/// A uniform random direction on the hemisphere above the xy plane: a point on the unit sphere with `z > 0`.
+///
+/// To sample around a surface normal, map `z` onto the normal (with any basis of the surface for `x` and `y`).
+pub fn hemisphere<F: FloatAlone>(source: &mut (impl Source + ?Sized)) -> Vec3<F> {
+    let z = 1.0 - bits(source);
+    around(source, z)
+}
+
+/// A cosine weighted random direction on the hemisphere above the xy plane (with `z ≥ 0`):
+/// the density is proportional to `z`, the cosine of the angle to the z axis, as for light scattered by a lambertian (diffuse) surface.
+///
+/// This is a point of the [unit disk](unit_disk), projected up onto the hemisphere (malley's method).
+pub fn cosine_hemisphere<F: FloatAlone>(source: &mut (impl Source + ?Sized)) -> Vec3<F> {
+    let radius = bits(source).sqrt();
+    let (sin, cos) = (bits(source) * TAU).sin_cos();
+    let z = (1.0 - radius * radius).max(0.0).sqrt();
+    Vec3::new(lit(radius * cos), lit(radius * sin), lit(z))
+}
+
+/// The point at the height `z` on the unit sphere, at a uniform random angle around the z axis.
+fn around<F: FloatAlone>(source: &mut (impl Source + ?Sized), z: f64) -> Vec3<F> {
+    let radius = (1.0 - z * z).max(0.0).sqrt();
+    let (sin, cos) = (bits(source) * TAU).sin_cos();
+    Vec3::new(lit(radius * cos), lit(radius * sin), lit(z))
+}
+
+#[test]
+fn directed() {
+    use super::Rng;
+    let mut rng = Rng::new(11);
+    let n = 20_000.0;
+    let mut sums = [[0.0; 3]; 5];
+    for _ in 0..20_000 {
+        let [x, y] = unit_circle::<f64>(&mut rng).to_array();
+        assert!((x * x + y * y - 1.0).abs() < 1e-12);
+        sums[0][0] += x;
+        sums[0][1] += y;
+        let [x, y] = unit_disk::<f64>(&mut rng).to_array();
+        assert!(x * x + y * y < 1.0);
+        // the mean radius of a uniform disk is 2 / 3
+        sums[1][0] += (x * x + y * y).sqrt();
+        let samples = [
+            unit_sphere::<f64>(&mut rng).to_array(),
+            hemisphere(&mut rng).to_array(),
+            cosine_hemisphere(&mut rng).to_array(),
+        ];
+        for (sum, [x, y, z]) in sums[2..].iter_mut().zip(samples) {
+            assert!((x * x + y * y + z * z - 1.0).abs() < 1e-12);
+            *sum = [sum[0] + x, sum[1] + y, sum[2] + z];
+        }
+        assert!(samples[1][2] > 0.0 && samples[2][2] >= 0.0);
+    }
+    let near = |sum: f64, expected: f64| (sum / n - expected).abs() < 0.01;
+    assert!(near(sums[0][0], 0.0) && near(sums[0][1], 0.0));
+    assert!(near(sums[1][0], 2.0 / 3.0));
+    assert!(sums[2].iter().all(|&s| near(s, 0.0)));
+    // the mean height of a uniform hemisphere is 1 / 2, and of a cosine weighted one, 2 / 3
+    assert!(near(sums[3][0], 0.0) && near(sums[3][1], 0.0) && near(sums[3][2], 0.5));
+    assert!(near(sums[4][0], 0.0) && near(sums[4][1], 0.0) && near(sums[4][2], 2.0 / 3.0));
+}
@@ -0,0 +1,176 @@
+use super::Source;
+use crate::generic_float::{lit, Constructors, Float, FloatAlone};
+
+/// A uniform random [`f64`] in `[0, 1)`: one of the `2^53` evenly spaced floats below 1.
+#[allow(clippy::cast_precision_loss)]
+pub(super) fn bits(source: &mut (impl Source + ?Sized)) -> f64 {
+    (source.next_u64() >> 11) as f64 * (1.0 / (1u64 << 53) as f64)
+}
+
+/// `x`, clamped into the finite range of `F`.
+fn finite<F: Float<B>, B: Into<f64>>(x: f64) -> F {
+    // SAFETY: MAX is finite
+    let max: f64 = unsafe { <F as Constructors>::max() }.take().into();
+    // SAFETY: the value is clamped, and so finite.
+    unsafe { F::from_f64(x.clamp(-max, max)) }
+}
+
+/// A uniform random float in `[0, 1)`.
+///
+/// It is one of the `2^53` evenly spaced [`f64`]s below 1, rounded to `F` (and, if that rounds up to 1, the float just below it).
+#[inline]
+pub fn unit<F: FloatAlone>(source: &mut (impl Source + ?Sized)) -> F {
+    // SAFETY: 1 is a valid float
+    let below = unsafe { F::one() }.next_down();
+    lit::<F>(bits(source)).min(below)
+}
+
+/// A uniform random float in `[low, high]` (`high` itself can only come up by rounding).
+///
+/// The result is interpolated, `low (1 - u) + high u`, and clamped, so that it is always in the range, even if `high - low` would overflow.
+///
+/// # Panics
+///
+/// If `low` is more than `high`.
+#[inline]
+#[track_caller]
+pub fn uniform<F: FloatAlone>(source: &mut (impl Source + ?Sized), low: F, high: F) -> F {
+    assert!(low <= high, "low must not be more than high");
+    let u: F = unit(source);
+    // SAFETY: 1 is a valid float
+    let one = unsafe { F::one() };
+    (low * (one - u) + high * u).max(low).min(high)
+}
+
+/// A normally distributed random float, with a mean of 0 and a standard deviation of 1, by the box-muller transform.
+///
+/// The uniform input is never 0, so the magnitude is at most about 8.6 (the tail beyond that has a probability of about `10^-17`).
+pub fn standard_normal<F: Float<B>, B: Into<f64>>(source: &mut (impl Source + ?Sized)) -> F {
+    // in (0, 1], so that the logarithm is finite.
+    let radius = 1.0 - bits(source);
+    let angle = bits(source) * core::f64::consts::TAU;
+    finite((-2.0 * radius.ln()).sqrt() * angle.cos())
+}
+
+/// A normally distributed random float, with a mean of `mean` and a standard deviation of `std_dev`.
+///
+/// The result is computed in [`f64`], and clamped into the finite range of `F` (so even a huge `std_dev` can not produce a infinity).
+///
+/// # Panics
+///
+/// If `std_dev` is negative.
+/// ```
+/// # use umath::random::{self, Rng};
+/// let mut rng = Rng::new(1);
+/// let samples: Vec<f64> = (0..10_000).map(|_| random::normal(&mut rng, 5.0, 2.0)).collect();
+/// let mean = samples.iter().sum::<f64>() / 10_000.0;
+/// assert!((mean - 5.0).abs() < 0.1);
+/// ```
+#[track_caller]
+pub fn normal<F: Float<B>, B: Into<f64>>(
+    source: &mut (impl Source + ?Sized),
+    mean: F,
+    std_dev: F,
+) -> F {
+    // SAFETY: 0 is a valid float
+    assert!(
+        std_dev >= unsafe { F::zero() },
+        "the standard deviation must not be negative"
+    );
+    let z: f64 = standard_normal::<f64, f64>(source);
+    let (mean, std_dev): (f64, f64) = (mean.take().into(), std_dev.take().into());
+    finite(mean + std_dev * z)
+}
+
+/// A exponentially distributed random float, with a rate of `rate` (so a mean of `1 / rate`): the time between events that happen `rate` times per unit of time, on average.
+///
+/// The uniform input is never 1, so the result is at most about `36.7 / rate` (and clamped into the finite range of `F`).
+///
+/// # Panics
+///
+/// If `rate` is not positive.
+#[track_caller]
+pub fn exponential<F: Float<B>, B: Into<f64>>(source: &mut (impl Source + ?Sized), rate: F) -> F {
+    // SAFETY: 0 is a valid float
+    assert!(rate > unsafe { F::zero() }, "the rate must be positive");
+    let rate: f64 = rate.take().into();
+    finite(-(1.0 - bits(source)).ln() / rate)
+}
+
+/// A triangularly distributed random float, from `low` to `high`, most likely at `mode`.
+///
+/// # Panics
+///
+/// If `mode` is not between `low` and `high`.
+#[track_caller]
+pub fn triangular<F: Float<B>, B: Into<f64>>(
+    source: &mut (impl Source + ?Sized),
+    low: F,
+    mode: F,
+    high: F,
+) -> F {
+    assert!(
+        low <= mode && mode <= high,
+        "the mode must be between low and high"
+    );
+    let (low, mode, high): (f64, f64, f64) =
+        (low.take().into(), mode.take().into(), high.take().into());
+    if high <= low {
+        return finite(low);
+    }
+    // the position of the mode, from 0 to 1 (halved, so that the differences can not overflow).
+    let peak = (mode * 0.5 - low * 0.5) / (high * 0.5 - low * 0.5);
+    let u = bits(source);
+    // the inverse of the cumulative distribution, on [0, 1].
+    let t = if u < peak {
+        (u * peak).sqrt()
+    } else {
+        1.0 - ((1.0 - u) * (1.0 - peak)).sqrt()
+    };
+    finite((low * (1.0 - t) + high * t).clamp(low, high))
+}
+
+#[test]
+fn sampled() {
+    use super::Rng;
+    use crate::FFloat;
+    let mut rng = Rng::new(3);
+    let moments = |samples: &[f64]| {
+        let n = f64::from(u32::try_from(samples.len()).unwrap());
+        let mean = samples.iter().sum::<f64>() / n;
+        let variance = samples.iter().map(|x| (x - mean) * (x - mean)).sum::<f64>() / n;
+        (mean, variance)
+    };
+    let draw = |rng: &mut Rng, f: &dyn Fn(&mut Rng) -> f64| {
+        (0..50_000).map(|_| f(rng)).collect::<Vec<_>>()
+    };
+    let (mean, variance) = moments(&draw(&mut rng, &|rng| normal(rng, -3.0, 0.5)));
+    assert!(
+        (mean + 3.0).abs() < 0.01 && (variance - 0.25).abs() < 0.01,
+        "{mean} {variance}"
+    );
+    let (mean, variance) = moments(&draw(&mut rng, &|rng| exponential(rng, 4.0)));
+    assert!(
+        (mean - 0.25).abs() < 0.01 && (variance - 0.0625).abs() < 0.01,
+        "{mean} {variance}"
+    );
+    let samples = draw(&mut rng, &|rng| triangular(rng, 1.0, 2.0, 6.0));
+    assert!(samples.iter().all(|x| (1.0..=6.0).contains(x)));
+    let (mean, variance) = moments(&samples);
+    // (a + b + c) / 3, and (a² + b² + c² - ab - ac - bc) / 18
+    assert!(
+        (mean - 3.0).abs() < 0.02 && (variance - 21.0 / 18.0).abs() < 0.03,
+        "{mean} {variance}"
+    );
+    let (mean, _) = moments(&draw(&mut rng, &|rng| uniform(rng, 2.0, 4.0)));
+    assert!((mean - 3.0).abs() < 0.01);
+    // never infinite, even when the scale is
+    for _ in 0..1000 {
+        let x = normal(&mut rng, unsafe { FFloat::new(0.0f32) }, unsafe {
+            FFloat::new(f32::MAX)
+        });
+        assert!(x.is_finite());
+        assert!(exponential(&mut rng, unsafe { FFloat::new(1e-45f32) }).is_finite());
+    }
+    assert!(triangular(&mut rng, -f64::MAX, 0.0, f64::MAX).is_finite());
+}
@@ -0,0 +1,156 @@
+//! a small, seedable, pseudo random number generator, producing floats that are always finite.
+//!
+//! [`Rng`] is xoshiro256++ (by Blackman and Vigna): fast, with 256 bits of state, and good statistical quality, but **not** cryptographically secure.
+//! Its floats are generic over [`FloatAlone`], and are always in range (and so finite), so a simulation can draw [`FFloat`](crate::FFloat) inputs directly, without any `unsafe`.
+//! ```
+//! # use umath::{FFloat, random::Rng};
+//! let mut rng = Rng::new(42);
+//! let x: FFloat<f32> = rng.next_unit();
+//! assert!((0.0..1.0).contains(&*x));
+//! let mut samples = [unsafe { FFloat::new(0.0f64) }; 64];
+//! rng.fill_range(&mut samples, unsafe { FFloat::new(-2.0) }, unsafe { FFloat::new(2.0) });
+//! assert!(samples.iter().all(|x| (-2.0..=2.0).contains(&**x)));
+//! ```
+//!
+//! The [distributions](normal), and the [directions](unit_sphere), sample from any [`Source`] of random bits: a [`Rng`], or, with the `rand_core` feature, any generator of the `rand` crates.
+//! ```
+//! # use umath::{FFloat, random::{self, Rng}};
+//! let mut rng = Rng::new(7);
+//! let x: FFloat<f64> = random::normal(&mut rng, unsafe { FFloat::new(10.0) }, unsafe { FFloat::new(2.0) });
+//! assert!((-10.0..30.0).contains(&*x));
+//! ```
+use crate::generic_float::FloatAlone;
+
+mod directions;
+mod distributions;
+pub use directions::*;
+pub use distributions::*;
+
+/// A source of random bits, for the distributions to sample from.
+///
+/// This is implemented by [`Rng`], and, with the `rand_core` feature, by every `rand_core::RngCore` (so any generator of the `rand` crates can be used).
+pub trait Source {
+    /// The next 64 random bits.
+    fn next_u64(&mut self) -> u64;
+}
+
+impl Source for Rng {
+    fn next_u64(&mut self) -> u64 {
+        Rng::next_u64(self)
+    }
+}
+
+#[cfg(feature = "rand_core")]
+impl<R: rand_core::RngCore + ?Sized> Source for R {
+    fn next_u64(&mut self) -> u64 {
+        rand_core::RngCore::next_u64(self)
+    }
+}
+
+/// A xoshiro256++ generator.
+///
+/// The same seed always produces the same sequence (on every platform), so simulations are reproducible.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Rng {
+    state: [u64; 4],
+}
+
+impl Rng {
+    /// Creates a generator, expanding `seed` into the state with splitmix64 (so similar seeds give unrelated sequences).
+    #[must_use]
+    pub fn new(seed: u64) -> Self {
+        let mut x = seed;
+        Self {
+            state: core::array::from_fn(|_| {
+                x = x.wrapping_add(0x9e37_79b9_7f4a_7c15);
+                let mut z = x;
+                z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+                z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+                z ^ (z >> 31)
+            }),
+        }
+    }
+
+    /// The next 64 random bits.
+    #[inline]
+    pub fn next_u64(&mut self) -> u64 {
+        let [s0, s1, s2, s3] = self.state;
+        let result = s0.wrapping_add(s3).rotate_left(23).wrapping_add(s0);
+        let t = s1 << 17;
+        let (s2, s3) = (s2 ^ s0, s3 ^ s1);
+        let (s1, s0) = (s1 ^ s2, s0 ^ s3);
+        self.state = [s0, s1, s2 ^ t, s3.rotate_left(45)];
+        result
+    }
+
+    /// A uniform random float in `[0, 1)` (refer to [`unit`]).
+    #[inline]
+    pub fn next_unit<F: FloatAlone>(&mut self) -> F {
+        unit(self)
+    }
+
+    /// A uniform random float in `[low, high]` (refer to [`uniform`]).
+    ///
+    /// # Panics
+    ///
+    /// If `low` is more than `high`.
+    #[inline]
+    #[track_caller]
+    pub fn range<F: FloatAlone>(&mut self, low: F, high: F) -> F {
+        uniform(self, low, high)
+    }
+
+    /// Fills `buffer` with uniform random floats in `[0, 1)` (refer to [`next_unit`](Self::next_unit)).
+    pub fn fill_unit<F: FloatAlone>(&mut self, buffer: &mut [F]) {
+        for x in buffer {
+            *x = self.next_unit();
+        }
+    }
+
+    /// Fills `buffer` with uniform random floats in `[low, high]` (refer to [`range`](Self::range)).
+    ///
+    /// # Panics
+    ///
+    /// If `low` is more than `high`.
+    #[track_caller]
+    pub fn fill_range<F: FloatAlone>(&mut self, buffer: &mut [F], low: F, high: F) {
+        assert!(low <= high, "low must not be more than high");
+        for x in buffer {
+            *x = self.range(low, high);
+        }
+    }
+}
+
+impl Default for Rng {
+    /// A generator seeded with 0.
+    fn default() -> Self {
+        Self::new(0)
+    }
+}
+
+#[test]
+#[allow(clippy::float_cmp)]
+fn random() {
+    // the reference output of xoshiro256++, from the state 1, 2, 3, 4
+    let mut reference = Rng {
+        state: [1, 2, 3, 4],
+    };
+    assert_eq!(reference.next_u64(), 41_943_041);
+    assert_eq!(reference.next_u64(), 58_720_359);
+    assert_eq!(reference.next_u64(), 3_588_806_011_781_223);
+    // reproducible, and different between seeds
+    assert_eq!(Rng::new(5).next_u64(), Rng::new(5).next_u64());
+    assert_ne!(Rng::new(5).next_u64(), Rng::new(6).next_u64());
+    let mut rng = Rng::default();
+    let mut unit = vec![0.0f32; 10_000];
+    rng.fill_unit(&mut unit);
+    assert!(unit.iter().all(|x| (0.0..1.0).contains(x)));
+    let mean = unit.iter().map(|&x| f64::from(x)).sum::<f64>() / 10_000.0;
+    assert!((mean - 0.5).abs() < 0.01, "{mean}");
+    // the whole range of a float, without overflowing
+    for _ in 0..1000 {
+        let x = rng.range(-f64::MAX, f64::MAX);
+        assert!(x.is_finite());
+    }
+    assert_eq!(rng.range(3.0f64, 3.0), 3.0);
+}
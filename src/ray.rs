@@ -0,0 +1,246 @@
+//! ray casting: the intersections of a [`Ray`] with [boxes](Aabb), [spheres](Sphere), and triangles.
+//!
+//! Every test is written so that it can not divide by zero, even for a ray parallel to a side of the box, or to the triangle, so it is safe with [`FFloat`](crate::FFloat)s:
+//! the reciprocal of a (nearly) zero direction component is clamped to a large, finite, value, and the triangle test rejects (nearly) parallel rays before it divides.
+//! Coordinates must still be far enough from the limits of the float for their products to be finite (within about `10^9` for `f32`, and `10^75` for `f64`, as the sphere test multiplies four of them).
+//! ```
+//! # use umath::{FFloat, bounds::Aabb, ray::Ray, vector::Vec3};
+//! let f = |x| unsafe { FFloat::new(x) };
+//! let v = |x, y, z| Vec3::new(f(x), f(y), f(z));
+//! // straight down the z axis, through the unit cube
+//! let ray = Ray::new(v(0.5f32, 0.5, -2.0), v(0.0, 0.0, 1.0));
+//! let cube = Aabb::new(v(0.0, 0.0, 0.0), v(1.0, 1.0, 1.0));
+//! assert_eq!(*ray.intersect_aabb(cube).unwrap(), 2.0);
+//! let (t, ..) = ray.intersect_triangle([v(0.0, 0.0, 1.0), v(2.0, 0.0, 1.0), v(0.0, 2.0, 1.0)]).unwrap();
+//! assert_eq!(ray.at(t), v(0.5, 0.5, 1.0));
+//! ```
+use crate::bounds::{Aabb, Sphere};
+use crate::generic_float::FloatAlone;
+use crate::vector::Vec3;
+
+/// A half line: the points `origin + direction · t`, for `t ≥ 0`. Refer to the [module level documentation](self).
+///
+/// The distances of the intersections are in units of `direction` (so they are euclidean distances, if it has a length of 1).
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Ray<F> {
+    origin: Vec3<F>,
+    direction: Vec3<F>,
+    /// The reciprocal of each component of `direction` (clamped, so that it is finite), for the slab test.
+    inverse: Vec3<F>,
+}
+
+impl<F: FloatAlone> Ray<F> {
+    /// Creates a ray, from `origin`, toward `direction`.
+    ///
+    /// # Panics
+    ///
+    /// If `direction` is zero.
+    #[track_caller]
+    pub fn new(origin: Vec3<F>, direction: Vec3<F>) -> Self {
+        // SAFETY: 0, 1, and ε are finite
+        let (zero, one, ε) = unsafe { (F::zero(), F::one(), F::ε()) };
+        assert!(
+            direction.length_squared() > zero,
+            "the direction must not be zero"
+        );
+        // the smallest magnitude a component is divided by: its reciprocal, 1 / ε², is about 10^14 for f32, and 10^31 for f64.
+        let tiny = ε * ε;
+        let reciprocal = |d: F| {
+            if d.abs() >= tiny {
+                one / d
+            } else if d < zero {
+                -one / tiny
+            } else {
+                one / tiny
+            }
+        };
+        Self {
+            origin,
+            direction,
+            inverse: Vec3::new(
+                reciprocal(direction.x),
+                reciprocal(direction.y),
+                reciprocal(direction.z),
+            ),
+        }
+    }
+
+    /// The origin.
+    pub fn origin(self) -> Vec3<F> {
+        self.origin
+    }
+
+    /// The direction.
+    pub fn direction(self) -> Vec3<F> {
+        self.direction
+    }
+
+    /// The point at the distance `t` along the ray, `origin + direction · t`.
+    pub fn at(self, t: F) -> Vec3<F> {
+        self.origin + self.direction * t
+    }
+
+    /// The distance to where the ray enters `aabb` (0, if it starts in it), or [`None`], if it misses it.
+    ///
+    /// This is the (branchless) slab test: the ray is in the box where it is between the planes of every pair of opposite sides,
+    /// so it is the intersection of the three intervals of `t` between them.
+    /// A ray that runs exactly along a side (with a direction component of 0) may, or may not, hit it.
+    pub fn intersect_aabb(self, aabb: Aabb<F>) -> Option<F> {
+        let near = (aabb.min - self.origin) * self.inverse;
+        let far = (aabb.max - self.origin) * self.inverse;
+        let (enter, exit) = (near.min(far), near.max(far));
+        // SAFETY: 0 is a valid float
+        let enter = enter.x.max(enter.y).max(enter.z).max(unsafe { F::zero() });
+        let exit = exit.x.min(exit.y).min(exit.z);
+        (enter <= exit).then_some(enter)
+    }
+
+    /// The distance to where the ray first hits the surface of `sphere` (where it leaves it, if it starts in it), or [`None`], if it misses it.
+    ///
+    /// The quadratic is solved in the stable form, which does not subtract nearly equal values for either root.
+    pub fn intersect_sphere(self, sphere: Sphere<F>) -> Option<F> {
+        // SAFETY: 0 is a valid float
+        let zero = unsafe { F::zero() };
+        let offset = self.origin - sphere.center;
+        // |offset + direction · t|² = r², as a t² + 2 b t + c = 0
+        let a = self.direction.length_squared();
+        let b = offset.dot(self.direction);
+        let c = offset.length_squared() - sphere.radius * sphere.radius;
+        let discriminant = b * b - a * c;
+        if discriminant < zero {
+            return None;
+        }
+        let root = discriminant.sqrt();
+        let q = if b < zero { root - b } else { -root - b };
+        // the roots are q / a and c / q; q is 0 only if b and the discriminant are, when the origin is on the surface.
+        let (first, second) = if q == zero {
+            (zero, zero)
+        } else {
+            let (one, other) = (q / a, c / q);
+            (one.min(other), one.max(other))
+        };
+        if first >= zero {
+            Some(first)
+        } else if second >= zero {
+            Some(second)
+        } else {
+            None
+        }
+    }
+
+    /// The distance to where the ray hits the triangle `[a, b, c]` (from either side), and the barycentric coordinates `(u, v)` of the hit,
+    /// so that it is at `a + (b - a) · u + (c - a) · v`; or [`None`], if it misses it.
+    ///
+    /// This is the möller-trumbore test, with the division by the determinant left to the end, after the ray is known to hit,
+    /// and a ray (nearly) parallel to the triangle (or a degenerate triangle) counted as a miss.
+    #[allow(clippy::many_single_char_names)]
+    pub fn intersect_triangle(self, [a, b, c]: [Vec3<F>; 3]) -> Option<(F, F, F)> {
+        // SAFETY: 0, 1, and ε are finite
+        let (zero, one, ε) = unsafe { (F::zero(), F::one(), F::ε()) };
+        let (ab, ac) = (b - a, c - a);
+        let p = self.direction.cross(ac);
+        let determinant = ab.dot(p);
+        let scale = ab.length() * ac.length() * self.direction.length();
+        if determinant.abs() <= ε * scale {
+            return None;
+        }
+        // the tests are multiplied through by the determinant, which is flipped to be positive.
+        let (sign, determinant) = if determinant < zero {
+            (-one, -determinant)
+        } else {
+            (one, determinant)
+        };
+        let s = (self.origin - a) * sign;
+        let u = s.dot(p);
+        if u < zero || u > determinant {
+            return None;
+        }
+        let q = s.cross(ab);
+        let v = self.direction.dot(q);
+        if v < zero || u + v > determinant {
+            return None;
+        }
+        let t = ac.dot(q);
+        if t < zero {
+            return None;
+        }
+        Some((t / determinant, u / determinant, v / determinant))
+    }
+}
+
+#[test]
+#[allow(clippy::float_cmp)]
+fn cast() {
+    let v = |x, y, z| Vec3::new(x, y, z);
+    let cube = Aabb::new(v(-1.0, -1.0, -1.0), v(1.0, 1.0, 1.0));
+    // along an axis (so the other components are 0), from outside, from inside, and away
+    assert_eq!(
+        Ray::new(v(-3.0, 0.0, 0.0), v(1.0, 0.0, 0.0)).intersect_aabb(cube),
+        Some(2.0)
+    );
+    assert_eq!(
+        Ray::new(v(0.5, 0.0, 0.0), v(0.0, -1.0, 0.0)).intersect_aabb(cube),
+        Some(0.0)
+    );
+    assert_eq!(
+        Ray::new(v(-3.0, 0.0, 0.0), v(-1.0, 0.0, 0.0)).intersect_aabb(cube),
+        None
+    );
+    // parallel to a side, outside of it, and inside of it
+    assert_eq!(
+        Ray::new(v(-3.0, 2.0, 0.0), v(1.0, 0.0, 0.0)).intersect_aabb(cube),
+        None
+    );
+    assert_eq!(
+        Ray::new(v(-3.0, 0.5, -0.5), v(1.0, 0.0, 0.0)).intersect_aabb(cube),
+        Some(2.0)
+    );
+    let diagonal = Ray::new(v(-2.0, -2.0, -2.0), v(1.0, 1.0, 1.0));
+    assert_eq!(diagonal.intersect_aabb(cube), Some(1.0));
+    assert_eq!(diagonal.at(1.0), v(-1.0, -1.0, -1.0));
+    // spheres, with a direction that is not normalized
+    let ball = Sphere::new(v(0.0, 0.0, 5.0), 1.0);
+    let ray = Ray::new(v(0.0, 0.0, 0.0), v(0.0, 0.0, 2.0));
+    assert_eq!(ray.intersect_sphere(ball), Some(2.0));
+    assert_eq!(
+        Ray::new(v(0.0, 0.0, 5.0), v(0.0, 0.0, 2.0)).intersect_sphere(ball),
+        Some(0.5)
+    );
+    assert_eq!(
+        Ray::new(v(0.0, 0.0, 0.0), v(0.0, 0.0, -1.0)).intersect_sphere(ball),
+        None
+    );
+    assert_eq!(
+        Ray::new(v(0.0, 2.0, 0.0), v(0.0, 0.0, 1.0)).intersect_sphere(ball),
+        None
+    );
+    assert_eq!(
+        Ray::new(v(0.0, 0.0, 4.0), v(0.0, 0.0, 1.0)).intersect_sphere(ball),
+        Some(0.0)
+    );
+    assert_eq!(
+        Ray::new(v(1.0, 0.0, 0.0), v(0.0, 0.0, 1.0)).intersect_sphere(ball),
+        Some(5.0)
+    );
+    // triangles, from both sides
+    let triangle = [v(0.0, 0.0, 3.0), v(1.0, 0.0, 3.0), v(0.0, 1.0, 3.0)];
+    let up = Ray::new(v(0.25, 0.5, 0.0), v(0.0, 0.0, 1.0));
+    assert_eq!(up.intersect_triangle(triangle), Some((3.0, 0.25, 0.5)));
+    let down = Ray::new(v(0.25, 0.5, 5.0), v(0.0, 0.0, -1.0));
+    assert_eq!(down.intersect_triangle(triangle), Some((2.0, 0.25, 0.5)));
+    assert_eq!(
+        Ray::new(v(0.75, 0.75, 0.0), v(0.0, 0.0, 1.0)).intersect_triangle(triangle),
+        None
+    );
+    assert_eq!(
+        Ray::new(v(0.25, 0.25, 4.0), v(0.0, 0.0, 1.0)).intersect_triangle(triangle),
+        None
+    );
+    // parallel, and degenerate
+    assert_eq!(
+        Ray::new(v(0.0, 0.0, 3.0), v(1.0, 0.0, 0.0)).intersect_triangle(triangle),
+        None
+    );
+    let flat = [v(0.0, 0.0, 3.0), v(1.0, 0.0, 3.0), v(2.0, 0.0, 3.0)];
+    assert_eq!(up.intersect_triangle(flat), None);
+}
@@ -0,0 +1,253 @@
+//! sample rate conversion, by linear or windowed sinc interpolation.
+//!
+//! A [`Resampler`] converts a stream of [`FFloat`] samples at one rate into a stream at another rate, by any (fixed) ratio:
+//! the input can be pushed in blocks of any size, with the same result as resampling it all at once.
+//! ```
+//! # use umath::{FFloat, resample::{Kernel, Resampler}};
+//! let f = |x| unsafe { FFloat::new(x) };
+//! let mut doubler = Resampler::new(2.0, Kernel::Linear);
+//! let output = doubler.resample(&[0.0, 1.0, 2.0].map(f));
+//! assert_eq!(output, [0.0, 0.5, 1.0, 1.5, 2.0, 1.0]);
+//! ```
+use crate::generic_float::{Constructors, FloatExt};
+use crate::policy::Policy;
+use crate::r#trait::{FastFloat, Wrapper};
+use crate::window::Window;
+use crate::{slice, FFloat};
+
+/// The number of fractional positions the sinc kernel is tabulated at; positions between them are linearly interpolated.
+const PHASES: usize = 256;
+
+/// How the input is interpolated, between its samples.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum Kernel {
+    /// Straight lines between neighbouring samples: cheap, but it neither removes the images (when upsampling) nor prevents aliasing (when downsampling).
+    Linear,
+    /// A (band limited) sinc, truncated to `half_width` input samples on either side, and tapered by `window`.
+    ///
+    /// Every output is a dot product of `2 * half_width` samples with the kernel; 8 to 32 samples, with a [`Kaiser`](Window::Kaiser) window, are typical.
+    /// When downsampling, the cutoff is lowered to the output's nyquist frequency, which filters out what would alias.
+    Sinc {
+        /// How many samples on either side of a output are used.
+        half_width: usize,
+        /// The taper of the sinc.
+        window: Window,
+    },
+}
+
+impl Default for Kernel {
+    /// A sinc over 16 samples on either side, with a kaiser window (`β = 8.6`, like a blackman window).
+    fn default() -> Self {
+        Self::Sinc {
+            half_width: 16,
+            window: Window::Kaiser(8.6),
+        }
+    }
+}
+
+/// A streaming sample rate converter, with a fixed ratio.
+///
+/// The output sample `k` is the input interpolated at the time `k / ratio` (in input samples), so the output is not shifted in time;
+/// however, as each output needs the input up to `half_width` samples after it, it is produced that many input samples later (refer to [`flush`](Self::flush)).
+#[derive(Clone, Debug, PartialEq)]
+pub struct Resampler<F> {
+    ratio: f64,
+    /// `1 / ratio`: the distance between outputs, in input samples.
+    step: f64,
+    half_width: usize,
+    /// The kernel, at each of the `PHASES + 1` fractional positions from 0 to 1, as rows of `2 * half_width` weights (empty, for [`Kernel::Linear`]).
+    table: Vec<F>,
+    /// The input still needed, from `half_width - 1` samples before the next output (with that many zeros before the first input).
+    history: Vec<F>,
+    /// The number of outputs so far: the next output is at the time `produced * step`.
+    /// (It is computed from this, instead of being accumulated, so that it does not depend on the sizes of the blocks.)
+    produced: u64,
+    /// The number of samples dropped from the start of `history`.
+    dropped: usize,
+}
+
+/// `sin(πx) / πx`
+fn sinc(x: f64) -> f64 {
+    if x == 0.0 {
+        1.0
+    } else {
+        let x = x * core::f64::consts::PI;
+        x.sin() / x
+    }
+}
+
+impl<T: FastFloat + FloatExt, P: Policy> Resampler<FFloat<T, P>> {
+    /// Creates a resampler, producing `ratio` output samples for each input sample (so `ratio` is the output rate divided by the input rate).
+    ///
+    /// # Panics
+    ///
+    /// If `ratio` is not positive and finite, or the `half_width` of a [`Kernel::Sinc`] is 0.
+    #[must_use]
+    #[track_caller]
+    #[allow(clippy::cast_precision_loss)]
+    pub fn new(ratio: f64, kernel: Kernel) -> Self {
+        assert!(
+            ratio > 0.0 && ratio.is_finite(),
+            "the ratio must be positive and finite"
+        );
+        let (half_width, table) = match kernel {
+            Kernel::Linear => (1, Vec::new()),
+            Kernel::Sinc { half_width, window } => {
+                assert!(half_width > 0, "the half width must be at least 1");
+                let cutoff = ratio.min(1.0);
+                let width = half_width as f64;
+                let mut table = Vec::with_capacity((PHASES + 1) * half_width * 2);
+                for phase in 0..=PHASES {
+                    let fraction = phase as f64 / PHASES as f64;
+                    // the distance from the output to each sample.
+                    let row: Vec<f64> = (0..half_width * 2)
+                        .map(|j| j as f64 - (width - 1.0) - fraction)
+                        .map(|d| sinc(d * cutoff) * window.at((d + width) / (width * 2.0)))
+                        .collect();
+                    // normalized, so that a constant passes through unchanged.
+                    let sum: f64 = row.iter().sum();
+                    // SAFETY: the weights are finite, and so is their sum (which is about 1 / cutoff).
+                    table.extend(
+                        row.iter()
+                            .map(|w| unsafe { FFloat::wrap(T::from_f64(w / sum)) }),
+                    );
+                }
+                (half_width, table)
+            }
+        };
+        let mut resampler = Self {
+            ratio,
+            step: 1.0 / ratio,
+            half_width,
+            table,
+            history: Vec::new(),
+            produced: 0,
+            dropped: 0,
+        };
+        resampler.reset();
+        resampler
+    }
+
+    /// The number of output samples for each input sample.
+    #[must_use]
+    pub fn ratio(&self) -> f64 {
+        self.ratio
+    }
+
+    /// Forgets the input so far, as if it was all zero, and starts the output over (at time 0).
+    pub fn reset(&mut self) {
+        self.history.clear();
+        // SAFETY: 0 is a valid float
+        self.history
+            .resize(self.half_width - 1, unsafe { FFloat::zero() });
+        self.produced = 0;
+        self.dropped = 0;
+    }
+
+    /// Pushes the block `input`, appending the output samples that it completes to `output`.
+    #[allow(
+        clippy::cast_precision_loss,
+        clippy::cast_possible_truncation,
+        clippy::cast_sign_loss
+    )]
+    pub fn process(&mut self, input: &[FFloat<T, P>], output: &mut Vec<FFloat<T, P>>) {
+        self.history.extend_from_slice(input);
+        let taps = self.half_width * 2;
+        loop {
+            let time = self.produced as f64 * self.step;
+            let index = time as usize;
+            // the first sample of the window is `half_width - 1` before `index`, which is where the zeros put it.
+            let start = index - self.dropped;
+            let Some(window) = self.history.get(start..start + taps) else {
+                break;
+            };
+            let fraction = time - index as f64;
+            let (low, high, position) = if self.table.is_empty() {
+                (window[0], window[1], fraction)
+            } else {
+                let u = fraction * PHASES as f64;
+                let row = (u as usize).min(PHASES - 1);
+                let weights = |row: usize| &self.table[row * taps..(row + 1) * taps];
+                (
+                    slice::dot(window, weights(row)),
+                    slice::dot(window, weights(row + 1)),
+                    u - row as f64,
+                )
+            };
+            // SAFETY: the position is in [0, 1].
+            let position = unsafe { FFloat::wrap(T::from_f64(position)) };
+            output.push(low + (high - low) * position);
+            self.produced += 1;
+        }
+        // drop the input before the next output's window.
+        let next = (self.produced as f64 * self.step) as usize;
+        let consumed = (next - self.dropped).min(self.history.len());
+        self.history.drain(..consumed);
+        self.dropped += consumed;
+    }
+
+    /// Pushes `half_width` zeros (one, for [`Kernel::Linear`]), appending the rest of the outputs before the end of the input so far to `output`.
+    ///
+    /// This ends the stream: the resampler should be [reset](Self::reset) before it is used again.
+    pub fn flush(&mut self, output: &mut Vec<FFloat<T, P>>) {
+        // SAFETY: 0 is a valid float
+        let silence = vec![unsafe { FFloat::zero() }; self.half_width];
+        self.process(&silence, output);
+    }
+
+    /// Resamples all of `input` at once: the `⌈input.len() * ratio⌉` samples, at the times from 0 up to the end of `input`.
+    ///
+    /// This [resets](Self::reset) the resampler, and [flushes](Self::flush) it after the input.
+    #[must_use]
+    pub fn resample(&mut self, input: &[FFloat<T, P>]) -> Vec<FFloat<T, P>> {
+        self.reset();
+        let mut output = Vec::new();
+        self.process(input, &mut output);
+        self.flush(&mut output);
+        output
+    }
+}
+
+#[test]
+#[allow(
+    clippy::cast_precision_loss,
+    clippy::cast_possible_truncation,
+    clippy::cast_sign_loss
+)]
+fn resampled() {
+    let f = |x| unsafe { FFloat::<f64>::new(x) };
+    let tone = |rate: f64, count: usize| -> Vec<_> {
+        (0..count)
+            .map(|i| f((i as f64 / rate * 0.05 * core::f64::consts::TAU).sin()))
+            .collect()
+    };
+    let input = tone(1.0, 400);
+    for ratio in [0.75, 1.0, 1.5, 3.0, 147.0 / 160.0] {
+        for kernel in [Kernel::Linear, Kernel::default()] {
+            let mut resampler = Resampler::new(ratio, kernel);
+            let output = resampler.resample(&input);
+            assert_eq!(output.len(), (400.0 * ratio).ceil() as usize, "{ratio}");
+            // close to the tone at the new rate (away from the edges, where the input is cut off)
+            let expected = tone(ratio, output.len());
+            let tolerance = if kernel == Kernel::Linear { 2e-2 } else { 1e-4 };
+            let margin = (20.0 * ratio) as usize;
+            assert!(
+                output[margin..output.len() - margin]
+                    .iter()
+                    .zip(&expected[margin..])
+                    .all(|(x, y)| (**x - **y).abs() < tolerance),
+                "{ratio} {kernel:?}"
+            );
+            // streaming, in blocks of any size, matches
+            for size in [1, 7, 64] {
+                resampler.reset();
+                let mut streamed = Vec::new();
+                for block in input.chunks(size) {
+                    resampler.process(block, &mut streamed);
+                }
+                resampler.flush(&mut streamed);
+                assert_eq!(streamed, output, "{ratio} {size}");
+            }
+        }
+    }
+}
@@ -0,0 +1,402 @@
+//! scalar root finding: solving `f(x) = 0`, for any <code>[Float]<[f64]></code>.
+//!
+//! [`bisection`] and [`brent`] need a bracket (two points where `f` has opposite signs), and then always converge;
+//! [`brent`] (inverse quadratic interpolation, falling back to bisection) is usually much faster, and should be the default.
+//! [`newton`] needs the derivative (which [`differentiate`](crate::dual::differentiate) can provide) and a good starting point, and converges quadratically from there, but may not converge at all from a bad one.
+//!
+//! Every solver stops at the [`Tolerance`], which is also how many evaluations they may spend.
+//! ```
+//! # use umath::{FFloat, generic_float::Trig, roots::{self, Tolerance}};
+//! // the (positive) solution to cos(x) = x
+//! let f = |x: FFloat<f64>| x.cos() - x;
+//! let a = unsafe { FFloat::new(0.0) };
+//! let b = unsafe { FFloat::new(1.0) };
+//! let x = roots::brent(f, a, b, Tolerance::DEFAULT).unwrap();
+//! assert!((*x - 0.7390851332151607).abs() < 1e-11);
+//! ```
+//!
+//! ## Safety Notice
+//!
+//! For a [`FFloat`](crate::FFloat), `f` must be finite at every point between the bracket (or, for [`newton`], wherever the iteration goes).
+use crate::generic_float::{lit, Float};
+use core::cmp::Ordering;
+
+/// When a solver stops: once the root is known to within `absolute + relative * |x|` (or to the nearest float), or after `iterations` evaluations of `f`.
+/// ```
+/// # use umath::roots::Tolerance;
+/// let loose = Tolerance::DEFAULT.with_absolute(1e-6).with_iterations(20);
+/// assert_eq!(loose.iterations(), 20);
+/// ```
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Tolerance {
+    absolute: f64,
+    relative: f64,
+    iterations: usize,
+}
+
+impl Tolerance {
+    /// The defaults: an absolute tolerance of `2e-12`, a relative tolerance of `4ε` (of a [`f64`]), and 100 iterations.
+    pub const DEFAULT: Self = Self {
+        absolute: 2e-12,
+        relative: 4.0 * f64::EPSILON,
+        iterations: 100,
+    };
+
+    /// Sets the absolute tolerance, which matters for roots near 0.
+    ///
+    /// # Panics
+    ///
+    /// If `absolute` is negative (or [`NAN`](f64::NAN)).
+    #[must_use]
+    #[track_caller]
+    pub const fn with_absolute(self, absolute: f64) -> Self {
+        assert!(absolute >= 0.0, "the tolerance must not be negative");
+        Self { absolute, ..self }
+    }
+
+    /// Sets the relative tolerance. (The solvers never try for less than a few ulps, so 0 means "as close as possible".)
+    ///
+    /// # Panics
+    ///
+    /// If `relative` is negative (or [`NAN`](f64::NAN)).
+    #[must_use]
+    #[track_caller]
+    pub const fn with_relative(self, relative: f64) -> Self {
+        assert!(relative >= 0.0, "the tolerance must not be negative");
+        Self { relative, ..self }
+    }
+
+    /// Sets the maximum number of iterations.
+    #[must_use]
+    pub const fn with_iterations(self, iterations: usize) -> Self {
+        Self { iterations, ..self }
+    }
+
+    /// The absolute tolerance.
+    #[must_use]
+    pub const fn absolute(self) -> f64 {
+        self.absolute
+    }
+
+    /// The relative tolerance.
+    #[must_use]
+    pub const fn relative(self) -> f64 {
+        self.relative
+    }
+
+    /// The maximum number of iterations.
+    #[must_use]
+    pub const fn iterations(self) -> usize {
+        self.iterations
+    }
+
+    /// The tolerance at `x`: never below 2 ulps of `x`.
+    fn at<F: Float<f64>>(self, x: F) -> F {
+        // SAFETY: ε is finite
+        let ε = unsafe { F::ε() };
+        x.abs() * (ε * 2.0).max(lit(self.relative)) + self.absolute
+    }
+}
+
+impl Default for Tolerance {
+    fn default() -> Self {
+        Self::DEFAULT
+    }
+}
+
+/// Why a solver did not find a root.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum RootError {
+    /// `f` has the same sign at both ends of the bracket.
+    NotBracketed,
+    /// [`newton`] reached a point where the derivative is 0.
+    ZeroDerivative,
+    /// The tolerance was not reached within the iterations.
+    NoConvergence,
+}
+
+impl core::fmt::Display for RootError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.write_str(match self {
+            Self::NotBracketed => "the function has the same sign at both ends of the bracket",
+            Self::ZeroDerivative => "the derivative is 0",
+            Self::NoConvergence => "the root was not found within the iterations",
+        })
+    }
+}
+
+impl std::error::Error for RootError {}
+
+/// Checks that `fa` and `fb` have opposite signs (or that either is 0).
+fn bracketed<F: Float<f64>>(fa: F, fb: F) -> Result<(), RootError> {
+    // comparing the signs, instead of checking fa * fb < 0, as the product may overflow.
+    match (fa.partial_cmp(&0.0), fb.partial_cmp(&0.0)) {
+        (Some(Ordering::Less), Some(Ordering::Less))
+        | (Some(Ordering::Greater), Some(Ordering::Greater)) => Err(RootError::NotBracketed),
+        _ => Ok(()),
+    }
+}
+
+/// Finds a root of `f` in `[a, b]` by bisection: halving the bracket, keeping the half where `f` changes sign.
+///
+/// This gains exactly one bit per evaluation, no matter how badly `f` behaves (as long as it is continuous), so it is a good fallback;
+/// but [`brent`] is usually several times faster.
+///
+/// # Errors
+///
+/// [`RootError::NotBracketed`] if `f(a)` and `f(b)` have the same sign, and [`RootError::NoConvergence`] if the bracket is still too wide after the iterations.
+/// ```
+/// # use umath::roots::{bisection, Tolerance};
+/// let x = bisection(|x: f64| x * x - 2.0, 0.0, 2.0, Tolerance::DEFAULT).unwrap();
+/// assert!((x - 2f64.sqrt()).abs() < 1e-11);
+/// assert!(bisection(|x: f64| x * x + 1.0, -1.0, 1.0, Tolerance::DEFAULT).is_err());
+/// ```
+pub fn bisection<F: Float<f64>>(
+    mut f: impl FnMut(F) -> F,
+    a: F,
+    b: F,
+    tolerance: Tolerance,
+) -> Result<F, RootError> {
+    let (mut low, mut high) = (a, b);
+    let flow = f(low);
+    let fhigh = f(high);
+    if flow == 0.0 {
+        return Ok(low);
+    }
+    if fhigh == 0.0 {
+        return Ok(high);
+    }
+    bracketed(flow, fhigh)?;
+    let rising = flow < 0.0;
+    for _ in 0..tolerance.iterations {
+        let (mid, half) = halves(low, high);
+        if tolerance.at(mid) >= half.abs() || mid == low || mid == high {
+            return Ok(mid);
+        }
+        let fmid = f(mid);
+        if fmid == 0.0 {
+            return Ok(mid);
+        }
+        if (fmid < 0.0) == rising {
+            low = mid;
+        } else {
+            high = mid;
+        }
+    }
+    Err(RootError::NoConvergence)
+}
+
+/// Finds a root of `f` with newton's method, starting at `x`: `f` returns the value and derivative at a point, and each step goes to where the tangent crosses 0.
+///
+/// Near a simple root, this doubles the correct digits every step; far from one, it may wander off, or cycle.
+/// It stops once a step is within the tolerance.
+///
+/// # Errors
+///
+/// [`RootError::ZeroDerivative`] if it reaches a point with a flat tangent, and [`RootError::NoConvergence`] if the steps are still too long after the iterations.
+/// ```
+/// # use umath::{FFloat, Float, dual::differentiate, roots::{newton, Tolerance}};
+/// fn f<F: Float<f64>>(x: F) -> F {
+///     x * x * x - x * 2.0 - 5.0
+/// }
+/// let x0 = unsafe { FFloat::new(2.0) };
+/// let x = newton(|x| differentiate(f, x), x0, Tolerance::DEFAULT).unwrap();
+/// assert!((*x - 2.0945514815423265).abs() < 1e-12);
+/// ```
+pub fn newton<F: Float<f64>>(
+    mut f: impl FnMut(F) -> (F, F),
+    x: F,
+    tolerance: Tolerance,
+) -> Result<F, RootError> {
+    let mut x = x;
+    for _ in 0..tolerance.iterations {
+        let (value, slope) = f(x);
+        if value == 0.0 {
+            return Ok(x);
+        }
+        if slope == 0.0 {
+            return Err(RootError::ZeroDerivative);
+        }
+        let step = value / slope;
+        x -= step;
+        if step.abs() <= tolerance.at(x) {
+            return Ok(x);
+        }
+    }
+    Err(RootError::NoConvergence)
+}
+
+/// Half of `a + b`, and half of `b - a`, halving before adding, so that even a bracket like [-MAX, MAX] does not overflow.
+/// This is done with (IEEE) [`f64`]s, as the fast math flags may factor `a * 0.5 + b * 0.5` back into `(a + b) * 0.5`.
+/// The half width stays a [`f64`], as it is negative when `b < a`, which not every `F` can hold.
+fn halves<F: Float<f64>>(a: F, b: F) -> (F, f64) {
+    let (a, b) = (a.take() * 0.5, b.take() * 0.5);
+    // SAFETY: the halves of two finite floats add up to a finite float.
+    (unsafe { F::new(a + b) }, b - a)
+}
+
+/// The (signed) width `b - a`, or half of it if the whole one would overflow.
+fn width<F: Float<f64>>(a: F, b: F) -> f64 {
+    let (_, half) = halves(a, b);
+    if half.abs() <= f64::MAX * 0.5 {
+        half * 2.0
+    } else {
+        half
+    }
+}
+
+/// Finds a root of `f` in `[a, b]` with Brent's method: inverse quadratic (or secant) interpolation,
+/// falling back to bisection whenever the interpolation would not shrink the bracket fast enough.
+///
+/// This converges superlinearly for smooth functions, and is never much slower than [`bisection`].
+///
+/// # Errors
+///
+/// [`RootError::NotBracketed`] if `f(a)` and `f(b)` have the same sign, and [`RootError::NoConvergence`] if the bracket is still too wide after the iterations.
+/// ```
+/// # use umath::roots::{brent, Tolerance};
+/// let x = brent(|x: f64| x.exp() - 10.0, 0.0, 5.0, Tolerance::DEFAULT).unwrap();
+/// assert!((x - 10f64.ln()).abs() < 1e-11);
+/// ```
+// the names are those of Brent's description (and most since).
+#[allow(clippy::many_single_char_names)]
+pub fn brent<F: Float<f64>>(
+    mut f: impl FnMut(F) -> F,
+    a: F,
+    b: F,
+    tolerance: Tolerance,
+) -> Result<F, RootError> {
+    // b is the best estimate, a the previous one, and the root is between b and c.
+    let (mut a, mut b) = (a, b);
+    let (mut fa, mut fb) = (f(a), f(b));
+    bracketed(fa, fb)?;
+    let (mut c, mut fc) = (a, fa);
+    // the last step, and the one before (as raw f64s, like the half width).
+    let mut d = width(a, b);
+    let mut e = d;
+    for _ in 0..tolerance.iterations {
+        if bracketed(fb, fc).is_err() {
+            (c, fc) = (a, fa);
+            d = width(a, b);
+            e = d;
+        }
+        if fc.abs() < fb.abs() {
+            (a, fa) = (b, fb);
+            (b, fb) = (c, fc);
+            (c, fc) = (a, fa);
+        }
+        let tol = tolerance.at(b).take() * 0.5;
+        let (_, half) = halves(b, c);
+        if half.abs() <= tol || fb == 0.0 {
+            return Ok(b);
+        }
+        // a step (or bracket) within a few bits of MAX bisects, as the interpolation would overflow.
+        let tame = e.abs() <= f64::MAX / 16.0 && half.abs() <= f64::MAX / 16.0;
+        if tame && e.abs() >= tol && fa.abs() > fb.abs() {
+            // interpolate, as p / q.
+            let s = fb / fa;
+            let (mut p, mut q) = if a == c {
+                // secant
+                (s * (half * 2.0), -s + 1.0)
+            } else {
+                // inverse quadratic
+                let q = fa / fc;
+                let r = fb / fc;
+                (
+                    s * (q * (q - r) * (half * 2.0) - (b - a) * (r - 1.0)),
+                    (q - 1.0) * (r - 1.0) * (s - 1.0),
+                )
+            };
+            if p > 0.0 {
+                q = -q;
+            }
+            p = p.abs();
+            // accept the interpolation only if it stays inside the bracket, and shrinks faster than bisection would.
+            if p * 2.0 < (q * (half * 3.0) - (q * tol).abs()).min((q * e).abs()) {
+                e = d;
+                d = (p / q).take();
+            } else {
+                d = half;
+                e = d;
+            }
+        } else {
+            d = half;
+            e = d;
+        }
+        (a, fa) = (b, fb);
+        b += if d.abs() > tol {
+            d
+        } else if half > 0.0 {
+            tol
+        } else {
+            -tol
+        };
+        fb = f(b);
+    }
+    Err(RootError::NoConvergence)
+}
+
+#[test]
+fn solved() {
+    use crate::{
+        generic_float::{FloatMethods, Trig},
+        FFloat,
+    };
+    let tolerance = Tolerance::DEFAULT;
+    let cubic = |x: f64| (x - 1.0) * (x + 2.0) * (x - 3.5);
+    for (a, b, root) in [(0.0, 2.0, 1.0), (-10.0, 0.0, -2.0), (2.0, 100.0, 3.5)] {
+        assert!((bisection(cubic, a, b, tolerance).unwrap() - root).abs() < 1e-11);
+        assert!((brent(cubic, a, b, tolerance).unwrap() - root).abs() < 1e-11);
+    }
+    assert_eq!(brent(cubic, 1.0, 5.0, tolerance), Ok(1.0));
+    // a reversed bracket has a negative width, which a non negative float can not hold.
+    let n = |x: f64| crate::NonNegative::new(x).unwrap();
+    assert_eq!(brent(|x| x, n(1.0), n(0.0), tolerance), Ok(n(0.0)));
+    assert_eq!(bisection(|x| x, n(1.0), n(0.0), tolerance), Ok(n(0.0)));
+    assert!((brent(cubic, 2.0, 0.0, tolerance).unwrap() - 1.0).abs() < 1e-11);
+    assert_eq!(
+        brent(cubic, 4.0, 5.0, tolerance),
+        Err(RootError::NotBracketed)
+    );
+    // brent needs far fewer evaluations
+    let mut count = 0;
+    let counted = |x: f64| {
+        count += 1;
+        x.powi(3) - 7.0
+    };
+    brent(counted, 0.0, 10.0, tolerance).unwrap();
+    assert!(count < 15, "{count}");
+    assert_eq!(
+        bisection(cubic, 0.0, 1.7, tolerance.with_iterations(5)),
+        Err(RootError::NoConvergence)
+    );
+    // a root at 0, and a loose tolerance
+    let loose = tolerance.with_absolute(1e-3);
+    assert!(
+        bisection(|x: f64| x * x * x, -1.0, 3.0, loose)
+            .unwrap()
+            .abs()
+            < 1e-3
+    );
+    assert!(brent(|x: f64| x.sin(), -1.0, 2.0, loose).unwrap().abs() < 1e-3);
+    // as close as possible
+    let exact = tolerance.with_absolute(0.0).with_relative(0.0);
+    let sqrt2 = brent(|x: f64| x * x - 2.0, 0.0, 2.0, exact).unwrap();
+    assert!((sqrt2 - 2f64.sqrt()).abs() <= f64::EPSILON * 4.0);
+    assert_eq!(
+        newton(|x: f64| (x * x + 1.0, 2.0 * x), 0.0, tolerance),
+        Err(RootError::ZeroDerivative)
+    );
+    let root = newton(|x: f64| (x.ln() - 1.0, 1.0 / x), 1.0, tolerance).unwrap();
+    assert!((root - core::f64::consts::E).abs() < 1e-12);
+    // the whole range (which takes a bit per halving), without overflowing a fast float
+    let whole = tolerance.with_iterations(3000);
+    let f = |x: f64| unsafe { FFloat::new(x) };
+    let functions: [fn(FFloat<f64>) -> FFloat<f64>; 3] =
+        [|x| x - 3.0, |x| x.cbrt() + 1e100, |x| x.atan() - 0.5];
+    for g in functions {
+        let low = bisection(g, f(f64::MIN), f(f64::MAX), whole).unwrap();
+        let high = brent(g, f(f64::MIN), f(f64::MAX), whole).unwrap();
+        assert!((*low - *high).abs() <= *whole.at(high) * 4.0);
+    }
+}
@@ -0,0 +1,120 @@
+use crate::generic_float::Constructors;
+use crate::policy::{Ieee, NoInf, Policy};
+use crate::r#trait::{FastFloat, Wrapper};
+use crate::FFloat;
+use core::ops::{
+    Add, AddAssign, Deref, DerefMut, Div, DivAssign, Mul, MulAssign, Rem, RemAssign, Sub,
+    SubAssign,
+};
+#[cfg(doc)]
+use std::f32::{INFINITY as INF, NAN};
+
+/// Clamps [`INF`] to ±MAX, and turns [`NAN`] into 0.
+#[inline(always)]
+fn saturate<T: FastFloat + Constructors>(x: T) -> T {
+    if !x.bad::<Ieee>() {
+        x
+    } else if x.bad::<NoInf>() {
+        // SAFETY: 0, MIN, and MAX are finite
+        unsafe { if x > T::zero() { T::max() } else { T::min() } }
+    } else {
+        unsafe { T::zero() }
+    }
+}
+
+macro_rules! saturating {
+    ($($name:ident => $op:ident, $trait:ident, $fn:ident, $assign:ident, $assign_fn:ident, $verb:literal);+ $(;)?) => {
+        /// Saturating arithmetic.
+        ///
+        /// These compute with ordinary IEEE operations, clamping a overflowing result to ±MAX, so they can never break the invariant.
+        /// As the only way to produce [`NAN`] from finite values is `0 / 0` | `x % 0`, those produce `0`.
+        /// ```
+        /// # use umath::FFloat;
+        /// let max = unsafe { FFloat::new(f32::MAX) };
+        /// assert_eq!(max.saturating_mul(max), max);
+        /// assert_eq!(*max.saturating_div(unsafe { FFloat::new(-0.0) }), f32::MIN);
+        /// ```
+        impl<T: FastFloat + Constructors, P: Policy> FFloat<T, P> {
+            $(
+                #[doc = concat!("Saturating ", $verb, ". Refer to the [`Saturating`] type.")]
+                pub fn $name(self, rhs: Self) -> Self {
+                    // SAFETY: IEEE operations are always safe, and the result is saturated.
+                    unsafe { Self::wrap(saturate(T::$op::<Ieee>(self.raw(), rhs.raw()))) }
+                }
+            )+
+        }
+
+        $(
+            impl<T: FastFloat + Constructors, P: Policy> $trait for Saturating<FFloat<T, P>> {
+                type Output = Self;
+                fn $fn(self, rhs: Self) -> Self {
+                    Self(self.0.$name(rhs.0))
+                }
+            }
+
+            impl<T: FastFloat + Constructors, P: Policy> $trait<FFloat<T, P>> for Saturating<FFloat<T, P>> {
+                type Output = Self;
+                fn $fn(self, rhs: FFloat<T, P>) -> Self {
+                    Self(self.0.$name(rhs))
+                }
+            }
+
+            impl<T: FastFloat + Constructors, P: Policy> $assign for Saturating<FFloat<T, P>> {
+                fn $assign_fn(&mut self, rhs: Self) {
+                    self.0 = self.0.$name(rhs.0);
+                }
+            }
+
+            impl<T: FastFloat + Constructors, P: Policy> $assign<FFloat<T, P>> for Saturating<FFloat<T, P>> {
+                fn $assign_fn(&mut self, rhs: FFloat<T, P>) {
+                    self.0 = self.0.$name(rhs);
+                }
+            }
+        )+
+    };
+}
+
+/// Saturating wrapper for [`FFloat`], comparable to [`core::num::Saturating`].
+///
+/// Operations that would overflow to ±[`INF`] clamp to ±MAX instead, so (finite) inputs can never break the [`FFloat`]'s invariant.
+/// ```
+/// # use umath::{FFloat, Saturating};
+/// let mut x = Saturating(unsafe { FFloat::new(1e300f64) });
+/// x *= unsafe { FFloat::new(1e300) };
+/// assert_eq!(*x.0, f64::MAX);
+/// ```
+#[repr(transparent)]
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Saturating<T>(pub T);
+
+impl<T> Deref for Saturating<T> {
+    type Target = T;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl<T> DerefMut for Saturating<T> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.0
+    }
+}
+
+saturating! {
+    saturating_add => add, Add, add, AddAssign, add_assign, "addition";
+    saturating_sub => sub, Sub, sub, SubAssign, sub_assign, "subtraction";
+    saturating_mul => mul, Mul, mul, MulAssign, mul_assign, "multiplication";
+    saturating_div => div, Div, div, DivAssign, div_assign, "division";
+    saturating_rem => rem, Rem, rem, RemAssign, rem_assign, "remainder";
+}
+
+#[test]
+#[allow(clippy::float_cmp)]
+fn clamps() {
+    let zero = unsafe { FFloat::new(0.0f32) };
+    assert_eq!(*zero.saturating_div(zero), 0.0);
+    assert_eq!(*zero.saturating_rem(zero), 0.0);
+    let min = Saturating(unsafe { FFloat::new(f32::MIN) });
+    assert_eq!(*(min + min).0, f32::MIN);
+}
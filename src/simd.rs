@@ -0,0 +1,155 @@
+//! [`FastFloat`] for [`Simd`] vectors, so that `FFloat<Simd<f32, N>>` applies the fast math flags to every lane.
+//!
+//! There are no fast math intrinsics for vectors, so each operation is written lane by lane, with the scalar intrinsics.
+//! LLVM merges these back into a single vector instruction (carrying the flags).
+use crate::policy::Policy;
+use crate::r#trait::{FastFloat, Wrapper};
+use crate::FFloat;
+use core::marker::PhantomData;
+use core::simd::num::SimdFloat;
+use core::simd::Simd;
+
+macro_rules! simd {
+    ($t:ident) => {
+        impl<const N: usize> FastFloat for Simd<$t, N> {
+            simd!(@op add, sub, div, mul, rem);
+
+            #[inline(always)]
+            fn bad<P: Policy>(self) -> bool {
+                (P::NNAN && self.is_nan().any()) || (P::NINF && self.is_infinite().any())
+            }
+
+            #[inline(always)]
+            fn hash<H: core::hash::Hasher>(self, state: &mut H) {
+                for x in self.to_array() {
+                    <$t as FastFloat>::hash(x, state);
+                }
+            }
+
+            fn display(self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+                f.write_str("[")?;
+                for (i, x) in self.to_array().into_iter().enumerate() {
+                    if i != 0 {
+                        f.write_str(", ")?;
+                    }
+                    core::fmt::Display::fmt(&x, f)?;
+                }
+                f.write_str("]")
+            }
+        }
+
+        impl<const N: usize> crate::slice::arch::Arch for Simd<$t, N> {}
+    };
+    (@op $($name:ident),+) => {
+        $(
+            #[inline(always)]
+            unsafe fn $name<P: Policy>(a: Self, b: Self) -> Self {
+                let (a, b) = (a.to_array(), b.to_array());
+                Simd::from_array(core::array::from_fn(|i| <_ as FastFloat>::$name::<P>(a[i], b[i])))
+            }
+        )+
+    };
+}
+
+simd!(f32);
+simd!(f64);
+
+macro_rules! packed {
+    ($t:ident) => {
+        /// Packed operations, for the `FF32x4` | `FF32x8` | `FF64x4` types (and any other width).
+        /// ```
+        /// # use umath::{FFloat, FF32x4};
+        /// let f = |x| unsafe { FFloat::new(x) };
+        /// let x = FF32x4::from([f(1.0), f(2.0), f(3.0), f(4.0)]);
+        /// let y = x * FF32x4::splat(f(2.0));
+        /// assert_eq!(*y.reduce_sum(), 20.0);
+        /// assert_eq!(*y.reduce_max(), 8.0);
+        /// let mut out = [f(0.0); 4];
+        /// y.store(&mut out);
+        /// assert_eq!(out, y.to_array());
+        /// ```
+        impl<const N: usize, P: Policy> FFloat<Simd<$t, N>, P> {
+            /// Creates a vector with every lane set to `x`.
+            pub fn splat(x: FFloat<$t, P>) -> Self {
+                Self(Simd::splat(x.0), PhantomData)
+            }
+
+            /// Creates a vector from its lanes.
+            pub fn from_array(x: [FFloat<$t, P>; N]) -> Self {
+                Self(Simd::from_array(x.map(|x| x.0)), PhantomData)
+            }
+
+            /// Returns the lanes.
+            pub fn to_array(self) -> [FFloat<$t, P>; N] {
+                self.0.to_array().map(|x| FFloat(x, PhantomData))
+            }
+
+            /// Loads a vector from the first `N` elements of `slice`.
+            ///
+            /// # Panics
+            ///
+            /// If `slice` is shorter than `N`.
+            pub fn load(slice: &[FFloat<$t, P>]) -> Self {
+                Self::from_array(slice[..N].try_into().unwrap())
+            }
+
+            /// Stores the vector into the first `N` elements of `slice`.
+            ///
+            /// # Panics
+            ///
+            /// If `slice` is shorter than `N`.
+            pub fn store(self, slice: &mut [FFloat<$t, P>]) {
+                slice[..N].copy_from_slice(&self.to_array());
+            }
+
+            /// Adds the lanes together. The order of the additions is unspecified.
+            pub fn reduce_sum(self) -> FFloat<$t, P> {
+                let sum = self
+                    .0
+                    .to_array()
+                    .into_iter()
+                    .reduce(|a, b| unsafe { <$t>::add::<P>(a, b) });
+                // SAFETY: checked by `wrap`.
+                unsafe { FFloat::wrap(sum.unwrap_or(0.0)) }
+            }
+
+            /// The smallest lane.
+            pub fn reduce_min(self) -> FFloat<$t, P> {
+                // this is one of the lanes, so it upholds the invariant.
+                FFloat(self.0.reduce_min(), PhantomData)
+            }
+
+            /// The largest lane.
+            pub fn reduce_max(self) -> FFloat<$t, P> {
+                // this is one of the lanes, so it upholds the invariant.
+                FFloat(self.0.reduce_max(), PhantomData)
+            }
+        }
+
+        impl<const N: usize, P: Policy> From<[FFloat<$t, P>; N]> for FFloat<Simd<$t, N>, P> {
+            fn from(x: [FFloat<$t, P>; N]) -> Self {
+                Self::from_array(x)
+            }
+        }
+
+        impl<const N: usize, P: Policy> From<FFloat<Simd<$t, N>, P>> for [FFloat<$t, P>; N] {
+            fn from(x: FFloat<Simd<$t, N>, P>) -> Self {
+                x.to_array()
+            }
+        }
+    };
+}
+
+packed!(f32);
+packed!(f64);
+
+#[test]
+#[cfg(any(
+    feature = "strict-checks",
+    all(debug_assertions, not(feature = "trusted"))
+))]
+#[should_panic = "[2, inf] is NAN | INF."]
+fn lanes() {
+    let x = unsafe { crate::FFloat::new(Simd::from_array([1.0f32, f32::MAX])) };
+    let _ = x * Simd::splat(2.0);
+}
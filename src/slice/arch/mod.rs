@@ -0,0 +1,142 @@
+//! explicit simd kernels, for the targets where the generic code leaves throughput behind.
+//!
+//! These are reached through [`Arch`], a supertrait of [`FastFloat`](crate::FastFloat) which can not be named outside of the crate, and which only [`f32`] and [`f64`] implement:
+//! they return [`None`] (or `false`) when this target has no explicit kernel, and the generic kernel runs instead.
+//! The kernels fuse and reorder the operations, and have their own accumulator count, so they are only used when the [policy allows it](allowed).
+#[cfg(target_arch = "aarch64")]
+mod neon;
+#[cfg(all(target_arch = "wasm32", target_feature = "simd128"))]
+mod wasm;
+
+use super::Tuning;
+use crate::policy::Policy;
+
+/// The explicit kernels of a float. The methods return [`None`] (or `false`) if there is none, which is the default.
+pub trait Arch: Sized {
+    /// The explicit dot product.
+    #[inline(always)]
+    fn arch_dot(_: &[Self], _: &[Self]) -> Option<Self> {
+        None
+    }
+
+    /// The explicit sum.
+    #[inline(always)]
+    fn arch_sum(_: &[Self]) -> Option<Self> {
+        None
+    }
+
+    /// The explicit `y += a * x`. Returns whether it ran.
+    #[inline(always)]
+    fn arch_axpy(_: Self, _: &[Self], _: &mut [Self]) -> bool {
+        false
+    }
+}
+
+/// Whether a reduction may use the explicit kernels: only if `P` allows contraction and reassociation (which the kernels apply),
+/// and `tuning` has the default accumulator count (which the kernels replace with their own).
+#[inline(always)]
+pub(crate) fn allowed<P: Policy>(tuning: Tuning) -> bool {
+    P::CONTRACT && P::REASSOC && tuning.accumulators() == Tuning::DEFAULT.accumulators()
+}
+
+macro_rules! arch {
+    ($t:ident, $dot:ident, $sum:ident, $axpy:ident) => {
+        /// The explicit dot product, if this target has one.
+        #[inline(always)]
+        fn $dot(a: &[$t], b: &[$t]) -> Option<$t> {
+            #[cfg(target_arch = "aarch64")]
+            let dot = Some(neon::$dot(a, b));
+            #[cfg(all(target_arch = "wasm32", target_feature = "simd128"))]
+            let dot = Some(wasm::$dot(a, b));
+            #[cfg(not(any(
+                target_arch = "aarch64",
+                all(target_arch = "wasm32", target_feature = "simd128")
+            )))]
+            let dot = {
+                let _ = (a, b);
+                None
+            };
+            dot
+        }
+
+        /// The explicit sum, if this target has one.
+        #[inline(always)]
+        fn $sum(x: &[$t]) -> Option<$t> {
+            #[cfg(target_arch = "aarch64")]
+            let sum = Some(neon::$sum(x));
+            #[cfg(all(target_arch = "wasm32", target_feature = "simd128"))]
+            let sum = Some(wasm::$sum(x));
+            #[cfg(not(any(
+                target_arch = "aarch64",
+                all(target_arch = "wasm32", target_feature = "simd128")
+            )))]
+            let sum = {
+                let _ = x;
+                None
+            };
+            sum
+        }
+
+        /// The explicit `y += a * x`, if this target has one. Returns whether it ran.
+        #[inline(always)]
+        fn $axpy(a: $t, x: &[$t], y: &mut [$t]) -> bool {
+            #[cfg(target_arch = "aarch64")]
+            neon::$axpy(a, x, y);
+            #[cfg(not(target_arch = "aarch64"))]
+            let _ = (a, x, y);
+            cfg!(target_arch = "aarch64")
+        }
+
+        impl Arch for $t {
+            #[inline(always)]
+            fn arch_dot(a: &[Self], b: &[Self]) -> Option<Self> {
+                $dot(a, b)
+            }
+
+            #[inline(always)]
+            fn arch_sum(x: &[Self]) -> Option<Self> {
+                $sum(x)
+            }
+
+            #[inline(always)]
+            fn arch_axpy(a: Self, x: &[Self], y: &mut [Self]) -> bool {
+                $axpy(a, x, y)
+            }
+        }
+    };
+}
+
+arch!(f32, dot_f32, sum_f32, axpy_f32);
+arch!(f64, dot_f64, sum_f64, axpy_f64);
+
+#[test]
+#[allow(clippy::cast_possible_truncation)]
+fn kernels() {
+    // on the targets with explicit kernels, they agree with the plain loops (up to their reordering); elsewhere, they do not run.
+    let x: Vec<f64> = (0..37).map(|i| f64::from(i) * 0.25 - 3.0).collect();
+    let y: Vec<f64> = (0..37).map(|i| 1.5 - f64::from(i % 7)).collect();
+    let dot: f64 = x.iter().zip(&y).map(|(x, y)| x * y).sum();
+    let sum: f64 = x.iter().sum();
+    let close = |a: f64, b: f64| (a - b).abs() <= 1e-12 * b.abs().max(1.0);
+    let kernel = cfg!(any(
+        target_arch = "aarch64",
+        all(target_arch = "wasm32", target_feature = "simd128")
+    ));
+    assert_eq!(f64::arch_dot(&x, &y).is_some(), kernel);
+    assert!(f64::arch_dot(&x, &y).is_none_or(|d| close(d, dot)));
+    assert!(f64::arch_sum(&x).is_none_or(|s| close(s, sum)));
+    let [x, y] = [&x, &y].map(|v| v.iter().map(|&v| v as f32).collect::<Vec<_>>());
+    assert!(f32::arch_dot(&x, &y).is_none_or(|d| (f64::from(d) - dot).abs() < 1e-3));
+    let mut out = y.clone();
+    if f32::arch_axpy(2.0, &x, &mut out) {
+        for ((out, x), y) in out.iter().zip(&x).zip(&y) {
+            assert!((out - (2.0 * x + y)).abs() < 1e-5);
+        }
+    }
+    // a policy without contraction or reassociation, or a tuned accumulator count, never uses them.
+    assert!(allowed::<crate::policy::Fast>(Tuning::DEFAULT));
+    assert!(!allowed::<crate::policy::Ieee>(Tuning::DEFAULT));
+    assert!(!allowed::<crate::policy::Fast>(
+        Tuning::DEFAULT.with_accumulators(2)
+    ));
+}
@@ -0,0 +1,120 @@
+//! explicit NEON kernels, for aarch64.
+//!
+//! The reductions use fused multiply adds (`FMLA`) into independent accumulators, and a paired reduction (`FADDP`) at the end;
+//! LLVM's vectorization of the generic code does not reliably get there.
+// the arithmetic intrinsics are safe to call (neon is a baseline feature), but the loads are not.
+#![allow(unused_unsafe)]
+use core::arch::aarch64::{
+    float32x4_t, float64x2_t, vaddq_f32, vaddq_f64, vaddvq_f32, vaddvq_f64, vdupq_n_f32,
+    vdupq_n_f64, vfmaq_f32, vfmaq_f64, vld1q_f32, vld1q_f64, vst1q_f32, vst1q_f64,
+};
+
+/// The number of independent vector accumulators.
+const VECTORS: usize = 4;
+
+macro_rules! kernels {
+    ($t:ident, $v:ident, $lanes:literal, $dot:ident, $sum:ident, $axpy:ident, $dup:ident, $add:ident, $fma:ident, $addv:ident, $load:ident, $store:ident) => {
+        /// Loads the `i`th vector of `chunk`.
+        #[inline(always)]
+        fn load(chunk: &[$t], i: usize) -> $v {
+            assert!(chunk.len() >= (i + 1) * $lanes);
+            // SAFETY: in bounds.
+            unsafe { $load(chunk.as_ptr().add(i * $lanes)) }
+        }
+
+        /// Adds the accumulators, then their lanes (pairwise).
+        #[inline(always)]
+        fn horizontal(acc: [$v; VECTORS]) -> $t {
+            unsafe { $addv($add($add(acc[0], acc[1]), $add(acc[2], acc[3]))) }
+        }
+
+        pub(crate) fn $dot(a: &[$t], b: &[$t]) -> $t {
+            let (ac, bc) = (
+                a.chunks_exact(VECTORS * $lanes),
+                b.chunks_exact(VECTORS * $lanes),
+            );
+            let (ar, br) = (ac.remainder(), bc.remainder());
+            let mut acc = [unsafe { $dup(0.0) }; VECTORS];
+            for (a, b) in ac.zip(bc) {
+                for (i, acc) in acc.iter_mut().enumerate() {
+                    *acc = unsafe { $fma(*acc, load(a, i), load(b, i)) };
+                }
+            }
+            let mut sum = horizontal(acc);
+            for (a, b) in ar.iter().zip(br) {
+                sum += a * b;
+            }
+            sum
+        }
+
+        pub(crate) fn $sum(x: &[$t]) -> $t {
+            let chunks = x.chunks_exact(VECTORS * $lanes);
+            let rest = chunks.remainder();
+            let mut acc = [unsafe { $dup(0.0) }; VECTORS];
+            for x in chunks {
+                for (i, acc) in acc.iter_mut().enumerate() {
+                    *acc = unsafe { $add(*acc, load(x, i)) };
+                }
+            }
+            horizontal(acc) + rest.iter().sum::<$t>()
+        }
+
+        pub(crate) fn $axpy(a: $t, x: &[$t], y: &mut [$t]) {
+            assert_eq!(x.len(), y.len());
+            let splat = unsafe { $dup(a) };
+            let mut yc = y.chunks_exact_mut($lanes);
+            let mut xc = x.chunks_exact($lanes);
+            for (y, x) in (&mut yc).zip(&mut xc) {
+                // SAFETY: both chunks hold one vector.
+                unsafe {
+                    $store(
+                        y.as_mut_ptr(),
+                        $fma($load(y.as_ptr()), $load(x.as_ptr()), splat),
+                    )
+                };
+            }
+            for (y, x) in yc.into_remainder().iter_mut().zip(xc.remainder()) {
+                *y += a * x;
+            }
+        }
+    };
+}
+
+mod x32 {
+    use super::*;
+    kernels!(
+        f32,
+        float32x4_t,
+        4,
+        dot,
+        sum,
+        axpy,
+        vdupq_n_f32,
+        vaddq_f32,
+        vfmaq_f32,
+        vaddvq_f32,
+        vld1q_f32,
+        vst1q_f32
+    );
+}
+
+mod x64 {
+    use super::*;
+    kernels!(
+        f64,
+        float64x2_t,
+        2,
+        dot,
+        sum,
+        axpy,
+        vdupq_n_f64,
+        vaddq_f64,
+        vfmaq_f64,
+        vaddvq_f64,
+        vld1q_f64,
+        vst1q_f64
+    );
+}
+
+pub(crate) use x32::{axpy as axpy_f32, dot as dot_f32, sum as sum_f32};
+pub(crate) use x64::{axpy as axpy_f64, dot as dot_f64, sum as sum_f64};
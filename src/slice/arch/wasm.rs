@@ -0,0 +1,71 @@
+//! explicit simd128 kernels, for wasm32.
+//!
+//! wasm has no fast math flags, so a reduction is never reassociated into lanes by the compiler: these write the lanes out with [`core::arch::wasm32`].
+//! (The element wise kernels need no help, they vectorize with `-C target-feature=+simd128` as they are.)
+use core::arch::wasm32::{
+    f32x4_add, f32x4_extract_lane, f32x4_mul, f32x4_splat, f64x2_add, f64x2_extract_lane,
+    f64x2_mul, f64x2_splat, v128, v128_load,
+};
+
+/// The number of independent vector accumulators.
+const VECTORS: usize = 4;
+
+macro_rules! kernels {
+    ($t:ident, $lanes:literal, $dot:ident, $sum:ident, $splat:ident, $add:ident, $mul:ident, $extract:ident [$($lane:literal),+]) => {
+        /// Loads the `i`th vector of `chunk`.
+        #[inline(always)]
+        fn load(chunk: &[$t], i: usize) -> v128 {
+            assert!(chunk.len() >= (i + 1) * $lanes);
+            // SAFETY: in bounds, and `v128_load` does not need alignment.
+            unsafe { v128_load(chunk.as_ptr().add(i * $lanes).cast()) }
+        }
+
+        /// Adds the accumulators, then their lanes.
+        #[inline(always)]
+        fn horizontal(acc: [v128; VECTORS]) -> $t {
+            let v = $add($add(acc[0], acc[1]), $add(acc[2], acc[3]));
+            0.0 $(+ $extract::<$lane>(v))+
+        }
+
+        pub(crate) fn $dot(a: &[$t], b: &[$t]) -> $t {
+            let (ac, bc) = (a.chunks_exact(VECTORS * $lanes), b.chunks_exact(VECTORS * $lanes));
+            let (ar, br) = (ac.remainder(), bc.remainder());
+            let mut acc = [$splat(0.0); VECTORS];
+            for (a, b) in ac.zip(bc) {
+                for (i, acc) in acc.iter_mut().enumerate() {
+                    *acc = $add(*acc, $mul(load(a, i), load(b, i)));
+                }
+            }
+            let mut sum = horizontal(acc);
+            for (a, b) in ar.iter().zip(br) {
+                sum += a * b;
+            }
+            sum
+        }
+
+        pub(crate) fn $sum(x: &[$t]) -> $t {
+            let chunks = x.chunks_exact(VECTORS * $lanes);
+            let rest = chunks.remainder();
+            let mut acc = [$splat(0.0); VECTORS];
+            for x in chunks {
+                for (i, acc) in acc.iter_mut().enumerate() {
+                    *acc = $add(*acc, load(x, i));
+                }
+            }
+            horizontal(acc) + rest.iter().sum::<$t>()
+        }
+    };
+}
+
+mod x32 {
+    use super::*;
+    kernels!(f32, 4, dot, sum, f32x4_splat, f32x4_add, f32x4_mul, f32x4_extract_lane [0, 1, 2, 3]);
+}
+
+mod x64 {
+    use super::*;
+    kernels!(f64, 2, dot, sum, f64x2_splat, f64x2_add, f64x2_mul, f64x2_extract_lane [0, 1]);
+}
+
+pub(crate) use x32::{dot as dot_f32, sum as sum_f32};
+pub(crate) use x64::{dot as dot_f64, sum as sum_f64};
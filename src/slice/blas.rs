@@ -0,0 +1,452 @@
+use super::reduce::{sum_map, tree};
+use super::{dispatch, raw, raw_mut, same, zip, Tuning, UNROLL};
+use crate::generic_float::{lit, Constructors, FloatExt, FloatMethods};
+use crate::policy::Policy;
+use crate::r#trait::{FastFloat, Wrapper};
+use crate::FFloat;
+use core::marker::PhantomData;
+
+/// `y += a * x`: the BLAS level 1 axpy.
+///
+/// Each element is computed as one [`Unchecked`](crate::Unchecked) expression, so that (with a policy that allows contraction) it becomes a fused multiply add.
+/// ```
+/// # use umath::{FFloat, slice};
+/// let f = |x| unsafe { FFloat::new(x) };
+/// let mut y = [f(1.0f32), f(2.0)];
+/// slice::axpy(f(3.0), &[f(1.0), f(-1.0)], &mut y);
+/// assert_eq!(y, [4.0, -1.0]);
+/// ```
+#[track_caller]
+pub fn axpy<T: FastFloat, P: Policy>(a: FFloat<T, P>, x: &[FFloat<T, P>], y: &mut [FFloat<T, P>]) {
+    same(x, y);
+    if P::CONTRACT && T::arch_axpy(a.0, raw(x), raw_mut(y)) {
+        for y in y {
+            y.check();
+        }
+        return;
+    }
+    zip(y, x, |y, x| *y = (a.lazy() * *x + *y).finish());
+}
+
+/// `x *= a`: the BLAS level 1 scal.
+pub fn scal<T: FastFloat, P: Policy>(a: FFloat<T, P>, x: &mut [FFloat<T, P>]) {
+    for x in x {
+        *x *= a;
+    }
+}
+
+/// `Σ |x[i]|`: the BLAS level 1 asum.
+#[must_use]
+pub fn asum<T: FastFloat + Constructors + FloatMethods, P: Policy>(
+    x: &[FFloat<T, P>],
+) -> FFloat<T, P> {
+    unsafe { FFloat::wrap(sum_map::<T, P>(x, T::abs)) }
+}
+
+/// The euclidean norm, `sqrt(Σ x[i]²)`: the BLAS level 1 nrm2.
+///
+/// The elements are scaled by the largest magnitude first, so that the squares can not overflow (or underflow).
+/// ```
+/// # use umath::{FFloat, slice};
+/// let big = unsafe { FFloat::new(3e30f32) };
+/// let norm = slice::nrm2(&[big, big * 4.0 / 3.0]);
+/// assert!((*norm / 5e30 - 1.0).abs() < 1e-6);
+/// ```
+#[must_use]
+pub fn nrm2<T: FastFloat + FloatExt + FloatMethods, P: Policy>(x: &[FFloat<T, P>]) -> FFloat<T, P> {
+    // SAFETY: 0 is a valid float
+    let zero = unsafe { T::zero() };
+    let Some(i) = iamax(x) else {
+        return unsafe { FFloat::wrap(zero) };
+    };
+    let scale = x[i].0.abs();
+    if scale == zero {
+        // not x[i], which may be -0.
+        return unsafe { FFloat::wrap(zero) };
+    }
+    // the reciprocal of a subnormal overflows (and `arcp` may divide by the reciprocal), so the scale is at least the smallest normal number.
+    let scale = scale.max(min_positive());
+    let sum = sum_map::<T, P>(x, |x| {
+        let x = unsafe { T::div::<P>(x, scale) };
+        unsafe { T::mul::<P>(x, x) }
+    });
+    unsafe { FFloat::wrap(T::mul::<P>(sum.sqrt(), scale)) }
+}
+
+/// Scales `x` to unit length, returning its (previous) euclidean norm. A zero vector is left as is.
+///
+/// Unlike [`nrm2`], this is one tree reduced [`dot`](super::dot), and one multiplication by the reciprocal square root of it, so the squares must not overflow.
+/// If they underflow (the norm is less than the square root of the smallest normal number), `x` is first scaled up by a power of two, and the dot product taken again.
+/// The reciprocal square root is `1 / √(x · x)` with `P`, which a policy with the fast math flags (such as [`Fast`](crate::policy::Fast)) allows to be compiled to
+/// a reciprocal square root estimate and a newton step, like [`Vec3::normalize`](crate::vector::Vec3::normalize).
+/// The norm is `x · x` times the reciprocal square root, so that there is no separate square root.
+/// ```
+/// # use umath::{FFloat, slice};
+/// let mut x = [3.0f32, 4.0].map(|x| unsafe { FFloat::new(x) });
+/// let norm = slice::normalize(&mut x);
+/// assert!((*norm - 5.0).abs() < 1e-5);
+/// assert!((*x[0] - 0.6).abs() < 1e-5);
+/// ```
+pub fn normalize<T: FastFloat + FloatExt + FloatMethods, P: Policy>(
+    x: &mut [FFloat<T, P>],
+) -> FFloat<T, P> {
+    let tiny = min_positive::<T>();
+    let mut square = super::dot(x, x);
+    let underflows = square.0 < tiny;
+    if underflows {
+        // every element is below the square root of `tiny`, so this is exact, and can not overflow.
+        // SAFETY: the reciprocal of the smallest normal number is finite.
+        scal(unsafe { FFloat::wrap(T::div::<P>(T::one(), tiny)) }, x);
+        square = super::dot(x, x);
+    }
+    // SAFETY: 0 is a valid float
+    if square.0 == unsafe { T::zero() } {
+        return square;
+    }
+    // SAFETY: 1 is a valid float
+    let inverse = unsafe { T::div::<P>(T::one(), square.0.sqrt()) };
+    let mut norm = unsafe { T::mul::<P>(square.0, inverse) };
+    if underflows {
+        norm = unsafe { T::mul::<P>(norm, tiny) };
+    }
+    scal(unsafe { FFloat::wrap(inverse) }, x);
+    // SAFETY: checked by `wrap`.
+    unsafe { FFloat::wrap(norm) }
+}
+
+/// The smallest normal number of `T`, which (unlike a subnormal) has a finite reciprocal.
+#[inline(always)]
+fn min_positive<T: FloatExt>() -> T {
+    lit(if size_of::<T>() == 8 {
+        f64::MIN_POSITIVE
+    } else {
+        f64::from(f32::MIN_POSITIVE)
+    })
+}
+
+/// The index of the (first) element with the largest magnitude, or [`None`] if `x` is empty: the BLAS level 1 iamax.
+#[must_use]
+pub fn iamax<T: FastFloat + FloatMethods, P: Policy>(x: &[FFloat<T, P>]) -> Option<usize> {
+    let mut best = (0, x.first()?.0.abs());
+    for (i, x) in x.iter().enumerate().skip(1) {
+        let x = x.0.abs();
+        if x > best.1 {
+            best = (i, x);
+        }
+    }
+    Some(best.0)
+}
+
+/// Exchanges the contents of `x` and `y`: the BLAS level 1 swap.
+#[track_caller]
+pub fn swap<T>(x: &mut [T], y: &mut [T]) {
+    same(x, y);
+    x.swap_with_slice(y);
+}
+
+/// Copies `x` into `y`: the BLAS level 1 copy.
+#[track_caller]
+pub fn copy<T: Copy>(x: &[T], y: &mut [T]) {
+    same(x, y);
+    y.copy_from_slice(x);
+}
+
+/// The number of rows [`gemv`] processes at once, sharing each load of `x`.
+const ROWS: usize = 4;
+
+/// `y = alpha * A * x + beta * y`: the BLAS level 2 gemv, for a row major `rows × cols` matrix `a`.
+///
+/// This is meant for small to medium matrices (up to about 512²), where calling out to a full BLAS costs more than the work.
+/// Blocks of 4 rows are processed together (each with [`UNROLL`](super) independent accumulators), so every element of `x` is loaded once per block.
+/// As with the BLAS, if `beta` is 0, `y` is overwritten instead of scaled.
+/// ```
+/// # use umath::{FFloat, slice};
+/// let f = |x| unsafe { FFloat::new(x) };
+/// let a = [1.0f32, 2.0, 3.0, 4.0, 5.0, 6.0].map(f); // 2 × 3
+/// let mut y = [f(1.0), f(1.0)];
+/// slice::gemv(f(2.0), &a, &[f(1.0), f(0.0), f(-1.0)], f(1.0), &mut y, 2, 3);
+/// assert_eq!(y, [-3.0, -3.0]);
+/// ```
+///
+/// # Panics
+///
+/// If `a` is not `rows × cols`, `x` is not `cols` long, or `y` is not `rows` long.
+#[track_caller]
+pub fn gemv<T: FastFloat + Constructors, P: Policy>(
+    alpha: FFloat<T, P>,
+    a: &[FFloat<T, P>],
+    x: &[FFloat<T, P>],
+    beta: FFloat<T, P>,
+    y: &mut [FFloat<T, P>],
+    rows: usize,
+    cols: usize,
+) {
+    assert_eq!(a.len(), rows * cols, "matrix is not rows × cols");
+    assert_eq!(x.len(), cols, "x is not cols long");
+    assert_eq!(y.len(), rows, "y is not rows long");
+    if cols == 0 {
+        // no rows to iterate; the product is 0.
+        return scal(beta, y);
+    }
+    // SAFETY: 0 is a valid float
+    let overwrite = beta.0 == unsafe { T::zero() };
+    let store = |y: &mut FFloat<T, P>, dot: T| {
+        *y = if overwrite {
+            alpha * FFloat(dot, PhantomData)
+        } else {
+            (alpha.lazy() * FFloat(dot, PhantomData) + beta.lazy() * *y).finish()
+        }
+    };
+    dispatch(
+        #[inline(always)]
+        || {
+            let mut blocks = a.chunks_exact(ROWS * cols);
+            let mut ys = y.chunks_exact_mut(ROWS);
+            for (a, y) in (&mut blocks).zip(&mut ys) {
+                let dots =
+                    dot_rows::<T, P, ROWS>(core::array::from_fn(|r| &a[r * cols..][..cols]), x);
+                for (y, dot) in y.iter_mut().zip(dots) {
+                    store(y, dot);
+                }
+            }
+            for (a, y) in blocks
+                .remainder()
+                .chunks_exact(cols)
+                .zip(ys.into_remainder())
+            {
+                let [dot] = dot_rows::<T, P, 1>([a], x);
+                store(y, dot);
+            }
+        },
+    );
+}
+
+/// The (unchecked) dot products of `R` rows with `x`, each with [`UNROLL`](super) accumulators.
+#[inline(always)]
+fn dot_rows<T: FastFloat + Constructors, P: Policy, const R: usize>(
+    rows: [&[FFloat<T, P>]; R],
+    x: &[FFloat<T, P>],
+) -> [T; R] {
+    // SAFETY: 0 is a valid float
+    let mut acc = [[unsafe { T::zero() }; UNROLL]; R];
+    let n = x.len() - x.len() % UNROLL;
+    for j in (0..n).step_by(UNROLL) {
+        let x = &x[j..j + UNROLL];
+        for (acc, row) in acc.iter_mut().zip(rows) {
+            let row = &row[j..j + UNROLL];
+            for i in 0..UNROLL {
+                acc[i] = unsafe { T::add::<P>(acc[i], T::mul::<P>(row[i].0, x[i].0)) };
+            }
+        }
+    }
+    core::array::from_fn(|r| {
+        let mut sum = tree::<T, P, UNROLL>(acc[r]);
+        for (a, x) in rows[r][n..].iter().zip(&x[n..]) {
+            sum = unsafe { T::add::<P>(sum, T::mul::<P>(a.0, x.0)) };
+        }
+        sum
+    })
+}
+
+/// The register tile of [`gemm`]: `MR` rows of `C` by `NR` columns.
+const MR: usize = 4;
+const NR: usize = 8;
+/// `C = alpha * A * B + beta * C`: the BLAS level 3 gemm, for row major matrices `a` (`m × k`), `b` (`k × n`), and `c` (`m × n`).
+///
+/// This is meant for small to medium matrices (tiny neural networks, stacks of transforms), where calling out to a full BLAS costs more than the work.
+/// `B` is blocked into `256 × 128` panels (by default, refer to [`Tuning`]) that stay in cache, and `C` is computed in `4 × 8` tiles held in registers:
+/// each row of a tile is 8 lanes wide, so that it compiles to explicit SIMD (with a policy that allows contraction, fused multiply adds).
+/// As with the BLAS, if `beta` is 0, `c` is overwritten instead of scaled.
+/// ```
+/// # use umath::{FFloat, slice};
+/// let f = |x| unsafe { FFloat::new(x) };
+/// let a = [1.0f32, 2.0, 3.0, 4.0].map(f);
+/// let identity = [1.0, 0.0, 0.0, 1.0].map(f);
+/// let mut c = [f(0.0); 4];
+/// slice::gemm(f(1.0), &a, &identity, f(0.0), &mut c, 2, 2, 2);
+/// assert_eq!(c, a);
+/// slice::gemm(f(1.0), &a, &a, f(-1.0), &mut c, 2, 2, 2);
+/// assert_eq!(c, [6.0, 8.0, 12.0, 18.0]);
+/// ```
+///
+/// # Panics
+///
+/// If `a` is not `m × k`, `b` is not `k × n`, or `c` is not `m × n`.
+#[track_caller]
+#[allow(clippy::too_many_arguments, clippy::many_single_char_names)]
+pub fn gemm<T: FastFloat + Constructors, P: Policy>(
+    alpha: FFloat<T, P>,
+    a: &[FFloat<T, P>],
+    b: &[FFloat<T, P>],
+    beta: FFloat<T, P>,
+    c: &mut [FFloat<T, P>],
+    m: usize,
+    k: usize,
+    n: usize,
+) {
+    Tuning::global().gemm(alpha, a, b, beta, c, m, k, n);
+}
+
+/// [`gemm`], with `tuning`.
+#[track_caller]
+#[allow(clippy::too_many_arguments, clippy::many_single_char_names)]
+pub(super) fn gemm_tuned<T: FastFloat + Constructors, P: Policy>(
+    tuning: Tuning,
+    alpha: FFloat<T, P>,
+    a: &[FFloat<T, P>],
+    b: &[FFloat<T, P>],
+    beta: FFloat<T, P>,
+    c: &mut [FFloat<T, P>],
+    m: usize,
+    k: usize,
+    n: usize,
+) {
+    assert_eq!(a.len(), m * k, "a is not m × k");
+    assert_eq!(b.len(), k * n, "b is not k × n");
+    assert_eq!(c.len(), m * n, "c is not m × n");
+    // SAFETY: 0 is a valid float
+    if beta.0 == unsafe { T::zero() } {
+        c.fill(beta);
+    } else if beta.0 != unsafe { T::one() } {
+        scal(beta, c);
+    }
+    dispatch(
+        #[inline(always)]
+        || {
+            let (kc_max, nc_max) = tuning.gemm_panel();
+            for p in (0..k).step_by(kc_max) {
+                let kc = kc_max.min(k - p);
+                for jc in (0..n).step_by(nc_max) {
+                    let nc = nc_max.min(n - jc);
+                    for i in (0..m).step_by(MR) {
+                        let mr = MR.min(m - i);
+                        for j in (jc..jc + nc).step_by(NR) {
+                            let nr = NR.min(jc + nc - j);
+                            let tile = Tile { i, j, p, kc, k, n };
+                            if mr == MR && nr == NR {
+                                // the constant bounds let this instance unroll, and vectorize.
+                                tile.run::<T, P>(alpha, a, b, c, MR, NR);
+                            } else {
+                                tile.run::<T, P>(alpha, a, b, c, mr, nr);
+                            }
+                        }
+                    }
+                }
+            }
+        },
+    );
+}
+
+/// One tile of [`gemm`]: `C[i.., j..] += alpha * A[i.., p..] * B[p.., j..]`, over `kc` elements of the shared dimension.
+#[derive(Copy, Clone)]
+struct Tile {
+    i: usize,
+    j: usize,
+    p: usize,
+    kc: usize,
+    /// the row length of `A`.
+    k: usize,
+    /// the row length of `B` and `C`.
+    n: usize,
+}
+
+impl Tile {
+    /// Computes `mr × nr` elements of `C` (at most `MR × NR`).
+    #[inline(always)]
+    fn run<T: FastFloat + Constructors, P: Policy>(
+        self,
+        alpha: FFloat<T, P>,
+        a: &[FFloat<T, P>],
+        b: &[FFloat<T, P>],
+        c: &mut [FFloat<T, P>],
+        mr: usize,
+        nr: usize,
+    ) {
+        // SAFETY: 0 is a valid float
+        let mut acc = [[unsafe { T::zero() }; NR]; MR];
+        let Self { k, n, .. } = self;
+        for p in self.p..self.p + self.kc {
+            let b = &b[p * n + self.j..][..nr];
+            for (r, acc) in acc.iter_mut().enumerate().take(mr) {
+                let a = a[(self.i + r) * k + p].0;
+                for (acc, b) in acc.iter_mut().zip(b) {
+                    *acc = unsafe { T::add::<P>(*acc, T::mul::<P>(a, b.0)) };
+                }
+            }
+        }
+        for (r, acc) in acc.iter().enumerate().take(mr) {
+            let c = &mut c[(self.i + r) * n + self.j..][..nr];
+            for (c, &acc) in c.iter_mut().zip(acc) {
+                *c = (alpha.lazy() * acc + *c).finish();
+            }
+        }
+    }
+}
+
+#[test]
+#[allow(clippy::float_cmp)]
+fn axpy_long() {
+    let x: Vec<_> = (0..20)
+        .map(|x| unsafe { FFloat::new(f64::from(x)) })
+        .collect();
+    let mut y = x.clone();
+    axpy(unsafe { FFloat::new(-1.0) }, &x, &mut y);
+    assert!(y.iter().all(|&y| y == 0.0));
+    let mut z = x.clone();
+    scal(unsafe { FFloat::new(-2.0) }, &mut z);
+    assert_eq!(iamax(&z), Some(19));
+    assert_eq!(*asum(&z), 380.0);
+    assert_eq!(*nrm2(&y), 0.0);
+    swap(&mut y, &mut z);
+    assert_eq!(*nrm2(&z[..4]), 0.0);
+    assert_eq!(*normalize(&mut z), 0.0);
+    assert!((*normalize(&mut y) - *nrm2(&x) * 2.0).abs() < 1e-9);
+    assert!((*nrm2(&y) - 1.0).abs() < 1e-9);
+    // a zero norm is +0, and subnormal elements are not scaled by their (infinite) reciprocal
+    let f = |x| unsafe { FFloat::<f64>::new(x) };
+    assert!(nrm2(&[f(-0.0), f(0.0)]).is_sign_positive());
+    assert!((*nrm2(&[f(3e-320), f(4e-320)]) / 5e-320 - 1.0).abs() < 1e-3);
+    // squares that underflow are scaled up first
+    let mut tiny = [f(3e-200), f(-4e-200)];
+    assert!((*normalize(&mut tiny) / 5e-200 - 1.0).abs() < 1e-12);
+    assert!((*tiny[0] - 0.6).abs() < 1e-12 && (*tiny[1] + 0.8).abs() < 1e-12);
+}
+
+#[test]
+#[allow(clippy::cast_precision_loss)]
+fn gemv_blocks() {
+    let f = |x| unsafe { FFloat::new(x) };
+    // 7 × 11, so both the row blocks and the column chunks have remainders.
+    let (rows, cols) = (7, 11);
+    let a: Vec<_> = (0..rows * cols).map(|i| f((i % 5) as f64)).collect();
+    let x: Vec<_> = (0..cols).map(|i| f(i as f64)).collect();
+    let mut y = vec![f(1.0); rows];
+    gemv(f(1.0), &a, &x, f(-1.0), &mut y, rows, cols);
+    for (r, y) in y.iter().enumerate() {
+        let expected = (0..cols)
+            .map(|c| ((r * cols + c) % 5 * c) as f64)
+            .sum::<f64>();
+        assert!((**y - (expected - 1.0)).abs() < 1e-9);
+    }
+    gemv(f(1.0), &a[..0], &[], f(0.0), &mut y, rows, 0);
+    assert!(y.iter().all(|&y| *y == 0.0));
+}
+
+#[test]
+#[allow(clippy::cast_precision_loss, clippy::many_single_char_names)]
+fn gemm_blocks() {
+    let f = |x| unsafe { FFloat::new(x) };
+    // every dimension has a remainder, and the shared one spans two blocks.
+    let (kc, nc) = Tuning::DEFAULT.gemm_panel();
+    let (m, k, n) = (6, kc + 3, nc + NR + 5);
+    let a: Vec<_> = (0..m * k).map(|i| f((i % 7) as f64 - 3.0)).collect();
+    let b: Vec<_> = (0..k * n).map(|i| f((i % 5) as f64)).collect();
+    let mut c = vec![f(2.0); m * n];
+    gemm(f(0.5), &a, &b, f(3.0), &mut c, m, k, n);
+    for i in 0..m {
+        for j in 0..n {
+            let dot = (0..k).map(|p| *a[i * k + p] * *b[p * n + j]).sum::<f64>();
+            assert!((*c[i * n + j] - (dot * 0.5 + 6.0)).abs() < 1e-9);
+        }
+    }
+}
@@ -0,0 +1,74 @@
+use crate::generic_float::FloatExt;
+use crate::policy::{NoNan, Policy};
+use crate::r#trait::FastFloat;
+use crate::FFloat;
+
+/// Checks if `a` and `b` are at most `eps` apart.
+fn close<T: FastFloat, P: Policy>(a: FFloat<T, P>, b: FFloat<T, P>, eps: FFloat<T, P>) -> bool {
+    a - b <= eps && b - a <= eps
+}
+
+/// Removes consecutive elements that are within `eps` of the last kept element, like [`Vec::dedup`].
+///
+/// The first element of each run is kept; as every element is compared to it (not to its neighbor), a slowly drifting sequence is not collapsed into one value.
+/// Sort first to remove every duplicate, or use [`unique_within`].
+/// ```
+/// # use umath::{FFloat, slice};
+/// let f = |x| unsafe { FFloat::new(x) };
+/// let mut x = vec![1.0f32, 1.25, 1.5, 1.75, 3.0].into_iter().map(f).collect();
+/// slice::dedup_by_tolerance(&mut x, f(0.5));
+/// assert_eq!(x, [1.0, 1.75, 3.0]);
+/// ```
+pub fn dedup_by_tolerance<T: FastFloat, P: Policy>(x: &mut Vec<FFloat<T, P>>, eps: FFloat<T, P>) {
+    x.dedup_by(|x, kept| close(*x, *kept, eps));
+}
+
+/// Sorts `x`, and replaces every cluster of values with its mean, where a cluster is a run with gaps of at most `eps` between neighbors.
+///
+/// This is for snapping together the roots found by a solver, or vertex coordinates, that should be equal but differ by rounding.
+/// Note that a long chain of close values is merged into one, even if its ends are far apart.
+/// ```
+/// # use umath::{FFloat, slice};
+/// let f = |x| unsafe { FFloat::new(x) };
+/// let mut roots = vec![2.0000001f64, -1.0, 1.9999999, -0.9999999].into_iter().map(f).collect();
+/// slice::unique_within(&mut roots, f(1e-6));
+/// assert_eq!(roots.len(), 2);
+/// assert!((*roots[0] + 1.0).abs() < 1e-6 && (*roots[1] - 2.0).abs() < 1e-6);
+/// ```
+#[allow(clippy::cast_precision_loss)]
+pub fn unique_within<T: FastFloat + FloatExt, P: NoNan>(
+    x: &mut Vec<FFloat<T, P>>,
+    eps: FFloat<T, P>,
+) {
+    x.sort_unstable();
+    let mut kept = 0;
+    let mut start = 0;
+    while start < x.len() {
+        let mut end = start + 1;
+        let mut sum = x[start];
+        while end < x.len() && close(x[end], x[end - 1], eps) {
+            sum += x[end];
+            end += 1;
+        }
+        // SAFETY: a count is finite (even as a f32).
+        x[kept] = sum / unsafe { T::from_f64((end - start) as f64) };
+        kept += 1;
+        start = end;
+    }
+    x.truncate(kept);
+}
+
+#[test]
+#[allow(clippy::float_cmp)]
+fn clusters() {
+    let f = |x| unsafe { FFloat::new(x) };
+    let mut x: Vec<_> = [5.0f64, 0.0, 0.1, 4.9, 0.2, 10.0, 5.1].map(f).to_vec();
+    dedup_by_tolerance(&mut x, f(0.15));
+    assert_eq!(x, [5.0, 0.0, 4.9, 0.2, 10.0, 5.1]);
+    unique_within(&mut x, f(0.25));
+    assert_eq!(x.len(), 3);
+    assert!((*x[0] - 0.1).abs() < 1e-12 && (*x[1] - 5.0).abs() < 1e-12 && x[2] == 10.0);
+    let mut empty: Vec<FFloat<f64>> = vec![];
+    unique_within(&mut empty, f(1.0));
+    assert!(empty.is_empty());
+}
@@ -0,0 +1,89 @@
+//! runtime cpu feature dispatch.
+//!
+//! The hot loops are wrapped in [`dispatch`], which runs them inside a function compiled for the widest vector extension the cpu supports,
+//! so that a binary built for the baseline target (SSE2, on x86-64) still gets AVX2 | AVX-512 throughput.
+//! The probe is cached, so a dispatch costs one relaxed load.
+//! NEON is part of the aarch64 baseline, and needs no dispatch.
+#[cfg(target_arch = "x86_64")]
+use core::sync::atomic::{AtomicU8, Ordering::Relaxed};
+
+/// The vector extensions a kernel can be compiled for.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord)]
+#[repr(u8)]
+pub(crate) enum Level {
+    /// The target's baseline.
+    Baseline = 1,
+    /// AVX2 and FMA.
+    Avx2,
+    /// AVX-512 (F, and the AVX2 features).
+    Avx512,
+}
+
+#[cfg(target_arch = "x86_64")]
+static LEVEL: AtomicU8 = AtomicU8::new(0);
+
+/// The widest [`Level`] this cpu supports (probed once).
+#[inline(always)]
+pub(crate) fn level() -> Level {
+    #[cfg(target_arch = "x86_64")]
+    {
+        match LEVEL.load(Relaxed) {
+            0 => probe(),
+            1 => Level::Baseline,
+            2 => Level::Avx2,
+            _ => Level::Avx512,
+        }
+    }
+    #[cfg(not(target_arch = "x86_64"))]
+    Level::Baseline
+}
+
+#[cfg(target_arch = "x86_64")]
+#[cold]
+fn probe() -> Level {
+    let level = if is_x86_feature_detected!("avx512f")
+        && is_x86_feature_detected!("avx2")
+        && is_x86_feature_detected!("fma")
+    {
+        Level::Avx512
+    } else if is_x86_feature_detected!("avx2") && is_x86_feature_detected!("fma") {
+        Level::Avx2
+    } else {
+        Level::Baseline
+    };
+    LEVEL.store(level as u8, Relaxed);
+    level
+}
+
+/// Runs `f` compiled for the widest [`Level`] this cpu supports. `f` should be marked `#[inline(always)]`, so it is compiled into each version.
+#[inline(always)]
+pub(super) fn dispatch<R>(f: impl FnOnce() -> R) -> R {
+    #[cfg(target_arch = "x86_64")]
+    match level() {
+        // SAFETY: the cpu supports these features.
+        Level::Avx512 => unsafe { avx512(f) },
+        Level::Avx2 => unsafe { avx2(f) },
+        Level::Baseline => f(),
+    }
+    #[cfg(not(target_arch = "x86_64"))]
+    f()
+}
+
+#[cfg(target_arch = "x86_64")]
+#[target_feature(enable = "avx2,fma")]
+unsafe fn avx2<R>(f: impl FnOnce() -> R) -> R {
+    f()
+}
+
+#[cfg(target_arch = "x86_64")]
+#[target_feature(enable = "avx512f,avx2,fma")]
+unsafe fn avx512<R>(f: impl FnOnce() -> R) -> R {
+    f()
+}
+
+#[test]
+fn cached() {
+    let level = level();
+    assert_eq!(level, self::level());
+    assert!(dispatch(|| true));
+}
@@ -0,0 +1,163 @@
+use super::reduce::tree;
+use super::{dispatch, same, UNROLL};
+use crate::generic_float::{Constructors, FloatMethods};
+use crate::policy::Policy;
+use crate::r#trait::{FastFloat, Wrapper};
+use crate::FFloat;
+
+/// Sums each of the `K` terms of `f(a[i], b[i])`, with [`UNROLL`] independent accumulators per term, in one pass, without checking.
+#[inline(always)]
+fn sums<T: FastFloat + Constructors, P: Policy, const K: usize>(
+    a: &[FFloat<T, P>],
+    b: &[FFloat<T, P>],
+    f: impl Fn(T, T) -> [T; K],
+) -> [T; K] {
+    dispatch(
+        #[inline(always)]
+        || {
+            // SAFETY: 0 is a valid float
+            let mut acc = [[unsafe { T::zero() }; UNROLL]; K];
+            let (ac, bc) = (a.chunks_exact(UNROLL), b.chunks_exact(UNROLL));
+            let (ar, br) = (ac.remainder(), bc.remainder());
+            for (a, b) in ac.zip(bc) {
+                for i in 0..UNROLL {
+                    for (acc, term) in acc.iter_mut().zip(f(a[i].0, b[i].0)) {
+                        acc[i] = unsafe { T::add::<P>(acc[i], term) };
+                    }
+                }
+            }
+            let mut sums = acc.map(tree::<T, P, UNROLL>);
+            for (a, b) in ar.iter().zip(br) {
+                for (sum, term) in sums.iter_mut().zip(f(a.0, b.0)) {
+                    *sum = unsafe { T::add::<P>(*sum, term) };
+                }
+            }
+            sums
+        },
+    )
+}
+
+/// The squared euclidean distance, `Σ (a[i] - b[i])²`.
+///
+/// Each element is a subtraction, and a square added to one of [`UNROLL`](super) accumulators (which, with a policy that allows contraction, is a fused multiply add),
+/// so this is one pass over both slices, without the square root of [`euclidean`]: enough to compare (or rank) distances.
+/// Like a [`Unchecked`](crate::Unchecked) expression, only the result is checked.
+/// ```
+/// # use umath::{FFloat, slice};
+/// let f = |x| unsafe { FFloat::new(x) };
+/// let (a, b) = ([f(1.0f32), f(2.0), f(3.0)], [f(4.0), f(6.0), f(3.0)]);
+/// assert_eq!(*slice::euclidean_squared(&a, &b), 25.0);
+/// assert_eq!(*slice::euclidean(&a, &b), 5.0);
+/// assert_eq!(*slice::manhattan(&a, &b), 7.0);
+/// ```
+#[track_caller]
+#[must_use]
+pub fn euclidean_squared<T: FastFloat + Constructors, P: Policy>(
+    a: &[FFloat<T, P>],
+    b: &[FFloat<T, P>],
+) -> FFloat<T, P> {
+    same(a, b);
+    let [sum] = sums::<T, P, 1>(a, b, |a, b| {
+        let d = unsafe { T::sub::<P>(a, b) };
+        [unsafe { T::mul::<P>(d, d) }]
+    });
+    unsafe { FFloat::wrap(sum) }
+}
+
+/// The euclidean distance, `√Σ (a[i] - b[i])²`.
+///
+/// Unlike [`nrm2`](super::nrm2), the differences are not scaled first, so their squares must not overflow. Refer to [`euclidean_squared`].
+#[track_caller]
+#[must_use]
+pub fn euclidean<T: FastFloat + Constructors + FloatMethods, P: Policy>(
+    a: &[FFloat<T, P>],
+    b: &[FFloat<T, P>],
+) -> FFloat<T, P> {
+    unsafe { FFloat::wrap(euclidean_squared(a, b).0.sqrt()) }
+}
+
+/// The manhattan (taxicab, or L1) distance, `Σ |a[i] - b[i]|`. Refer to [`euclidean_squared`].
+#[track_caller]
+#[must_use]
+pub fn manhattan<T: FastFloat + Constructors + FloatMethods, P: Policy>(
+    a: &[FFloat<T, P>],
+    b: &[FFloat<T, P>],
+) -> FFloat<T, P> {
+    same(a, b);
+    let [sum] = sums::<T, P, 1>(a, b, |a, b| [unsafe { T::sub::<P>(a, b) }.abs()]);
+    unsafe { FFloat::wrap(sum) }
+}
+
+/// The cosine similarity, `(a · b) / (|a| |b|)`: the cosine of the angle between `a` and `b`, from -1 (opposite) to 1 (the same direction);
+/// or [`None`], if either is zero (so has no direction).
+///
+/// The dot product, and both squared norms, are accumulated in the same pass. The result is clamped to `[-1, 1]`, which rounding could otherwise leave it just outside of.
+/// ```
+/// # use umath::{FFloat, slice};
+/// let f = |x| unsafe { FFloat::new(x) };
+/// let a = [f(1.0f64), f(0.0)];
+/// assert_eq!(*slice::cosine_similarity(&a, &[f(3.0), f(3.0)]).unwrap(), 0.5f64.sqrt());
+/// assert_eq!(*slice::cosine_distance(&a, &[f(-2.0), f(0.0)]).unwrap(), 2.0);
+/// assert_eq!(slice::cosine_similarity(&a, &[f(0.0), f(0.0)]), None);
+/// ```
+#[track_caller]
+#[must_use]
+pub fn cosine_similarity<T: FastFloat + Constructors + FloatMethods, P: Policy>(
+    a: &[FFloat<T, P>],
+    b: &[FFloat<T, P>],
+) -> Option<FFloat<T, P>> {
+    same(a, b);
+    let [dot, aa, bb] = sums::<T, P, 3>(a, b, |a, b| unsafe {
+        [T::mul::<P>(a, b), T::mul::<P>(a, a), T::mul::<P>(b, b)]
+    });
+    // SAFETY: 0 and 1 are valid floats
+    let (zero, one) = unsafe { (T::zero(), T::one()) };
+    if aa == zero || bb == zero {
+        return None;
+    }
+    // the norms are multiplied after their square roots, so that the product can not overflow where the squares do not.
+    let cosine = unsafe { T::div::<P>(dot, T::mul::<P>(aa.sqrt(), bb.sqrt())) };
+    let cosine = if cosine > one {
+        one
+    } else if cosine < -one {
+        -one
+    } else {
+        cosine
+    };
+    Some(unsafe { FFloat::wrap(cosine) })
+}
+
+/// The cosine distance, `1 - `[`cosine_similarity`]: from 0 (the same direction) to 2 (opposite); or [`None`], if either is zero.
+#[track_caller]
+#[must_use]
+pub fn cosine_distance<T: FastFloat + Constructors + FloatMethods, P: Policy>(
+    a: &[FFloat<T, P>],
+    b: &[FFloat<T, P>],
+) -> Option<FFloat<T, P>> {
+    // SAFETY: 1 is a valid float
+    cosine_similarity(a, b).map(|cosine| unsafe { FFloat::wrap(T::one()) } - cosine)
+}
+
+#[test]
+#[allow(clippy::float_cmp)]
+fn distances() {
+    // long enough to fill the accumulators twice, with a remainder
+    let f = |x| unsafe { FFloat::new(f64::from(x)) };
+    let a: Vec<_> = (0..21).map(f).collect();
+    let b: Vec<_> = (0..21).map(|x| f(x * 3 - 10)).collect();
+    let differences = (0..21).map(|x| f64::from(x * 2 - 10));
+    assert_eq!(
+        *euclidean_squared(&a, &b),
+        differences.clone().map(|d| d * d).sum::<f64>()
+    );
+    assert_eq!(*manhattan(&a, &b), differences.map(f64::abs).sum::<f64>());
+    assert_eq!(*euclidean(&a, &a), 0.0);
+    assert_eq!(*euclidean(&a[..0], &b[..0]), 0.0);
+    // scaled copies point the same way, and negated ones the opposite way
+    let scaled: Vec<_> = a.iter().map(|&x| x * 2.5).collect();
+    let negated: Vec<_> = a.iter().map(|&x| -x).collect();
+    assert!((*cosine_similarity(&a, &scaled).unwrap() - 1.0).abs() < 1e-15);
+    assert!(*cosine_distance(&a, &scaled).unwrap() < 1e-15);
+    assert!((*cosine_similarity(&a, &negated).unwrap() + 1.0).abs() < 1e-15);
+    assert_eq!(cosine_similarity(&a[..1], &b[..1]), None);
+}
@@ -0,0 +1,117 @@
+use super::{each, same, zip, zip3};
+use crate::policy::Policy;
+use crate::r#trait::FastFloat;
+use crate::FFloat;
+
+macro_rules! elementwise {
+    ($($assign:ident, $out:ident => $op:tt $assign_op:tt, $verb:literal);+ $(;)?) => {
+        $(
+            #[doc = concat!("Element wise ", $verb, ", in place: `dst[i] ", stringify!($op), "= src[i]`.")]
+            #[track_caller]
+            pub fn $assign<T: FastFloat, P: Policy>(dst: &mut [FFloat<T, P>], src: &[FFloat<T, P>]) {
+                same(dst, src);
+                zip(dst, src, |d, s| *d $assign_op *s);
+            }
+
+            #[doc = concat!("Element wise ", $verb, ": `out[i] = a[i] ", stringify!($op), " b[i]`.")]
+            #[track_caller]
+            pub fn $out<T: FastFloat, P: Policy>(out: &mut [FFloat<T, P>], a: &[FFloat<T, P>], b: &[FFloat<T, P>]) {
+                same(out, a);
+                same(a, b);
+                out.copy_from_slice(a);
+                $assign(out, b);
+            }
+        )+
+    };
+}
+
+elementwise! {
+    add_assign, add => + +=, "addition";
+    sub_assign, sub => - -=, "subtraction";
+    mul_assign, mul => * *=, "multiplication";
+    div_assign, div => / /=, "division";
+}
+
+/// Applies a affine map (scale, then offset) in place: `x[i] = a * x[i] + b`.
+///
+/// Like [`axpy`](super::axpy), each element is one [`Unchecked`](crate::Unchecked) expression, so (with a policy that allows contraction) it is a fused multiply add.
+/// ```
+/// # use umath::{FFloat, slice};
+/// let f = |x| unsafe { FFloat::new(x) };
+/// let mut celsius = [f(0.0f32), f(100.0)];
+/// slice::affine_assign(&mut celsius, f(1.8), f(32.0));
+/// assert_eq!(celsius, [32.0, 212.0]);
+/// ```
+pub fn affine_assign<T: FastFloat, P: Policy>(
+    x: &mut [FFloat<T, P>],
+    a: FFloat<T, P>,
+    b: FFloat<T, P>,
+) {
+    each(x, |x| *x = (a.lazy() * *x + b).finish());
+}
+
+/// Applies a affine map (scale, then offset): `out[i] = a * x[i] + b`. Refer to [`affine_assign`].
+#[track_caller]
+pub fn affine<T: FastFloat, P: Policy>(
+    out: &mut [FFloat<T, P>],
+    x: &[FFloat<T, P>],
+    a: FFloat<T, P>,
+    b: FFloat<T, P>,
+) {
+    same(out, x);
+    zip(out, x, |out, x| *out = (a.lazy() * *x + b).finish());
+}
+
+/// Element wise fused multiply add, accumulating: `dst[i] += a[i] * b[i]`.
+///
+/// Each element is one [`Unchecked`](crate::Unchecked) expression, so (with a policy that allows contraction) it is a fused multiply add.
+/// ```
+/// # use umath::{FFloat, slice};
+/// let f = |x| unsafe { FFloat::new(x) };
+/// let mut acc = [f(1.0f32), f(1.0)];
+/// slice::fma_assign(&mut acc, &[f(2.0), f(3.0)], &[f(4.0), f(5.0)]);
+/// assert_eq!(acc, [9.0, 16.0]);
+/// ```
+#[track_caller]
+pub fn fma_assign<T: FastFloat, P: Policy>(
+    dst: &mut [FFloat<T, P>],
+    a: &[FFloat<T, P>],
+    b: &[FFloat<T, P>],
+) {
+    same(dst, a);
+    same(a, b);
+    zip3(dst, a, b, |d, a, b| *d = (a.lazy() * *b + *d).finish());
+}
+
+/// Element wise fused multiply add: `out[i] = a[i] * b[i] + c[i]`. Refer to [`fma_assign`].
+#[track_caller]
+pub fn fma<T: FastFloat, P: Policy>(
+    out: &mut [FFloat<T, P>],
+    a: &[FFloat<T, P>],
+    b: &[FFloat<T, P>],
+    c: &[FFloat<T, P>],
+) {
+    same(out, c);
+    out.copy_from_slice(c);
+    fma_assign(out, a, b);
+}
+
+#[test]
+fn unrolled() {
+    let v: Vec<_> = (1..=19)
+        .map(|x| unsafe { FFloat::new(f64::from(x)) })
+        .collect();
+    let mut out = vec![unsafe { FFloat::new(0.0) }; 19];
+    sub(&mut out, &v, &v);
+    add_assign(&mut out, &v);
+    assert_eq!(out, v);
+    let [half, one, two] = [0.5, 1.0, 2.0].map(|x| unsafe { FFloat::new(x) });
+    affine(&mut out, &v, two, -two);
+    affine_assign(&mut out, half, one);
+    assert_eq!(out, v);
+    let mut squares = out.clone();
+    fma(&mut squares, &v, &v, &out);
+    fma_assign(&mut out, &v, &v);
+    assert_eq!(out, squares);
+    assert_eq!(out[2], 12.0);
+}
@@ -0,0 +1,512 @@
+use super::{dispatch, same, scal, sum_fast, summary};
+use crate::generic_float::{lit, FloatExt, FloatMethods};
+use crate::policy::{Ieee, Policy};
+use crate::r#trait::{FastFloat, Wrapper};
+use crate::FFloat;
+use core::marker::PhantomData;
+
+/// The softmax, in place: `x[i] = e^x[i] / Σ e^x[j]`.
+///
+/// The maximum is subtracted first, so every exponential is at most 1, and the sum at least 1: for any (finite) input, this can never produce [`INF`](f32::INFINITY) | [`NAN`](f32::NAN).
+/// (The subtraction itself is done in halves, `e^(x/2 - max/2)²`, as the difference of two finite floats can overflow.)
+/// ```
+/// # use umath::{FFloat, slice};
+/// let mut logits = [1000.0f32, 1000.0, -1e38].map(|x| unsafe { FFloat::new(x) });
+/// slice::softmax(&mut logits);
+/// assert_eq!(logits, [0.5, 0.5, 0.0]);
+/// ```
+pub fn softmax<T: FastFloat + FloatExt + FloatMethods, P: Policy>(x: &mut [FFloat<T, P>]) {
+    if x.is_empty() {
+        return;
+    }
+    let half = lit::<T>(0.5);
+    let max = unsafe { T::mul::<P>(summary(x).max.0, half) };
+    for x in &mut *x {
+        let e = unsafe { T::sub::<P>(T::mul::<P>(x.0, half), max) }.exp();
+        // SAFETY: 0 <= e <= 1
+        *x = unsafe { FFloat::wrap(T::mul::<P>(e, e)) };
+    }
+    // SAFETY: the maximum element is 1, so the sum is at least 1.
+    let inverse = unsafe { FFloat::wrap(T::div::<P>(T::one(), sum_fast(x).0)) };
+    scal(inverse, x);
+}
+
+/// The softmax: `out[i] = e^x[i] / Σ e^x[j]`. Refer to [`softmax`].
+#[track_caller]
+pub fn softmax_into<T: FastFloat + FloatExt + FloatMethods, P: Policy>(
+    out: &mut [FFloat<T, P>],
+    x: &[FFloat<T, P>],
+) {
+    same(out, x);
+    out.copy_from_slice(x);
+    softmax(out);
+}
+
+/// `dst[i] = f(src[i])`, [dispatched](dispatch).
+///
+/// Unlike [`zip`](super::zip), this is a plain loop, left to the loop vectorizer, as the polynomial kernels are too large to unroll.
+#[inline(always)]
+fn map<A, B>(dst: &mut [A], src: &[B], f: impl Fn(&B) -> A) {
+    // the slices are passed as arguments (not captured), so that they keep their noalias information.
+    #[inline(always)]
+    fn each<A, B>(dst: &mut [A], src: &[B], f: impl Fn(&B) -> A) {
+        for (d, s) in dst.iter_mut().zip(src) {
+            *d = f(s);
+        }
+    }
+    dispatch(
+        #[inline(always)]
+        || each(dst, src, f),
+    );
+}
+
+/// `π/2`, split into parts with trailing zeros, so that `q * π/2` is subtracted exactly (Cody-Waite reduction).
+const PI_2_F32: [f64; 3] = [
+    1.570_312_5,
+    4.837_512_969_970_703e-4,
+    7.549_789_954_891_882e-8,
+];
+const PI_2_F64: [f64; 3] = [
+    1.570_796_251_296_997,
+    7.549_789_415_861_596e-8,
+    5.390_302_858_158_119e-15,
+];
+/// Minimax coefficients (in `r²`, highest first) of `(sin(r) - r) / r³` and `(cos(r) - 1 + r²/2) / r⁴`, on `[-π/4, π/4]`.
+const SIN_F32: &[f64] = &[-1.951_529_589_1e-4, 8.332_160_873_6e-3, -1.666_665_461_1e-1];
+const COS_F32: &[f64] = &[
+    2.443_315_711_809_948e-5,
+    -1.388_731_625_493_765e-3,
+    4.166_664_568_298_827e-2,
+];
+const SIN_F64: &[f64] = &[
+    1.589_623_015_765_465_7e-10,
+    -2.505_074_776_285_780_7e-8,
+    2.755_731_362_138_572_4e-6,
+    -1.984_126_982_958_954e-4,
+    8.333_333_333_322_118e-3,
+    -1.666_666_666_666_663e-1,
+];
+const COS_F64: &[f64] = &[
+    -1.135_853_652_138_768_2e-11,
+    2.087_570_084_197_473e-9,
+    -2.755_731_417_929_674e-7,
+    2.480_158_728_885_170_5e-5,
+    -1.388_888_888_887_305_6e-3,
+    4.166_666_666_666_659e-2,
+];
+
+/// The largest `|x|` that [`sin`] | [`cos`] reduce with the polynomial kernel.
+fn trig_limit<T>() -> f64 {
+    if size_of::<T>() == 8 {
+        1_073_741_824.0
+    } else {
+        8192.0
+    }
+}
+
+/// `sin(x)` (or `cos(x)`, if `COS`) of a `|x| <= trig_limit()`, branchless.
+///
+/// This is computed with IEEE operations, as reassociating the reduction would cancel it out.
+#[inline(always)]
+fn sin_cos<T: FastFloat + FloatExt + FloatMethods, const COS: bool>(x: T) -> T {
+    let (pi_2, sin_coefficients, cos_coefficients) = if size_of::<T>() == 8 {
+        (PI_2_F64, SIN_F64, COS_F64)
+    } else {
+        (PI_2_F32, SIN_F32, COS_F32)
+    };
+    let add = |a, b| unsafe { T::add::<Ieee>(a, b) };
+    let sub = |a, b| unsafe { T::sub::<Ieee>(a, b) };
+    let mul = |a, b| unsafe { T::mul::<Ieee>(a, b) };
+    // x = n * π/2 + r, with |r| <= π/4
+    let n = add(mul(x, lit(core::f64::consts::FRAC_2_PI)), lit(0.5)).floor();
+    let r = pi_2.iter().fold(x, |r, &part| sub(r, mul(n, lit(part))));
+    let z = mul(r, r);
+    let horner = |coefficients: &[f64]| {
+        coefficients[1..]
+            .iter()
+            .fold(lit(coefficients[0]), |acc, &c| add(mul(acc, z), lit(c)))
+    };
+    let sine = add(r, mul(mul(r, z), horner(sin_coefficients)));
+    let cosine = add(
+        sub(lit(1.0), mul(z, lit(0.5))),
+        mul(mul(z, z), horner(cos_coefficients)),
+    );
+    // n mod 4
+    let quadrant = sub(n, mul(lit(4.0), mul(n, lit(0.25)).floor()));
+    let odd = sub(quadrant, mul(lit(2.0), mul(quadrant, lit(0.5)).floor())) > lit(0.5);
+    let (value, negative) = if COS {
+        let value = if odd { sine } else { cosine };
+        (value, quadrant > lit(0.5) && quadrant < lit(2.5))
+    } else {
+        (if odd { cosine } else { sine }, quadrant > lit(1.5))
+    };
+    if negative {
+        -value
+    } else {
+        value
+    }
+}
+
+/// Applies [`sin_cos`] to every element, and then fixes up the elements too large for it with the scalar function.
+#[inline(always)]
+fn trig<T: FastFloat + FloatExt + FloatMethods, P: Policy, const COS: bool>(
+    src: &[FFloat<T, P>],
+    dst: &mut [FFloat<T, P>],
+    scalar: fn(T) -> T,
+) {
+    same(src, dst);
+    let limit = lit::<T>(trig_limit::<T>());
+    let small = move |x: T| x <= limit && x >= -limit;
+    // SAFETY: sin | cos of a finite float is in -1..=1.
+    map(dst, src, move |x| {
+        FFloat(
+            sin_cos::<T, COS>(if small(x.0) { x.0 } else { lit(0.0) }),
+            PhantomData,
+        )
+    });
+    for (d, s) in dst.iter_mut().zip(src) {
+        if !small(s.0) {
+            *d = FFloat(scalar(s.0), PhantomData);
+        }
+    }
+}
+
+/// The sine of every element: `dst[i] = sin(src[i])`.
+///
+/// This reduces the argument to `[-π/4, π/4]` (by multiples of `π/2`), and evaluates a minimax polynomial, without branches or calls, so it vectorizes;
+/// the absolute error is below the ulp of 1 (`1.2e-7` for [`f32`], `2.2e-16` for [`f64`]).
+/// This holds for `|x| <= 8192` ([`f32`]) | `2^30` ([`f64`]); larger elements fall back to [`f32::sin`].
+/// ```
+/// # use umath::{FFloat, slice};
+/// let f = |x| unsafe { FFloat::new(x) };
+/// let x: Vec<_> = (0..64).map(|i| f(i as f32 * 0.1)).collect();
+/// let mut y = vec![f(0.0); 64];
+/// slice::sin(&x, &mut y);
+/// assert!(x.iter().zip(&y).all(|(x, y)| (x.sin() - **y).abs() < 1e-6));
+/// ```
+///
+/// # Panics
+///
+/// If the lengths differ.
+#[track_caller]
+pub fn sin<T: FastFloat + FloatExt + FloatMethods, P: Policy>(
+    src: &[FFloat<T, P>],
+    dst: &mut [FFloat<T, P>],
+) {
+    trig::<T, P, false>(src, dst, T::sin);
+}
+
+/// The cosine of every element: `dst[i] = cos(src[i])`. Refer to [`sin`].
+///
+/// # Panics
+///
+/// If the lengths differ.
+#[track_caller]
+pub fn cos<T: FastFloat + FloatExt + FloatMethods, P: Policy>(
+    src: &[FFloat<T, P>],
+    dst: &mut [FFloat<T, P>],
+) {
+    trig::<T, P, true>(src, dst, T::cos);
+}
+
+/// The layout of a [`f64`]: `Bits` (the unsigned integer of its size), `MANTISSA` (the number of mantissa bits), `BIAS` (the exponent bias),
+/// `SCALE` (`2^MANTISSA`), `MIN_POSITIVE` (the smallest normal number), and `OFFSET` (`SCALE + BIAS`).
+mod double {
+    pub type Bits = u64;
+    pub const MANTISSA: u32 = 52;
+    pub const BIAS: Bits = 1023;
+    pub const SCALE: f64 = 4_503_599_627_370_496.0;
+    pub const MIN_POSITIVE: f64 = f64::MIN_POSITIVE;
+    pub const OFFSET: f64 = SCALE + 1023.0;
+}
+
+/// The layout of a [`f32`]. Refer to [`double`].
+mod single {
+    pub type Bits = u32;
+    pub const MANTISSA: u32 = 23;
+    pub const BIAS: Bits = 127;
+    pub const SCALE: f64 = 8_388_608.0;
+    pub const MIN_POSITIVE: f64 = f32::MIN_POSITIVE as f64;
+    pub const OFFSET: f64 = SCALE + 127.0;
+}
+
+/// Runs `$body` with the [`single`] | [`double`] layout in scope, for the width of `T`.
+macro_rules! layout {
+    ($body:block) => {
+        if size_of::<T>() == 8 {
+            use double::*;
+            $body
+        } else {
+            use single::*;
+            $body
+        }
+    };
+}
+
+/// Reinterprets the bits of `x` as a `U` of the same size.
+#[inline(always)]
+fn cast<T: Copy, U: Copy>(x: T) -> U {
+    assert_eq!(size_of::<T>(), size_of::<U>());
+    // SAFETY: every bit pattern is a valid float | unsigned integer, and the sizes are equal.
+    unsafe { core::mem::transmute_copy(&x) }
+}
+
+/// `2^n`, of a integral `n` in the normal exponent range, by writing `n` into the exponent field.
+#[inline(always)]
+fn pow2<T: FastFloat + FloatExt>(n: T) -> T {
+    layout!({
+        // 1.5 * 2^MANTISSA + n has n (in two's complement) in its low bits.
+        let bits: Bits = cast(unsafe { T::add::<Ieee>(n, lit(1.5 * SCALE)) });
+        cast(bits.wrapping_add(BIAS) << MANTISSA)
+    })
+}
+
+/// `ln(2)`, split in two, so that `n * ln(2)` is exact (Cody-Waite).
+const LN_2_F32: [f64; 2] = [0.693_359_375, -2.121_944_4e-4];
+const LN_2_F64: [f64; 2] = [0.693_145_751_953_125, 1.428_606_820_309_417_3e-6];
+/// The Taylor coefficients of `(e^r - 1 - r) / r²`, highest first, for `|r| <= ln(2) / 2`.
+const EXP_F32: &[f64] = &[1.0 / 720.0, 1.0 / 120.0, 1.0 / 24.0, 1.0 / 6.0, 0.5];
+const EXP_F64: &[f64] = &[
+    1.0 / 6_227_020_800.0,
+    1.0 / 479_001_600.0,
+    1.0 / 39_916_800.0,
+    1.0 / 3_628_800.0,
+    1.0 / 362_880.0,
+    1.0 / 40_320.0,
+    1.0 / 5040.0,
+    1.0 / 720.0,
+    1.0 / 120.0,
+    1.0 / 24.0,
+    1.0 / 6.0,
+    0.5,
+];
+
+/// Evaluates the polynomial with `coefficients` (highest first) at `z`, with Horner's method.
+#[inline(always)]
+fn horner<T: FastFloat + FloatExt>(coefficients: &[f64], z: T) -> T {
+    coefficients[1..]
+        .iter()
+        .fold(lit(coefficients[0]), |acc, &c| unsafe {
+            T::add::<Ieee>(T::mul::<Ieee>(acc, z), lit(c))
+        })
+}
+
+/// `e^x`, branchless. Overflows to [`INF`](f32::INFINITY), and underflows (gradually) to 0.
+#[inline(always)]
+fn exp_kernel<T: FastFloat + FloatExt + FloatMethods>(x: T) -> T {
+    let wide = size_of::<T>() == 8;
+    let (ln_2, coefficients, limit) = if wide {
+        (LN_2_F64, EXP_F64, 1400.0)
+    } else {
+        (LN_2_F32, EXP_F32, 170.0)
+    };
+    let add = |a, b| unsafe { T::add::<Ieee>(a, b) };
+    let sub = |a, b| unsafe { T::sub::<Ieee>(a, b) };
+    let mul = |a, b| unsafe { T::mul::<Ieee>(a, b) };
+    // past the limit, the result is 0 | INF anyway; within it, n / 2 is a normal exponent.
+    let x = if x > lit(limit) {
+        lit(limit)
+    } else if x < lit(-limit) {
+        lit(-limit)
+    } else {
+        x
+    };
+    // x = n * ln(2) + r, with |r| <= ln(2) / 2
+    let n = add(mul(x, lit(core::f64::consts::LOG2_E)), lit(0.5)).floor();
+    let r = ln_2.iter().fold(x, |r, &part| sub(r, mul(n, lit(part))));
+    let exp_r = add(add(lit(1.0), r), mul(mul(r, r), horner(coefficients, r)));
+    // 2^n as 2^half * 2^(n - half), so that it can reach the subnormals | overflow.
+    let half = mul(n, lit(0.5)).floor();
+    mul(mul(exp_r, pow2(half)), pow2(sub(n, half)))
+}
+
+/// The exponential of every element: `dst[i] = e^src[i]`.
+///
+/// This reduces the argument to `[-ln(2)/2, ln(2)/2]` (by multiples of `ln(2)`), evaluates a polynomial, and scales the result by writing the exponent bits, without branches or calls, so it vectorizes.
+/// The relative error is at most 2 ulp; small results underflow gradually, like [`f32::exp`].
+/// ```
+/// # use umath::{FFloat, slice};
+/// let f = |x| unsafe { FFloat::new(x) };
+/// let x: Vec<_> = (-40..40).map(|i| f(i as f32 * 0.5)).collect();
+/// let mut y = x.clone();
+/// slice::exp(&x, &mut y);
+/// assert!(x.iter().zip(&y).all(|(x, y)| (x.exp() / **y - 1.0).abs() < 1e-6));
+/// ```
+///
+/// # Panics
+///
+/// If the lengths differ, or (with checks enabled) if a result overflows to [`INF`](f32::INFINITY).
+#[track_caller]
+pub fn exp<T: FastFloat + FloatExt + FloatMethods, P: Policy>(
+    src: &[FFloat<T, P>],
+    dst: &mut [FFloat<T, P>],
+) {
+    same(src, dst);
+    map(dst, src, |x| unsafe { FFloat::wrap(exp_kernel(x.0)) });
+}
+
+/// The coefficients of `(atanh(s) - s) / s³`, in `s²`, highest first, for `|s| <= 3 - 2√2`.
+const LN_F32: &[f64] = &[1.0 / 11.0, 1.0 / 9.0, 1.0 / 7.0, 1.0 / 5.0, 1.0 / 3.0];
+const LN_F64: &[f64] = &[
+    1.0 / 21.0,
+    1.0 / 19.0,
+    1.0 / 17.0,
+    1.0 / 15.0,
+    1.0 / 13.0,
+    1.0 / 11.0,
+    1.0 / 9.0,
+    1.0 / 7.0,
+    1.0 / 5.0,
+    1.0 / 3.0,
+];
+
+/// `ln(x)`, branchless. This is -[`INF`](f32::INFINITY) for 0, and [`NAN`](f32::NAN) for negative numbers.
+#[inline(always)]
+fn ln_kernel<T: FastFloat + FloatExt + FloatMethods>(x: T) -> T {
+    let (ln_2, coefficients) = if size_of::<T>() == 8 {
+        (LN_2_F64, LN_F64)
+    } else {
+        (LN_2_F32, LN_F32)
+    };
+    let add = |a, b| unsafe { T::add::<Ieee>(a, b) };
+    let sub = |a, b| unsafe { T::sub::<Ieee>(a, b) };
+    let mul = |a, b| unsafe { T::mul::<Ieee>(a, b) };
+    let div = |a, b| unsafe { T::div::<Ieee>(a, b) };
+    // x = mantissa * 2^exponent, with 1 <= mantissa < 2
+    let (mantissa, exponent) = layout!({
+        // subnormals are scaled into the normal range first.
+        let (x, offset) = if x < lit(MIN_POSITIVE) {
+            (mul(x, lit(SCALE)), lit(f64::from(MANTISSA)))
+        } else {
+            (x, lit(0.0))
+        };
+        let bits: Bits = cast(x);
+        let mantissa: T = cast(bits & ((1 << MANTISSA) - 1) | cast::<T, Bits>(lit(1.0)));
+        // SCALE + the biased exponent, as a float.
+        let biased: T = cast(bits >> MANTISSA | cast::<T, Bits>(lit(SCALE)));
+        (mantissa, sub(biased, add(lit(OFFSET), offset)))
+    });
+    // √½ <= mantissa < √2, so that ln(mantissa) is small (and accurate) near x = 1.
+    let (mantissa, exponent) = if mantissa > lit(core::f64::consts::SQRT_2) {
+        (mul(mantissa, lit(0.5)), add(exponent, lit(1.0)))
+    } else {
+        (mantissa, exponent)
+    };
+    // ln(mantissa) = 2 atanh(s)
+    let f = sub(mantissa, lit(1.0));
+    let s = div(f, add(lit(2.0), f));
+    let w = mul(s, s);
+    let ln_mantissa = add(add(s, s), mul(mul(add(s, s), w), horner(coefficients, w)));
+    let ln = add(
+        mul(exponent, lit(ln_2[0])),
+        add(ln_mantissa, mul(exponent, lit(ln_2[1]))),
+    );
+    if x > lit(0.0) {
+        ln
+    } else if x < lit(0.0) {
+        lit(f64::NAN)
+    } else {
+        lit(f64::NEG_INFINITY)
+    }
+}
+
+/// The natural logarithm of every element: `dst[i] = ln(src[i])`.
+///
+/// This splits off the exponent (from the bits), and evaluates a polynomial of the mantissa, without branches or calls, so it vectorizes.
+/// The error is at most 1 ulp, or `1e-7` (`f32`) | `2e-16` (`f64`) absolute, near 1.
+/// ```
+/// # use umath::{FFloat, slice};
+/// let f = |x| unsafe { FFloat::new(x) };
+/// let x: Vec<_> = (1..100).map(|i| f(i as f32 * 0.37)).collect();
+/// let mut y = x.clone();
+/// slice::ln(&x, &mut y);
+/// assert!(x.iter().zip(&y).all(|(x, y)| (x.ln() - **y).abs() < 1e-6));
+/// ```
+///
+/// # Panics
+///
+/// If the lengths differ, or (with checks enabled) if an element is not positive.
+#[track_caller]
+pub fn ln<T: FastFloat + FloatExt + FloatMethods, P: Policy>(
+    src: &[FFloat<T, P>],
+    dst: &mut [FFloat<T, P>],
+) {
+    same(src, dst);
+    map(dst, src, |x| unsafe { FFloat::wrap(ln_kernel(x.0)) });
+}
+
+#[test]
+fn extremes() {
+    let f = |x| unsafe { FFloat::new(x) };
+    let x = [f(f64::MAX), f(-f64::MAX), f(0.0), f(f64::MAX)];
+    let mut out = [f(0.0); 4];
+    softmax_into(&mut out, &x);
+    assert_eq!(out, [0.5, 0.0, 0.0, 0.5]);
+    let mut one = [f(-3.0)];
+    softmax(&mut one);
+    assert!((*one[0] - 1.0).abs() < 1e-12);
+}
+
+/// The largest absolute error of the slice `sin` | `cos` at 20001 points in `-limit..=limit`, against the [`f64`] functions.
+#[cfg(test)]
+fn trig_error<T: FastFloat + FloatExt + FloatMethods + Into<f64>>(limit: f64) -> [f64; 2] {
+    let x: Vec<FFloat<T>> = (-10_000..=10_000)
+        .map(|i| unsafe { FFloat::new(T::from_f64(f64::from(i) / 10_000.0 * limit)) })
+        .collect();
+    let mut y = x.clone();
+    [
+        (sin as fn(&_, &mut _), f64::sin as fn(f64) -> f64),
+        (cos, f64::cos),
+    ]
+    .map(|(kernel, scalar)| {
+        kernel(&x, &mut y);
+        x.iter()
+            .zip(&y)
+            .map(|(x, y)| (y.0.into() - scalar(x.0.into())).abs())
+            .fold(0.0, f64::max)
+    })
+}
+
+#[test]
+fn trigonometry() {
+    for limit in [1.0, 8192.0, 1e6] {
+        assert!(trig_error::<f32>(limit).iter().all(|&e| e < 1.2e-7));
+    }
+    for limit in [1.0, 1e9, 1e12] {
+        assert!(trig_error::<f64>(limit).iter().all(|&e| e < 2.3e-16));
+    }
+}
+
+/// The largest error of the slice `kernel` at `x`, against the [`f64`] `scalar`, in ulp of `T` (of the result, or of 1 if it is smaller).
+#[cfg(test)]
+fn ulps<T: FastFloat + FloatExt + FloatMethods + Into<f64>>(
+    kernel: fn(&[FFloat<T>], &mut [FFloat<T>]),
+    scalar: fn(f64) -> f64,
+    x: impl Iterator<Item = f64>,
+) -> f64 {
+    let x: Vec<FFloat<T>> = x.map(|x| unsafe { FFloat::new(T::from_f64(x)) }).collect();
+    let mut y = x.clone();
+    kernel(&x, &mut y);
+    let ulp = 2f64.powi(if size_of::<T>() == 8 { -52 } else { -23 });
+    x.iter()
+        .zip(&y)
+        .map(|(x, y)| {
+            let exact = scalar(x.0.into());
+            (y.0.into() - exact).abs() / (exact.abs().max(1.0) * ulp)
+        })
+        .fold(0.0, f64::max)
+}
+
+#[test]
+fn exponentials() {
+    let range = |from: f64, to: f64| {
+        (0..=20_000).map(move |i| from + (to - from) * f64::from(i) / 20_000.0)
+    };
+    assert!(ulps::<f32>(exp, f64::exp, range(-87.0, 88.0)) <= 2.0);
+    assert!(ulps::<f64>(exp, f64::exp, range(-708.0, 709.0)) <= 2.0);
+    // subnormal results, in ulp of 1
+    assert!(ulps::<f32>(exp, f64::exp, range(-103.0, -87.5)) < 1e-30);
+    assert!(ulps::<f32>(ln, f64::ln, range(-103.0, 88.0).map(f64::exp)) <= 1.0);
+    assert!(ulps::<f64>(ln, f64::ln, range(-744.0, 709.0).map(f64::exp)) <= 1.0);
+    assert!(ulps::<f64>(ln, f64::ln, range(0.5, 2.0)) <= 1.0);
+}
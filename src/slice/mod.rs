@@ -0,0 +1,181 @@
+//! kernels over slices of [`FFloat`]s.
+//!
+//! These are written over the fast intrinsics, and unrolled, so that bulk math gets vectorized without every user rewriting the same loops.
+//! Every function that takes multiple slices panics if their lengths differ.
+//!
+//! - element wise arithmetic ([`add_assign`], [`affine_assign`], [`fma_assign`], ..), and the BLAS level 1 to 3 kernels ([`axpy`], [`dot`], [`nrm2`], [`gemv`], [`gemm`], ..).
+//! - the transcendental functions ([`sin`], [`cos`], [`exp`], [`ln`]), evaluated with branchless polynomials, so that they vectorize too.
+//! - distances between two vectors ([`euclidean`], [`manhattan`], [`cosine_similarity`]), the core of nearest neighbour search and clustering, in one fused pass over both slices.
+//! - sliding window sums, means, and extrema, as iterators of the [`Rolling`] extension trait.
+//! - sorting plain `f32` | `f64` slices by the total order of [`FFloat`], with [`SortFloats`].
+//! - medians, percentiles, and the largest few elements, without a full sort, with [`select_nth`] and [`top_k`].
+//! - searching sorted data (and interpolating tables) with [`binary_search`], [`interpolation_search`], and [`lookup_interpolated`].
+//! - non contiguous data (columns, interleaved channels), through [`Strided`] views.
+//!
+//! The hot loops are compiled for several vector extensions (AVX2, AVX-512), and the widest one the cpu supports is picked at runtime,
+//! so that a binary built for the baseline target is not limited to its vector width.
+//! On aarch64 (NEON) and wasm32 (with `simd128`), the f32 | f64 reductions (and axpy, on aarch64) are written with explicit vector intrinsics, for the policies that allow contraction and reassociation.
+//! The accumulator counts and block sizes can be tuned, per call, or globally, with [`Tuning`].
+//! With the `rayon` feature, the `par_` kernels split large slices into chunks, and run the sequential kernels on every core.
+//! ```
+//! # use umath::{FFloat, slice};
+//! let f = |x| unsafe { FFloat::new(x) };
+//! let mut a = [f(1.0f32), f(2.0), f(3.0)];
+//! slice::mul_assign(&mut a, &[f(2.0), f(2.0), f(2.0)]);
+//! assert_eq!(a, [2.0, 4.0, 6.0]);
+//! ```
+use crate::FFloat;
+
+/// Runs `$e` with the const `$n` set to the accumulator count of `$tuning` (which is one of 1, 2, 4, 8, 16).
+macro_rules! accumulators {
+    ($tuning:expr, $n:ident => $e:expr) => {
+        match $tuning.accumulators() {
+            1 => {
+                const $n: usize = 1;
+                $e
+            }
+            2 => {
+                const $n: usize = 2;
+                $e
+            }
+            4 => {
+                const $n: usize = 4;
+                $e
+            }
+            8 => {
+                const $n: usize = 8;
+                $e
+            }
+            _ => {
+                const $n: usize = 16;
+                $e
+            }
+        }
+    };
+}
+
+pub(crate) mod arch;
+mod blas;
+mod dedup;
+pub(crate) mod dispatch;
+mod distance;
+mod elementwise;
+mod math;
+#[cfg(feature = "rayon")]
+mod par;
+mod reduce;
+mod rolling;
+mod scan;
+mod search;
+mod select;
+mod sort;
+mod strided;
+mod tuning;
+pub use blas::*;
+pub use dedup::*;
+use dispatch::dispatch;
+pub use distance::*;
+pub use elementwise::*;
+pub use math::*;
+#[cfg(feature = "rayon")]
+pub use par::*;
+pub use reduce::*;
+pub use rolling::{Extremum, Rolling, RollingMax, RollingMean, RollingMin, RollingSum};
+pub use scan::*;
+pub use search::*;
+pub use select::*;
+pub use sort::SortFloats;
+pub use strided::{Strided, StridedMut};
+pub use tuning::Tuning;
+
+/// The number of elements processed per iteration of a unrolled loop.
+const UNROLL: usize = 8;
+
+/// Reinterprets the slice as raw floats.
+#[inline(always)]
+pub(crate) fn raw<T, P>(x: &[FFloat<T, P>]) -> &[T] {
+    // SAFETY: FFloat is repr(transparent)
+    unsafe { core::slice::from_raw_parts(x.as_ptr().cast(), x.len()) }
+}
+
+/// Reinterprets the slice as raw floats, mutably. The caller must [`check`](crate::r#trait::Wrapper::check) what it writes.
+#[inline(always)]
+fn raw_mut<T, P>(x: &mut [FFloat<T, P>]) -> &mut [T] {
+    // SAFETY: FFloat is repr(transparent)
+    unsafe { core::slice::from_raw_parts_mut(x.as_mut_ptr().cast(), x.len()) }
+}
+
+/// Asserts that two slices have the same length.
+#[track_caller]
+#[inline(always)]
+fn same<A, B>(a: &[A], b: &[B]) {
+    assert_eq!(a.len(), b.len(), "slice lengths differ");
+}
+
+/// Applies `f` to each pair of elements, unrolled by [`UNROLL`] (and [dispatched](dispatch)).
+#[inline(always)]
+fn zip<A, B>(a: &mut [A], b: &[B], f: impl Fn(&mut A, &B)) {
+    // the slices are passed as arguments (not captured), so that they keep their noalias information.
+    #[inline(always)]
+    fn unrolled<A, B>(a: &mut [A], b: &[B], f: impl Fn(&mut A, &B)) {
+        let mut ac = a.chunks_exact_mut(UNROLL);
+        let mut bc = b.chunks_exact(UNROLL);
+        for (a, b) in (&mut ac).zip(&mut bc) {
+            for i in 0..UNROLL {
+                f(&mut a[i], &b[i]);
+            }
+        }
+        for (a, b) in ac.into_remainder().iter_mut().zip(bc.remainder()) {
+            f(a, b);
+        }
+    }
+    dispatch(
+        #[inline(always)]
+        || unrolled(a, b, f),
+    );
+}
+
+/// Applies `f` to each element, unrolled by [`UNROLL`] (and [dispatched](dispatch)). Refer to [`zip`].
+#[inline(always)]
+fn each<A>(a: &mut [A], f: impl Fn(&mut A)) {
+    #[inline(always)]
+    fn unrolled<A>(a: &mut [A], f: impl Fn(&mut A)) {
+        let mut ac = a.chunks_exact_mut(UNROLL);
+        for a in &mut ac {
+            for a in a {
+                f(a);
+            }
+        }
+        for a in ac.into_remainder() {
+            f(a);
+        }
+    }
+    dispatch(
+        #[inline(always)]
+        || unrolled(a, f),
+    );
+}
+
+/// Applies `f` to each triple of elements, unrolled by [`UNROLL`] (and [dispatched](dispatch)). Refer to [`zip`].
+#[inline(always)]
+fn zip3<A, B, C>(a: &mut [A], b: &[B], c: &[C], f: impl Fn(&mut A, &B, &C)) {
+    #[inline(always)]
+    fn unrolled<A, B, C>(a: &mut [A], b: &[B], c: &[C], f: impl Fn(&mut A, &B, &C)) {
+        let mut ac = a.chunks_exact_mut(UNROLL);
+        let mut bc = b.chunks_exact(UNROLL);
+        let mut cc = c.chunks_exact(UNROLL);
+        for ((a, b), c) in (&mut ac).zip(&mut bc).zip(&mut cc) {
+            for i in 0..UNROLL {
+                f(&mut a[i], &b[i], &c[i]);
+            }
+        }
+        let remainder = bc.remainder().iter().zip(cc.remainder());
+        for (a, (b, c)) in ac.into_remainder().iter_mut().zip(remainder) {
+            f(a, b, c);
+        }
+    }
+    dispatch(
+        #[inline(always)]
+        || unrolled(a, b, c, f),
+    );
+}
@@ -0,0 +1,118 @@
+//! the `rayon` kernels.
+use super::{dot, same, sum_pairwise};
+use crate::generic_float::Constructors;
+use crate::policy::Policy;
+use crate::r#trait::FastFloat;
+use crate::FFloat;
+use rayon::prelude::*;
+
+/// The number of elements each task processes with the sequential kernels.
+/// Below this, splitting costs more than it saves.
+const CHUNK: usize = 1 << 14;
+
+/// The sum, split into chunks that are summed in parallel with [`sum_pairwise`].
+/// ```
+/// # use umath::{FFloat, slice};
+/// let v = vec![unsafe { FFloat::new(0.5f64) }; 1 << 20];
+/// assert_eq!(*slice::par_sum(&v), (1 << 19) as f64);
+/// ```
+#[must_use]
+pub fn par_sum<T, P>(x: &[FFloat<T, P>]) -> FFloat<T, P>
+where
+    T: FastFloat + Constructors + Send + Sync,
+    P: Policy + Send + Sync,
+{
+    x.par_chunks(CHUNK)
+        .map(sum_pairwise)
+        .reduce(|| sum_pairwise(&[]), |a, b| a + b)
+}
+
+/// The dot product, split into chunks that run [`dot`] in parallel.
+#[track_caller]
+#[must_use]
+pub fn par_dot<T, P>(a: &[FFloat<T, P>], b: &[FFloat<T, P>]) -> FFloat<T, P>
+where
+    T: FastFloat + Constructors + Send + Sync,
+    P: Policy + Send + Sync,
+{
+    same(a, b);
+    a.par_chunks(CHUNK)
+        .zip(b.par_chunks(CHUNK))
+        .map(|(a, b)| dot(a, b))
+        .reduce(|| sum_pairwise(&[]), |a, b| a + b)
+}
+
+/// Applies `f` to every element in parallel: `out[i] = f(x[i])`.
+/// ```
+/// # use umath::{FFloat, slice};
+/// let x = vec![unsafe { FFloat::new(3.0f32) }; 100_000];
+/// let mut out = x.clone();
+/// slice::par_map(&mut out, &x, |x| x * x);
+/// assert!(out.iter().all(|&x| x == 9.0));
+/// ```
+#[track_caller]
+pub fn par_map<T: Copy + Send + Sync, U: Send>(out: &mut [U], x: &[T], f: impl Fn(T) -> U + Sync) {
+    same(out, x);
+    out.par_chunks_mut(CHUNK)
+        .zip(x.par_chunks(CHUNK))
+        .for_each(|(out, x)| {
+            for (out, x) in out.iter_mut().zip(x) {
+                *out = f(*x);
+            }
+        });
+}
+
+macro_rules! par {
+    ($($par:ident, $par_out:ident => $assign:ident, $out:ident);+ $(;)?) => {
+        $(
+            #[doc = concat!("[`", stringify!($assign), "`](super::", stringify!($assign), "), split into chunks that run in parallel.")]
+            #[track_caller]
+            pub fn $par<T, P>(dst: &mut [FFloat<T, P>], src: &[FFloat<T, P>])
+            where
+                T: FastFloat + Send + Sync,
+                P: Policy + Send + Sync,
+            {
+                same(dst, src);
+                dst.par_chunks_mut(CHUNK)
+                    .zip(src.par_chunks(CHUNK))
+                    .for_each(|(dst, src)| super::$assign(dst, src));
+            }
+
+            #[doc = concat!("[`", stringify!($out), "`](super::", stringify!($out), "), split into chunks that run in parallel.")]
+            #[track_caller]
+            pub fn $par_out<T, P>(out: &mut [FFloat<T, P>], a: &[FFloat<T, P>], b: &[FFloat<T, P>])
+            where
+                T: FastFloat + Send + Sync,
+                P: Policy + Send + Sync,
+            {
+                same(out, a);
+                same(a, b);
+                out.par_chunks_mut(CHUNK)
+                    .zip(a.par_chunks(CHUNK))
+                    .zip(b.par_chunks(CHUNK))
+                    .for_each(|((out, a), b)| super::$out(out, a, b));
+            }
+        )+
+    };
+}
+
+par! {
+    par_add_assign, par_add => add_assign, add;
+    par_sub_assign, par_sub => sub_assign, sub;
+    par_mul_assign, par_mul => mul_assign, mul;
+    par_div_assign, par_div => div_assign, div;
+}
+
+#[test]
+#[allow(clippy::float_cmp, clippy::cast_precision_loss)]
+fn chunks() {
+    let v: Vec<_> = (0..CHUNK * 3 + 5)
+        .map(|x| unsafe { FFloat::new((x % 3) as f64) })
+        .collect();
+    assert_eq!(*par_sum(&v), *sum_pairwise(&v));
+    let mut out = v.clone();
+    par_add(&mut out, &v, &v);
+    par_sub_assign(&mut out, &v);
+    assert_eq!(out, v);
+    assert_eq!(*par_dot(&v, &v), *dot(&v, &v));
+}
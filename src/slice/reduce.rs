@@ -0,0 +1,480 @@
+use super::arch::allowed;
+use super::{dispatch, raw, same, Tuning, UNROLL};
+use crate::generic_float::{Constructors, FloatExt};
+use crate::policy::Policy;
+use crate::r#trait::{FastFloat, Wrapper};
+use crate::FFloat;
+use core::marker::PhantomData;
+
+/// `tuning`, with one accumulator if `P` does not allow reassociation, so that the reductions add in order.
+#[inline(always)]
+fn in_order<P: Policy>(tuning: Tuning) -> Tuning {
+    if P::REASSOC {
+        tuning
+    } else {
+        tuning.with_accumulators(1)
+    }
+}
+
+/// Adds the accumulators together, as a tree.
+#[inline(always)]
+pub(super) fn tree<T: FastFloat, P: Policy, const N: usize>(mut acc: [T; N]) -> T {
+    let mut n = N;
+    while n > 1 {
+        n /= 2;
+        for i in 0..n {
+            acc[i] = unsafe { T::add::<P>(acc[i], acc[i + n]) };
+        }
+    }
+    acc[0]
+}
+
+/// The dot product, `Σ a[i] * b[i]`.
+///
+/// This uses 8 (by default, refer to [`Tuning`]) independent accumulators (added together as a tree), so that the reduction vectorizes.
+/// If the policy does not allow reassociation (such as [`Ieee`](crate::policy::Ieee)), it is added in order instead, with one accumulator.
+/// Like a [`Unchecked`](crate::Unchecked) expression, only the result is checked.
+/// ```
+/// # use umath::{FFloat, slice};
+/// let v: Vec<_> = (1..=10).map(|x| unsafe { FFloat::new(x as f32) }).collect();
+/// assert_eq!(*slice::dot(&v, &v), 385.0);
+/// ```
+#[track_caller]
+pub fn dot<T: FastFloat + Constructors, P: Policy>(
+    a: &[FFloat<T, P>],
+    b: &[FFloat<T, P>],
+) -> FFloat<T, P> {
+    Tuning::global().dot(a, b)
+}
+
+/// [`dot`], with `tuning`.
+#[track_caller]
+pub(super) fn dot_tuned<T: FastFloat + Constructors, P: Policy>(
+    tuning: Tuning,
+    a: &[FFloat<T, P>],
+    b: &[FFloat<T, P>],
+) -> FFloat<T, P> {
+    same(a, b);
+    if allowed::<P>(tuning) {
+        if let Some(sum) = T::arch_dot(raw(a), raw(b)) {
+            return unsafe { FFloat::wrap(sum) };
+        }
+    }
+    let sum = accumulators!(in_order::<P>(tuning), N => dot_raw::<T, P, N>(a, b));
+    unsafe { FFloat::wrap(sum) }
+}
+
+/// The dot product with `N` independent accumulators, without checking.
+#[inline(always)]
+fn dot_raw<T: FastFloat + Constructors, P: Policy, const N: usize>(
+    a: &[FFloat<T, P>],
+    b: &[FFloat<T, P>],
+) -> T {
+    dispatch(
+        #[inline(always)]
+        || {
+            // SAFETY: 0 is a valid float
+            let mut acc = [unsafe { T::zero() }; N];
+            let (ac, bc) = (a.chunks_exact(N), b.chunks_exact(N));
+            let (ar, br) = (ac.remainder(), bc.remainder());
+            for (a, b) in ac.zip(bc) {
+                for i in 0..N {
+                    acc[i] = unsafe { T::add::<P>(acc[i], T::mul::<P>(a[i].0, b[i].0)) };
+                }
+            }
+            let mut sum = tree::<T, P, N>(acc);
+            for (a, b) in ar.iter().zip(br) {
+                sum = unsafe { T::add::<P>(sum, T::mul::<P>(a.0, b.0)) };
+            }
+            sum
+        },
+    )
+}
+
+/// Sums with the accumulators of `tuning`, without checking.
+#[inline(always)]
+fn sum_raw<T: FastFloat + Constructors, P: Policy>(tuning: Tuning, x: &[FFloat<T, P>]) -> T {
+    if allowed::<P>(tuning) {
+        if let Some(sum) = T::arch_sum(raw(x)) {
+            return sum;
+        }
+    }
+    accumulators!(in_order::<P>(tuning), N => sum_n::<T, P, N>(x, |x| x))
+}
+
+/// Sums `f(x[i])` with [`UNROLL`] independent accumulators (or in order, if `P` does not allow reassociation), without checking.
+#[inline(always)]
+pub(super) fn sum_map<T: FastFloat + Constructors, P: Policy>(
+    x: &[FFloat<T, P>],
+    f: impl Fn(T) -> T,
+) -> T {
+    if P::REASSOC {
+        sum_n::<T, P, UNROLL>(x, f)
+    } else {
+        sum_n::<T, P, 1>(x, f)
+    }
+}
+
+/// Sums `f(x[i])` with `N` independent accumulators, without checking.
+#[inline(always)]
+fn sum_n<T: FastFloat + Constructors, P: Policy, const N: usize>(
+    x: &[FFloat<T, P>],
+    f: impl Fn(T) -> T,
+) -> T {
+    dispatch(
+        #[inline(always)]
+        || {
+            // SAFETY: 0 is a valid float
+            let mut acc = [unsafe { T::zero() }; N];
+            let chunks = x.chunks_exact(N);
+            let rest = chunks.remainder();
+            for x in chunks {
+                for i in 0..N {
+                    acc[i] = unsafe { T::add::<P>(acc[i], f(x[i].0)) };
+                }
+            }
+            let mut sum = tree::<T, P, N>(acc);
+            for x in rest {
+                sum = unsafe { T::add::<P>(sum, f(x.0)) };
+            }
+            sum
+        },
+    )
+}
+
+/// The sum, `Σ x[i]`, added in any order (using 8 independent accumulators, by default).
+///
+/// This is the fastest sum, but its error grows linearly with the length. Refer to [`sum_pairwise`].
+/// If the policy does not allow reassociation, it is added in order.
+#[must_use]
+pub fn sum_fast<T: FastFloat + Constructors, P: Policy>(x: &[FFloat<T, P>]) -> FFloat<T, P> {
+    Tuning::global().sum_fast(x)
+}
+
+/// [`sum_fast`], with `tuning`.
+pub(super) fn sum_fast_tuned<T: FastFloat + Constructors, P: Policy>(
+    tuning: Tuning,
+    x: &[FFloat<T, P>],
+) -> FFloat<T, P> {
+    unsafe { FFloat::wrap(sum_raw(tuning, x)) }
+}
+
+/// The sum, `Σ x[i]`, using pairwise (recursive) summation.
+///
+/// The error only grows with `O(log n)`, while being nearly as fast as [`sum_fast`] (which it uses for blocks of 128 elements, by default).
+/// This is the recommended way to sum a slice.
+/// ```
+/// # use umath::{FFloat, slice};
+/// let v = vec![unsafe { FFloat::new(0.1f32) }; 1_000_000];
+/// assert!((*slice::sum_pairwise(&v) - 100_000.0).abs() < 0.1);
+/// ```
+#[must_use]
+pub fn sum_pairwise<T: FastFloat + Constructors, P: Policy>(x: &[FFloat<T, P>]) -> FFloat<T, P> {
+    Tuning::global().sum_pairwise(x)
+}
+
+/// [`sum_pairwise`], with `tuning`.
+pub(super) fn sum_pairwise_tuned<T: FastFloat + Constructors, P: Policy>(
+    tuning: Tuning,
+    x: &[FFloat<T, P>],
+) -> FFloat<T, P> {
+    fn rec<T: FastFloat + Constructors, P: Policy>(tuning: Tuning, x: &[FFloat<T, P>]) -> T {
+        if x.len() <= tuning.pairwise_block() {
+            return sum_raw(tuning, x);
+        }
+        let (a, b) = x.split_at(x.len() / 2);
+        unsafe { T::add::<P>(rec(tuning, a), rec(tuning, b)) }
+    }
+    unsafe { FFloat::wrap(rec(tuning, x)) }
+}
+
+/// The best element (and its index) seen in each of [`UNROLL`] lanes, for the index returning reductions.
+struct Lanes<T> {
+    value: [T; UNROLL],
+    index: [usize; UNROLL],
+}
+
+impl<T: FastFloat> Lanes<T> {
+    #[inline(always)]
+    fn new(first: &[T; UNROLL]) -> Self {
+        Self {
+            value: *first,
+            index: core::array::from_fn(|i| i),
+        }
+    }
+
+    /// Keeps the elements of the chunk (starting at `base`) that are strictly `better`, so each lane has the first best index.
+    #[inline(always)]
+    fn update(&mut self, base: usize, chunk: &[T; UNROLL], better: impl Fn(T, T) -> bool) {
+        for (i, &x) in chunk.iter().enumerate() {
+            if better(x, self.value[i]) {
+                self.value[i] = x;
+                self.index[i] = base + i;
+            }
+        }
+    }
+
+    /// Merges the lanes (breaking ties with the lowest index), then the `rest` (starting at `base`).
+    #[inline(always)]
+    fn finish(self, base: usize, rest: &[T], better: impl Fn(T, T) -> bool) -> usize {
+        let mut best = (self.value[0], self.index[0]);
+        for (&v, &i) in self.value.iter().zip(&self.index).skip(1) {
+            if better(v, best.0) || (!better(best.0, v) && i < best.1) {
+                best = (v, i);
+            }
+        }
+        for (i, &v) in rest.iter().enumerate() {
+            if better(v, best.0) {
+                best = (v, base + i);
+            }
+        }
+        best.1
+    }
+}
+
+/// Runs the lanes over `x`, or [`None`] if it is shorter than one chunk.
+#[inline(always)]
+fn lanes<T: FastFloat, const N: usize>(
+    x: &[T],
+    better: [fn(T, T) -> bool; N],
+) -> Option<[usize; N]> {
+    let (first, x) = x.split_first_chunk::<UNROLL>()?;
+    let mut lanes = better.map(|_| Lanes::new(first));
+    let chunks = x.chunks_exact(UNROLL);
+    let rest = chunks.remainder();
+    let base = UNROLL + x.len() - rest.len();
+    for (j, chunk) in chunks.enumerate() {
+        let chunk = chunk.try_into().unwrap();
+        for (lanes, better) in lanes.iter_mut().zip(better) {
+            lanes.update(UNROLL * (j + 1), chunk, better);
+        }
+    }
+    let mut out = [0; N];
+    for ((out, lanes), better) in out.iter_mut().zip(lanes).zip(better) {
+        *out = lanes.finish(base, rest, better);
+    }
+    Some(out)
+}
+
+/// The index of the first best element, for short slices.
+fn short<T: FastFloat>(x: &[T], better: fn(T, T) -> bool) -> Option<usize> {
+    let mut best = (*x.first()?, 0);
+    for (i, &v) in x.iter().enumerate().skip(1) {
+        if better(v, best.0) {
+            best = (v, i);
+        }
+    }
+    Some(best.1)
+}
+
+/// The index of the (first) smallest element, or [`None`] if `x` is empty.
+///
+/// [`FFloat`]s are not [`NAN`](f32::NAN) (unless the policy allows it, in which case the result is unspecified),
+/// so each of [`UNROLL`] lanes tracks its own minimum with a plain comparison, which vectorizes.
+/// ```
+/// # use umath::{FFloat, slice};
+/// let x = [3.0f32, 1.0, 4.0, 1.0, 5.0].map(|x| unsafe { FFloat::new(x) });
+/// assert_eq!(slice::argmin(&x), Some(1));
+/// assert_eq!(slice::argmax(&x), Some(4));
+/// assert_eq!(slice::min_max(&x), Some((1, 4)));
+/// ```
+#[must_use]
+pub fn argmin<T: FastFloat, P: Policy>(x: &[FFloat<T, P>]) -> Option<usize> {
+    let x = raw(x);
+    lanes(x, [|a, b| a < b])
+        .map(|[i]| i)
+        .or_else(|| short(x, |a, b| a < b))
+}
+
+/// The index of the (first) largest element, or [`None`] if `x` is empty. Refer to [`argmin`].
+#[must_use]
+pub fn argmax<T: FastFloat, P: Policy>(x: &[FFloat<T, P>]) -> Option<usize> {
+    let x = raw(x);
+    lanes(x, [|a, b| a > b])
+        .map(|[i]| i)
+        .or_else(|| short(x, |a, b| a > b))
+}
+
+/// The indices of the (first) smallest, and largest, elements, in one pass; or [`None`] if `x` is empty. Refer to [`argmin`].
+#[must_use]
+pub fn min_max<T: FastFloat, P: Policy>(x: &[FFloat<T, P>]) -> Option<(usize, usize)> {
+    let x = raw(x);
+    match lanes(x, [|a, b| a < b, |a, b| a > b]) {
+        Some([min, max]) => Some((min, max)),
+        None => Some((short(x, |a, b| a < b)?, short(x, |a, b| a > b)?)),
+    }
+}
+
+/// The basic aggregates of a slice, from [`summary`].
+///
+/// Summaries of consecutive parts can be [merged](Summary::merge), so a buffer can also be summarized in pieces (or in parallel).
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Summary<T: FastFloat, P: Policy = crate::policy::Fast> {
+    /// The sum of the elements (0 if there are none).
+    pub sum: FFloat<T, P>,
+    /// The smallest element (the maximum float if there are none).
+    pub min: FFloat<T, P>,
+    /// The largest element (the minimum float if there are none).
+    pub max: FFloat<T, P>,
+    /// The number of elements.
+    pub count: usize,
+}
+
+impl<T: FastFloat + FloatExt, P: Policy> Summary<T, P> {
+    /// The summary of no elements.
+    #[must_use]
+    pub fn empty() -> Self {
+        // SAFETY: 0, MIN, and MAX are finite
+        unsafe {
+            Self {
+                sum: FFloat::wrap(T::zero()),
+                min: FFloat::wrap(T::max()),
+                max: FFloat::wrap(T::min()),
+                count: 0,
+            }
+        }
+    }
+
+    /// Combines the summaries of two parts.
+    #[must_use]
+    pub fn merge(self, other: Self) -> Self {
+        Self {
+            sum: self.sum + other.sum,
+            min: if other.min < self.min {
+                other.min
+            } else {
+                self.min
+            },
+            max: if other.max > self.max {
+                other.max
+            } else {
+                self.max
+            },
+            count: self.count + other.count,
+        }
+    }
+
+    /// The mean, or [`None`] if there are no elements.
+    #[allow(clippy::cast_precision_loss)]
+    pub fn mean(self) -> Option<FFloat<T, P>> {
+        // SAFETY: a count is finite (even as a f32).
+        (self.count != 0).then(|| self.sum / unsafe { T::from_f64(self.count as f64) })
+    }
+}
+
+impl<T: FastFloat + FloatExt, P: Policy> Default for Summary<T, P> {
+    fn default() -> Self {
+        Self::empty()
+    }
+}
+
+/// The sum, minimum, maximum, and count of `x`, in one pass (with [`UNROLL`] lanes of each), instead of one pass per aggregate.
+/// ```
+/// # use umath::{FFloat, slice};
+/// let x = [3.0f32, 1.0, 4.0, 1.0, 5.0].map(|x| unsafe { FFloat::new(x) });
+/// let s = slice::summary(&x);
+/// assert_eq!((*s.sum, *s.min, *s.max, s.count), (14.0, 1.0, 5.0, 5));
+/// assert_eq!(s.mean().unwrap(), 2.8);
+/// ```
+#[must_use]
+pub fn summary<T: FastFloat + FloatExt, P: Policy>(x: &[FFloat<T, P>]) -> Summary<T, P> {
+    let empty = Summary::<T, P>::empty();
+    let (mut sum, mut min, mut max) = (
+        [empty.sum.0; UNROLL],
+        [empty.min.0; UNROLL],
+        [empty.max.0; UNROLL],
+    );
+    let chunks = raw(x).chunks_exact(UNROLL);
+    let rest = chunks.remainder();
+    for chunk in chunks {
+        for (i, &x) in chunk.iter().enumerate() {
+            sum[i] = unsafe { T::add::<P>(sum[i], x) };
+            if x < min[i] {
+                min[i] = x;
+            }
+            if x > max[i] {
+                max[i] = x;
+            }
+        }
+    }
+    let mut s = Summary {
+        sum: unsafe { FFloat::wrap(tree::<T, P, UNROLL>(sum)) },
+        // these are elements (or the empty summary), so they uphold the invariant.
+        min: FFloat(
+            min.into_iter()
+                .fold(min[0], |a, b| if b < a { b } else { a }),
+            PhantomData,
+        ),
+        max: FFloat(
+            max.into_iter()
+                .fold(max[0], |a, b| if b > a { b } else { a }),
+            PhantomData,
+        ),
+        count: x.len() - rest.len(),
+    };
+    for &x in rest {
+        let one = Summary {
+            sum: FFloat(x, PhantomData),
+            min: FFloat(x, PhantomData),
+            max: FFloat(x, PhantomData),
+            count: 1,
+        };
+        s = s.merge(one);
+    }
+    s
+}
+
+#[test]
+#[allow(clippy::float_cmp)]
+fn remainder() {
+    let v: Vec<_> = (0..21)
+        .map(|x| unsafe { FFloat::new(f64::from(x)) })
+        .collect();
+    assert_eq!(*dot(&v, &v), (0..21).map(|x| f64::from(x * x)).sum::<f64>());
+    assert_eq!(*sum_fast(&v), 210.0);
+    assert_eq!(*sum_pairwise(&v[..0]), 0.0);
+}
+
+#[test]
+fn extrema() {
+    // the extremes appear twice (in different lanes), and in the remainder.
+    let v: Vec<_> = [5, 0, 9, 2, 9, 0, 7, 3, 1, 9, 4, 0, 6, 8, 2, 0, 9, 5, 3]
+        .map(|x| unsafe { FFloat::new(f64::from(x)) })
+        .to_vec();
+    assert_eq!(argmin(&v), Some(1));
+    assert_eq!(argmax(&v), Some(2));
+    assert_eq!(min_max(&v[2..]), Some((3, 0)));
+    assert_eq!(min_max(&v[..3]), Some((1, 2)));
+    assert_eq!(argmin(&v[..0]), None);
+}
+
+#[test]
+#[allow(clippy::float_cmp)]
+fn summarize() {
+    let v: Vec<_> = (0..21)
+        .map(|x| unsafe { FFloat::new(f64::from((x * 7) % 11)) })
+        .collect();
+    let (a, b) = v.split_at(13);
+    let s = summary(&v);
+    assert_eq!(summary(a).merge(summary(b)), s);
+    assert_eq!((*s.min, *s.max, s.count), (0.0, 10.0, 21));
+    assert_eq!(summary(&v[..0]), Summary::empty());
+    assert_eq!(Summary::<f32>::default().mean(), None);
+}
+
+#[test]
+fn ieee() {
+    use crate::policy::Ieee;
+    // without reassociation (or contraction), the reductions round exactly like the plain loops.
+    let v: Vec<_> = (0..37)
+        .map(|x| unsafe { FFloat::<f64, Ieee>::with_policy(0.1 * f64::from(x) - 1.7) })
+        .collect();
+    let w: Vec<_> = v.iter().map(|&x| x * 0.3).collect();
+    let dot_in_order = v.iter().zip(&w).fold(0.0, |acc, (a, b)| acc + **a * **b);
+    let sum_in_order = v.iter().fold(0.0, |acc, x| acc + **x);
+    for tuning in [Tuning::DEFAULT, Tuning::DEFAULT.with_accumulators(16)] {
+        assert_eq!(tuning.dot(&v, &w).to_bits(), dot_in_order.to_bits());
+        assert_eq!(tuning.sum_fast(&v).to_bits(), sum_in_order.to_bits());
+    }
+    assert_eq!(dot(&v, &w).to_bits(), dot_in_order.to_bits());
+}
@@ -0,0 +1,246 @@
+use super::sum_fast;
+use crate::generic_float::{Constructors, FloatExt};
+use crate::policy::Policy;
+use crate::r#trait::FastFloat;
+use crate::FFloat;
+use std::collections::VecDeque;
+
+/// Rolling aggregates over the sliding windows of a slice, each updated in O(1) (amortized) per step.
+///
+/// Every iterator yields one item per full window: `len - window + 1` of them.
+/// ```
+/// # use umath::{FFloat, slice::Rolling};
+/// let x = [1.0f32, 3.0, 2.0, 5.0, 4.0].map(|x| unsafe { FFloat::new(x) });
+/// assert!(x.rolling_sum(2).eq([4.0, 5.0, 7.0, 9.0]));
+/// assert!(x.rolling_max(3).eq([3.0, 5.0, 5.0]));
+/// assert!(x.rolling_min(3).eq([1.0, 2.0, 2.0]));
+/// assert!(x.rolling_mean(5).eq([3.0]));
+/// ```
+pub trait Rolling<T: FastFloat, P: Policy> {
+    /// The sums of each window. Refer to [`RollingSum`].
+    ///
+    /// # Panics
+    ///
+    /// If `window` is 0.
+    fn rolling_sum(&self, window: usize) -> RollingSum<'_, T, P>;
+
+    /// The means of each window.
+    ///
+    /// # Panics
+    ///
+    /// If `window` is 0.
+    fn rolling_mean(&self, window: usize) -> RollingMean<'_, T, P>;
+
+    /// The minimum of each window, with a monotonic queue.
+    ///
+    /// # Panics
+    ///
+    /// If `window` is 0.
+    fn rolling_min(&self, window: usize) -> RollingMin<'_, T, P>;
+
+    /// The maximum of each window, with a monotonic queue.
+    ///
+    /// # Panics
+    ///
+    /// If `window` is 0.
+    fn rolling_max(&self, window: usize) -> RollingMax<'_, T, P>;
+}
+
+impl<T: FastFloat + FloatExt, P: Policy> Rolling<T, P> for [FFloat<T, P>] {
+    #[track_caller]
+    fn rolling_sum(&self, window: usize) -> RollingSum<'_, T, P> {
+        assert_ne!(window, 0, "window must not be 0");
+        RollingSum {
+            x: self,
+            window,
+            i: 0,
+            sum: None,
+        }
+    }
+
+    #[track_caller]
+    #[allow(clippy::cast_precision_loss)]
+    fn rolling_mean(&self, window: usize) -> RollingMean<'_, T, P> {
+        RollingMean {
+            sum: self.rolling_sum(window),
+            // SAFETY: 1 is a valid float, and a window length is finite (even as a f32).
+            scale: FFloat(unsafe { T::one() }, core::marker::PhantomData)
+                / unsafe { T::from_f64(window as f64) },
+        }
+    }
+
+    #[track_caller]
+    fn rolling_min(&self, window: usize) -> RollingMin<'_, T, P> {
+        Extremum::new(self, window)
+    }
+
+    #[track_caller]
+    fn rolling_max(&self, window: usize) -> RollingMax<'_, T, P> {
+        Extremum::new(self, window)
+    }
+}
+
+/// The iterator of [`Rolling::rolling_sum`].
+///
+/// Each step adds the element entering the window, and subtracts the one leaving it.
+/// As that accumulates rounding error, the sum is recomputed from scratch every `window` steps (which keeps the cost amortized O(1)).
+#[derive(Clone, Debug)]
+pub struct RollingSum<'a, T: FastFloat, P: Policy> {
+    x: &'a [FFloat<T, P>],
+    window: usize,
+    /// The start of the next window.
+    i: usize,
+    sum: Option<FFloat<T, P>>,
+}
+
+impl<T: FastFloat + Constructors, P: Policy> Iterator for RollingSum<'_, T, P> {
+    type Item = FFloat<T, P>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let window = self.x.get(self.i..self.i + self.window)?;
+        let sum = match self.sum {
+            Some(sum) if !self.i.is_multiple_of(self.window) => {
+                sum + window[self.window - 1] - self.x[self.i - 1]
+            }
+            _ => sum_fast(window),
+        };
+        self.i += 1;
+        self.sum = Some(sum);
+        Some(sum)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let n = (self.x.len() + 1).saturating_sub(self.i + self.window);
+        (n, Some(n))
+    }
+}
+
+impl<T: FastFloat + Constructors, P: Policy> ExactSizeIterator for RollingSum<'_, T, P> {}
+
+/// The iterator of [`Rolling::rolling_mean`].
+#[derive(Clone, Debug)]
+pub struct RollingMean<'a, T: FastFloat, P: Policy> {
+    sum: RollingSum<'a, T, P>,
+    scale: FFloat<T, P>,
+}
+
+impl<T: FastFloat + Constructors, P: Policy> Iterator for RollingMean<'_, T, P> {
+    type Item = FFloat<T, P>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.sum.next().map(|sum| sum * self.scale)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.sum.size_hint()
+    }
+}
+
+impl<T: FastFloat + Constructors, P: Policy> ExactSizeIterator for RollingMean<'_, T, P> {}
+
+/// The iterator of [`Rolling::rolling_min`].
+pub type RollingMin<'a, T, P> = Extremum<'a, T, P, false>;
+/// The iterator of [`Rolling::rolling_max`].
+pub type RollingMax<'a, T, P> = Extremum<'a, T, P, true>;
+
+/// The iterator of [`Rolling::rolling_min`] | [`Rolling::rolling_max`].
+///
+/// This keeps a monotonic queue of the indices that could still become the extremum, so each element is pushed and popped once.
+#[derive(Clone, Debug)]
+pub struct Extremum<'a, T: FastFloat, P: Policy, const MAX: bool> {
+    x: &'a [FFloat<T, P>],
+    window: usize,
+    /// The index of the next element to enter.
+    i: usize,
+    queue: VecDeque<usize>,
+}
+
+impl<'a, T: FastFloat, P: Policy, const MAX: bool> Extremum<'a, T, P, MAX> {
+    #[track_caller]
+    fn new(x: &'a [FFloat<T, P>], window: usize) -> Self {
+        assert_ne!(window, 0, "window must not be 0");
+        Self {
+            x,
+            window,
+            i: 0,
+            queue: VecDeque::with_capacity(window),
+        }
+    }
+
+    /// Checks if `a` beats (or ties) `b`, so `b` can never be the extremum while `a` is in the window.
+    fn beats(a: FFloat<T, P>, b: FFloat<T, P>) -> bool {
+        if MAX {
+            a >= b
+        } else {
+            a <= b
+        }
+    }
+
+    fn push(&mut self, i: usize) {
+        while let Some(&back) = self.queue.back() {
+            if !Self::beats(self.x[i], self.x[back]) {
+                break;
+            }
+            self.queue.pop_back();
+        }
+        self.queue.push_back(i);
+    }
+}
+
+impl<T: FastFloat, P: Policy, const MAX: bool> Iterator for Extremum<'_, T, P, MAX> {
+    type Item = FFloat<T, P>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.i >= self.x.len() {
+            return None;
+        }
+        if self.i == 0 {
+            if self.x.len() < self.window {
+                self.i = self.x.len();
+                return None;
+            }
+            for i in 0..self.window - 1 {
+                self.push(i);
+            }
+            self.i = self.window - 1;
+        }
+        self.push(self.i);
+        let start = self.i + 1 - self.window;
+        while self.queue.front().is_some_and(|&front| front < start) {
+            self.queue.pop_front();
+        }
+        self.i += 1;
+        Some(self.x[self.queue[0]])
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let n = if self.i == 0 {
+            (self.x.len() + 1).saturating_sub(self.window)
+        } else {
+            self.x.len() - self.i
+        };
+        (n, Some(n))
+    }
+}
+
+impl<T: FastFloat, P: Policy, const MAX: bool> ExactSizeIterator for Extremum<'_, T, P, MAX> {}
+
+#[test]
+#[allow(clippy::float_cmp)]
+fn windows() {
+    let x: Vec<_> = [5, 1, 4, 1, 5, 9, 2, 6, 5, 3, 5]
+        .map(|x| unsafe { FFloat::new(f64::from(x)) })
+        .to_vec();
+    for window in 1..=x.len() + 1 {
+        let w = x.windows(window);
+        let sum = x.rolling_sum(window);
+        assert_eq!(sum.len(), w.len());
+        assert!(sum.eq(w.clone().map(|w| w.iter().map(|x| **x).sum::<f64>())));
+        let min = x.rolling_min(window);
+        assert_eq!(min.len(), w.len());
+        assert!(min.eq(w.clone().map(|w| *w.iter().min().unwrap())));
+        assert!(x
+            .rolling_max(window)
+            .eq(w.map(|w| *w.iter().max().unwrap())));
+    }
+}
@@ -0,0 +1,199 @@
+use super::{same, sub, UNROLL};
+use crate::generic_float::{Constructors, FloatExt};
+use crate::policy::Policy;
+use crate::r#trait::{FastFloat, Wrapper};
+use crate::FFloat;
+
+/// The running `op` of one block, starting from `carry`, in `log2(UNROLL)` steps of shifted operations (which become vector shuffles).
+#[inline(always)]
+fn scan_block<T: FastFloat, P: Policy>(
+    x: &mut [FFloat<T, P>],
+    carry: FFloat<T, P>,
+    op: impl Fn(&mut FFloat<T, P>, FFloat<T, P>),
+) {
+    let mut shift = 1;
+    while shift < UNROLL {
+        for i in (shift..UNROLL).rev() {
+            let prev = x[i - shift];
+            op(&mut x[i], prev);
+        }
+        shift *= 2;
+    }
+    for x in &mut x[..UNROLL] {
+        op(x, carry);
+    }
+}
+
+/// A blocked inclusive scan, with `identity` as the initial carry.
+#[inline(always)]
+fn scan<T: FastFloat, P: Policy>(
+    x: &mut [FFloat<T, P>],
+    identity: FFloat<T, P>,
+    op: impl Fn(&mut FFloat<T, P>, FFloat<T, P>),
+) {
+    let mut carry = identity;
+    let mut chunks = x.chunks_exact_mut(UNROLL);
+    for x in &mut chunks {
+        scan_block(x, carry, &op);
+        carry = x[UNROLL - 1];
+    }
+    for x in chunks.into_remainder() {
+        op(x, carry);
+        carry = *x;
+    }
+}
+
+/// The cumulative (prefix) sum, in place: `x[i] = x[0] + … + x[i]`.
+///
+/// Blocks of [`UNROLL`](super) elements are summed as a tree, then offset by the total of the blocks before, so this vectorizes;
+/// the additions are reassociated, so the results can differ from a sequential sum in the last bits.
+/// ```
+/// # use umath::{FFloat, slice};
+/// let mut cdf = [0.1f32, 0.2, 0.3, 0.4].map(|x| unsafe { FFloat::new(x) });
+/// slice::cumsum(&mut cdf);
+/// assert!((*cdf[3] - 1.0).abs() < 1e-6);
+/// ```
+pub fn cumsum<T: FastFloat + Constructors, P: Policy>(x: &mut [FFloat<T, P>]) {
+    // SAFETY: 0 is a valid float
+    scan(x, unsafe { FFloat::wrap(T::zero()) }, |x, y| *x += y);
+}
+
+/// The cumulative (prefix) sum: `out[i] = x[0] + … + x[i]`. Refer to [`cumsum`].
+#[track_caller]
+pub fn cumsum_into<T: FastFloat + Constructors, P: Policy>(
+    out: &mut [FFloat<T, P>],
+    x: &[FFloat<T, P>],
+) {
+    same(out, x);
+    out.copy_from_slice(x);
+    cumsum(out);
+}
+
+/// The cumulative product, in place: `x[i] = x[0] * … * x[i]`.
+///
+/// This is blocked like [`cumsum`]. Long products tend to zero, or overflow (breaking the invariant); for those, use [`cumprod_scaled`].
+/// ```
+/// # use umath::{FFloat, slice};
+/// let mut x = [2.0f32, 3.0, 4.0].map(|x| unsafe { FFloat::new(x) });
+/// slice::cumprod(&mut x);
+/// assert_eq!(x, [2.0, 6.0, 24.0]);
+/// ```
+pub fn cumprod<T: FastFloat + Constructors, P: Policy>(x: &mut [FFloat<T, P>]) {
+    // SAFETY: 1 is a valid float
+    scan(x, unsafe { FFloat::wrap(T::one()) }, |x, y| *x *= y);
+}
+
+/// The cumulative product: `out[i] = x[0] * … * x[i]`. Refer to [`cumprod`].
+#[track_caller]
+pub fn cumprod_into<T: FastFloat + Constructors, P: Policy>(
+    out: &mut [FFloat<T, P>],
+    x: &[FFloat<T, P>],
+) {
+    same(out, x);
+    out.copy_from_slice(x);
+    cumprod(out);
+}
+
+/// The renormalization step of [`cumprod_scaled`], as a power of two.
+const SCALE_BITS: i32 = 32;
+
+/// The cumulative product, in place, with a separate scaling exponent: `x[0] * … * x[i] = x[i] * 2^exponents[i]`.
+///
+/// Whenever the running product leaves `2^-32..=2^32`, it is rescaled by a (exact) power of two, and the exponent adjusted,
+/// so this neither drifts to zero nor overflows, over any length (as long as no single element exceeds `MAX / 2^32`).
+/// This is sequential.
+/// ```
+/// # use umath::{FFloat, slice};
+/// let mut x = vec![unsafe { FFloat::new(1e-3f32) }; 100];
+/// let mut exponents = vec![0; 100];
+/// slice::cumprod_scaled(&mut x, &mut exponents);
+/// // 1e-300 = x * 2^exponent
+/// let log10 = x[99].log10() + exponents[99] as f32 * 2f32.log10();
+/// assert!((log10 + 300.0).abs() < 1e-2);
+/// ```
+///
+/// # Panics
+///
+/// If the lengths differ.
+#[track_caller]
+pub fn cumprod_scaled<T: FastFloat + FloatExt, P: Policy>(
+    x: &mut [FFloat<T, P>],
+    exponents: &mut [i32],
+) {
+    same(x, exponents);
+    // SAFETY: 0, 1, and 2^±32 are finite (even as a f32).
+    let [zero, one, up, down] = [0.0, 1.0, 2f64.powi(SCALE_BITS), 2f64.powi(-SCALE_BITS)]
+        .map(|x| unsafe { FFloat::<T, P>::wrap(T::from_f64(x)) });
+    let (mut product, mut exponent) = (one, 0);
+    for (x, e) in x.iter_mut().zip(exponents) {
+        product *= *x;
+        while product.0 > up.0 || product.0 < -up.0 {
+            product *= down;
+            exponent += SCALE_BITS;
+        }
+        while product.0 != zero.0 && product.0 < down.0 && product.0 > -down.0 {
+            product *= up;
+            exponent -= SCALE_BITS;
+        }
+        (*x, *e) = (product, exponent);
+    }
+}
+
+/// The adjacent differences, `dst[i] = src[i + 1] - src[i]`: the inverse of [`cumsum`], for derivative estimation and delta encoding.
+/// ```
+/// # use umath::{FFloat, slice};
+/// let f = |x| unsafe { FFloat::new(x) };
+/// let mut deltas = [f(0.0f32); 3];
+/// slice::diff(&[1.0, 4.0, 9.0, 16.0].map(f), &mut deltas);
+/// assert_eq!(deltas, [3.0, 5.0, 7.0]);
+/// ```
+///
+/// # Panics
+///
+/// If `dst` is not one element shorter than `src` (or empty, if `src` is).
+#[track_caller]
+pub fn diff<T: FastFloat, P: Policy>(src: &[FFloat<T, P>], dst: &mut [FFloat<T, P>]) {
+    assert_eq!(
+        dst.len(),
+        src.len().saturating_sub(1),
+        "dst must be one shorter than src"
+    );
+    if let Some(next) = src.get(1..) {
+        sub(dst, next, &src[..dst.len()]);
+    }
+}
+
+#[test]
+#[allow(clippy::float_cmp, clippy::cast_precision_loss)]
+fn running() {
+    let x: Vec<_> = (1..=21)
+        .map(|x| unsafe { FFloat::new(f64::from(x)) })
+        .collect();
+    let mut out = x.clone();
+    cumsum_into(&mut out, &x);
+    for (i, x) in out.iter().enumerate() {
+        let n = i as f64 + 1.0;
+        assert_eq!(**x, n * (n + 1.0) / 2.0);
+    }
+}
+
+#[test]
+#[allow(clippy::float_cmp)]
+fn scaled() {
+    let f = |x| unsafe { FFloat::new(x) };
+    let mut x = vec![f(1e20f32); 20];
+    x.push(f(0.0));
+    let mut exponents = vec![0; x.len()];
+    cumprod_scaled(&mut x, &mut exponents);
+    // 1e400 would overflow a f64
+    let log2 = f64::from(*x[19]).log2() + f64::from(exponents[19]);
+    assert!((log2 - 400.0 * 10f64.log2()).abs() < 1e-3);
+    assert_eq!(*x[20], 0.0);
+    let mut y = [f(2.0); 11];
+    cumprod(&mut y);
+    assert_eq!(*y[10], 2048.0);
+    let mut d = [f(0.0); 10];
+    diff(&y, &mut d);
+    assert_eq!(d[..2], [2.0, 4.0]);
+    diff::<f32, crate::policy::Fast>(&[], &mut []);
+}
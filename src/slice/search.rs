@@ -0,0 +1,176 @@
+use super::same;
+use crate::policy::{NoNan, Policy};
+use crate::r#trait::FastFloat;
+use crate::FFloat;
+use core::cmp::Ordering;
+
+/// The index of the first element for which `pred` is false, given that `x` is partitioned (true for a prefix, then false).
+///
+/// Unlike [`slice::partition_point`](https://doc.rust-lang.org/std/primitive.slice.html#method.partition_point),
+/// the loop runs a fixed number of times (`log2(len)`), and picks the half with a conditional move instead of a branch,
+/// so the searches do not stall on mispredictions.
+/// ```
+/// # use umath::{FFloat, slice};
+/// let x = [1.0f32, 2.0, 2.0, 3.0].map(|x| unsafe { FFloat::new(x) });
+/// assert_eq!(slice::partition_point(&x, |x| x < 2.0), 1);
+/// assert_eq!(slice::partition_point(&x, |x| x <= 2.0), 3);
+/// ```
+pub fn partition_point<T: FastFloat, P: Policy>(
+    x: &[FFloat<T, P>],
+    pred: impl Fn(FFloat<T, P>) -> bool,
+) -> usize {
+    if x.is_empty() {
+        return 0;
+    }
+    let (mut base, mut size) = (0, x.len());
+    while size > 1 {
+        let half = size / 2;
+        let mid = base + half;
+        base = if pred(x[mid]) { mid } else { base };
+        size -= half;
+    }
+    base + usize::from(pred(x[base]))
+}
+
+/// Turns the lower bound `i` of `v` into a [`binary_search`](https://doc.rust-lang.org/std/primitive.slice.html#method.binary_search) result.
+fn found<T: FastFloat, P: Policy>(
+    x: &[FFloat<T, P>],
+    i: usize,
+    v: FFloat<T, P>,
+) -> Result<usize, usize> {
+    match x.get(i) {
+        Some(&e) if e == v => Ok(i),
+        _ => Err(i),
+    }
+}
+
+/// Searches the ascending `x` for `v`: [`Ok`] with the index of the first match, or [`Err`] with the index where `v` could be inserted.
+///
+/// This is branchless: refer to [`partition_point`].
+/// ```
+/// # use umath::{FFloat, slice};
+/// let f = |x| unsafe { FFloat::new(x) };
+/// let x = [1.0f32, 2.0, 2.0, 3.0].map(f);
+/// assert_eq!(slice::binary_search(&x, f(2.0)), Ok(1));
+/// assert_eq!(slice::binary_search(&x, f(2.5)), Err(3));
+/// ```
+///
+/// # Errors
+///
+/// If `v` is not in `x`, with the index that keeps `x` sorted if `v` is inserted there.
+pub fn binary_search<T: FastFloat, P: NoNan>(
+    x: &[FFloat<T, P>],
+    v: FFloat<T, P>,
+) -> Result<usize, usize> {
+    found(x, partition_point(x, |e| e < v), v)
+}
+
+/// Searches the ascending `x` for `v`, like [`binary_search`], but guesses the position by linearly interpolating between the bounds.
+///
+/// For evenly spread data (timestamps, sample positions) this takes O(log log n) probes, instead of O(log n).
+/// As uneven data can make the guesses arbitrarily bad, after `log2(len)` guesses this finishes with a binary search,
+/// so it is never much slower than one.
+/// ```
+/// # use umath::{FFloat, slice};
+/// let f = |x| unsafe { FFloat::new(x) };
+/// let x: Vec<_> = (0..1000).map(|x| f(x as f64 * 0.5)).collect();
+/// assert_eq!(slice::interpolation_search(&x, f(321.5)), Ok(643));
+/// assert_eq!(slice::interpolation_search(&x, f(-1.0)), Err(0));
+/// ```
+///
+/// # Errors
+///
+/// If `v` is not in `x`: refer to [`binary_search`].
+pub fn interpolation_search<T: FastFloat + Into<f64>, P: NoNan>(
+    x: &[FFloat<T, P>],
+    v: FFloat<T, P>,
+) -> Result<usize, usize> {
+    let f = |x: FFloat<T, P>| -> f64 { x.0.into() };
+    // x[..lo] < v, and x[hi..] >= v
+    let (mut lo, mut hi) = (0, x.len());
+    for _ in 0..x.len().checked_ilog2().unwrap_or(0) {
+        if hi - lo < 8 {
+            break;
+        }
+        let (first, last) = (x[lo], x[hi - 1]);
+        if v <= first {
+            hi = lo;
+            break;
+        }
+        if v > last {
+            lo = hi;
+            break;
+        }
+        // first < v <= last
+        let t = (f(v) - f(first)) / (f(last) - f(first));
+        #[allow(
+            clippy::cast_precision_loss,
+            clippy::cast_possible_truncation,
+            clippy::cast_sign_loss
+        )]
+        let mid = (lo + (t * (hi - 1 - lo) as f64) as usize).clamp(lo, hi - 1);
+        match x[mid].cmp(&v) {
+            Ordering::Less => lo = mid + 1,
+            _ => hi = mid,
+        }
+    }
+    found(x, lo + partition_point(&x[lo..hi], |e| e < v), v)
+}
+
+/// Evaluates the piecewise linear function through the points `(xs[i], ys[i])` at `x`, where `xs` is ascending.
+///
+/// The segment is found with [`partition_point`]; outside of `xs`, the first | last `y` is returned (the ends are clamped).
+/// ```
+/// # use umath::{FFloat, slice};
+/// let f = |x| unsafe { FFloat::new(x) };
+/// let (xs, ys) = ([0.0f32, 1.0, 3.0].map(f), [0.0, 10.0, 30.0].map(f));
+/// assert_eq!(slice::lookup_interpolated(&xs, &ys, f(2.0)), 20.0);
+/// assert_eq!(slice::lookup_interpolated(&xs, &ys, f(5.0)), 30.0);
+/// ```
+///
+/// # Panics
+///
+/// If the lengths differ, or the table is empty.
+#[track_caller]
+pub fn lookup_interpolated<T: FastFloat, P: NoNan>(
+    xs: &[FFloat<T, P>],
+    ys: &[FFloat<T, P>],
+    x: FFloat<T, P>,
+) -> FFloat<T, P> {
+    same(xs, ys);
+    assert!(!xs.is_empty(), "table must not be empty");
+    let i = partition_point(xs, |e| e <= x);
+    if i == 0 {
+        return ys[0];
+    }
+    if i == xs.len() {
+        return ys[i - 1];
+    }
+    // xs[i - 1] <= x < xs[i], so this does not divide by zero.
+    let t = (x - xs[i - 1]) / (xs[i] - xs[i - 1]);
+    (t.lazy() * (ys[i] - ys[i - 1]) + ys[i - 1]).finish()
+}
+
+#[test]
+#[allow(clippy::float_cmp)]
+fn searches() {
+    let f = |x| unsafe { FFloat::new(x) };
+    // uneven, with duplicates
+    let x: Vec<_> = (0..200).map(|i| f(f64::from(i / 3).powi(3))).collect();
+    for v in [-1.0, 0.0, 1.0, 8.0, 9.0, 27.0, 1e3, 2e5, 3e5, 1e9] {
+        let expected = x.partition_point(|&e| e < v);
+        let expected = if x.get(expected) == Some(&f(v)) {
+            Ok(expected)
+        } else {
+            Err(expected)
+        };
+        assert_eq!(binary_search(&x, f(v)), expected);
+        assert_eq!(interpolation_search(&x, f(v)), expected);
+    }
+    assert_eq!(binary_search(&[], f(1.0)), Err(0));
+    let xs = [0.0, 1.0, 1.0, 2.0].map(f);
+    let ys = [0.0, 1.0, 3.0, 5.0].map(f);
+    assert_eq!(lookup_interpolated(&xs, &ys, f(1.0)), 3.0);
+    assert_eq!(lookup_interpolated(&xs, &ys, f(1.5)), 4.0);
+    assert_eq!(lookup_interpolated(&xs, &ys, f(-1.0)), 0.0);
+}
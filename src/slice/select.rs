@@ -0,0 +1,73 @@
+use crate::policy::NoNan;
+use crate::r#trait::FastFloat;
+use crate::FFloat;
+
+/// The `n`th smallest element (counting from 0), found by quickselect in O(n).
+///
+/// `x` is reordered so that the element is at index `n`, everything before it is not greater, and everything after it is not smaller:
+/// so the `n` smallest elements are `x[..n]` (in no particular order). This is how to take a median or percentile without sorting.
+/// ```
+/// # use umath::{FFloat, slice};
+/// let mut x = [9.0f32, 1.0, 8.0, 2.0, 7.0].map(|x| unsafe { FFloat::new(x) });
+/// let median = slice::select_nth(&mut x, 2);
+/// assert_eq!(median, 7.0);
+/// assert!(x[..2].iter().all(|&x| x < median));
+/// ```
+///
+/// # Panics
+///
+/// If `n >= x.len()`.
+#[track_caller]
+pub fn select_nth<T: FastFloat, P: NoNan>(x: &mut [FFloat<T, P>], n: usize) -> FFloat<T, P> {
+    *x.select_nth_unstable(n).1
+}
+
+/// The `k` largest elements, largest first, moved to the front of `x` (the rest are left in no particular order).
+///
+/// This selects in O(n), and then only sorts the `k` selected, so it is much cheaper than a full sort when `k` is small.
+/// If `k > x.len()`, this sorts all of `x`.
+/// ```
+/// # use umath::{FFloat, slice};
+/// let mut x = [3.0f32, 1.0, 4.0, 1.0, 5.0, 9.0, 2.0, 6.0].map(|x| unsafe { FFloat::new(x) });
+/// assert_eq!(slice::top_k(&mut x, 3), [9.0, 6.0, 5.0]);
+/// assert_eq!(slice::bottom_k(&mut x, 2), [1.0, 1.0]);
+/// ```
+pub fn top_k<T: FastFloat, P: NoNan>(x: &mut [FFloat<T, P>], k: usize) -> &mut [FFloat<T, P>] {
+    let k = k.min(x.len());
+    if k != 0 && k < x.len() {
+        x.select_nth_unstable_by(k - 1, |a, b| b.cmp(a));
+    }
+    let top = &mut x[..k];
+    top.sort_unstable_by(|a, b| b.cmp(a));
+    top
+}
+
+/// The `k` smallest elements, smallest first, moved to the front of `x`. Refer to [`top_k`].
+pub fn bottom_k<T: FastFloat, P: NoNan>(x: &mut [FFloat<T, P>], k: usize) -> &mut [FFloat<T, P>] {
+    let k = k.min(x.len());
+    if k != 0 && k < x.len() {
+        x.select_nth_unstable(k - 1);
+    }
+    let bottom = &mut x[..k];
+    bottom.sort_unstable();
+    bottom
+}
+
+#[test]
+fn selection() {
+    let x: Vec<_> = (0..101)
+        .map(|i| unsafe { FFloat::new(f64::from((i * 37) % 101)) })
+        .collect();
+    let mut sorted = x.clone();
+    sorted.sort_unstable();
+    for n in [0, 1, 50, 99, 100] {
+        assert_eq!(select_nth(&mut x.clone(), n), sorted[n]);
+    }
+    for k in [0, 1, 10, 101, 200] {
+        let k = k.min(x.len());
+        assert_eq!(bottom_k(&mut x.clone(), k), &sorted[..k]);
+        assert!(top_k(&mut x.clone(), k)
+            .iter()
+            .eq(sorted.iter().rev().take(k)));
+    }
+}
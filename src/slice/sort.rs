@@ -0,0 +1,50 @@
+use crate::convert::NotFinite;
+use crate::generic_float::Constructors;
+use crate::policy::Fast;
+use crate::r#trait::FastFloat;
+use crate::FFloat;
+
+/// Sorting of plain float slices, through the total order of [`FFloat`].
+///
+/// The slice is checked once, up front, and then sorted as `[FFloat]`, whose comparisons need no NAN handling
+/// (unlike [`f32::total_cmp`], or `partial_cmp().unwrap()`), so the sort is branchless.
+/// ```
+/// # use umath::slice::SortFloats;
+/// let mut x = [3.0f32, -1.0, 2.5, 0.0];
+/// x.sort_fast().unwrap();
+/// assert_eq!(x, [-1.0, 0.0, 2.5, 3.0]);
+/// assert!([1.0, f64::NAN].sort_fast().is_err());
+/// ```
+pub trait SortFloats {
+    /// Sorts the slice, ascending, with the unstable sort.
+    ///
+    /// # Errors
+    ///
+    /// If any element is [`NAN`](f32::NAN) | [`INF`](f32::INFINITY), leaving the slice untouched.
+    fn sort_fast(&mut self) -> Result<(), NotFinite>;
+}
+
+impl<T: FastFloat + Constructors> SortFloats for [T] {
+    fn sort_fast(&mut self) -> Result<(), NotFinite> {
+        if self.iter().any(|x| x.bad::<Fast>()) {
+            return Err(NotFinite);
+        }
+        // SAFETY: FFloat is repr(transparent), and every element upholds its invariant.
+        let x: &mut [FFloat<T>] =
+            unsafe { core::slice::from_raw_parts_mut(self.as_mut_ptr().cast(), self.len()) };
+        x.sort_unstable();
+        Ok(())
+    }
+}
+
+#[test]
+#[allow(clippy::float_cmp)]
+fn sorted() {
+    let mut x: Vec<_> = (0..100).map(|i| f64::from((i * 37) % 101) - 50.0).collect();
+    x.sort_fast().unwrap();
+    assert!(x.is_sorted());
+    x[7] = f64::INFINITY;
+    let copy = x.clone();
+    assert_eq!(x.sort_fast(), Err(NotFinite));
+    assert_eq!(x, copy);
+}
@@ -0,0 +1,308 @@
+use super::reduce::tree;
+use super::UNROLL;
+use crate::generic_float::Constructors;
+use crate::policy::Policy;
+use crate::r#trait::{FastFloat, Wrapper};
+use crate::FFloat;
+use core::iter::StepBy;
+use core::ops::{Index, IndexMut};
+
+/// A view of every `stride`th element of a slice: a column of a row major matrix, one channel of interleaved audio, or one field of a array of structs.
+///
+/// The kernels are methods, so that these layouts get the same routines as contiguous slices, without copying into a temporary.
+/// A view with a stride of 1 uses the contiguous kernels.
+/// ```
+/// # use umath::{FFloat, slice::Strided};
+/// let f = |x| unsafe { FFloat::new(x) };
+/// // a 3 × 2 matrix
+/// let m = [1.0f32, 2.0, 3.0, 4.0, 5.0, 6.0].map(f);
+/// let column = Strided::new(&m[1..], 2);
+/// assert_eq!(column.len(), 3);
+/// assert_eq!(column[2], 6.0);
+/// assert_eq!(*column.sum(), 12.0);
+/// assert_eq!(*column.dot(Strided::new(&m, 2)), 44.0);
+/// ```
+#[derive(Debug)]
+pub struct Strided<'a, T> {
+    data: &'a [T],
+    stride: usize,
+}
+
+impl<T> Clone for Strided<'_, T> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<T> Copy for Strided<'_, T> {}
+
+/// The mutable version of [`Strided`].
+/// ```
+/// # use umath::{FFloat, slice::{Strided, StridedMut}};
+/// let f = |x| unsafe { FFloat::new(x) };
+/// // stereo, interleaved: [l, r, l, r]
+/// let mut audio = [0.5f32, 1.0, 0.25, 1.0].map(f);
+/// let gain = [f(2.0), f(4.0)];
+/// StridedMut::new(&mut audio, 2).mul_assign(Strided::from(&gain[..]));
+/// assert_eq!(audio, [1.0, 1.0, 1.0, 1.0]);
+/// ```
+#[derive(Debug)]
+pub struct StridedMut<'a, T> {
+    data: &'a mut [T],
+    stride: usize,
+}
+
+macro_rules! view {
+    ($view:ident $(, $mut:ident)?) => {
+        impl<'a, T> $view<'a, T> {
+            /// Creates a view of `data[0]`, `data[stride]`, `data[2 * stride]`, and so on. Offset the view by slicing `data`.
+            ///
+            /// # Panics
+            ///
+            /// If `stride` is 0.
+            #[track_caller]
+            pub fn new(data: &'a $($mut)? [T], stride: usize) -> Self {
+                assert_ne!(stride, 0, "stride must not be 0");
+                Self { data, stride }
+            }
+
+            /// The distance between elements.
+            pub fn stride(&self) -> usize {
+                self.stride
+            }
+
+            /// The number of elements.
+            pub fn len(&self) -> usize {
+                self.data.len().div_ceil(self.stride)
+            }
+
+            /// Checks if there are no elements.
+            pub fn is_empty(&self) -> bool {
+                self.data.is_empty()
+            }
+
+            /// Returns the `i`th element, or [`None`] if out of bounds.
+            pub fn get(&self, i: usize) -> Option<&T> {
+                self.data.get(i.checked_mul(self.stride)?)
+            }
+
+            /// Iterates over the elements.
+            pub fn iter(&self) -> StepBy<core::slice::Iter<'_, T>> {
+                self.data.iter().step_by(self.stride)
+            }
+
+            /// The elements, if they are contiguous (the stride is 1).
+            pub fn as_slice(&self) -> Option<&[T]> {
+                (self.stride == 1).then_some(&*self.data)
+            }
+        }
+
+        impl<T> Index<usize> for $view<'_, T> {
+            type Output = T;
+
+            #[track_caller]
+            fn index(&self, i: usize) -> &T {
+                &self.data[i * self.stride]
+            }
+        }
+
+        impl<'a, T> IntoIterator for &'a $view<'_, T> {
+            type Item = &'a T;
+            type IntoIter = StepBy<core::slice::Iter<'a, T>>;
+
+            fn into_iter(self) -> Self::IntoIter {
+                self.iter()
+            }
+        }
+
+        impl<'a, T> From<&'a $($mut)? [T]> for $view<'a, T> {
+            fn from(data: &'a $($mut)? [T]) -> Self {
+                Self { data, stride: 1 }
+            }
+        }
+    };
+}
+
+view!(Strided);
+view!(StridedMut, mut);
+
+impl<T> StridedMut<'_, T> {
+    /// Returns the `i`th element mutably, or [`None`] if out of bounds.
+    pub fn get_mut(&mut self, i: usize) -> Option<&mut T> {
+        self.data.get_mut(i.checked_mul(self.stride)?)
+    }
+
+    /// Iterates over the elements mutably.
+    pub fn iter_mut(&mut self) -> StepBy<core::slice::IterMut<'_, T>> {
+        self.data.iter_mut().step_by(self.stride)
+    }
+
+    /// The elements, if they are contiguous (the stride is 1).
+    pub fn as_mut_slice(&mut self) -> Option<&mut [T]> {
+        (self.stride == 1).then_some(&mut *self.data)
+    }
+
+    /// Reborrows as a immutable view.
+    #[must_use]
+    pub fn as_view(&self) -> Strided<'_, T> {
+        Strided {
+            data: self.data,
+            stride: self.stride,
+        }
+    }
+
+    /// Copies `src` into this view: [`copy`](super::copy).
+    ///
+    /// # Panics
+    ///
+    /// If the lengths differ.
+    #[track_caller]
+    pub fn copy_from(&mut self, src: Strided<'_, T>)
+    where
+        T: Copy,
+    {
+        self.zip(src, |d, s| *d = *s);
+    }
+
+    /// Applies `f` to each pair of elements.
+    #[track_caller]
+    fn zip<B>(&mut self, src: Strided<'_, B>, f: impl Fn(&mut T, &B)) {
+        assert_eq!(self.len(), src.len(), "slice lengths differ");
+        match (self.as_mut_slice(), src.as_slice()) {
+            (Some(a), Some(b)) => super::zip(a, b, f),
+            _ => self.iter_mut().zip(src.iter()).for_each(|(a, b)| f(a, b)),
+        }
+    }
+}
+
+impl<'a, T> IntoIterator for &'a mut StridedMut<'_, T> {
+    type Item = &'a mut T;
+    type IntoIter = StepBy<core::slice::IterMut<'a, T>>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter_mut()
+    }
+}
+
+impl<T> IndexMut<usize> for StridedMut<'_, T> {
+    #[track_caller]
+    fn index_mut(&mut self, i: usize) -> &mut T {
+        &mut self.data[i * self.stride]
+    }
+}
+
+/// Adds up `x` with [`UNROLL`] independent accumulators, without checking.
+/// If `P` does not allow reassociation, it is added in order instead, with one accumulator.
+#[inline(always)]
+fn accumulate<T: FastFloat + Constructors, P: Policy>(mut x: impl Iterator<Item = T>) -> T {
+    // SAFETY: 0 is a valid float
+    let zero = unsafe { T::zero() };
+    if !P::REASSOC {
+        return x.fold(zero, |acc, x| unsafe { T::add::<P>(acc, x) });
+    }
+    let mut acc = [zero; UNROLL];
+    'out: loop {
+        for acc in &mut acc {
+            let Some(x) = x.next() else { break 'out };
+            *acc = unsafe { T::add::<P>(*acc, x) };
+        }
+    }
+    tree::<T, P, UNROLL>(acc)
+}
+
+impl<T: FastFloat + Constructors, P: Policy> Strided<'_, FFloat<T, P>> {
+    /// The dot product: [`dot`](super::dot).
+    ///
+    /// # Panics
+    ///
+    /// If the lengths differ.
+    #[track_caller]
+    #[must_use]
+    pub fn dot(self, other: Strided<'_, FFloat<T, P>>) -> FFloat<T, P> {
+        assert_eq!(self.len(), other.len(), "slice lengths differ");
+        if let (Some(a), Some(b)) = (self.as_slice(), other.as_slice()) {
+            return super::dot(a, b);
+        }
+        let x = self
+            .iter()
+            .zip(other.iter())
+            .map(|(a, b)| unsafe { T::mul::<P>(a.0, b.0) });
+        unsafe { FFloat::wrap(accumulate::<T, P>(x)) }
+    }
+
+    /// The sum: [`sum_fast`](super::sum_fast).
+    #[must_use]
+    pub fn sum(self) -> FFloat<T, P> {
+        if let Some(x) = self.as_slice() {
+            return super::sum_fast(x);
+        }
+        unsafe { FFloat::wrap(accumulate::<T, P>(self.iter().map(|x| x.0))) }
+    }
+}
+
+macro_rules! elementwise {
+    ($($assign:ident => $op:tt, $verb:literal);+ $(;)?) => {
+        $(
+            #[doc = concat!("Element wise ", $verb, ", in place: [`", stringify!($assign), "`](super::", stringify!($assign), ").")]
+            ///
+            /// # Panics
+            ///
+            /// If the lengths differ.
+            #[track_caller]
+            pub fn $assign(&mut self, src: Strided<'_, FFloat<T, P>>) {
+                self.zip(src, |d, s| *d $op *s);
+            }
+        )+
+    };
+}
+
+impl<T: FastFloat, P: Policy> StridedMut<'_, FFloat<T, P>> {
+    elementwise! {
+        add_assign => +=, "addition";
+        sub_assign => -=, "subtraction";
+        mul_assign => *=, "multiplication";
+        div_assign => /=, "division";
+    }
+
+    /// `self += a * x`: [`axpy`](super::axpy).
+    ///
+    /// # Panics
+    ///
+    /// If the lengths differ.
+    #[track_caller]
+    pub fn axpy(&mut self, a: FFloat<T, P>, x: Strided<'_, FFloat<T, P>>) {
+        self.zip(x, |y, x| *y = (a.lazy() * *x + *y).finish());
+    }
+
+    /// `self *= a`: [`scal`](super::scal).
+    pub fn scal(&mut self, a: FFloat<T, P>) {
+        for x in self.iter_mut() {
+            *x *= a;
+        }
+    }
+}
+
+#[test]
+#[allow(clippy::float_cmp)]
+fn columns() {
+    let f = |x| unsafe { FFloat::new(x) };
+    // a 5 × 3 matrix, row major
+    let mut m: Vec<_> = (0..15).map(|x| f(f64::from(x))).collect();
+    let last = Strided::new(&m[2..], 3);
+    assert_eq!(
+        last.iter().map(|x| **x).collect::<Vec<_>>(),
+        [2., 5., 8., 11., 14.]
+    );
+    assert_eq!(last.get(5), None);
+    assert_eq!(*last.sum(), 40.0);
+    let ones = [f(1.0); 5];
+    let mut first = StridedMut::new(&mut m, 3);
+    first.axpy(f(2.0), Strided::from(&ones[..]));
+    first.scal(f(0.5));
+    assert_eq!(*first.as_view().dot(Strided::from(&ones[..])), 20.0);
+    assert_eq!(m[12], 7.0);
+    // a policy without reassociation adds in order: ((1 + 1e100) + 1) - 1e100
+    let g = |x| unsafe { FFloat::<f64, crate::policy::Ieee>::with_policy(x) };
+    let x = [1.0, 0.0, 1e100, 0.0, 1.0, 0.0, -1e100, 0.0].map(g);
+    assert_eq!(*Strided::new(&x, 2).sum(), 0.0);
+}
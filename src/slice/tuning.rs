@@ -0,0 +1,212 @@
+use super::blas::gemm_tuned;
+use super::reduce::{dot_tuned, sum_fast_tuned, sum_pairwise_tuned};
+use crate::generic_float::Constructors;
+use crate::policy::Policy;
+use crate::r#trait::FastFloat;
+use crate::FFloat;
+use core::sync::atomic::{AtomicUsize, Ordering::Relaxed};
+
+/// The tunable parameters of the reduction and gemm kernels, so that they can be fit to a cache hierarchy.
+///
+/// The plain kernels ([`dot`](super::dot), [`sum_fast`](super::sum_fast), [`sum_pairwise`](super::sum_pairwise), [`gemm`](super::gemm))
+/// use the [global](Tuning::global) tuning; the methods of the same names use this one.
+/// ```
+/// # use umath::{FFloat, slice::{self, Tuning}};
+/// let v = vec![unsafe { FFloat::new(0.5f64) }; 1000];
+/// let tuning = Tuning::DEFAULT.with_accumulators(16).with_pairwise_block(256);
+/// assert_eq!(*tuning.sum_pairwise(&v), 500.0);
+/// tuning.set_global();
+/// assert_eq!(*slice::sum_pairwise(&v), 500.0);
+/// # Tuning::DEFAULT.set_global();
+/// ```
+///
+/// The explicit kernels of aarch64 | wasm32 have a fixed accumulator count, so they are only used with the default one.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Tuning {
+    accumulators: usize,
+    pairwise_block: usize,
+    kc: usize,
+    nc: usize,
+}
+
+static GLOBAL: [AtomicUsize; 4] = {
+    let Tuning {
+        accumulators,
+        pairwise_block,
+        kc,
+        nc,
+    } = Tuning::DEFAULT;
+    [
+        AtomicUsize::new(accumulators),
+        AtomicUsize::new(pairwise_block),
+        AtomicUsize::new(kc),
+        AtomicUsize::new(nc),
+    ]
+};
+
+impl Tuning {
+    /// The defaults: 8 accumulators, pairwise blocks of 128 elements, and gemm panels of 256 × 128.
+    pub const DEFAULT: Self = Self {
+        accumulators: 8,
+        pairwise_block: 128,
+        kc: 256,
+        nc: 128,
+    };
+
+    /// The global tuning, used by the plain kernels.
+    #[must_use]
+    pub fn global() -> Self {
+        let [accumulators, pairwise_block, kc, nc] = GLOBAL.each_ref().map(|x| x.load(Relaxed));
+        Self {
+            accumulators,
+            pairwise_block,
+            kc,
+            nc,
+        }
+    }
+
+    /// Sets the global tuning. (Kernels that run at the same time may see a mix of the old and new parameters.)
+    pub fn set_global(self) {
+        let [accumulators, pairwise_block, kc, nc] = &GLOBAL;
+        accumulators.store(self.accumulators, Relaxed);
+        pairwise_block.store(self.pairwise_block, Relaxed);
+        kc.store(self.kc, Relaxed);
+        nc.store(self.nc, Relaxed);
+    }
+
+    /// Sets the number of independent accumulators (which is also the unroll factor) of the reductions.
+    /// More hide more latency, but need more registers.
+    ///
+    /// # Panics
+    ///
+    /// If `n` is not one of 1, 2, 4, 8, 16.
+    #[must_use]
+    #[track_caller]
+    pub const fn with_accumulators(self, n: usize) -> Self {
+        assert!(
+            n.is_power_of_two() && n <= 16,
+            "accumulators must be one of 1, 2, 4, 8, 16"
+        );
+        Self {
+            accumulators: n,
+            ..self
+        }
+    }
+
+    /// Sets the length below which [`sum_pairwise`](super::sum_pairwise) stops splitting.
+    ///
+    /// # Panics
+    ///
+    /// If `n` is 0.
+    #[must_use]
+    #[track_caller]
+    pub const fn with_pairwise_block(self, n: usize) -> Self {
+        assert!(n != 0, "the pairwise block must not be empty");
+        Self {
+            pairwise_block: n,
+            ..self
+        }
+    }
+
+    /// Sets the `kc × nc` panel of `B` that [`gemm`](super::gemm) keeps in cache.
+    ///
+    /// # Panics
+    ///
+    /// If either is 0.
+    #[must_use]
+    #[track_caller]
+    pub const fn with_gemm_panel(self, kc: usize, nc: usize) -> Self {
+        assert!(kc != 0 && nc != 0, "the gemm panel must not be empty");
+        Self { kc, nc, ..self }
+    }
+
+    /// The number of independent accumulators.
+    #[must_use]
+    pub const fn accumulators(self) -> usize {
+        self.accumulators
+    }
+
+    /// The length below which [`sum_pairwise`](super::sum_pairwise) stops splitting.
+    #[must_use]
+    pub const fn pairwise_block(self) -> usize {
+        self.pairwise_block
+    }
+
+    /// The `(kc, nc)` panel of [`gemm`](super::gemm).
+    #[must_use]
+    pub const fn gemm_panel(self) -> (usize, usize) {
+        (self.kc, self.nc)
+    }
+
+    /// [`dot`](super::dot), with this tuning.
+    #[track_caller]
+    #[must_use]
+    pub fn dot<T: FastFloat + Constructors, P: Policy>(
+        self,
+        a: &[FFloat<T, P>],
+        b: &[FFloat<T, P>],
+    ) -> FFloat<T, P> {
+        dot_tuned(self, a, b)
+    }
+
+    /// [`sum_fast`](super::sum_fast), with this tuning.
+    #[must_use]
+    pub fn sum_fast<T: FastFloat + Constructors, P: Policy>(
+        self,
+        x: &[FFloat<T, P>],
+    ) -> FFloat<T, P> {
+        sum_fast_tuned(self, x)
+    }
+
+    /// [`sum_pairwise`](super::sum_pairwise), with this tuning.
+    #[must_use]
+    pub fn sum_pairwise<T: FastFloat + Constructors, P: Policy>(
+        self,
+        x: &[FFloat<T, P>],
+    ) -> FFloat<T, P> {
+        sum_pairwise_tuned(self, x)
+    }
+
+    /// [`gemm`](super::gemm), with this tuning.
+    #[track_caller]
+    #[allow(clippy::too_many_arguments, clippy::many_single_char_names)]
+    pub fn gemm<T: FastFloat + Constructors, P: Policy>(
+        self,
+        alpha: FFloat<T, P>,
+        a: &[FFloat<T, P>],
+        b: &[FFloat<T, P>],
+        beta: FFloat<T, P>,
+        c: &mut [FFloat<T, P>],
+        m: usize,
+        k: usize,
+        n: usize,
+    ) {
+        gemm_tuned(self, alpha, a, b, beta, c, m, k, n);
+    }
+}
+
+impl Default for Tuning {
+    fn default() -> Self {
+        Self::DEFAULT
+    }
+}
+
+#[test]
+#[allow(clippy::float_cmp)]
+fn tuned() {
+    let v: Vec<_> = (0..100)
+        .map(|x| unsafe { FFloat::new(f64::from(x)) })
+        .collect();
+    for n in [1, 2, 4, 8, 16] {
+        let tuning = Tuning::DEFAULT.with_accumulators(n).with_pairwise_block(7);
+        assert_eq!(*tuning.dot(&v, &v), 328_350.0);
+        assert_eq!(*tuning.sum_fast(&v), 4950.0);
+        assert_eq!(*tuning.sum_pairwise(&v), 4950.0);
+    }
+    let mut c = [unsafe { FFloat::new(0.0) }; 100];
+    let tuning = Tuning::DEFAULT.with_gemm_panel(3, 5);
+    tuning.gemm(v[1], &v, &v, v[0], &mut c, 10, 10, 10);
+    let mut expected = c;
+    super::gemm(v[1], &v, &v, v[0], &mut expected, 10, 10, 10);
+    assert_eq!(c, expected);
+}
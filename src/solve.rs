@@ -0,0 +1,113 @@
+//! the internal linear solvers: dense, and tridiagonal.
+use crate::generic_float::FloatAlone;
+
+/// Solves `a · x = b` for `x` (written over `b`), with gaussian elimination and partial pivoting.
+/// `a` is a row major `n × n` matrix, and is destroyed.
+///
+/// Returns [`None`] if `a` is singular (or so close to it that the solution would not be meaningful, or finite).
+#[track_caller]
+#[allow(clippy::cast_precision_loss)]
+pub(crate) fn solve<F: FloatAlone>(a: &mut [F], b: &mut [F]) -> Option<()> {
+    let n = b.len();
+    assert_eq!(a.len(), n * n, "matrix is not square");
+    // SAFETY: 0 and ε are finite
+    let (zero, ε) = unsafe { (F::zero(), F::ε()) };
+    let scale = a.iter().fold(zero, |m, &x| m.max(x.abs()));
+    // SAFETY: n is finite.
+    let tiny = scale * ε * unsafe { F::from_f64(n as f64) };
+    for col in 0..n {
+        let pivot = (col..n).fold(col, |best, row| {
+            if a[row * n + col].abs() > a[best * n + col].abs() {
+                row
+            } else {
+                best
+            }
+        });
+        if a[pivot * n + col].abs() <= tiny {
+            return None;
+        }
+        if pivot != col {
+            for k in 0..n {
+                a.swap(pivot * n + k, col * n + k);
+            }
+            b.swap(pivot, col);
+        }
+        let diagonal = a[col * n + col];
+        for row in col + 1..n {
+            let factor = a[row * n + col] / diagonal;
+            if factor == zero {
+                continue;
+            }
+            for k in col..n {
+                let above = a[col * n + k];
+                a[row * n + k] -= factor * above;
+            }
+            let above = b[col];
+            b[row] -= factor * above;
+        }
+    }
+    for col in (0..n).rev() {
+        let mut sum = b[col];
+        for k in col + 1..n {
+            sum -= a[col * n + k] * b[k];
+        }
+        b[col] = sum / a[col * n + col];
+    }
+    Some(())
+}
+
+/// Solves the tridiagonal system `lower[i - 1] x[i - 1] + diagonal[i] x[i] + upper[i] x[i + 1] = b[i]` for `x` (written over `b`),
+/// with the thomas algorithm (elimination without pivoting, in `O(n)`). `diagonal` is destroyed.
+///
+/// This is only stable for diagonally dominant systems (such as those of splines).
+/// Returns [`None`] if a pivot is 0.
+#[track_caller]
+pub(crate) fn tridiagonal<F: FloatAlone>(
+    lower: &[F],
+    diagonal: &mut [F],
+    upper: &[F],
+    b: &mut [F],
+) -> Option<()> {
+    let n = b.len();
+    assert!(
+        diagonal.len() == n && lower.len() + 1 == n.max(1) && upper.len() + 1 == n.max(1),
+        "slice lengths differ"
+    );
+    // SAFETY: 0 is a valid float
+    let zero = unsafe { F::zero() };
+    for i in 1..n {
+        if diagonal[i - 1] == zero {
+            return None;
+        }
+        let factor = lower[i - 1] / diagonal[i - 1];
+        diagonal[i] -= factor * upper[i - 1];
+        let above = b[i - 1];
+        b[i] -= factor * above;
+    }
+    for i in (0..n).rev() {
+        if diagonal[i] == zero {
+            return None;
+        }
+        let below = if i + 1 < n { upper[i] * b[i + 1] } else { zero };
+        b[i] = (b[i] - below) / diagonal[i];
+    }
+    Some(())
+}
+
+#[test]
+fn solved() {
+    let mut a = [0.0, 2.0, 1.0, 1.0, 1.0, 1.0, 2.0, 1.0, 3.0];
+    let mut b = [7.0, 6.0, 13.0];
+    solve::<f64>(&mut a, &mut b).unwrap();
+    for (x, e) in b.iter().zip([1.0, 2.0, 3.0]) {
+        assert!((x - e).abs() < 1e-12);
+    }
+    let mut singular = [1.0, 2.0, 2.0, 4.0];
+    assert_eq!(solve::<f64>(&mut singular, &mut [1.0, 2.0]), None);
+    // 2x + y = 4, x + 3y + z = 9.5, y + 2z = 7
+    let mut b = [4.0, 9.5, 7.0];
+    tridiagonal::<f64>(&[1.0, 1.0], &mut [2.0, 3.0, 2.0], &[1.0, 1.0], &mut b).unwrap();
+    for (x, e) in b.iter().zip([1.0, 2.0, 2.5]) {
+        assert!((x - e).abs() < 1e-12);
+    }
+}
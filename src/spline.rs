@@ -0,0 +1,311 @@
+//! piecewise cubic interpolation of tables: cubic splines, and monotone (PCHIP) interpolants.
+//!
+//! Both are stored as cubic hermite pieces (a value and slope at each knot), and differ in how the slopes are chosen:
+//! a [cubic spline](Spline::cubic) has a continuous second derivative, so it is as smooth as possible, but can overshoot between the points;
+//! a [monotone](Spline::monotone) interpolant never overshoots (it is monotone wherever the data is), at the cost of only a continuous first derivative.
+//! ```
+//! # use umath::{FFloat, spline::{Boundary, Spline}};
+//! let f = |x| unsafe { FFloat::new(x) };
+//! let x = [0.0, 1.0, 2.0, 3.0].map(f);
+//! let y = [0.0, 1.0, 8.0, 27.0].map(f);
+//! let spline = Spline::cubic(&x, &y, Boundary::Natural).unwrap();
+//! assert_eq!(*spline.eval(f(2.0)), 8.0);
+//! let monotone = Spline::monotone(&x, &y).unwrap();
+//! assert!(*monotone.eval(f(0.5)) >= 0.0);
+//! ```
+use crate::generic_float::{lit, FloatAlone};
+use crate::solve::tridiagonal;
+
+/// The end conditions of a [cubic spline](Spline::cubic).
+#[derive(Copy, Clone, Debug, Default, PartialEq)]
+pub enum Boundary<F> {
+    /// The second derivative is 0 at both ends.
+    #[default]
+    Natural,
+    /// The first derivative at the ends is given: `(start, end)`.
+    Clamped(F, F),
+}
+
+/// A piecewise cubic interpolant through a table of points. Refer to the [module level documentation](self).
+///
+/// Outside of the table, the first (or last) piece is extended.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Spline<F> {
+    x: Vec<F>,
+    y: Vec<F>,
+    slopes: Vec<F>,
+}
+
+/// The slopes of the secants between consecutive points, or [`None`] if `x` is not strictly increasing.
+fn secants<F: FloatAlone>(x: &[F], y: &[F]) -> Option<Vec<F>> {
+    x.windows(2)
+        .zip(y.windows(2))
+        .map(|(x, y)| (x[1] > x[0]).then(|| (y[1] - y[0]) / (x[1] - x[0])))
+        .collect()
+}
+
+/// Checks the table: the lengths must match, there must be at least 2 points, and `x` must be strictly increasing.
+#[track_caller]
+fn table<F: FloatAlone>(x: &[F], y: &[F]) -> Option<Vec<F>> {
+    assert_eq!(x.len(), y.len(), "slice lengths differ");
+    if x.len() < 2 {
+        return None;
+    }
+    secants(x, y)
+}
+
+impl<F: FloatAlone> Spline<F> {
+    /// The cubic spline through the points `(x[i], y[i])`, with `boundary` conditions at the ends.
+    ///
+    /// Returns [`None`] if there are fewer than 2 points, or `x` is not strictly increasing.
+    ///
+    /// # Panics
+    ///
+    /// If `x` and `y` differ in length.
+    /// ```
+    /// # use umath::spline::{Boundary, Spline};
+    /// // a clamped spline reproduces a cubic exactly
+    /// let x = [0.0, 0.5, 2.0, 3.0];
+    /// let y = x.map(|x: f64| x * x * x);
+    /// let spline = Spline::cubic(&x, &y, Boundary::Clamped(0.0, 27.0)).unwrap();
+    /// assert!((spline.eval(1.0) - 1.0).abs() < 1e-12);
+    /// assert!((spline.derivative(1.0) - 3.0).abs() < 1e-12);
+    /// ```
+    #[must_use]
+    #[track_caller]
+    pub fn cubic(x: &[F], y: &[F], boundary: Boundary<F>) -> Option<Self> {
+        let secant = table(x, y)?;
+        let n = x.len();
+        let width: Vec<F> = x.windows(2).map(|x| x[1] - x[0]).collect();
+        // SAFETY: 0 and 1 are valid floats
+        let (zero, one) = unsafe { (F::zero(), F::one()) };
+        let (two, three) = (lit::<F>(2.0), lit::<F>(3.0));
+        // continuity of the second derivative at each interior knot:
+        // w[i] m[i - 1] + 2 (w[i - 1] + w[i]) m[i] + w[i - 1] m[i + 1] = 3 (w[i] s[i - 1] + w[i - 1] s[i])
+        let mut lower = vec![zero; n - 1];
+        let mut diagonal = vec![zero; n];
+        let mut upper = vec![zero; n - 1];
+        let mut slopes = vec![zero; n];
+        for i in 1..n - 1 {
+            lower[i - 1] = width[i];
+            diagonal[i] = (width[i - 1] + width[i]) * two;
+            upper[i] = width[i - 1];
+            slopes[i] = (width[i] * secant[i - 1] + width[i - 1] * secant[i]) * three;
+        }
+        match boundary {
+            Boundary::Natural => {
+                (diagonal[0], upper[0], slopes[0]) = (two, one, secant[0] * three);
+                (lower[n - 2], diagonal[n - 1], slopes[n - 1]) = (one, two, secant[n - 2] * three);
+            }
+            Boundary::Clamped(start, end) => {
+                (diagonal[0], upper[0], slopes[0]) = (one, zero, start);
+                (lower[n - 2], diagonal[n - 1], slopes[n - 1]) = (zero, one, end);
+            }
+        }
+        tridiagonal(&lower, &mut diagonal, &upper, &mut slopes)?;
+        Some(Self {
+            x: x.to_vec(),
+            y: y.to_vec(),
+            slopes,
+        })
+    }
+
+    /// The monotone piecewise cubic interpolant (PCHIP) through the points `(x[i], y[i])`, with the slopes of Fritsch and Butland:
+    /// 0 at local extrema, and a weighted harmonic mean of the neighbouring secants otherwise, so that no piece overshoots.
+    ///
+    /// Returns [`None`] if there are fewer than 2 points, or `x` is not strictly increasing.
+    ///
+    /// # Panics
+    ///
+    /// If `x` and `y` differ in length.
+    /// ```
+    /// # use umath::spline::Spline;
+    /// // a step: a cubic spline would ring around it
+    /// let x = [0.0, 1.0, 2.0, 3.0, 4.0];
+    /// let y = [0.0, 0.0, 1.0, 1.0, 1.0];
+    /// let pchip = Spline::monotone(&x, &y).unwrap();
+    /// let samples = pchip.resample(0.0, 4.0, 41);
+    /// assert!(samples.windows(2).all(|w| w[0] <= w[1]));
+    /// assert!(samples.iter().all(|&y| (0.0..=1.0).contains(&y)));
+    /// ```
+    #[must_use]
+    #[track_caller]
+    pub fn monotone(x: &[F], y: &[F]) -> Option<Self> {
+        let secant = table(x, y)?;
+        let n = x.len();
+        let width: Vec<F> = x.windows(2).map(|x| x[1] - x[0]).collect();
+        // SAFETY: 0 is a valid float
+        let zero = unsafe { F::zero() };
+        let (two, three) = (lit::<F>(2.0), lit::<F>(3.0));
+        let sign = |x: F| x.partial_cmp(&zero);
+        let mut slopes = vec![secant[0]; n];
+        for i in 1..n - 1 {
+            let (before, after) = (secant[i - 1], secant[i]);
+            slopes[i] = if sign(before) != sign(after) || before == zero || after == zero {
+                zero
+            } else {
+                let w1 = width[i] * two + width[i - 1];
+                let w2 = width[i] + width[i - 1] * two;
+                (w1 + w2) / (w1 / before + w2 / after)
+            };
+        }
+        if n > 2 {
+            // a three point estimate at each end, limited to keep the end pieces monotone.
+            let end = |h0: F, h1: F, s0: F, s1: F| {
+                let slope = ((h0 * two + h1) * s0 - h0 * s1) / (h0 + h1);
+                if sign(slope) != sign(s0) {
+                    zero
+                } else if sign(s0) != sign(s1) && slope.abs() > (s0 * three).abs() {
+                    s0 * three
+                } else {
+                    slope
+                }
+            };
+            slopes[0] = end(width[0], width[1], secant[0], secant[1]);
+            slopes[n - 1] = end(width[n - 2], width[n - 3], secant[n - 2], secant[n - 3]);
+        }
+        Some(Self {
+            x: x.to_vec(),
+            y: y.to_vec(),
+            slopes,
+        })
+    }
+
+    /// The knots.
+    #[must_use]
+    pub fn x(&self) -> &[F] {
+        &self.x
+    }
+
+    /// The values at the knots.
+    #[must_use]
+    pub fn y(&self) -> &[F] {
+        &self.y
+    }
+
+    /// The first derivative at the knots.
+    #[must_use]
+    pub fn slopes(&self) -> &[F] {
+        &self.slopes
+    }
+
+    /// The piece containing `x` (the first or last piece, outside of the table), and the position `t` in it, from 0 to 1.
+    fn piece(&self, x: F) -> (usize, F, F) {
+        let i = self
+            .x
+            .partition_point(|&knot| knot <= x)
+            .clamp(1, self.x.len() - 1)
+            - 1;
+        let width = self.x[i + 1] - self.x[i];
+        (i, width, (x - self.x[i]) / width)
+    }
+
+    /// The interpolated value at `x`.
+    #[must_use]
+    pub fn eval(&self, x: F) -> F {
+        let (i, width, t) = self.piece(x);
+        let (one, two, three) = (lit::<F>(1.0), lit::<F>(2.0), lit::<F>(3.0));
+        let t2 = t * t;
+        let t3 = t2 * t;
+        let h00 = t3 * two - t2 * three + one;
+        let h10 = t3 - t2 * two + t;
+        let h01 = t2 * three - t3 * two;
+        let h11 = t3 - t2;
+        h00 * self.y[i]
+            + h10 * width * self.slopes[i]
+            + h01 * self.y[i + 1]
+            + h11 * width * self.slopes[i + 1]
+    }
+
+    /// The first derivative of the interpolant at `x`.
+    #[must_use]
+    pub fn derivative(&self, x: F) -> F {
+        let (i, width, t) = self.piece(x);
+        let (one, two, three, four, six) = (
+            lit::<F>(1.0),
+            lit::<F>(2.0),
+            lit::<F>(3.0),
+            lit::<F>(4.0),
+            lit::<F>(6.0),
+        );
+        let t2 = t * t;
+        let h00 = (t2 - t) * six;
+        let h10 = t2 * three - t * four + one;
+        let h11 = t2 * three - t * two;
+        (h00 * (self.y[i] - self.y[i + 1])) / width
+            + h10 * self.slopes[i]
+            + h11 * self.slopes[i + 1]
+    }
+
+    /// Evaluates the interpolant at every `x`, into `out`.
+    ///
+    /// # Panics
+    ///
+    /// If `x` and `out` differ in length.
+    #[track_caller]
+    pub fn eval_slice(&self, x: &[F], out: &mut [F]) {
+        assert_eq!(x.len(), out.len(), "slice lengths differ");
+        for (&x, out) in x.iter().zip(out) {
+            *out = self.eval(x);
+        }
+    }
+
+    /// Evaluates the interpolant at `count` evenly spaced points from `start` to `end` (inclusive).
+    /// ```
+    /// # use umath::spline::Spline;
+    /// let line = Spline::monotone(&[0.0, 10.0], &[0.0, 5.0]).unwrap();
+    /// assert_eq!(line.resample(0.0f64, 10.0, 3), [0.0, 2.5, 5.0]);
+    /// ```
+    #[must_use]
+    #[allow(clippy::cast_precision_loss)]
+    pub fn resample(&self, start: F, end: F, count: usize) -> Vec<F> {
+        let step = (end - start) / lit((count.max(2) - 1) as f64);
+        (0..count)
+            .map(|i| {
+                if i + 1 == count && count > 1 {
+                    end
+                } else {
+                    start + step * lit(i as f64)
+                }
+            })
+            .map(|x| self.eval(x))
+            .collect()
+    }
+}
+
+#[test]
+fn interpolated() {
+    let x = [0.0, 0.7, 1.5, 2.0, 3.2, 4.0];
+    let y = x.map(f64::sin);
+    for spline in [
+        Spline::cubic(&x, &y, Boundary::Natural).unwrap(),
+        Spline::cubic(&x, &y, Boundary::Clamped(1.0, 4f64.cos())).unwrap(),
+        Spline::monotone(&x, &y).unwrap(),
+    ] {
+        // through the points
+        for (&x, &y) in x.iter().zip(&y) {
+            assert!((spline.eval(x) - y).abs() < 1e-12);
+        }
+        // close to the function, and its derivative
+        for i in 0..=40u8 {
+            let at = f64::from(i) / 10.0;
+            assert!((spline.eval(at) - at.sin()).abs() < 0.05, "{at}");
+            assert!((spline.derivative(at) - at.cos()).abs() < 0.3, "{at}");
+        }
+    }
+    // the natural spline has no curvature at the ends
+    let natural = Spline::cubic(&x, &y, Boundary::Natural).unwrap();
+    let curvature = |x: f64| (natural.derivative(x + 1e-6) - natural.derivative(x - 1e-6)) / 2e-6;
+    assert!(curvature(0.0).abs() < 1e-4 && curvature(4.0).abs() < 1e-4);
+    assert_eq!(
+        Spline::cubic(&[1.0, 1.0], &[0.0, 1.0], Boundary::Natural),
+        None
+    );
+    assert_eq!(Spline::monotone(&[1.0], &[0.0]), None);
+    // a monotone interpolant of monotone data is monotone, even when the data is very uneven.
+    let x = [0.0, 1.0, 1.1, 5.0, 5.5, 9.0];
+    let y = [0.0, 0.1, 3.0, 3.1, 9.0, 9.0];
+    let pchip = Spline::monotone(&x, &y).unwrap();
+    let samples = pchip.resample(0.0, 9.0, 901);
+    assert!(samples.windows(2).all(|w| w[0] <= w[1] + 1e-12));
+}
@@ -0,0 +1,205 @@
+use crate::generic_float::{lit, FloatExt, FloatMethods};
+use crate::policy::{NoNan, Policy};
+use crate::r#trait::{FastFloat, Wrapper};
+use crate::slice::raw;
+use crate::FFloat;
+
+/// Lanes per accumulator, so the sums vectorize.
+const LANES: usize = 8;
+
+/// The means of two slices, and their co-moments: `Σ (x - mean_x)²`, `Σ (y - mean_y)²`, and `Σ (x - mean_x)(y - mean_y)`, or [`None`] if they are empty.
+///
+/// These are computed in one pass, from the sums of the deviations from the first pair of elements (so that a large mean does not cancel the spread).
+#[track_caller]
+#[allow(clippy::cast_precision_loss)]
+pub(super) fn comoments<T: FastFloat + FloatExt, P: Policy>(
+    x: &[FFloat<T, P>],
+    y: &[FFloat<T, P>],
+) -> Option<[FFloat<T, P>; 5]> {
+    assert_eq!(x.len(), y.len(), "slice lengths differ");
+    let (x, y) = (raw(x), raw(y));
+    let (&sx, &sy) = (x.first()?, y.first()?);
+    let (add, sub, mul) = (T::add::<P>, T::sub::<P>, T::mul::<P>);
+    // SAFETY: 0 is a valid float
+    let zero = unsafe { T::zero() };
+    // Σ dx, Σ dy, Σ dx², Σ dy², Σ dx dy
+    let mut acc = [[zero; LANES]; 5];
+    let mut each = |lane: usize, x: T, y: T| unsafe {
+        let (dx, dy) = (sub(x, sx), sub(y, sy));
+        for (sums, v) in acc
+            .iter_mut()
+            .zip([dx, dy, mul(dx, dx), mul(dy, dy), mul(dx, dy)])
+        {
+            sums[lane] = add(sums[lane], v);
+        }
+    };
+    let (xc, yc) = (x.chunks_exact(LANES), y.chunks_exact(LANES));
+    let (xr, yr) = (xc.remainder(), yc.remainder());
+    for (x, y) in xc.zip(yc) {
+        for lane in 0..LANES {
+            each(lane, x[lane], y[lane]);
+        }
+    }
+    for (&x, &y) in xr.iter().zip(yr) {
+        each(0, x, y);
+    }
+    let [dx, dy, xx, yy, xy] =
+        acc.map(|sums| unsafe { FFloat::wrap(sums.into_iter().fold(zero, |a, b| add(a, b))) });
+    // SAFETY: the length is finite.
+    let n: FFloat<T, P> = unsafe { FFloat::wrap(T::from_f64(x.len() as f64)) };
+    Some([
+        dx / n + sx,
+        dy / n + sy,
+        xx - dx * dx / n,
+        yy - dy * dy / n,
+        xy - dx * dy / n,
+    ])
+}
+
+/// The population covariance of `x` and `y`, `Σ (x - mean_x)(y - mean_y) / n`, in one pass; or [`None`] if they are empty.
+/// ```
+/// # use umath::{FFloat, stats};
+/// let f = |x: [f64; 4]| x.map(|x| unsafe { FFloat::new(x) });
+/// let (x, y) = (f([1.0, 2.0, 3.0, 4.0]), f([2.0, 4.0, 6.0, 8.0]));
+/// assert_eq!(*stats::covariance(&x, &y).unwrap(), 2.5);
+/// assert_eq!(*stats::sample_covariance(&x, &y).unwrap(), 10.0 / 3.0);
+/// ```
+///
+/// # Panics
+///
+/// If the lengths differ.
+#[must_use]
+#[track_caller]
+#[allow(clippy::cast_precision_loss)]
+pub fn covariance<T: FastFloat + FloatExt, P: Policy>(
+    x: &[FFloat<T, P>],
+    y: &[FFloat<T, P>],
+) -> Option<FFloat<T, P>> {
+    // SAFETY: the length is finite.
+    comoments(x, y).map(|[.., xy]| xy / unsafe { T::from_f64(x.len() as f64) })
+}
+
+/// The sample covariance of `x` and `y`, `Σ (x - mean_x)(y - mean_y) / (n - 1)`, in one pass; or [`None`] if they have less than 2 elements.
+///
+/// # Panics
+///
+/// If the lengths differ.
+#[must_use]
+#[track_caller]
+#[allow(clippy::cast_precision_loss)]
+pub fn sample_covariance<T: FastFloat + FloatExt, P: Policy>(
+    x: &[FFloat<T, P>],
+    y: &[FFloat<T, P>],
+) -> Option<FFloat<T, P>> {
+    let [.., xy] = comoments(x, y)?;
+    // SAFETY: the length is finite.
+    (x.len() > 1).then(|| xy / unsafe { T::from_f64((x.len() - 1) as f64) })
+}
+
+/// The Pearson correlation coefficient of `x` and `y` (how linearly related they are, in `[-1, 1]`), in one pass;
+/// or [`None`] if they are empty, or either is constant.
+/// ```
+/// # use umath::{FFloat, stats};
+/// let f = |x: [f64; 5]| x.map(|x| unsafe { FFloat::new(x) });
+/// let x = f([1.0, 2.0, 3.0, 4.0, 5.0]);
+/// assert!((*stats::pearson(&x, &f([3.0, 5.0, 7.0, 9.0, 11.0])).unwrap() - 1.0).abs() < 1e-12);
+/// assert!((*stats::pearson(&x, &f([5.0, 4.0, 3.0, 2.0, 1.0])).unwrap() + 1.0).abs() < 1e-12);
+/// assert_eq!(stats::pearson(&x, &f([2.0; 5])), None);
+/// ```
+///
+/// # Panics
+///
+/// If the lengths differ.
+#[must_use]
+#[track_caller]
+pub fn pearson<T: FastFloat + FloatExt + FloatMethods, P: Policy>(
+    x: &[FFloat<T, P>],
+    y: &[FFloat<T, P>],
+) -> Option<FFloat<T, P>> {
+    let [_, _, xx, yy, xy] = comoments(x, y)?;
+    // SAFETY: 0 is a valid float
+    let zero = unsafe { T::zero() };
+    (*xx > zero && *yy > zero).then(|| {
+        // rounding may take it just outside of [-1, 1]
+        let one = lit::<FFloat<T, P>>(1.0);
+        FloatMethods::max(FloatMethods::min(xy / (xx * yy).sqrt(), one), -one)
+    })
+}
+
+/// The (fractional) rank of each element of `x`, from 1: equal elements share the mean of their ranks.
+#[allow(clippy::cast_precision_loss)]
+fn ranks<T: FastFloat + FloatExt, P: NoNan>(x: &[FFloat<T, P>]) -> Vec<FFloat<T, P>> {
+    let mut order: Vec<usize> = (0..x.len()).collect();
+    order.sort_unstable_by_key(|&i| x[i]);
+    // SAFETY: 0 is a valid float
+    let mut ranks = vec![unsafe { FFloat::wrap(T::zero()) }; x.len()];
+    let mut start = 0;
+    for tie in order.chunk_by(|&a, &b| x[a] == x[b]) {
+        // the mean of the ranks start + 1 ..= start + tie.len()
+        let rank = start as f64 + (tie.len() + 1) as f64 / 2.0;
+        for &i in tie {
+            // SAFETY: the rank is finite.
+            ranks[i] = unsafe { FFloat::wrap(T::from_f64(rank)) };
+        }
+        start += tie.len();
+    }
+    ranks
+}
+
+/// The Spearman rank correlation coefficient of `x` and `y` (how monotonically related they are, in `[-1, 1]`): the [Pearson correlation](pearson) of their ranks;
+/// or [`None`] if they are empty, or either is constant.
+///
+/// This sorts (a copy of the indices of) each slice.
+/// ```
+/// # use umath::{FFloat, stats};
+/// let f = |x: [f64; 5]| x.map(|x| unsafe { FFloat::new(x) });
+/// let x = f([1.0, 2.0, 3.0, 4.0, 5.0]);
+/// // not linear, but increasing
+/// assert_eq!(*stats::spearman(&x, &f([1.0, 8.0, 27.0, 64.0, 125.0])).unwrap(), 1.0);
+/// ```
+///
+/// # Panics
+///
+/// If the lengths differ.
+#[must_use]
+#[track_caller]
+pub fn spearman<T: FastFloat + FloatExt + FloatMethods, P: NoNan>(
+    x: &[FFloat<T, P>],
+    y: &[FFloat<T, P>],
+) -> Option<FFloat<T, P>> {
+    assert_eq!(x.len(), y.len(), "slice lengths differ");
+    pearson(&ranks(x), &ranks(y))
+}
+
+#[test]
+fn correlated() {
+    fn comoment(a: &[f64], b: &[f64]) -> f64 {
+        let mean = |x: &[f64]| x.iter().sum::<f64>() / 500.0;
+        let (ma, mb) = (mean(a), mean(b));
+        a.iter()
+            .zip(b)
+            .map(|(a, b)| (a - ma) * (b - mb))
+            .sum::<f64>()
+            / 500.0
+    }
+    let f = |x| unsafe { FFloat::<f64>::new(x) };
+    let plain: (Vec<f64>, Vec<f64>) = (0..500u32)
+        .map(|i| {
+            let x = 1e6 + f64::from((i * 37) % 101);
+            (x, x * 2.0 + f64::from(i % 7))
+        })
+        .unzip();
+    let (x, y): (Vec<_>, Vec<_>) = (
+        plain.0.iter().map(|&x| f(x)).collect(),
+        plain.1.iter().map(|&y| f(y)).collect(),
+    );
+    let xy = comoment(&plain.0, &plain.1);
+    assert!((*covariance(&x, &y).unwrap() - xy).abs() < 1e-6);
+    let r = xy / (comoment(&plain.0, &plain.0) * comoment(&plain.1, &plain.1)).sqrt();
+    assert!((*pearson(&x, &y).unwrap() - r).abs() < 1e-9);
+    let tied = [1.0, 2.0, 2.0, 3.0].map(f);
+    assert_eq!(ranks(&tied), [1.0, 2.5, 2.5, 4.0]);
+    let rho = *spearman(&tied, &[1.0, 3.0, 2.0, 4.0].map(f)).unwrap();
+    assert!((rho - 0.948_683_298_050_513_8).abs() < 1e-12);
+    assert_eq!(covariance::<f64, crate::policy::Fast>(&[], &[]), None);
+}
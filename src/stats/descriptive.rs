@@ -0,0 +1,289 @@
+use crate::generic_float::{lit, Constructors, FloatExt, FloatMethods};
+use crate::policy::{Ieee, NoNan, Policy};
+use crate::r#trait::{FastFloat, Wrapper};
+use crate::slice::{self, raw};
+use crate::FFloat;
+
+/// Lanes per accumulator, so the power sums vectorize.
+const LANES: usize = 8;
+
+/// `[Σ d, Σ d², ..]` (the first `K` powers) of `d = x[i] - shift`, in one pass, without checking.
+#[inline(always)]
+fn power_sums<T: FastFloat + Constructors, P: Policy, const K: usize>(
+    x: &[FFloat<T, P>],
+    shift: T,
+) -> [T; K] {
+    let (add, mul) = (T::add::<P>, T::mul::<P>);
+    // SAFETY: 0 is a valid float
+    let zero = unsafe { T::zero() };
+    let mut acc = [[zero; LANES]; K];
+    let chunks = raw(x).chunks_exact(LANES);
+    let rest = chunks.remainder();
+    let mut each = |lane: usize, x: T| unsafe {
+        let d = T::sub::<P>(x, shift);
+        let mut power = d;
+        for sums in &mut acc {
+            sums[lane] = add(sums[lane], power);
+            power = mul(power, d);
+        }
+    };
+    for chunk in chunks {
+        for (lane, &x) in chunk.iter().enumerate() {
+            each(lane, x);
+        }
+    }
+    for &x in rest {
+        each(0, x);
+    }
+    acc.map(|sums| sums.into_iter().fold(zero, |a, b| unsafe { add(a, b) }))
+}
+
+/// The first four moments of a slice, from [`moments`] | [`moments_one_pass`].
+///
+/// The central moments are those of the population (divided by `n`), and the [skewness](Self::skewness) and [kurtosis](Self::kurtosis) are computed from them.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Moments<T: FastFloat, P: Policy = crate::policy::Fast> {
+    count: usize,
+    mean: FFloat<T, P>,
+    /// `Σ (x - mean)^k / n`, for `k` in 2, 3, 4.
+    central: [FFloat<T, P>; 3],
+}
+
+impl<T: FastFloat + FloatExt + FloatMethods, P: Policy> Moments<T, P> {
+    /// The moments of `x` from its power sums about `shift`: the closer the shift is to the mean, the less cancellation there is.
+    #[allow(clippy::cast_precision_loss)]
+    fn about(x: &[FFloat<T, P>], shift: FFloat<T, P>) -> Self {
+        let wrap = |x| unsafe { FFloat::<T, P>::wrap(x) };
+        // SAFETY: the length is finite.
+        let n = wrap(unsafe { T::from_f64(x.len() as f64) });
+        let [a1, a2, a3, a4] = power_sums::<T, P, 4>(x, shift.raw()).map(|s| wrap(s) / n);
+        let a1sq = a1 * a1;
+        // the variance can not be negative, but the rounding of a one pass sum may make it so.
+        let m2 = (a2 - a1sq).max(wrap(unsafe { T::zero() }));
+        let m3 = a3 - a1 * a2 * lit::<T>(3.0) + a1sq * a1 * lit::<T>(2.0);
+        let m4 =
+            a4 - a1 * a3 * lit::<T>(4.0) + a1sq * a2 * lit::<T>(6.0) - a1sq * a1sq * lit::<T>(3.0);
+        Self {
+            count: x.len(),
+            mean: shift + a1,
+            central: [m2, m3, m4],
+        }
+    }
+
+    /// The number of elements.
+    #[must_use]
+    pub fn count(&self) -> usize {
+        self.count
+    }
+
+    /// The mean.
+    #[must_use]
+    pub fn mean(&self) -> FFloat<T, P> {
+        self.mean
+    }
+
+    /// The population variance, `Σ (x - mean)² / n`.
+    #[must_use]
+    pub fn variance(&self) -> FFloat<T, P> {
+        self.central[0]
+    }
+
+    /// The sample variance, `Σ (x - mean)² / (n - 1)`, or [`None`] if there is only one element.
+    #[must_use]
+    #[allow(clippy::cast_precision_loss)]
+    pub fn sample_variance(&self) -> Option<FFloat<T, P>> {
+        // SAFETY: the count is finite.
+        (self.count > 1).then(|| {
+            let n = unsafe { T::from_f64(self.count as f64) };
+            self.central[0] * n / unsafe { T::sub::<P>(n, T::one()) }
+        })
+    }
+
+    /// The population standard deviation.
+    #[must_use]
+    pub fn stddev(&self) -> FFloat<T, P> {
+        self.central[0].sqrt()
+    }
+
+    /// The skewness, `m₃ / m₂^1.5` (the population skewness, with no sample size correction), or [`None`] if the variance is 0.
+    #[must_use]
+    pub fn skewness(&self) -> Option<FFloat<T, P>> {
+        let [m2, m3, _] = self.central;
+        (*m2 > unsafe { T::zero() }).then(|| m3 / (m2 * m2.sqrt()))
+    }
+
+    /// The excess kurtosis, `m₄ / m₂² - 3` (0 for a normal distribution), or [`None`] if the variance is 0.
+    #[must_use]
+    pub fn kurtosis(&self) -> Option<FFloat<T, P>> {
+        let [m2, _, m4] = self.central;
+        (*m2 > unsafe { T::zero() }).then(|| m4 / (m2 * m2) - lit::<T>(3.0))
+    }
+}
+
+/// The moments of `x`, in two passes: the [mean], and then the sums of powers of the deviations from it; or [`None`] if `x` is empty.
+///
+/// The second pass also sums the deviations themselves (which would be 0, without rounding), to correct for the rounding of the mean.
+/// ```
+/// # use umath::{FFloat, stats};
+/// let x = [2.0f64, 4.0, 4.0, 4.0, 5.0, 5.0, 7.0, 9.0].map(|x| unsafe { FFloat::new(x) });
+/// let m = stats::moments(&x).unwrap();
+/// assert_eq!((*m.mean(), *m.variance(), *m.stddev()), (5.0, 4.0, 2.0));
+/// assert!((*m.skewness().unwrap() - 0.65625).abs() < 1e-12);
+/// assert!((*m.kurtosis().unwrap() - -0.21875).abs() < 1e-12);
+/// ```
+#[must_use]
+pub fn moments<T: FastFloat + FloatExt + FloatMethods, P: Policy>(
+    x: &[FFloat<T, P>],
+) -> Option<Moments<T, P>> {
+    Some(Moments::about(x, mean(x)?))
+}
+
+/// The moments of `x`, in one pass, or [`None`] if `x` is empty.
+///
+/// This sums the powers of the deviations from the first element (instead of the mean), which halves the memory traffic of [`moments`],
+/// and does not cancel catastrophically when the mean is large compared to the spread, but the higher moments lose precision if the first element is far from the mean.
+/// Use this for large slices (or ones that are expensive to read twice).
+/// ```
+/// # use umath::{FFloat, stats};
+/// let x = [1e9 + 2.0f64, 1e9 + 4.0, 1e9 + 6.0].map(|x| unsafe { FFloat::new(x) });
+/// let m = stats::moments_one_pass(&x).unwrap();
+/// assert_eq!((*m.mean(), *m.sample_variance().unwrap()), (1e9 + 4.0, 4.0));
+/// ```
+#[must_use]
+pub fn moments_one_pass<T: FastFloat + FloatExt + FloatMethods, P: Policy>(
+    x: &[FFloat<T, P>],
+) -> Option<Moments<T, P>> {
+    Some(Moments::about(x, *x.first()?))
+}
+
+/// The mean of `x`, with [pairwise summation](slice::sum_pairwise), or [`None`] if `x` is empty.
+#[must_use]
+#[allow(clippy::cast_precision_loss)]
+pub fn mean<T: FastFloat + FloatExt, P: Policy>(x: &[FFloat<T, P>]) -> Option<FFloat<T, P>> {
+    // SAFETY: the length is finite.
+    (!x.is_empty()).then(|| slice::sum_pairwise(x) / unsafe { T::from_f64(x.len() as f64) })
+}
+
+/// `Σ (x - mean)²`, in two passes (with the correction of [`moments`]).
+#[allow(clippy::cast_precision_loss)]
+fn squares<T: FastFloat + FloatExt, P: Policy>(x: &[FFloat<T, P>]) -> Option<FFloat<T, P>> {
+    let mean = mean(x)?;
+    let [sum, squares] = power_sums::<T, P, 2>(x, mean.raw()).map(|x| unsafe { FFloat::wrap(x) });
+    // SAFETY: the length is finite.
+    Some(squares - sum * sum / unsafe { T::from_f64(x.len() as f64) })
+}
+
+/// The population variance of `x`, `Σ (x - mean)² / n`, in two passes; or [`None`] if `x` is empty.
+/// ```
+/// # use umath::{FFloat, stats};
+/// let x = [1.0f32, 2.0, 3.0, 4.0].map(|x| unsafe { FFloat::new(x) });
+/// assert_eq!(*stats::variance(&x).unwrap(), 1.25);
+/// assert_eq!(*stats::sample_variance(&x).unwrap(), 5.0 / 3.0);
+/// ```
+#[must_use]
+#[allow(clippy::cast_precision_loss)]
+pub fn variance<T: FastFloat + FloatExt, P: Policy>(x: &[FFloat<T, P>]) -> Option<FFloat<T, P>> {
+    // SAFETY: the length is finite.
+    squares(x).map(|s| s / unsafe { T::from_f64(x.len() as f64) })
+}
+
+/// The sample variance of `x`, `Σ (x - mean)² / (n - 1)`, in two passes; or [`None`] if `x` has less than 2 elements.
+#[must_use]
+#[allow(clippy::cast_precision_loss)]
+pub fn sample_variance<T: FastFloat + FloatExt, P: Policy>(
+    x: &[FFloat<T, P>],
+) -> Option<FFloat<T, P>> {
+    // SAFETY: the length is finite.
+    (x.len() > 1)
+        .then(|| squares(x))?
+        .map(|s| s / unsafe { T::from_f64((x.len() - 1) as f64) })
+}
+
+/// The population standard deviation of `x`, or [`None`] if `x` is empty. Refer to [`variance`].
+#[must_use]
+pub fn stddev<T: FastFloat + FloatExt + FloatMethods, P: Policy>(
+    x: &[FFloat<T, P>],
+) -> Option<FFloat<T, P>> {
+    variance(x).map(FloatMethods::sqrt)
+}
+
+/// The sample standard deviation of `x`, or [`None`] if `x` has less than 2 elements. Refer to [`sample_variance`].
+#[must_use]
+pub fn sample_stddev<T: FastFloat + FloatExt + FloatMethods, P: Policy>(
+    x: &[FFloat<T, P>],
+) -> Option<FFloat<T, P>> {
+    sample_variance(x).map(FloatMethods::sqrt)
+}
+
+/// The skewness of `x` (refer to [`Moments::skewness`]), or [`None`] if `x` is empty, or its variance is 0.
+#[must_use]
+pub fn skewness<T: FastFloat + FloatExt + FloatMethods, P: Policy>(
+    x: &[FFloat<T, P>],
+) -> Option<FFloat<T, P>> {
+    moments(x)?.skewness()
+}
+
+/// The excess kurtosis of `x` (refer to [`Moments::kurtosis`]), or [`None`] if `x` is empty, or its variance is 0.
+#[must_use]
+pub fn kurtosis<T: FastFloat + FloatExt + FloatMethods, P: Policy>(
+    x: &[FFloat<T, P>],
+) -> Option<FFloat<T, P>> {
+    moments(x)?.kurtosis()
+}
+
+/// The median of `x` (the mean of the two middle elements, for a even length), or [`None`] if `x` is empty.
+///
+/// This [selects](slice::select_nth) the middle in O(n), instead of sorting, so `x` is reordered.
+/// ```
+/// # use umath::{FFloat, stats};
+/// let mut x = [5.0f32, 1.0, 4.0, 2.0].map(|x| unsafe { FFloat::new(x) });
+/// assert_eq!(*stats::median(&mut x).unwrap(), 3.0);
+/// assert_eq!(*stats::median(&mut x[..3]).unwrap(), 2.0);
+/// ```
+#[must_use]
+pub fn median<T: FastFloat + FloatExt, P: NoNan>(x: &mut [FFloat<T, P>]) -> Option<FFloat<T, P>> {
+    if x.is_empty() {
+        return None;
+    }
+    let half = x.len() / 2;
+    let upper = slice::select_nth(x, half);
+    if x.len() % 2 == 1 {
+        return Some(upper);
+    }
+    // everything before the upper middle is not greater, so the lower middle is the largest of them.
+    let lower = *x[..half].iter().max()?;
+    // halving before adding, as upper - lower overflows for data like [-MAX, MAX];
+    // with IEEE operations, as the fast math flags may factor it back into (lower + upper) * 0.5.
+    let half = |x: FFloat<T, P>| unsafe { T::mul::<Ieee>(x.0, lit(0.5)) };
+    Some(unsafe { FFloat::wrap(T::add::<Ieee>(half(lower), half(upper))) })
+}
+
+#[test]
+#[allow(clippy::float_cmp)]
+fn described() {
+    let f = |x| unsafe { FFloat::<f64>::new(x) };
+    let x: Vec<_> = (0..1000)
+        .map(|i| f(1e6 + f64::from((i * 37) % 101).powi(2) * 0.01))
+        .collect();
+    let plain: Vec<f64> = x.iter().map(|x| **x).collect();
+    let average = plain.iter().sum::<f64>() / 1000.0;
+    let m = |k| plain.iter().map(|x| (x - average).powi(k)).sum::<f64>() / 1000.0;
+    let (m2, m3, m4) = (m(2), m(3), m(4));
+    for moments in [moments(&x).unwrap(), moments_one_pass(&x).unwrap()] {
+        assert_eq!(moments.count(), 1000);
+        assert!((*moments.mean() - average).abs() < 1e-6);
+        assert!((*moments.variance() - m2).abs() / m2 < 1e-9);
+        assert!((*moments.skewness().unwrap() - m3 / m2.powf(1.5)).abs() < 1e-6);
+        assert!((*moments.kurtosis().unwrap() - (m4 / (m2 * m2) - 3.0)).abs() < 1e-6);
+    }
+    assert!((*variance(&x).unwrap() - m2).abs() / m2 < 1e-12);
+    assert!((*sample_stddev(&x).unwrap() - (m2 * 1000.0 / 999.0).sqrt()).abs() < 1e-9);
+    let mut sorted = plain.clone();
+    sorted.sort_by(f64::total_cmp);
+    let mut x = x;
+    assert!((*median(&mut x).unwrap() - f64::midpoint(sorted[499], sorted[500])).abs() < 1e-9);
+    assert_eq!(*median(&mut [f(f64::MIN), f(f64::MAX)]).unwrap(), 0.0);
+    assert_eq!(mean::<f64, crate::policy::Fast>(&[]), None);
+    assert_eq!(sample_variance(&[f(1.0)]), None);
+    assert_eq!(skewness(&[f(2.0), f(2.0)]), None);
+}
@@ -0,0 +1,159 @@
+use super::correlation::comoments;
+use crate::generic_float::{lit, Constants, FloatExt, FloatMethods};
+use crate::policy::Policy;
+use crate::r#trait::{FastFloat, Wrapper};
+use crate::solve::solve;
+use crate::FFloat;
+
+/// The least squares line through the points `(x[i], y[i])`: `(slope, intercept, r²)`, in one pass (refer to [`covariance`](super::covariance));
+/// or [`None`] if there are less than 2 points, or every `x` is the same.
+///
+/// `r²` (the coefficient of determination) is the fraction of the variance of `y` that the line explains: 1 for points on a line (including a constant `y`), and 0 for no linear relation.
+/// ```
+/// # use umath::{FFloat, stats};
+/// let f = |x: [f64; 4]| x.map(|x| unsafe { FFloat::new(x) });
+/// let (slope, intercept, r2) = stats::linear_fit(&f([0.0, 1.0, 2.0, 3.0]), &f([1.0, 3.0, 5.0, 7.0])).unwrap();
+/// assert_eq!((*slope, *intercept, *r2), (2.0, 1.0, 1.0));
+/// ```
+///
+/// # Panics
+///
+/// If the lengths differ.
+#[must_use]
+#[track_caller]
+#[allow(clippy::type_complexity)]
+pub fn linear_fit<T: FastFloat + FloatExt + FloatMethods, P: Policy>(
+    x: &[FFloat<T, P>],
+    y: &[FFloat<T, P>],
+) -> Option<(FFloat<T, P>, FFloat<T, P>, FFloat<T, P>)> {
+    let [mx, my, xx, yy, xy] = comoments(x, y)?;
+    // SAFETY: 0 and 1 are valid floats
+    let (zero, one) = unsafe { (T::zero(), FFloat::wrap(T::one())) };
+    if x.len() < 2 || *xx <= zero {
+        return None;
+    }
+    let slope = xy / xx;
+    let r2 = if *yy > zero {
+        FloatMethods::min(xy * xy / (xx * yy), one)
+    } else {
+        one
+    };
+    Some((slope, my - slope * mx, r2))
+}
+
+/// The coefficients (constant first, as [`Polynomial::new`](crate::polynomial::Polynomial::new) takes them) of the polynomial of `degree` that fits the points `(x[i], y[i])` best, in the least squares sense;
+/// or [`None`] if there are not more points than the degree (with distinct `x`).
+///
+/// This solves the normal equations, after mapping `x` onto `[-1, 1]` (which keeps them much better conditioned, especially for `f32`),
+/// so it is meant for small degrees (calibration curves, trendlines): beyond 5 or so, the solution becomes sensitive to rounding.
+/// ```
+/// # use umath::{FFloat, stats};
+/// let f = |x| unsafe { FFloat::new(x) };
+/// let x: Vec<_> = (0..10).map(|i| f(f64::from(i))).collect();
+/// let y: Vec<_> = x.iter().map(|&x| x * x * 0.5 - x * 3.0 + 2.0).collect();
+/// let c = stats::polyfit(&x, &y, 2).unwrap();
+/// for (c, e) in c.iter().zip([2.0, -3.0, 0.5]) {
+///     assert!((**c - e).abs() < 1e-9);
+/// }
+/// ```
+///
+/// # Panics
+///
+/// If the lengths differ.
+#[must_use]
+#[track_caller]
+#[allow(clippy::cast_precision_loss)]
+pub fn polyfit<T: FastFloat + FloatExt + Constants + FloatMethods, P: Policy>(
+    x: &[FFloat<T, P>],
+    y: &[FFloat<T, P>],
+    degree: usize,
+) -> Option<Vec<FFloat<T, P>>> {
+    assert_eq!(x.len(), y.len(), "slice lengths differ");
+    let n = degree + 1;
+    if x.len() < n {
+        return None;
+    }
+    let lo = x.iter().copied().reduce(FloatMethods::min)?;
+    let hi = x.iter().copied().reduce(FloatMethods::max)?;
+    // SAFETY: 0 and 1 are valid floats
+    let (zero, one) = unsafe {
+        (
+            FFloat::<T, P>::wrap(T::zero()),
+            FFloat::<T, P>::wrap(T::one()),
+        )
+    };
+    if degree > 0 && hi <= lo {
+        return None;
+    }
+    // x = center + half * t, with t in [-1, 1]
+    let center = lo + (hi - lo) * lit::<T>(0.5);
+    let half = if degree > 0 {
+        (hi - lo) * lit::<T>(0.5)
+    } else {
+        one
+    };
+    let inverse = one / half;
+    // Σ t^k for k in 0..2n-1, and Σ y t^k for k in 0..n
+    let mut power_sums = vec![zero; 2 * n - 1];
+    let mut rhs = vec![zero; n];
+    for (&x, &y) in x.iter().zip(y) {
+        let t = (x - center) * inverse;
+        let mut power = one;
+        for (k, sum) in power_sums.iter_mut().enumerate() {
+            *sum += power;
+            if k < n {
+                rhs[k] += y * power;
+            }
+            power *= t;
+        }
+    }
+    let mut normal: Vec<_> = (0..n * n).map(|i| power_sums[i / n + i % n]).collect();
+    solve(&mut normal, &mut rhs)?;
+    // expand Σ a_k ((x - center) / half)^k into powers of x, one factor at a time (horner, with the polynomial as the accumulator)
+    let mut coefficients = vec![zero; n];
+    let (c0, c1) = (-center * inverse, inverse);
+    for &a in rhs.iter().rev() {
+        // coefficients = coefficients * (c0 + c1 x) + a
+        let mut carry = zero;
+        for c in &mut coefficients {
+            let next = *c;
+            *c = *c * c0 + carry * c1;
+            carry = next;
+        }
+        coefficients[0] += a;
+    }
+    Some(coefficients)
+}
+
+#[test]
+fn fitted() {
+    let f = |x| unsafe { FFloat::<f64>::new(x) };
+    let x: Vec<_> = (0..50).map(|i| f(100.0 + f64::from(i) * 0.5)).collect();
+    let wobble = |i: usize| f([0.1, -0.1][i % 2]);
+    let y: Vec<_> = x
+        .iter()
+        .enumerate()
+        .map(|(i, &x)| x * 3.0 - 7.0 + wobble(i))
+        .collect();
+    let (slope, intercept, r2) = linear_fit(&x, &y).unwrap();
+    assert!((*slope - 3.0).abs() < 1e-3 && (*intercept + 7.0).abs() < 0.5);
+    assert!(*r2 > 0.999 && *r2 < 1.0);
+    let line = polyfit(&x, &y, 1).unwrap();
+    assert!((*line[1] - *slope).abs() < 1e-9 && (*line[0] - *intercept).abs() < 1e-6);
+    let cubic: Vec<_> = x
+        .iter()
+        .map(|&x| (x - 110.0) * (x - 110.0) * (x - 120.0) * 0.01 + 4.0)
+        .collect();
+    let coefficients = polyfit(&x, &cubic, 3).unwrap();
+    for (&at, &expect) in x.iter().zip(&cubic) {
+        let value = coefficients
+            .iter()
+            .rev()
+            .fold(f(0.0), |acc, &c| acc * at + c);
+        assert!((*value - *expect).abs() < 1e-6);
+    }
+    let mean = polyfit(&x, &y, 0).unwrap();
+    assert!((*mean[0] - *y.iter().fold(f(0.0), |a, &b| a + b) / 50.0).abs() < 1e-9);
+    assert_eq!(linear_fit(&[f(1.0), f(1.0)], &[f(1.0), f(2.0)]), None);
+    assert_eq!(polyfit(&x[..3], &y[..3], 3), None);
+}
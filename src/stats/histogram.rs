@@ -0,0 +1,291 @@
+use crate::generic_float::{Constructors, FloatExt};
+use crate::policy::Policy;
+use crate::r#trait::{FastFloat, Wrapper};
+use crate::slice::partition_point;
+use crate::FFloat;
+
+/// The bin edges of a [`Histogram`].
+#[derive(Clone, Debug, PartialEq)]
+enum Bins<F> {
+    /// `bins` equal bins over `[min, max]`, with `scale = bins / (max - min)`.
+    Uniform {
+        min: F,
+        max: F,
+        scale: F,
+        bins: usize,
+    },
+    /// Strictly increasing edges.
+    Edges(Vec<F>),
+}
+
+/// Counts of values falling in each of a set of bins.
+///
+/// The bins are either [uniform](Self::uniform), where the bin of a value is found with a multiply by the inverse width (no division, and no search),
+/// or given by [custom edges](Self::with_edges), where it is found with a binary search.
+/// Each bin `i` holds `edge(i) <= x < edge(i + 1)`, except the last, which also holds its upper edge;
+/// values outside of every bin are counted in [`below`](Self::below) | [`above`](Self::above).
+///
+/// Histograms with the same bins can be [merged](Self::merge), and [quantiles](Self::quantile) estimated from them.
+/// ```
+/// # use umath::{FFloat, stats::Histogram};
+/// let f = |x| unsafe { FFloat::new(x) };
+/// let mut h = Histogram::uniform(f(0.0f32), f(10.0), 5);
+/// h.extend([1.0, 3.0, 3.5, 9.0, 10.0, 12.0].map(f));
+/// assert_eq!(h.counts(), [1, 2, 0, 0, 2]);
+/// assert_eq!(h.above(), 1);
+/// assert_eq!(*h.edge(1), 2.0);
+/// ```
+#[derive(Clone, Debug, PartialEq)]
+pub struct Histogram<F> {
+    bins: Bins<F>,
+    counts: Vec<u64>,
+    below: u64,
+    above: u64,
+}
+
+impl<T: FastFloat + FloatExt + Into<f64>, P: Policy> Histogram<FFloat<T, P>> {
+    /// Creates a histogram of `bins` equal bins over `[min, max]`.
+    ///
+    /// # Panics
+    ///
+    /// If `bins` is 0, `min` is not less than `max`, or `max - min` overflows (as with `[-MAX, MAX]`).
+    #[must_use]
+    #[track_caller]
+    #[allow(clippy::cast_precision_loss)]
+    pub fn uniform(min: FFloat<T, P>, max: FFloat<T, P>, bins: usize) -> Self {
+        assert_ne!(bins, 0, "a histogram needs at least one bin");
+        assert!(min < max, "min must be less than max");
+        // in f64, where the span of two f32s is exact (and the span of two f64s is INF if it overflows).
+        let (lo, hi): (f64, f64) = (min.raw().into(), max.raw().into());
+        // SAFETY: MAX is finite.
+        let limit: f64 = unsafe { <T as Constructors>::max() }.into();
+        assert!(hi - lo <= limit, "the range of the histogram overflows");
+        Self {
+            // SAFETY: the bin count is finite.
+            bins: Bins::Uniform {
+                min,
+                max,
+                scale: unsafe { FFloat::wrap(T::from_f64(bins as f64)) } / (max - min),
+                bins,
+            },
+            counts: vec![0; bins],
+            below: 0,
+            above: 0,
+        }
+    }
+
+    /// Creates a histogram with the bins between each of the `edges`.
+    ///
+    /// # Panics
+    ///
+    /// If there are less than 2 edges, or they are not strictly increasing.
+    #[must_use]
+    #[track_caller]
+    pub fn with_edges(edges: Vec<FFloat<T, P>>) -> Self {
+        assert!(edges.len() > 1, "a histogram needs at least one bin");
+        assert!(
+            edges.windows(2).all(|w| w[0] < w[1]),
+            "edges must be strictly increasing"
+        );
+        Self {
+            counts: vec![0; edges.len() - 1],
+            bins: Bins::Edges(edges),
+            below: 0,
+            above: 0,
+        }
+    }
+
+    /// The bin `x` falls in, or [`Err`] with whether it is above every bin.
+    #[inline]
+    #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+    fn bin(&self, x: FFloat<T, P>) -> Result<usize, bool> {
+        match &self.bins {
+            &Bins::Uniform {
+                min,
+                max,
+                scale,
+                bins,
+            } => {
+                if x < min || x > max {
+                    return Err(x > max);
+                }
+                let i: f64 = ((x - min) * scale).raw().into();
+                // the product may round up to `bins`, at (or just below) `max`.
+                Ok((i as usize).min(bins - 1))
+            }
+            Bins::Edges(edges) => {
+                if x < edges[0] || x > edges[edges.len() - 1] {
+                    return Err(x > edges[0]);
+                }
+                Ok(partition_point(edges, |e| e <= x).clamp(1, edges.len() - 1) - 1)
+            }
+        }
+    }
+
+    /// Counts a value.
+    pub fn push(&mut self, x: FFloat<T, P>) {
+        match self.bin(x) {
+            Ok(i) => self.counts[i] += 1,
+            Err(false) => self.below += 1,
+            Err(true) => self.above += 1,
+        }
+    }
+
+    /// The lower edge of bin `i` (or, for `i == bins`, the upper edge of the last bin).
+    ///
+    /// # Panics
+    ///
+    /// If `i` is more than the number of bins.
+    #[must_use]
+    #[track_caller]
+    #[allow(clippy::cast_precision_loss)]
+    pub fn edge(&self, i: usize) -> FFloat<T, P> {
+        match &self.bins {
+            &Bins::Uniform { min, max, bins, .. } => {
+                assert!(i <= bins, "edge index out of bounds");
+                if i == bins {
+                    return max;
+                }
+                // the fraction is a f64, so that the fast math flags can not reorder this into (max - min) * i / bins, which may overflow.
+                // SAFETY: the fraction is in [0, 1).
+                let fraction = unsafe { FFloat::wrap(T::from_f64(i as f64 / bins as f64)) };
+                min + (max - min) * fraction
+            }
+            Bins::Edges(edges) => edges[i],
+        }
+    }
+
+    /// The count of each bin.
+    #[must_use]
+    pub fn counts(&self) -> &[u64] {
+        &self.counts
+    }
+
+    /// The number of values below the first bin.
+    #[must_use]
+    pub fn below(&self) -> u64 {
+        self.below
+    }
+
+    /// The number of values above the last bin.
+    #[must_use]
+    pub fn above(&self) -> u64 {
+        self.above
+    }
+
+    /// The number of values counted, including those outside of every bin.
+    #[must_use]
+    pub fn total(&self) -> u64 {
+        self.counts.iter().sum::<u64>() + self.below + self.above
+    }
+
+    /// Adds the counts of `other`, which must have the same bins.
+    ///
+    /// # Panics
+    ///
+    /// If the bins differ.
+    #[track_caller]
+    pub fn merge(&mut self, other: &Self) {
+        assert!(self.bins == other.bins, "histograms have different bins");
+        for (a, b) in self.counts.iter_mut().zip(&other.counts) {
+            *a += b;
+        }
+        self.below += other.below;
+        self.above += other.above;
+    }
+
+    /// Estimates the `q` quantile (`q` in `[0, 1]`: 0.5 is the median, 0.99 the 99th percentile), or [`None`] if the histogram is empty.
+    ///
+    /// Within a bin, the values are assumed to be spread evenly, so the result is interpolated between its edges.
+    /// Values below (above) every bin are taken to be at the first (last) edge.
+    /// ```
+    /// # use umath::{FFloat, stats::Histogram};
+    /// let f = |x| unsafe { FFloat::new(x) };
+    /// let mut h = Histogram::with_edges(vec![f(0.0f64), f(20.0), f(100.0)]);
+    /// h.extend((0..100).map(|i| f(f64::from(i))));
+    /// assert_eq!(*h.quantile(0.05).unwrap(), 5.0);
+    /// assert_eq!(*h.quantile(0.5).unwrap(), 50.0);
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// If `q` is not in `[0, 1]`.
+    #[must_use]
+    #[track_caller]
+    #[allow(clippy::cast_precision_loss)]
+    pub fn quantile(&self, q: f64) -> Option<FFloat<T, P>> {
+        assert!((0.0..=1.0).contains(&q), "quantile must be in [0, 1]");
+        let total = self.total();
+        if total == 0 {
+            return None;
+        }
+        let rank = q * total as f64;
+        let mut seen = self.below as f64;
+        if rank <= seen && self.below != 0 {
+            return Some(self.edge(0));
+        }
+        for (i, &count) in self.counts.iter().enumerate() {
+            let next = seen + count as f64;
+            if count != 0 && rank <= next {
+                let (lo, hi) = (self.edge(i), self.edge(i + 1));
+                // SAFETY: the fraction is in [0, 1].
+                let t = unsafe { FFloat::wrap(T::from_f64((rank - seen) / count as f64)) };
+                return Some(lo + (hi - lo) * t);
+            }
+            seen = next;
+        }
+        Some(self.edge(self.counts.len()))
+    }
+}
+
+impl<T: FastFloat + FloatExt + Into<f64>, P: Policy> Extend<FFloat<T, P>>
+    for Histogram<FFloat<T, P>>
+{
+    fn extend<I: IntoIterator<Item = FFloat<T, P>>>(&mut self, iter: I) {
+        for x in iter {
+            self.push(x);
+        }
+    }
+}
+
+#[test]
+fn binned() {
+    let f = |x| unsafe { FFloat::<f64>::new(x) };
+    let x: Vec<_> = (0..1000)
+        .map(|i| f(f64::from((i * 37) % 1000) * 0.01))
+        .collect();
+    let mut uniform = Histogram::uniform(f(0.0), f(10.0), 10);
+    let edges = (0..=10).map(|i| f(f64::from(i))).collect();
+    let mut custom = Histogram::with_edges(edges);
+    let (a, b) = x.split_at(300);
+    uniform.extend(a.iter().copied());
+    let mut rest = Histogram::uniform(f(0.0), f(10.0), 10);
+    rest.extend(b.iter().copied());
+    uniform.merge(&rest);
+    custom.extend(x.iter().copied());
+    assert_eq!(uniform.counts(), [100; 10]);
+    assert_eq!(uniform.counts(), custom.counts());
+    assert_eq!(uniform.total(), 1000);
+    for q in [0.0, 0.1, 0.25, 0.5, 0.9, 1.0] {
+        let expect = q * 10.0;
+        assert!((*uniform.quantile(q).unwrap() - expect).abs() < 1e-9);
+        assert!((*custom.quantile(q).unwrap() - expect).abs() < 1e-9);
+    }
+    custom.push(f(-1.0));
+    custom.push(f(10.0));
+    custom.push(f(11.0));
+    assert_eq!((custom.below(), custom.above()), (1, 1));
+    assert_eq!(custom.counts()[9], 101);
+    assert_eq!(Histogram::uniform(f(0.0), f(1.0), 3).quantile(0.5), None);
+    let mut wide = Histogram::uniform(f(0.0), f(f64::MAX), 4);
+    wide.push(f(f64::MAX * 0.6));
+    assert_eq!(wide.counts(), [0, 0, 1, 0]);
+    assert!((*wide.edge(3) / f64::MAX - 0.75).abs() < 1e-12);
+}
+
+#[test]
+#[should_panic = "the range of the histogram overflows"]
+fn overflowing() {
+    let f = |x| unsafe { FFloat::<f64>::new(x) };
+    _ = Histogram::uniform(f(f64::MIN), f(f64::MAX), 4);
+}
@@ -0,0 +1,43 @@
+//! statistics.
+//!
+//! The descriptive statistics of a slice of [`FFloat`](crate::FFloat)s ([`mean`], [`variance`], [`skewness`], [`kurtosis`], ..) are computed with the fast reductions of [`slice`](crate::slice):
+//! each is two pass (the mean, then the deviations from it), and [`moments_one_pass`] gets all of them in one.
+//! Two slices can be compared with [`covariance`], and the [`pearson`] | [`spearman`] correlation, and fit with [`linear_fit`] | [`polyfit`].
+//! The order statistics ([`median`], [`quantile`]) are found by selection, without sorting.
+//! ```
+//! # use umath::{FFloat, stats};
+//! let x = [1.0f32, 2.0, 3.0, 4.0].map(|x| unsafe { FFloat::new(x) });
+//! assert_eq!(*stats::mean(&x).unwrap(), 2.5);
+//! assert_eq!(*stats::variance(&x).unwrap(), 1.25);
+//! ```
+//!
+//! [`OnlineStats`] accumulates the moments of a stream in a single pass, in constant memory.
+//! [`Ewma`] and [`RollingMean`] smooth a stream, updating in O(1).
+//! [`P2Quantile`] estimates a quantile (a median, or a p99 latency) of a stream, without storing it.
+//! These work over any [`FloatAlone`](crate::generic_float::FloatAlone) ([`FFloat`](crate::FFloat)s, and plain `f32` | `f64`).
+//! A [`Histogram`] counts [`FFloat`](crate::FFloat)s (only) into bins.
+//! ```
+//! # use umath::{FFloat, stats::OnlineStats};
+//! let stats: OnlineStats<_> = [2.0f64, 4.0, 4.0, 4.0, 5.0, 5.0, 7.0, 9.0]
+//!     .map(|x| unsafe { FFloat::new(x) })
+//!     .into_iter()
+//!     .collect();
+//! assert_eq!(*stats.mean().unwrap(), 5.0);
+//! assert_eq!(*stats.stddev().unwrap(), 2.0);
+//! ```
+mod correlation;
+mod descriptive;
+mod fit;
+mod histogram;
+mod online;
+mod p2;
+mod quantile;
+mod smoothing;
+pub use correlation::*;
+pub use descriptive::*;
+pub use fit::{linear_fit, polyfit};
+pub use histogram::Histogram;
+pub use online::OnlineStats;
+pub use p2::P2Quantile;
+pub use quantile::{quantile, Interpolation};
+pub use smoothing::{Ewma, RollingMean};
@@ -0,0 +1,159 @@
+use crate::generic_float::FloatAlone;
+
+/// Streaming mean and variance, with Welford's algorithm.
+///
+/// Each [`push`](Self::push) updates the mean and the sum of squared deviations from it,
+/// which (unlike keeping `Σx` and `Σx²`) does not cancel catastrophically when the mean is large compared to the spread.
+/// Accumulators of separate parts of a stream (for example, one per thread) can be [merged](Self::merge).
+/// ```
+/// # use umath::{FFloat, stats::OnlineStats};
+/// let f = |x| unsafe { FFloat::new(x) };
+/// let mut a = OnlineStats::new();
+/// a.extend([f(1e9f64 + 1.0), f(1e9 + 2.0)]);
+/// let mut b = OnlineStats::new();
+/// b.push(f(1e9 + 3.0));
+/// let all = a.merge(b);
+/// assert_eq!(all.count(), 3);
+/// assert_eq!(*all.mean().unwrap(), 1e9 + 2.0);
+/// assert_eq!(*all.sample_variance().unwrap(), 1.0);
+/// ```
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct OnlineStats<F> {
+    count: usize,
+    mean: F,
+    /// `Σ (x - mean)²`
+    m2: F,
+}
+
+impl<F: FloatAlone> OnlineStats<F> {
+    /// Creates a empty accumulator.
+    #[must_use]
+    pub fn new() -> Self {
+        // SAFETY: 0 is a valid float.
+        let zero = unsafe { F::zero() };
+        Self {
+            count: 0,
+            mean: zero,
+            m2: zero,
+        }
+    }
+
+    /// Adds a value.
+    #[allow(clippy::cast_precision_loss)]
+    pub fn push(&mut self, x: F) {
+        self.count += 1;
+        let delta = x - self.mean;
+        // SAFETY: the count is finite, and not 0.
+        self.mean += delta / unsafe { F::from_f64(self.count as f64) };
+        self.m2 += delta * (x - self.mean);
+    }
+
+    /// Combines the accumulators of two parts of a stream (Chan et al.).
+    #[must_use]
+    #[allow(clippy::cast_precision_loss)]
+    pub fn merge(self, other: Self) -> Self {
+        if other.count == 0 {
+            return self;
+        }
+        if self.count == 0 {
+            return other;
+        }
+        let count = self.count + other.count;
+        // SAFETY: the counts are finite, and not 0.
+        let [n, a, b] = [count, self.count, other.count].map(|n| unsafe { F::from_f64(n as f64) });
+        let delta = other.mean - self.mean;
+        Self {
+            count,
+            mean: self.mean + delta * (b / n),
+            m2: self.m2 + other.m2 + delta * delta * (a / n * b),
+        }
+    }
+
+    /// The number of values pushed.
+    #[must_use]
+    pub fn count(&self) -> usize {
+        self.count
+    }
+
+    /// The mean, or [`None`] if there are no values.
+    #[must_use]
+    pub fn mean(&self) -> Option<F> {
+        (self.count != 0).then_some(self.mean)
+    }
+
+    /// The population variance (`Σ (x - mean)² / n`), or [`None`] if there are no values.
+    #[must_use]
+    #[allow(clippy::cast_precision_loss)]
+    pub fn variance(&self) -> Option<F> {
+        // SAFETY: the count is finite, and not 0.
+        (self.count != 0).then(|| self.m2 / unsafe { F::from_f64(self.count as f64) })
+    }
+
+    /// The sample variance (`Σ (x - mean)² / (n - 1)`, with Bessel's correction), or [`None`] if there are less than 2 values.
+    #[must_use]
+    #[allow(clippy::cast_precision_loss)]
+    pub fn sample_variance(&self) -> Option<F> {
+        // SAFETY: the count is finite, and not 0.
+        (self.count > 1).then(|| self.m2 / unsafe { F::from_f64((self.count - 1) as f64) })
+    }
+
+    /// The population standard deviation, or [`None`] if there are no values.
+    #[must_use]
+    pub fn stddev(&self) -> Option<F> {
+        self.variance().map(F::sqrt)
+    }
+
+    /// The sample standard deviation, or [`None`] if there are less than 2 values.
+    #[must_use]
+    pub fn sample_stddev(&self) -> Option<F> {
+        self.sample_variance().map(F::sqrt)
+    }
+}
+
+impl<F: FloatAlone> Default for OnlineStats<F> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<F: FloatAlone> Extend<F> for OnlineStats<F> {
+    fn extend<I: IntoIterator<Item = F>>(&mut self, iter: I) {
+        for x in iter {
+            self.push(x);
+        }
+    }
+}
+
+impl<F: FloatAlone> FromIterator<F> for OnlineStats<F> {
+    fn from_iter<I: IntoIterator<Item = F>>(iter: I) -> Self {
+        let mut stats = Self::new();
+        stats.extend(iter);
+        stats
+    }
+}
+
+#[test]
+fn merged() {
+    let x: Vec<f64> = (0..1000)
+        .map(|i| f64::from((i * 37) % 101) * 0.1 + 1e6)
+        .collect();
+    let all: OnlineStats<f64> = x.iter().copied().collect();
+    let mean = x.iter().sum::<f64>() / 1000.0;
+    let variance = x.iter().map(|x| (x - mean) * (x - mean)).sum::<f64>() / 1000.0;
+    assert!((all.mean().unwrap() - mean).abs() < 1e-9);
+    assert!((all.variance().unwrap() - variance).abs() < 1e-9);
+    let parts = x
+        .chunks(77)
+        .map(|c| c.iter().copied().collect::<OnlineStats<f64>>())
+        .fold(OnlineStats::new(), OnlineStats::merge);
+    assert_eq!(parts.count(), 1000);
+    assert!((parts.mean().unwrap() - mean).abs() < 1e-9);
+    assert!((parts.variance().unwrap() - variance).abs() < 1e-9);
+    let empty = OnlineStats::<f32>::new();
+    assert_eq!(empty.mean(), None);
+    assert_eq!(empty.merge(empty), empty);
+    let mut one = empty;
+    one.push(3.0);
+    assert_eq!(one.variance(), Some(0.0));
+    assert_eq!(one.sample_variance(), None);
+}
@@ -0,0 +1,179 @@
+use crate::generic_float::FloatAlone;
+
+/// A streaming estimate of the `p` quantile, with the P² algorithm (Jain & Chlamtac), in constant memory.
+///
+/// Five markers track the minimum, the `p / 2`, `p`, and `(1 + p) / 2` quantiles, and the maximum.
+/// Each value moves the marker positions, and the heights of the middle markers are adjusted along a parabola through their neighbours,
+/// so long running services can track (for example) their p99 latency without storing every sample.
+/// The first five values are kept exactly, so until then the estimate is the nearest rank quantile of the values so far.
+/// ```
+/// # use umath::{FFloat, stats::P2Quantile};
+/// let mut median = P2Quantile::new(0.5);
+/// for i in 0..1001 {
+///     median.push(unsafe { FFloat::new(f64::from((i * 37) % 1001)) });
+/// }
+/// assert!((*median.estimate().unwrap() - 500.0).abs() < 5.0);
+/// ```
+#[derive(Copy, Clone, Debug)]
+pub struct P2Quantile<F> {
+    p: f64,
+    /// the marker heights (the first `count` values, before there are five).
+    height: [F; 5],
+    /// the actual marker positions.
+    position: [i64; 5],
+    /// the desired marker positions (in f64, so that they keep moving long after a f32 would stop resolving the steps).
+    desired: [f64; 5],
+    /// how much each desired position moves per value.
+    step: [f64; 5],
+    count: usize,
+}
+
+impl<F: FloatAlone> P2Quantile<F> {
+    /// Creates a empty estimator of the `p` quantile.
+    ///
+    /// # Panics
+    ///
+    /// If `p` is not in `(0, 1)`.
+    #[must_use]
+    #[track_caller]
+    pub fn new(p: f64) -> Self {
+        assert!(p > 0.0 && p < 1.0, "quantile must be in (0, 1)");
+        // SAFETY: each of these is finite.
+        let f = |x| unsafe { F::from_f64(x) };
+        Self {
+            p,
+            height: [f(0.0); 5],
+            position: [1, 2, 3, 4, 5],
+            desired: [1.0, 1.0 + 2.0 * p, 1.0 + 4.0 * p, 3.0 + 2.0 * p, 5.0],
+            step: [0.0, p / 2.0, p, f64::midpoint(1.0, p), 1.0],
+            count: 0,
+        }
+    }
+
+    /// Adds a value.
+    pub fn push(&mut self, x: F) {
+        if self.count < 5 {
+            self.height[self.count] = x;
+            self.count += 1;
+            if self.count == 5 {
+                self.height.sort_unstable_by(|a, b| {
+                    a.partial_cmp(b).unwrap_or(core::cmp::Ordering::Equal)
+                });
+            }
+            return;
+        }
+        self.count += 1;
+        let q = &mut self.height;
+        // the cell the value falls in
+        let k = if x < q[0] {
+            q[0] = x;
+            0
+        } else if x >= q[4] {
+            q[4] = x;
+            3
+        } else {
+            (1..4).find(|&i| x < q[i]).unwrap_or(4) - 1
+        };
+        for n in &mut self.position[k + 1..] {
+            *n += 1;
+        }
+        for (d, &s) in self.desired.iter_mut().zip(&self.step) {
+            *d += s;
+        }
+        for i in 1..4 {
+            self.adjust(i);
+        }
+    }
+
+    /// Moves the middle marker `i` by one position, if it is at least one away from where it should be (and its neighbours leave room).
+    #[allow(clippy::cast_precision_loss)]
+    fn adjust(&mut self, i: usize) {
+        let (q, n) = (&mut self.height, &self.position);
+        // SAFETY: the positions are finite.
+        let float = |x: i64| unsafe { F::from_f64(x as f64) };
+        let ahead = self.desired[i] - n[i] as f64;
+        let (step, next) = if ahead >= 1.0 && n[i + 1] - n[i] > 1 {
+            (1, i + 1)
+        } else if ahead <= -1.0 && n[i - 1] - n[i] < -1 {
+            (-1, i - 1)
+        } else {
+            return;
+        };
+        let sign = float(step);
+        // the positions are distinct, so none of these divide by 0.
+        let parabolic = q[i]
+            + sign / float(n[i + 1] - n[i - 1])
+                * (float(n[i] - n[i - 1] + step) * (q[i + 1] - q[i]) / float(n[i + 1] - n[i])
+                    + float(n[i + 1] - n[i] - step) * (q[i] - q[i - 1]) / float(n[i] - n[i - 1]));
+        q[i] = if q[i - 1] < parabolic && parabolic < q[i + 1] {
+            parabolic
+        } else {
+            q[i] + sign * (q[next] - q[i]) / float(n[next] - n[i])
+        };
+        self.position[i] += step;
+    }
+
+    /// The estimated quantile, or [`None`] if no values have been pushed.
+    #[must_use]
+    #[allow(
+        clippy::cast_precision_loss,
+        clippy::cast_possible_truncation,
+        clippy::cast_sign_loss
+    )]
+    pub fn estimate(&self) -> Option<F> {
+        match self.count {
+            0 => None,
+            n @ 1..5 => {
+                let mut x = self.height;
+                let x = &mut x[..n];
+                x.sort_unstable_by(|a, b| a.partial_cmp(b).unwrap_or(core::cmp::Ordering::Equal));
+                Some(x[(self.p * (n - 1) as f64).round() as usize])
+            }
+            _ => Some(self.height[2]),
+        }
+    }
+
+    /// The quantile being estimated.
+    #[must_use]
+    pub fn quantile(&self) -> f64 {
+        self.p
+    }
+
+    /// The number of values pushed.
+    #[must_use]
+    pub fn count(&self) -> usize {
+        self.count
+    }
+}
+
+#[test]
+#[allow(clippy::float_cmp, clippy::cast_precision_loss)]
+fn tracks() {
+    let mut p99 = P2Quantile::new(0.99);
+    let mut p10 = P2Quantile::<f64>::new(0.1);
+    assert_eq!(p99.estimate(), None);
+    p99.push(3.0);
+    p99.push(1.0);
+    assert_eq!(p99.estimate(), Some(3.0));
+    for i in 0..100_000u32 {
+        // a shuffled uniform sequence
+        let x = f64::from(i.wrapping_mul(2_654_435_761) % 100_000);
+        p99.push(x);
+        p10.push(x);
+    }
+    assert!((p99.estimate().unwrap() - 99_000.0).abs() < 500.0);
+    assert!((p10.estimate().unwrap() - 10_000.0).abs() < 500.0);
+    assert_eq!(p99.count(), 100_002);
+    // past 2^24 values, the markers of a f32 estimator still move
+    let mut median = P2Quantile::<f32>::new(0.5);
+    for x in [0.0, 1.0, 2.0, 3.0, 4.0] {
+        median.push(x);
+    }
+    median.count = 1 << 25;
+    median.position = [1, 1 << 23, 1 << 24, 3 << 23, 1 << 25];
+    median.desired = median.position.map(|n| n as f64);
+    for _ in 0..64 {
+        median.push(100.0);
+    }
+    assert!(median.estimate().unwrap() > 2.0);
+}
@@ -0,0 +1,110 @@
+use crate::generic_float::FloatExt;
+use crate::policy::{Ieee, NoNan};
+use crate::r#trait::{FastFloat, Wrapper};
+use crate::slice;
+use crate::FFloat;
+
+/// How [`quantile`] picks a value when the quantile falls between two elements (at the fractional rank `q * (n - 1)`).
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub enum Interpolation {
+    /// The element nearest to the rank (ties to the even index).
+    Nearest,
+    /// Linear interpolation between the two elements around the rank.
+    #[default]
+    Linear,
+    /// The mean of the two elements around the rank.
+    Midpoint,
+}
+
+/// The `q` quantile of `x` (`q` in `[0, 1]`: 0.5 is the median, 0.95 the 95th percentile), or [`None`] if `x` is empty.
+///
+/// The element below the rank is [selected](slice::select_nth) in O(n), and the one above it is the smallest of the rest, so `x` is reordered, but not sorted.
+/// For many quantiles of the same data, sorting it once (with [`SortFloats`](slice::SortFloats)) and indexing is cheaper.
+/// ```
+/// # use umath::{FFloat, stats::{self, Interpolation}};
+/// let mut x = [7.0f64, 1.0, 3.0, 9.0].map(|x| unsafe { FFloat::new(x) });
+/// // the rank is 0.25 * 3 = 0.75, between 1 and 3
+/// assert_eq!(*stats::quantile(&mut x, 0.25, Interpolation::Linear).unwrap(), 2.5);
+/// assert_eq!(*stats::quantile(&mut x, 0.25, Interpolation::Nearest).unwrap(), 3.0);
+/// assert_eq!(*stats::quantile(&mut x, 0.25, Interpolation::Midpoint).unwrap(), 2.0);
+/// assert_eq!(*stats::quantile(&mut x, 1.0, Interpolation::Linear).unwrap(), 9.0);
+/// ```
+///
+/// # Panics
+///
+/// If `q` is not in `[0, 1]`.
+#[must_use]
+#[track_caller]
+#[allow(
+    clippy::cast_precision_loss,
+    clippy::cast_possible_truncation,
+    clippy::cast_sign_loss
+)]
+pub fn quantile<T: FastFloat + FloatExt, P: NoNan>(
+    x: &mut [FFloat<T, P>],
+    q: f64,
+    method: Interpolation,
+) -> Option<FFloat<T, P>> {
+    assert!((0.0..=1.0).contains(&q), "quantile must be in [0, 1]");
+    if x.is_empty() {
+        return None;
+    }
+    let rank = q * (x.len() - 1) as f64;
+    if method == Interpolation::Nearest {
+        return Some(slice::select_nth(x, rank.round_ties_even() as usize));
+    }
+    let i = rank.floor() as usize;
+    let lower = slice::select_nth(x, i);
+    let fraction = rank - i as f64;
+    if fraction == 0.0 {
+        return Some(lower);
+    }
+    // everything after the lower element is not smaller, so the upper one is the smallest of them.
+    let upper = *x[i + 1..].iter().min()?;
+    let t = match method {
+        Interpolation::Midpoint => 0.5,
+        _ => fraction,
+    };
+    // SAFETY: the fraction is in (0, 1), and so is 1 minus it.
+    let (t, rest) = unsafe { (T::from_f64(t), T::from_f64(1.0 - t)) };
+    // weighting each end, as upper - lower overflows for data like [-MAX, MAX];
+    // with IEEE operations, as the fast math flags may factor the midpoint (where both weights are 0.5) into (lower + upper) * 0.5.
+    let blend =
+        unsafe { T::add::<Ieee>(T::mul::<Ieee>(lower.0, rest), T::mul::<Ieee>(upper.0, t)) };
+    // rounding may take it just outside of [lower, upper].
+    let blend = if blend < lower.0 {
+        lower.0
+    } else if blend > upper.0 {
+        upper.0
+    } else {
+        blend
+    };
+    Some(unsafe { FFloat::wrap(blend) })
+}
+
+#[test]
+#[allow(clippy::float_cmp)]
+fn interpolated() {
+    let mut x: Vec<_> = (0..101)
+        .map(|i| unsafe { FFloat::<f64>::new(f64::from((i * 37) % 101)) })
+        .collect();
+    for (q, method, expect) in [
+        (0.0, Interpolation::Linear, 0.0),
+        (0.5, Interpolation::Linear, 50.0),
+        (0.123, Interpolation::Linear, 12.3),
+        (0.123, Interpolation::Nearest, 12.0),
+        (0.125, Interpolation::Nearest, 12.0),
+        (0.123, Interpolation::Midpoint, 12.5),
+        (1.0, Interpolation::Midpoint, 100.0),
+    ] {
+        assert!((*quantile(&mut x, q, method).unwrap() - expect).abs() < 1e-9);
+    }
+    assert_eq!(quantile(&mut x[..0], 0.5, Interpolation::Linear), None);
+    let mut wide = [f64::MIN, f64::MAX].map(|x| unsafe { FFloat::<f64>::new(x) });
+    let middle = quantile(&mut wide, 0.5, Interpolation::Linear).unwrap();
+    assert!(middle.abs() < 1.0);
+    let mut top = [f64::MAX; 2].map(|x| unsafe { FFloat::<f64>::new(x) });
+    for method in [Interpolation::Midpoint, Interpolation::Linear] {
+        assert_eq!(*quantile(&mut top, 0.3, method).unwrap(), f64::MAX);
+    }
+}
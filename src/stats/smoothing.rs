@@ -0,0 +1,191 @@
+use crate::generic_float::FloatAlone;
+
+/// A exponentially weighted moving average: each new value moves the average `alpha` of the way toward it.
+///
+/// Cheap smoothing for noisy signals (sensor readings, frame times), in constant memory.
+/// A larger `alpha` reacts faster, a smaller one smooths more; the weight of a value halves every `ln(2) / -ln(1 - alpha)` updates.
+/// ```
+/// # use umath::{FFloat, stats::Ewma};
+/// let f = |x| unsafe { FFloat::new(x) };
+/// let mut avg = Ewma::new(f(0.5f32));
+/// assert_eq!(avg.value(), None);
+/// assert_eq!(*avg.push(f(16.0)), 16.0); // the first value is taken as is
+/// assert_eq!(*avg.push(f(8.0)), 12.0);
+/// assert_eq!(*avg.push(f(8.0)), 10.0);
+/// ```
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Ewma<F> {
+    alpha: F,
+    value: Option<F>,
+}
+
+impl<F: FloatAlone> Ewma<F> {
+    /// Creates a empty average, with the smoothing factor `alpha`, which should be in `(0, 1]`.
+    #[must_use]
+    pub fn new(alpha: F) -> Self {
+        Self { alpha, value: None }
+    }
+
+    /// Adds a value, returning the new average.
+    pub fn push(&mut self, x: F) -> F {
+        let value = match self.value {
+            Some(v) => v + self.alpha * (x - v),
+            None => x,
+        };
+        self.value = Some(value);
+        value
+    }
+
+    /// The current average, or [`None`] if no values have been pushed.
+    #[must_use]
+    pub fn value(&self) -> Option<F> {
+        self.value
+    }
+
+    /// The smoothing factor.
+    #[must_use]
+    pub fn alpha(&self) -> F {
+        self.alpha
+    }
+
+    /// Forgets every value.
+    pub fn reset(&mut self) {
+        self.value = None;
+    }
+}
+
+/// The mean of the last `window` values, with a ring buffer, updated in O(1).
+///
+/// The running sum is recomputed from the buffer once per `window` updates, so that the rounding error of the adds and subtracts does not accumulate forever.
+/// Until `window` values have been pushed, the mean is of the values so far.
+/// For the windows of a whole slice, refer to [`Rolling`](crate::slice::Rolling) (and [`slice::RollingMean`](crate::slice::RollingMean)).
+/// ```
+/// # use umath::{FFloat, stats::RollingMean};
+/// let f = |x| unsafe { FFloat::new(x) };
+/// let mut avg = RollingMean::new(3);
+/// assert_eq!(*avg.push(f(3.0f64)), 3.0);
+/// assert_eq!(*avg.push(f(6.0)), 4.5);
+/// assert_eq!(*avg.push(f(9.0)), 6.0);
+/// assert_eq!(*avg.push(f(12.0)), 9.0); // 3.0 has left the window
+/// ```
+#[derive(Clone, Debug)]
+pub struct RollingMean<F> {
+    buffer: Vec<F>,
+    window: usize,
+    /// where the next value goes, once the buffer is full.
+    next: usize,
+    sum: F,
+    /// updates since the sum was last recomputed.
+    updates: usize,
+}
+
+impl<F: FloatAlone> RollingMean<F> {
+    /// Creates a empty mean over the last `window` values.
+    ///
+    /// # Panics
+    ///
+    /// If `window` is 0.
+    #[must_use]
+    #[track_caller]
+    pub fn new(window: usize) -> Self {
+        assert_ne!(window, 0, "window must not be 0");
+        Self {
+            buffer: Vec::with_capacity(window),
+            window,
+            next: 0,
+            // SAFETY: 0 is a valid float.
+            sum: unsafe { F::zero() },
+            updates: 0,
+        }
+    }
+
+    /// Adds a value (dropping the oldest one, if the window is full), returning the new mean.
+    pub fn push(&mut self, x: F) -> F {
+        if self.buffer.len() < self.window {
+            self.buffer.push(x);
+            self.sum += x;
+        } else {
+            let old = core::mem::replace(&mut self.buffer[self.next], x);
+            self.next = (self.next + 1) % self.window;
+            self.updates += 1;
+            if self.updates == self.window {
+                self.updates = 0;
+                // SAFETY: 0 is a valid float.
+                self.sum = self.buffer.iter().fold(unsafe { F::zero() }, |a, &b| a + b);
+            } else {
+                self.sum += x - old;
+            }
+        }
+        self.current()
+    }
+
+    #[allow(clippy::cast_precision_loss)]
+    fn current(&self) -> F {
+        // SAFETY: the length is finite, and not 0.
+        self.sum / unsafe { F::from_f64(self.buffer.len() as f64) }
+    }
+
+    /// The current mean, or [`None`] if no values have been pushed.
+    #[must_use]
+    pub fn mean(&self) -> Option<F> {
+        (!self.buffer.is_empty()).then(|| self.current())
+    }
+
+    /// The number of values in the window (at most [`window`](Self::window)).
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.buffer.len()
+    }
+
+    /// Checks if no values have been pushed.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.buffer.is_empty()
+    }
+
+    /// Checks if the window is full.
+    #[must_use]
+    pub fn is_full(&self) -> bool {
+        self.buffer.len() == self.window
+    }
+
+    /// The window length.
+    #[must_use]
+    pub fn window(&self) -> usize {
+        self.window
+    }
+
+    /// Forgets every value.
+    pub fn reset(&mut self) {
+        self.buffer.clear();
+        self.next = 0;
+        // SAFETY: 0 is a valid float.
+        self.sum = unsafe { F::zero() };
+        self.updates = 0;
+    }
+}
+
+#[test]
+#[allow(clippy::float_cmp, clippy::cast_precision_loss)]
+fn smoothing() {
+    let x: Vec<f64> = (0..500).map(|i| f64::from((i * 37) % 101) * 0.1).collect();
+    let mut rolling = RollingMean::new(7);
+    for (i, &v) in x.iter().enumerate() {
+        let mean = rolling.push(v);
+        let window = &x[i.saturating_sub(6)..=i];
+        assert!((mean - window.iter().sum::<f64>() / window.len() as f64).abs() < 1e-12);
+    }
+    assert!(rolling.is_full());
+    rolling.reset();
+    assert_eq!(rolling.mean(), None);
+
+    let mut ewma = Ewma::new(0.25);
+    for _ in 0..200 {
+        ewma.push(4.0);
+    }
+    assert_eq!(ewma.value(), Some(4.0));
+    for _ in 0..200 {
+        ewma.push(0.0);
+    }
+    assert!(ewma.value().unwrap() < 1e-20);
+}
@@ -1,31 +1,118 @@
+use crate::policy::{Lowering, Policy};
 use core::intrinsics::{
-    fadd_fast as add, fdiv_fast as div, fmul_fast as mul, frem_fast as rem, fsub_fast as sub,
+    fadd_algebraic, fadd_fast, fdiv_algebraic, fdiv_fast, fmul_algebraic, fmul_fast,
+    frem_algebraic, frem_fast, fsub_algebraic, fsub_fast,
 };
 macro_rules! meth {
-    ($($name:ident)|+) => {
-        pub trait FastFloat: Copy + core::fmt::Display + core::fmt::Debug + core::ops::Neg<Output = Self> + core::cmp::PartialEq + core::cmp::PartialOrd {
-            $(#[doc(hidden)] unsafe fn $name(a: Self, b: Self) -> Self;)+
+    ($($name:ident($fast:ident, $algebraic:ident, $op:tt))|+) => {
+        pub trait FastFloat: Copy + core::fmt::Debug + core::ops::Neg<Output = Self> + core::cmp::PartialEq + core::cmp::PartialOrd + crate::slice::arch::Arch {
+            $(#[doc(hidden)] unsafe fn $name<P: Policy>(a: Self, b: Self) -> Self;)+
             #[doc(hidden)]
-            fn bad(self) -> bool;
+            fn bad<P: Policy>(self) -> bool;
+            #[doc(hidden)]
+            fn hash<H: core::hash::Hasher>(self, state: &mut H);
+            #[doc(hidden)]
+            fn display(self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result;
         }
 
         impl FastFloat for f32 {
-            $(#[inline(always)] unsafe fn $name(a: Self, b: Self) -> Self {
-                $name(a, b)
+            $(#[inline(always)] unsafe fn $name<P: Policy>(a: Self, b: Self) -> Self {
+                if P::FAST {
+                    $fast(a, b)
+                } else if P::ALGEBRAIC {
+                    $algebraic(a, b)
+                } else {
+                    a $op b
+                }
             })+
 
             #[inline(always)]
-            fn bad(self) -> bool { self.is_nan() || self.is_infinite() }
+            fn bad<P: Policy>(self) -> bool { (P::NNAN && self.is_nan()) || (P::NINF && self.is_infinite()) }
+
+            #[inline(always)]
+            fn hash<H: core::hash::Hasher>(self, state: &mut H) { state.write_u32((self + 0.0).to_bits()) }
+
+            fn display(self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result { core::fmt::Display::fmt(&self, f) }
         }
 
         impl FastFloat for f64 {
-            $(#[inline(always)] unsafe fn $name(a: Self, b: Self) -> Self {
-                $name(a, b)
+            $(#[inline(always)] unsafe fn $name<P: Policy>(a: Self, b: Self) -> Self {
+                if P::FAST {
+                    $fast(a, b)
+                } else if P::ALGEBRAIC {
+                    $algebraic(a, b)
+                } else {
+                    a $op b
+                }
             })+
 
             #[inline(always)]
-            fn bad(self) -> bool { self.is_nan() || self.is_infinite() }
+            fn bad<P: Policy>(self) -> bool { (P::NNAN && self.is_nan()) || (P::NINF && self.is_infinite()) }
+
+            #[inline(always)]
+            fn hash<H: core::hash::Hasher>(self, state: &mut H) { state.write_u64((self + 0.0).to_bits()) }
+
+            fn display(self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result { core::fmt::Display::fmt(&self, f) }
         }
     };
 }
-meth!(add | sub | div | mul | rem);
+meth!(
+    add(fadd_fast, fadd_algebraic, +)
+        | sub(fsub_fast, fsub_algebraic, -)
+        | div(fdiv_fast, fdiv_algebraic, /)
+        | mul(fmul_fast, fmul_algebraic, *)
+        | rem(frem_fast, frem_algebraic, %)
+);
+
+/// Implemented by the float wrappers, so that they may share their operator and trait implementations.
+pub trait Wrapper: Copy {
+    /// The wrapped float.
+    type Inner: FastFloat;
+    /// Decides the operations used, and the invariant upheld.
+    type Policy: Policy;
+
+    /// Reads the inner float, without checking it.
+    fn raw(self) -> Self::Inner;
+
+    /// Checks if somebody else made a mistake (broke the policies invariant), cause UB or panic if so.
+    /// With the `strict-checks` feature, this always panics. With the `trusted` feature, this does nothing,
+    /// unless `strict-checks` is enabled too (by another crate in the graph, as features are additive), which takes precedence.
+    /// # Safety
+    ///
+    /// This can never cause UB unless someone else made a mistake, therefore ub has already occured.
+    #[inline(always)]
+    fn check(self) {
+        if cfg!(all(feature = "trusted", not(feature = "strict-checks"))) {
+            return;
+        }
+        let x = self.raw();
+        if x.bad::<Self::Policy>() {
+            if cfg!(any(debug_assertions, feature = "strict-checks")) {
+                let forbidden = match (Self::Policy::NNAN, Self::Policy::NINF) {
+                    (true, true) => "NAN | INF",
+                    (true, false) => "NAN",
+                    _ => "INF",
+                };
+                panic!("{} is {forbidden}.", Show(x));
+            } else {
+                unsafe { core::hint::unreachable_unchecked() };
+            }
+        }
+    }
+
+    /// Wraps the result of a operation.
+    ///
+    /// # Safety
+    ///
+    /// The result must uphold the wrappers invariant.
+    unsafe fn wrap(x: Self::Inner) -> Self;
+}
+
+/// Displays a [`FastFloat`] (which may not implement [`Display`](core::fmt::Display) itself).
+pub struct Show<T>(pub T);
+
+impl<T: FastFloat> core::fmt::Display for Show<T> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        self.0.display(f)
+    }
+}
@@ -0,0 +1,104 @@
+use crate::generic_float::Constructors;
+use crate::policy::Algebraic;
+use crate::r#trait::{FastFloat, Wrapper};
+use crate::Float;
+use core::ops::Deref;
+#[cfg(doc)]
+use {
+    crate::FFloat,
+    std::f32::{INFINITY as INF, NAN},
+};
+
+/// Safe float wrapper that is always within the unit interval, `0..=1`.
+/// This float also implements [`Ord`], [`Hash`], and [`Eq`].
+///
+/// Every operation saturates its result into `0..=1` ([`NAN`] becomes 0), so the invariant (which subsumes the [`NAN`] | [`INF`] one) can not be broken.
+/// The operations themselves use the algebraic intrinsics, as the saturation makes any [`NAN`] | [`INF`] harmless.
+/// ```
+/// # use umath::UnitFloat;
+/// let alpha = UnitFloat::new(0.75f32).unwrap();
+/// assert_eq!(*(alpha + 0.5), 1.0);
+/// assert_eq!(*(alpha - 1.0), 0.0);
+/// assert_eq!(*alpha.complement(), 0.25);
+/// assert_eq!(alpha.lerp(0.0, 8.0), 6.0);
+/// assert_eq!(UnitFloat::new(1.5f32), None);
+/// ```
+///
+/// `UnitFloat<F>` is guaranteed to have the same memory layout and ABI as F.
+#[repr(transparent)]
+#[derive(Copy, Clone, PartialEq)]
+pub struct UnitFloat<T>(T);
+
+impl<T: FastFloat + Constructors> UnitFloat<T> {
+    /// Create a new [`UnitFloat`] from your {[`f32`], [`f64`]}, if it is within `0..=1`.
+    pub fn new(from: T) -> Option<Self> {
+        // SAFETY: 0 and 1 are valid floats
+        (unsafe { T::zero() } <= from && from <= unsafe { T::one() }).then_some(Self(from))
+    }
+
+    /// Create a new [`UnitFloat`] from your {[`f32`], [`f64`]}, clamping it into `0..=1`. [`NAN`] becomes 0.
+    pub fn saturating(from: T) -> Self {
+        // SAFETY: 0 and 1 are valid floats
+        let (zero, one) = unsafe { (T::zero(), T::one()) };
+        if from >= one {
+            Self(one)
+        } else if from >= zero {
+            Self(from)
+        } else {
+            Self(zero)
+        }
+    }
+
+    /// `1 - self`.
+    pub fn complement(self) -> Self {
+        Self::saturating(unsafe { T::sub::<Algebraic>(T::one(), self.0) })
+    }
+
+    /// Linearly interpolates between `a` and `b`, using `self` as the factor.
+    pub fn lerp<F: Float<T>>(self, a: F, b: F) -> F {
+        a + (b - a) * self.0
+    }
+}
+
+impl<T: FastFloat + Constructors> Wrapper for UnitFloat<T> {
+    type Inner = T;
+    type Policy = Algebraic;
+
+    #[inline(always)]
+    fn raw(self) -> T {
+        self.0
+    }
+
+    #[inline(always)]
+    unsafe fn wrap(x: T) -> Self {
+        Self::saturating(x)
+    }
+}
+
+impl<T> Deref for UnitFloat<T> {
+    type Target = T;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl<F: FastFloat + Float<F>> Float<F> for UnitFloat<F> {
+    /// Create a new [`UnitFloat`] from your {[`f32`], [`f64`]}. This function is safe to call, and saturates `from` into `0..=1`.
+    unsafe fn new(from: F) -> Self {
+        Self::saturating(from)
+    }
+
+    fn take(self) -> F {
+        self.0
+    }
+}
+
+#[test]
+#[allow(clippy::float_cmp)]
+fn saturates() {
+    let half = UnitFloat::new(0.5f64).unwrap();
+    assert_eq!(*(half / 0.0), 1.0);
+    assert_eq!(*(half * f64::NAN), 0.0);
+    assert_eq!(*UnitFloat::saturating(-3.0f64), 0.0);
+}
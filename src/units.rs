@@ -0,0 +1,377 @@
+//! units of measure.
+//!
+//! A [`Quantity`] is a float tagged (at compile time) with its physical dimension, the exponents of the SI base dimensions it is made of.
+//! Adding or subtracting quantities of different dimensions does not compile, and multiplying or dividing them computes the new dimension.
+//! ```
+//! # use umath::{FF32, FFloat, units::*};
+//! let f = |x| unsafe { FFloat::new(x) };
+//! let distance = Quantity::<FF32, Length>::new(f(100.0));
+//! let time = Quantity::<FF32, Time>::new(f(9.58));
+//! let speed: Quantity<FF32, Velocity> = distance / time;
+//! let area: Quantity<FF32, Area> = distance * distance;
+//! assert_eq!(*area.value(), 10000.0);
+//! assert_eq!(format!("{area}"), "10000 m^2");
+//! let _acceleration: Quantity<FF32, Acceleration> = speed / time;
+//! ```
+//! ```compile_fail
+//! # use umath::{FF32, FFloat, units::*};
+//! let distance = Quantity::<FF32, Length>::new(unsafe { FFloat::new(1.0) });
+//! let time = Quantity::<FF32, Time>::new(unsafe { FFloat::new(1.0) });
+//! distance + time;
+//! ```
+use crate::generic_float::FloatAlone;
+use core::cmp::Ordering;
+use core::fmt::{Debug, Display, Formatter, Result};
+use core::marker::PhantomData;
+use core::ops::{Add, AddAssign, Div, DivAssign, Mul, MulAssign, Neg, Sub, SubAssign};
+
+/// Type level integers, used for the exponents of a [`Dim`].
+pub mod int {
+    use core::marker::PhantomData;
+
+    /// A type level integer.
+    pub trait Int {
+        /// The value of this integer.
+        const VALUE: i32;
+    }
+
+    /// Zero.
+    pub struct Z;
+    /// The successor of a non negative integer (`N + 1`).
+    pub struct P<N>(PhantomData<N>);
+    /// The predecessor of a non positive integer (`N - 1`).
+    pub struct N<N>(PhantomData<N>);
+
+    impl Int for Z {
+        const VALUE: i32 = 0;
+    }
+    impl<I: Int> Int for P<I> {
+        const VALUE: i32 = I::VALUE + 1;
+    }
+    impl<I: Int> Int for N<I> {
+        const VALUE: i32 = I::VALUE - 1;
+    }
+
+    /// `+1`
+    pub trait Inc {
+        /// The result.
+        type Output;
+    }
+    impl Inc for Z {
+        type Output = P<Z>;
+    }
+    impl<I> Inc for P<I> {
+        type Output = P<P<I>>;
+    }
+    impl<I> Inc for N<I> {
+        type Output = I;
+    }
+
+    /// `-1`
+    pub trait Dec {
+        /// The result.
+        type Output;
+    }
+    impl Dec for Z {
+        type Output = N<Z>;
+    }
+    impl<I> Dec for P<I> {
+        type Output = I;
+    }
+    impl<I> Dec for N<I> {
+        type Output = N<N<I>>;
+    }
+
+    /// Addition.
+    pub trait Sum<R> {
+        /// The result.
+        type Output;
+    }
+    impl<R> Sum<R> for Z {
+        type Output = R;
+    }
+    impl<I: Sum<R>, R> Sum<R> for P<I>
+    where
+        I::Output: Inc,
+    {
+        type Output = <I::Output as Inc>::Output;
+    }
+    impl<I: Sum<R>, R> Sum<R> for N<I>
+    where
+        I::Output: Dec,
+    {
+        type Output = <I::Output as Dec>::Output;
+    }
+
+    /// Negation.
+    pub trait Negate {
+        /// The result.
+        type Output;
+    }
+    impl Negate for Z {
+        type Output = Z;
+    }
+    impl<I: Negate> Negate for P<I>
+    where
+        I::Output: Dec,
+    {
+        type Output = <I::Output as Dec>::Output;
+    }
+    impl<I: Negate> Negate for N<I>
+    where
+        I::Output: Inc,
+    {
+        type Output = <I::Output as Inc>::Output;
+    }
+
+    /// Subtraction.
+    pub trait Diff<R> {
+        /// The result.
+        type Output;
+    }
+    impl<I: Sum<<R as Negate>::Output>, R: Negate> Diff<R> for I {
+        type Output = <I as Sum<R::Output>>::Output;
+    }
+
+    /// 1
+    pub type P1 = P<Z>;
+    /// 2
+    pub type P2 = P<P1>;
+    /// 3
+    pub type P3 = P<P2>;
+    /// -1
+    pub type N1 = N<Z>;
+    /// -2
+    pub type N2 = N<N1>;
+    /// -3
+    pub type N3 = N<N2>;
+}
+
+use int::{Diff, Int, Sum, N1, N2, N3, P1, P2, P3, Z};
+
+/// A physical dimension: the exponents of length, mass, time, current, and temperature.
+pub struct Dim<L, M, T, I, K>(PhantomData<(L, M, T, I, K)>);
+
+/// A dimension. This is implemented by every [`Dim`].
+pub trait Dimension {
+    /// The exponents, in the order (length, mass, time, current, temperature).
+    const EXPONENTS: [i32; 5];
+}
+
+impl<L: Int, M: Int, T: Int, I: Int, K: Int> Dimension for Dim<L, M, T, I, K> {
+    const EXPONENTS: [i32; 5] = [L::VALUE, M::VALUE, T::VALUE, I::VALUE, K::VALUE];
+}
+
+/// The dimension of a product.
+pub trait DimMul<R> {
+    /// The result.
+    type Output;
+}
+
+/// The dimension of a quotient.
+pub trait DimDiv<R> {
+    /// The result.
+    type Output;
+}
+
+impl<L, M, T, I, K, L2, M2, T2, I2, K2> DimMul<Dim<L2, M2, T2, I2, K2>> for Dim<L, M, T, I, K>
+where
+    L: Sum<L2>,
+    M: Sum<M2>,
+    T: Sum<T2>,
+    I: Sum<I2>,
+    K: Sum<K2>,
+{
+    type Output = Dim<L::Output, M::Output, T::Output, I::Output, K::Output>;
+}
+
+impl<L, M, T, I, K, L2, M2, T2, I2, K2> DimDiv<Dim<L2, M2, T2, I2, K2>> for Dim<L, M, T, I, K>
+where
+    L: Diff<L2>,
+    M: Diff<M2>,
+    T: Diff<T2>,
+    I: Diff<I2>,
+    K: Diff<K2>,
+{
+    type Output = Dim<L::Output, M::Output, T::Output, I::Output, K::Output>;
+}
+
+/// No dimension (a plain number).
+pub type Dimensionless = Dim<Z, Z, Z, Z, Z>;
+/// Length (m).
+pub type Length = Dim<P1, Z, Z, Z, Z>;
+/// Mass (kg).
+pub type Mass = Dim<Z, P1, Z, Z, Z>;
+/// Time (s).
+pub type Time = Dim<Z, Z, P1, Z, Z>;
+/// Electric current (A).
+pub type Current = Dim<Z, Z, Z, P1, Z>;
+/// Temperature (K).
+pub type Temperature = Dim<Z, Z, Z, Z, P1>;
+/// Area (m²).
+pub type Area = Dim<P2, Z, Z, Z, Z>;
+/// Volume (m³).
+pub type Volume = Dim<P3, Z, Z, Z, Z>;
+/// Frequency (Hz, s⁻¹).
+pub type Frequency = Dim<Z, Z, N1, Z, Z>;
+/// Velocity (m s⁻¹).
+pub type Velocity = Dim<P1, Z, N1, Z, Z>;
+/// Acceleration (m s⁻²).
+pub type Acceleration = Dim<P1, Z, N2, Z, Z>;
+/// Force (N, kg m s⁻²).
+pub type Force = Dim<P1, P1, N2, Z, Z>;
+/// Energy (J, kg m² s⁻²).
+pub type Energy = Dim<P2, P1, N2, Z, Z>;
+/// Power (W, kg m² s⁻³).
+pub type Power = Dim<P2, P1, N3, Z, Z>;
+
+/// A float with a physical dimension `D`. Refer to the [module level documentation](self).
+#[repr(transparent)]
+pub struct Quantity<F, D>(F, PhantomData<D>);
+
+impl<F: FloatAlone, D> Quantity<F, D> {
+    /// Creates a new [`Quantity`], in SI base units.
+    pub const fn new(value: F) -> Self {
+        Self(value, PhantomData)
+    }
+
+    /// The value, in SI base units.
+    pub fn value(self) -> F {
+        self.0
+    }
+}
+
+impl<F: Copy, D> Clone for Quantity<F, D> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<F: Copy, D> Copy for Quantity<F, D> {}
+
+impl<F: PartialEq, D> PartialEq for Quantity<F, D> {
+    fn eq(&self, other: &Self) -> bool {
+        self.0 == other.0
+    }
+}
+
+impl<F: PartialOrd, D> PartialOrd for Quantity<F, D> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        self.0.partial_cmp(&other.0)
+    }
+}
+
+impl<F: FloatAlone, D> Add for Quantity<F, D> {
+    type Output = Self;
+    fn add(self, rhs: Self) -> Self {
+        Self::new(self.0 + rhs.0)
+    }
+}
+
+impl<F: FloatAlone, D> Sub for Quantity<F, D> {
+    type Output = Self;
+    fn sub(self, rhs: Self) -> Self {
+        Self::new(self.0 - rhs.0)
+    }
+}
+
+impl<F: FloatAlone, D: DimMul<D2>, D2> Mul<Quantity<F, D2>> for Quantity<F, D> {
+    type Output = Quantity<F, D::Output>;
+    fn mul(self, rhs: Quantity<F, D2>) -> Self::Output {
+        Quantity::new(self.0 * rhs.0)
+    }
+}
+
+impl<F: FloatAlone, D: DimDiv<D2>, D2> Div<Quantity<F, D2>> for Quantity<F, D> {
+    type Output = Quantity<F, D::Output>;
+    fn div(self, rhs: Quantity<F, D2>) -> Self::Output {
+        Quantity::new(self.0 / rhs.0)
+    }
+}
+
+impl<F: FloatAlone, D> Mul<F> for Quantity<F, D> {
+    type Output = Self;
+    fn mul(self, rhs: F) -> Self {
+        Self::new(self.0 * rhs)
+    }
+}
+
+impl<F: FloatAlone, D> Div<F> for Quantity<F, D> {
+    type Output = Self;
+    fn div(self, rhs: F) -> Self {
+        Self::new(self.0 / rhs)
+    }
+}
+
+impl<F: FloatAlone, D> Neg for Quantity<F, D> {
+    type Output = Self;
+    fn neg(self) -> Self {
+        Self::new(-self.0)
+    }
+}
+
+impl<F: FloatAlone, D> AddAssign for Quantity<F, D> {
+    fn add_assign(&mut self, rhs: Self) {
+        self.0 += rhs.0;
+    }
+}
+
+impl<F: FloatAlone, D> SubAssign for Quantity<F, D> {
+    fn sub_assign(&mut self, rhs: Self) {
+        self.0 -= rhs.0;
+    }
+}
+
+impl<F: FloatAlone, D> MulAssign<F> for Quantity<F, D> {
+    fn mul_assign(&mut self, rhs: F) {
+        self.0 *= rhs;
+    }
+}
+
+impl<F: FloatAlone, D> DivAssign<F> for Quantity<F, D> {
+    fn div_assign(&mut self, rhs: F) {
+        self.0 /= rhs;
+    }
+}
+
+impl<F: FloatAlone> From<F> for Quantity<F, Dimensionless> {
+    fn from(value: F) -> Self {
+        Self::new(value)
+    }
+}
+
+/// Writes the SI base units, like `kg m s^-2`.
+fn units<D: Dimension>(f: &mut Formatter<'_>) -> Result {
+    for (exp, unit) in D::EXPONENTS.into_iter().zip(["m", "kg", "s", "A", "K"]) {
+        match exp {
+            0 => {}
+            1 => write!(f, " {unit}")?,
+            n => write!(f, " {unit}^{n}")?,
+        }
+    }
+    Ok(())
+}
+
+impl<F: Display, D: Dimension> Display for Quantity<F, D> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+        Display::fmt(&self.0, f)?;
+        units::<D>(f)
+    }
+}
+
+impl<F: Debug, D: Dimension> Debug for Quantity<F, D> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+        Debug::fmt(&self.0, f)?;
+        units::<D>(f)
+    }
+}
+
+#[test]
+fn algebra() {
+    let force = Quantity::<f64, Mass>::new(2.0) * Quantity::<_, Acceleration>::new(3.0);
+    let work: Quantity<f64, Energy> = force * Quantity::<_, Length>::new(4.0);
+    let power: Quantity<f64, Power> = work / Quantity::<_, Time>::new(6.0);
+    assert_eq!(power.to_string(), "4 m^2 kg s^-3");
+    let ratio: Quantity<f64, Dimensionless> = power / power;
+    assert_eq!(ratio.to_string(), "1");
+}
@@ -0,0 +1,380 @@
+//! small fixed size vectors: [`Vec2`], [`Vec3`], and [`Vec4`], and [`VecN`], for any other dimension.
+//!
+//! The vectors are generic over [`FloatAlone`], so a <code>[Vec3]<[FFloat](crate::FFloat)></code> computes everything with the fast intrinsics, and every component stays finite.
+//! They are [`Point`](crate::curve::Point)s, so they can be used with the [curves](crate::curve), and are transformed by [matrices](crate::matrix) and [quaternions](crate::quaternion).
+//! ```
+//! # use umath::{FFloat, vector::Vec3};
+//! let f = |x| unsafe { FFloat::new(x) };
+//! let x = Vec3::new(f(1.0f32), f(0.0), f(0.0));
+//! let y = Vec3::new(f(0.0), f(1.0), f(0.0));
+//! assert_eq!(x.cross(y), Vec3::new(f(0.0), f(0.0), f(1.0)));
+//! assert_eq!(*x.dot(y), 0.0);
+//! let v = Vec3::new(f(3.0), f(0.0), f(4.0));
+//! assert_eq!(*v.length(), 5.0);
+//! assert!((*v.normalize().length() - 1.0).abs() < 1e-6);
+//! ```
+use crate::generic_float::{FloatAlone, FloatMethods};
+use core::ops::{
+    Add, AddAssign, Div, DivAssign, Index, IndexMut, Mul, MulAssign, Neg, Sub, SubAssign,
+};
+
+macro_rules! vector {
+    ($(#[$meta:meta])* $name:ident, $n:literal, [$($field:ident),+]) => {
+        $(#[$meta])*
+        #[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Hash)]
+        pub struct $name<F> {
+            $(
+                #[doc = concat!("The `", stringify!($field), "` component.")]
+                pub $field: F,
+            )+
+        }
+
+        impl<F: FloatAlone> $name<F> {
+            #[doc = concat!("Creates a new [`", stringify!($name), "`].")]
+            pub const fn new($($field: F),+) -> Self {
+                Self { $($field),+ }
+            }
+
+            /// A vector with every component set to `value`.
+            pub const fn splat(value: F) -> Self {
+                Self { $($field: value),+ }
+            }
+
+            /// The components, as an array.
+            pub const fn to_array(self) -> [F; $n] {
+                [$(self.$field),+]
+            }
+
+            /// The dot product, `Σ self[i] · rhs[i]`.
+            pub fn dot(self, rhs: Self) -> F {
+                vector!(@sum $(self.$field * rhs.$field),+)
+            }
+
+            /// The squared length, `self · self`. This is cheaper than [`length`](Self::length).
+            pub fn length_squared(self) -> F {
+                self.dot(self)
+            }
+
+            /// The (euclidean) length, `√(self · self)`.
+            pub fn length(self) -> F {
+                self.length_squared().sqrt()
+            }
+
+            /// The vector scaled to a length of 1, by multiplying it with the reciprocal square root of its squared length
+            /// (which the fast math flags allow to be compiled to a reciprocal square root estimate, and a newton step).
+            ///
+            /// `self` must not be zero (the reciprocal of its length would be infinite).
+            pub fn normalize(self) -> Self {
+                // SAFETY: 1 is a valid float
+                self * (unsafe { F::one() } / self.length())
+            }
+
+            /// The linear interpolation from `self` (at `t = 0`) to `other` (at `t = 1`).
+            pub fn lerp(self, other: Self, t: F) -> Self {
+                self + (other - self) * t
+            }
+
+            /// The smaller of each pair of components.
+            pub fn min(self, other: Self) -> Self {
+                Self { $($field: FloatMethods::min(self.$field, other.$field)),+ }
+            }
+
+            /// The larger of each pair of components.
+            pub fn max(self, other: Self) -> Self {
+                Self { $($field: FloatMethods::max(self.$field, other.$field)),+ }
+            }
+        }
+
+        impl<F> From<[F; $n]> for $name<F> {
+            fn from([$($field),+]: [F; $n]) -> Self {
+                Self { $($field),+ }
+            }
+        }
+
+        impl<F> From<$name<F>> for [F; $n] {
+            fn from(v: $name<F>) -> Self {
+                [$(v.$field),+]
+            }
+        }
+
+        impl<F> From<$name<F>> for VecN<F, $n> {
+            fn from(v: $name<F>) -> Self {
+                Self(v.into())
+            }
+        }
+
+        impl<F> From<VecN<F, $n>> for $name<F> {
+            fn from(v: VecN<F, $n>) -> Self {
+                v.0.into()
+            }
+        }
+
+        impl<F: FloatAlone> Neg for $name<F> {
+            type Output = Self;
+            fn neg(self) -> Self {
+                Self { $($field: -self.$field),+ }
+            }
+        }
+
+        vector!(@ops $name [$($field),+] Add, add, AddAssign, add_assign);
+        vector!(@ops $name [$($field),+] Sub, sub, SubAssign, sub_assign);
+        vector!(@ops $name [$($field),+] Mul, mul, MulAssign, mul_assign);
+        vector!(@ops $name [$($field),+] Div, div, DivAssign, div_assign);
+    };
+    (@sum $first:expr $(, $rest:expr)*) => {
+        $first $(+ $rest)*
+    };
+    (@ops $name:ident [$($field:ident),+] $trait:ident, $fn:ident, $assign:ident, $assign_fn:ident) => {
+        /// Componentwise.
+        impl<F: FloatAlone> $trait for $name<F> {
+            type Output = Self;
+            fn $fn(self, rhs: Self) -> Self {
+                Self { $($field: self.$field.$fn(rhs.$field)),+ }
+            }
+        }
+
+        /// With every component.
+        impl<F: FloatAlone> $trait<F> for $name<F> {
+            type Output = Self;
+            fn $fn(self, rhs: F) -> Self {
+                Self { $($field: self.$field.$fn(rhs)),+ }
+            }
+        }
+
+        impl<F: FloatAlone> $assign for $name<F> {
+            fn $assign_fn(&mut self, rhs: Self) {
+                *self = (*self).$fn(rhs);
+            }
+        }
+
+        impl<F: FloatAlone> $assign<F> for $name<F> {
+            fn $assign_fn(&mut self, rhs: F) {
+                *self = (*self).$fn(rhs);
+            }
+        }
+    };
+}
+
+vector! {
+    /// A two dimensional vector, `[x, y]`. Refer to the [module level documentation](self).
+    Vec2, 2, [x, y]
+}
+
+vector! {
+    /// A three dimensional vector, `[x, y, z]`. Refer to the [module level documentation](self).
+    Vec3, 3, [x, y, z]
+}
+
+vector! {
+    /// A four dimensional vector, `[x, y, z, w]` (such as a point in homogeneous coordinates, or a color with alpha). Refer to the [module level documentation](self).
+    Vec4, 4, [x, y, z, w]
+}
+
+impl<F: FloatAlone> Vec2<F> {
+    /// The (z component of the) cross product, `self.x · rhs.y - self.y · rhs.x`: the signed area of the parallelogram the vectors span
+    /// (positive when `rhs` is counterclockwise from `self`).
+    pub fn perp_dot(self, rhs: Self) -> F {
+        self.x * rhs.y - self.y * rhs.x
+    }
+}
+
+impl<F: FloatAlone> Vec3<F> {
+    /// The cross product: perpendicular to both vectors (by the right hand rule), with the area of the parallelogram they span as its length.
+    pub fn cross(self, rhs: Self) -> Self {
+        Self::new(
+            self.y * rhs.z - self.z * rhs.y,
+            self.z * rhs.x - self.x * rhs.z,
+            self.x * rhs.y - self.y * rhs.x,
+        )
+    }
+}
+
+/// A vector of any (fixed) dimension, `N`, on a array: for feature vectors, joint states, and anything else that is not two, three, or four dimensional.
+///
+/// It has the operations of the other vectors (but not their named components, so it is indexed instead).
+/// ```
+/// # use umath::{FFloat, vector::VecN};
+/// let f = |x| unsafe { FFloat::new(x) };
+/// let joints = VecN([0.5, -1.0, 0.5, 2.0, 0.0, 1.5].map(f));
+/// let target = VecN::splat(f(0.0f64));
+/// assert_eq!(*(joints - target).length_squared(), 7.75);
+/// assert_eq!(*joints[3], 2.0);
+/// ```
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct VecN<F, const N: usize>(pub [F; N]);
+
+impl<F: FloatAlone, const N: usize> VecN<F, N> {
+    /// Creates a new [`VecN`].
+    pub const fn new(components: [F; N]) -> Self {
+        Self(components)
+    }
+
+    /// A vector with every component set to `value`.
+    pub const fn splat(value: F) -> Self {
+        Self([value; N])
+    }
+
+    /// The components, as an array.
+    pub const fn to_array(self) -> [F; N] {
+        self.0
+    }
+
+    /// The dot product, `Σ self[i] · rhs[i]` (0, if `N` is 0).
+    pub fn dot(self, rhs: Self) -> F {
+        // SAFETY: 0 is a valid float
+        let zero = unsafe { F::zero() };
+        self.0
+            .iter()
+            .zip(rhs.0)
+            .fold(zero, |sum, (&a, b)| sum + a * b)
+    }
+
+    /// The squared length, `self · self`. This is cheaper than [`length`](Self::length).
+    pub fn length_squared(self) -> F {
+        self.dot(self)
+    }
+
+    /// The (euclidean) length, or norm, `√(self · self)`.
+    pub fn length(self) -> F {
+        self.length_squared().sqrt()
+    }
+
+    /// The vector scaled to a length of 1 (refer to [`Vec3::normalize`]).
+    ///
+    /// `self` must not be zero (the reciprocal of its length would be infinite).
+    pub fn normalize(self) -> Self {
+        // SAFETY: 1 is a valid float
+        self * (unsafe { F::one() } / self.length())
+    }
+
+    /// The linear interpolation from `self` (at `t = 0`) to `other` (at `t = 1`).
+    pub fn lerp(self, other: Self, t: F) -> Self {
+        self + (other - self) * t
+    }
+
+    /// The smaller of each pair of components.
+    pub fn min(self, other: Self) -> Self {
+        Self(core::array::from_fn(|i| {
+            FloatMethods::min(self.0[i], other.0[i])
+        }))
+    }
+
+    /// The larger of each pair of components.
+    pub fn max(self, other: Self) -> Self {
+        Self(core::array::from_fn(|i| {
+            FloatMethods::max(self.0[i], other.0[i])
+        }))
+    }
+}
+
+impl<F: Default, const N: usize> Default for VecN<F, N> {
+    fn default() -> Self {
+        Self(core::array::from_fn(|_| F::default()))
+    }
+}
+
+impl<F, const N: usize> From<[F; N]> for VecN<F, N> {
+    fn from(components: [F; N]) -> Self {
+        Self(components)
+    }
+}
+
+impl<F, const N: usize> From<VecN<F, N>> for [F; N] {
+    fn from(v: VecN<F, N>) -> Self {
+        v.0
+    }
+}
+
+impl<F, const N: usize> Index<usize> for VecN<F, N> {
+    type Output = F;
+    fn index(&self, index: usize) -> &F {
+        &self.0[index]
+    }
+}
+
+impl<F, const N: usize> IndexMut<usize> for VecN<F, N> {
+    fn index_mut(&mut self, index: usize) -> &mut F {
+        &mut self.0[index]
+    }
+}
+
+impl<F: FloatAlone, const N: usize> Neg for VecN<F, N> {
+    type Output = Self;
+    fn neg(self) -> Self {
+        Self(self.0.map(|x| -x))
+    }
+}
+
+macro_rules! ops {
+    ($($trait:ident, $fn:ident, $assign:ident, $assign_fn:ident);+ $(;)?) => {
+        $(
+            /// Componentwise.
+            impl<F: FloatAlone, const N: usize> $trait for VecN<F, N> {
+                type Output = Self;
+                fn $fn(self, rhs: Self) -> Self {
+                    Self(core::array::from_fn(|i| self.0[i].$fn(rhs.0[i])))
+                }
+            }
+
+            /// With every component.
+            impl<F: FloatAlone, const N: usize> $trait<F> for VecN<F, N> {
+                type Output = Self;
+                fn $fn(self, rhs: F) -> Self {
+                    Self(self.0.map(|x| x.$fn(rhs)))
+                }
+            }
+
+            impl<F: FloatAlone, const N: usize> $assign for VecN<F, N> {
+                fn $assign_fn(&mut self, rhs: Self) {
+                    *self = (*self).$fn(rhs);
+                }
+            }
+
+            impl<F: FloatAlone, const N: usize> $assign<F> for VecN<F, N> {
+                fn $assign_fn(&mut self, rhs: F) {
+                    *self = (*self).$fn(rhs);
+                }
+            }
+        )+
+    };
+}
+
+ops! {
+    Add, add, AddAssign, add_assign;
+    Sub, sub, SubAssign, sub_assign;
+    Mul, mul, MulAssign, mul_assign;
+    Div, div, DivAssign, div_assign;
+}
+
+#[test]
+#[allow(clippy::float_cmp)]
+fn vectors() {
+    let a = Vec3::new(1.0, 2.0, 3.0);
+    let b = Vec3::from([4.0, -5.0, 6.0]);
+    assert_eq!(a + b, Vec3::new(5.0, -3.0, 9.0));
+    assert_eq!(a - b, -(b - a));
+    assert_eq!(a * b, Vec3::new(4.0, -10.0, 18.0));
+    assert_eq!(b / Vec3::splat(2.0), b * 0.5);
+    assert_eq!(a.dot(b), 12.0);
+    let c = a.cross(b);
+    assert_eq!((c.dot(a), c.dot(b)), (0.0, 0.0));
+    assert_eq!(a.min(b), Vec3::new(1.0, -5.0, 3.0));
+    assert_eq!(a.max(b).to_array(), [4.0, 2.0, 6.0]);
+    assert_eq!(a.lerp(b, 0.5), Vec3::new(2.5, -1.5, 4.5));
+    let mut v = Vec4::new(2.0, 0.0, 0.0, 0.0);
+    v += Vec4::splat(1.0);
+    v *= 2.0;
+    assert_eq!(<[f64; 4]>::from(v), [6.0, 2.0, 2.0, 2.0]);
+    assert_eq!(v.length_squared(), 48.0);
+    assert!((Vec2::new(3.0f32, 4.0).normalize().length() - 1.0).abs() < 1e-6);
+    assert_eq!(Vec2::new(1.0, 0.0).perp_dot(Vec2::new(0.0, 1.0)), 1.0);
+    let mut wide = VecN::from([1.0, 2.0, 3.0, 4.0, 5.0]);
+    wide[4] = -5.0;
+    wide -= VecN::splat(1.0);
+    assert_eq!(wide.to_array(), [0.0, 1.0, 2.0, 3.0, -6.0]);
+    assert_eq!((wide * 2.0).dot(wide), 100.0);
+    assert_eq!(wide.min(-wide), VecN([0.0, -1.0, -2.0, -3.0, -6.0]));
+    assert_eq!(VecN::from(a).dot(VecN::from(b)), a.dot(b));
+    assert_eq!(Vec3::from(VecN::from(a)), a);
+    assert_eq!(VecN::<f64, 0>::default().length(), 0.0);
+}
@@ -0,0 +1,169 @@
+//! window functions, for tapering a block of samples before a [fft](crate::fft) (to reduce spectral leakage), or a fir filter design.
+//!
+//! The windows are computed in [`f64`], and written into (or multiplied onto) buffers of [`FFloat`]s.
+//! ```
+//! # use umath::{FFloat, window::{Symmetry, Window}};
+//! let mut buffer = [unsafe { FFloat::new(0.0f32) }; 5];
+//! Window::Hann.fill(Symmetry::Symmetric, &mut buffer);
+//! assert_eq!(buffer, [0.0, 0.5, 1.0, 0.5, 0.0]);
+//! ```
+use crate::generic_float::FloatExt;
+use crate::policy::Policy;
+use crate::r#trait::{FastFloat, Wrapper};
+use crate::FFloat;
+use core::f64::consts::TAU;
+
+/// A window function: a taper from the middle of a block (where it is 1, or close to it) toward its ends.
+///
+/// The windows trade the width of the main lobe (the frequency resolution) against the height of the side lobes (the leakage), roughly in the order listed.
+#[derive(Copy, Clone, Debug, Default, PartialEq)]
+pub enum Window {
+    /// `1`: no tapering. The narrowest main lobe, and the highest side lobes (-13 dB).
+    Rectangular,
+    /// `0.5 - 0.5 cos(2πx)`: a raised cosine, reaching 0 at the ends. The side lobes are at -31 dB, and fall off quickly.
+    #[default]
+    Hann,
+    /// `0.54 - 0.46 cos(2πx)`: a raised cosine, tuned to cancel the first side lobe (-43 dB), but not reaching 0.
+    Hamming,
+    /// The three term blackman window, `0.42 - 0.5 cos(2πx) + 0.08 cos(4πx)`. The side lobes are at -58 dB.
+    Blackman,
+    /// The four term (minimum) blackman-harris window. The side lobes are at -92 dB.
+    BlackmanHarris,
+    /// The kaiser window, `I₀(β √(1 - (2x - 1)²)) / I₀(β)`, which approximates the best concentration of energy in the main lobe.
+    /// Larger `β`s widen the main lobe, and lower the side lobes: 0 is [rectangular](Self::Rectangular), 5 is close to [hamming](Self::Hamming), and 8.6 to [blackman](Self::Blackman).
+    Kaiser(f64),
+}
+
+/// How a window is laid out over a block of `n` samples.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub enum Symmetry {
+    /// Symmetric, with both ends on the ends of the window: for filter design.
+    Symmetric,
+    /// One period of a window of `n + 1` samples, without the last one (so that it repeats seamlessly): for spectral analysis.
+    #[default]
+    Periodic,
+}
+
+/// The modified bessel function of the first kind, of order 0: `I₀(x) = Σ ((x / 2)^k / k!)²`.
+///
+/// The power series converges for every `x` (the terms are all positive, so there is no cancellation), within about `|x|` terms.
+fn bessel_i0(x: f64) -> f64 {
+    let quarter = x * x / 4.0;
+    let (mut sum, mut term) = (1.0, 1.0);
+    let mut k = 1.0;
+    while term > sum * f64::EPSILON {
+        term *= quarter / (k * k);
+        sum += term;
+        k += 1.0;
+    }
+    sum
+}
+
+impl Window {
+    /// The window at `x`, from 0 (the start) to 1 (the end).
+    #[must_use]
+    pub fn at(self, x: f64) -> f64 {
+        let cosine = |terms: &[f64]| {
+            terms
+                .iter()
+                .zip(0u8..)
+                .map(|(&a, k)| {
+                    let sign = if k % 2 == 0 { 1.0 } else { -1.0 };
+                    sign * a * (TAU * f64::from(k) * x).cos()
+                })
+                .sum()
+        };
+        match self {
+            Self::Rectangular => 1.0,
+            Self::Hann => cosine(&[0.5, 0.5]),
+            Self::Hamming => cosine(&[0.54, 0.46]),
+            Self::Blackman => cosine(&[0.42, 0.5, 0.08]),
+            Self::BlackmanHarris => cosine(&[0.358_75, 0.488_29, 0.141_28, 0.011_68]),
+            Self::Kaiser(β) => {
+                let centered = 2.0 * x - 1.0;
+                bessel_i0(β * (1.0 - centered * centered).max(0.0).sqrt()) / bessel_i0(β)
+            }
+        }
+    }
+
+    /// The window of `n` samples, laid out by `symmetry`, at each index.
+    #[allow(clippy::cast_precision_loss)]
+    fn samples(self, symmetry: Symmetry, n: usize) -> impl Iterator<Item = f64> {
+        let period = match symmetry {
+            Symmetry::Symmetric => n.saturating_sub(1),
+            Symmetry::Periodic => n,
+        };
+        (0..n).map(move |i| {
+            if period == 0 {
+                // a window of 1 sample is just its middle
+                self.at(0.5)
+            } else {
+                self.at(i as f64 / period as f64)
+            }
+        })
+    }
+
+    /// Writes the window, laid out by `symmetry`, into `buffer`.
+    pub fn fill<T: FastFloat + FloatExt, P: Policy>(
+        self,
+        symmetry: Symmetry,
+        buffer: &mut [FFloat<T, P>],
+    ) {
+        let n = buffer.len();
+        for (x, w) in buffer.iter_mut().zip(self.samples(symmetry, n)) {
+            // SAFETY: the windows are finite.
+            *x = unsafe { FFloat::wrap(T::from_f64(w)) };
+        }
+    }
+
+    /// Multiplies `buffer` by the window, laid out by `symmetry`, in place.
+    pub fn apply<T: FastFloat + FloatExt, P: Policy>(
+        self,
+        symmetry: Symmetry,
+        buffer: &mut [FFloat<T, P>],
+    ) {
+        let n = buffer.len();
+        for (x, w) in buffer.iter_mut().zip(self.samples(symmetry, n)) {
+            // SAFETY: the windows are finite.
+            *x *= unsafe { FFloat::wrap(T::from_f64(w)) };
+        }
+    }
+}
+
+#[test]
+#[allow(clippy::float_cmp)]
+fn windowed() {
+    assert!((bessel_i0(1.0) - 1.266_065_877_752_008_4).abs() < 1e-15);
+    assert!((bessel_i0(10.0) / 2_815.716_628_466_254 - 1.0).abs() < 1e-14);
+    let f = |x| unsafe { FFloat::<f64>::new(x) };
+    let windows = [
+        Window::Rectangular,
+        Window::Hann,
+        Window::Hamming,
+        Window::Blackman,
+        Window::BlackmanHarris,
+        Window::Kaiser(6.0),
+    ];
+    for window in windows {
+        // symmetric, and 1 in the middle
+        let mut symmetric = [f(0.0); 9];
+        window.fill(Symmetry::Symmetric, &mut symmetric);
+        assert!((*symmetric[4] - 1.0).abs() < 1e-12, "{window:?}");
+        assert!((0..9).all(|i| (*symmetric[i] - *symmetric[8 - i]).abs() < 1e-12));
+        // the periodic window is the symmetric window one sample longer, without the last sample
+        let mut periodic = [f(0.0); 8];
+        window.fill(Symmetry::Periodic, &mut periodic);
+        assert!(periodic
+            .iter()
+            .zip(&symmetric)
+            .all(|(x, y)| (**x - **y).abs() < 1e-12));
+        let mut signal = [f(2.0); 8];
+        window.apply(Symmetry::Periodic, &mut signal);
+        assert!(signal.iter().zip(&periodic).all(|(x, w)| *x == *w * 2.0));
+    }
+    assert_eq!(Window::Kaiser(0.0).at(0.1), 1.0);
+    assert!((Window::Hamming.at(0.0) - 0.08).abs() < 1e-12);
+    let mut single = [f(0.0)];
+    Window::Hann.fill(Symmetry::Symmetric, &mut single);
+    assert_eq!(*single[0], 1.0);
+}